@@ -0,0 +1,134 @@
+//! Benchmarks for the audio hot paths: spectrum FFT, EQ sample processing and
+//! handing spectrum frames to the UI
+//!
+//! Run with `cargo bench`. Everything is fed synthetic buffers, so no JACK or
+//! PipeWire server is needed.
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use rustfft::{FftPlanner, num_complex::Complex};
+use std::sync::Arc;
+
+// wavewire is a binary crate, so pull the self-contained DSP modules in directly.
+// Their lints are already reported by the main build
+#[allow(dead_code, unused_imports, clippy::all)]
+#[path = "../src/audio/eq.rs"]
+mod eq;
+#[allow(dead_code, unused_imports, clippy::all)]
+#[path = "../src/audio/fft.rs"]
+mod fft;
+
+use eq::{EqProcessor, EqSettings};
+use fft::FftProcessor;
+
+const SAMPLE_RATE: u32 = 48000;
+const NUM_BINS: usize = 128;
+const FFT_SIZES: [usize; 3] = [1024, 2048, 4096];
+/// Typical JACK period
+const BLOCK_SIZE: usize = 512;
+
+/// A 1 kHz sine plus a quieter 5 kHz tone
+fn test_signal(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            0.5 * (2.0 * std::f32::consts::PI * 1000.0 * t).sin()
+                + 0.1 * (2.0 * std::f32::consts::PI * 5000.0 * t).sin()
+        })
+        .collect()
+}
+
+fn bench_fft_process(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft_process");
+    for size in FFT_SIZES {
+        let samples = test_signal(size);
+        let mut processor = FftProcessor::new(size, NUM_BINS, SAMPLE_RATE);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &samples, |b, samples| {
+            b.iter(|| processor.process(black_box(samples)))
+        });
+    }
+    group.finish();
+}
+
+/// Cost of building a fresh plan for every frame vs reusing a cached one
+fn bench_fft_planning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft_planning");
+    for size in FFT_SIZES {
+        let input: Vec<Complex<f32>> = test_signal(size)
+            .into_iter()
+            .map(|s| Complex::new(s, 0.0))
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("replan", size), &input, |b, input| {
+            let mut buffer = input.clone();
+            b.iter(|| {
+                buffer.copy_from_slice(input);
+                let fft = FftPlanner::<f32>::new().plan_fft_forward(size);
+                fft.process(black_box(&mut buffer));
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("cached", size), &input, |b, input| {
+            let mut buffer = input.clone();
+            let fft = FftPlanner::<f32>::new().plan_fft_forward(size);
+            b.iter(|| {
+                buffer.copy_from_slice(input);
+                fft.process(black_box(&mut buffer));
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_eq_block(c: &mut Criterion) {
+    let left = test_signal(BLOCK_SIZE);
+    let right: Vec<f32> = left.iter().map(|s| -s).collect();
+
+    // Non-flat curve so every band does real filtering work
+    let mut settings = EqSettings::default();
+    for (i, band) in settings.bands.iter_mut().enumerate() {
+        band.gain_db = if i % 2 == 0 { 6.0 } else { -4.0 };
+    }
+
+    let mut group = c.benchmark_group("eq_process_sample");
+    group.throughput(Throughput::Elements(BLOCK_SIZE as u64));
+    group.bench_function(BenchmarkId::from_parameter(BLOCK_SIZE), |b| {
+        let mut processor = EqProcessor::new(SAMPLE_RATE as f32, settings.clone());
+        let mut out_left = vec![0.0; BLOCK_SIZE];
+        let mut out_right = vec![0.0; BLOCK_SIZE];
+        b.iter(|| {
+            for i in 0..BLOCK_SIZE {
+                let (l, r) = processor.process_sample(black_box(left[i]), black_box(right[i]));
+                out_left[i] = l;
+                out_right[i] = r;
+            }
+            black_box((&out_left, &out_right));
+        })
+    });
+    group.finish();
+}
+
+/// Keeping a spectrum frame in the UI by deep copy vs sharing the audio thread's `Arc`
+fn bench_spectrum_handoff(c: &mut Criterion) {
+    let mut processor = FftProcessor::new(FFT_SIZES[1], NUM_BINS, SAMPLE_RATE);
+    let frame = Arc::new(processor.process(&test_signal(FFT_SIZES[1])));
+
+    let mut group = c.benchmark_group("spectrum_handoff");
+    group.bench_function("deep_copy", |b| {
+        b.iter(|| {
+            let (bins, frequencies) = black_box(&*frame);
+            black_box((bins.clone(), frequencies.clone()))
+        })
+    });
+    group.bench_function("shared", |b| b.iter(|| black_box(Arc::clone(black_box(&frame)))));
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_fft_process,
+    bench_fft_planning,
+    bench_eq_block,
+    bench_spectrum_handoff
+);
+criterion_main!(benches);
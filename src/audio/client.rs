@@ -1,22 +1,38 @@
-use anyhow::{Context, Result};
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use pipewire::{
-    context::ContextRc, link::Link, main_loop::MainLoopRc, node::Node, port::Port,
-    types::ObjectType,
+    context::ContextRc, link::Link, main_loop::MainLoopRc, metadata::Metadata, node::{Node, NodeState},
+    port::Port, types::ObjectType,
 };
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
-use super::device::VirtualDevice;
+use super::clock::{GraphClock, SETTINGS_METADATA};
+use super::defaults::{DefaultNodes, DEFAULT_METADATA};
+use super::command_stats::CommandStats;
+use super::crossfeed::CrossfeedSettings;
+use super::device::{VirtualDevice, VIRTUAL_NODE_PREFIX};
 use super::eq::EqSettings;
+use super::fft::{is_valid_fft_size, FFT_SIZE_RANGE};
 use super::graph::{DeviceInfo, RoutingGraph};
+use super::pairing::capture_pair;
+use super::monitor_ports::{default_monitor_port_patterns, MonitorPortPattern};
+use super::saturation::clamp_saturation_drive;
+use super::width::clamp_stereo_width;
 use super::volume::VolumeSettings;
-use super::stream::{AudioCaptureStream, AudioProcessingStream};
+use super::stream::{
+    AudioCaptureStream, AudioProcessingStream, CAPTURE_TICK, DEFAULT_IDLE_THRESHOLD_DB,
+    DEFAULT_PROCESS_INTERVAL,
+};
 use super::types::{
     AudioCommand, AudioEvent, DeviceId, DeviceType, PortDirection, PortId, PortInfo,
 };
+use crate::error::{Result, WavewireError};
 
 // Thread-local storage at module level for PipeWire objects
 // These must be at module level to be accessible from closures
@@ -25,18 +41,85 @@ thread_local! {
     static CONNECTION_TO_LINK: RefCell<HashMap<(PortId, PortId), u32>> = RefCell::new(HashMap::new());
     static CAPTURE_STREAMS: RefCell<HashMap<DeviceId, AudioCaptureStream>> = RefCell::new(HashMap::new());
     static PROCESSING_STREAMS: RefCell<HashMap<DeviceId, AudioProcessingStream>> = RefCell::new(HashMap::new());
+    static EQ_TAP_STREAMS: RefCell<HashMap<DeviceId, AudioCaptureStream>> = RefCell::new(HashMap::new());
+    static NODES: RefCell<Vec<Node>> = const { RefCell::new(Vec::new()) };
+    static VIRTUAL_NODES: RefCell<HashMap<DeviceId, (VirtualDevice, Node)>> = RefCell::new(HashMap::new());
+    static PORTS: RefCell<Vec<Port>> = const { RefCell::new(Vec::new()) };
+    static METADATA: RefCell<Vec<Metadata>> = const { RefCell::new(Vec::new()) };
+    static LISTENERS: RefCell<Vec<Box<dyn Any>>> = RefCell::new(Vec::new());
+    static PROCESSED_NODES: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
+    static PROCESSED_PORTS: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
+    static PROCESS_INTERVAL: Cell<Duration> = const { Cell::new(DEFAULT_PROCESS_INTERVAL) };
+    static STEREO_CAPTURE: Cell<bool> = const { Cell::new(false) };
+    static WAVEFORM: Cell<bool> = const { Cell::new(false) };
+    static LEVEL_METERS: Cell<bool> = const { Cell::new(false) };
+    static STEREO_SPECTRUM: Cell<bool> = const { Cell::new(false) };
+    static IDLE_DETECTION: Cell<(f32, Option<Duration>)> = const { Cell::new((DEFAULT_IDLE_THRESHOLD_DB, None)) };
+    static MONITOR_PORT_PATTERNS: RefCell<Vec<MonitorPortPattern>> = RefCell::new(default_monitor_port_patterns());
+}
+
+/// Delay between attempts to reconnect after the daemon goes away
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How long `deactivate` waits for the event loop thread before leaving it behind
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What the event loop thread receives from the rest of the app
+#[derive(Clone)]
+struct LoopReceivers {
+    /// Commands from the UI thread
+    commands: Receiver<AudioCommand>,
+    /// Shutdown signal from `deactivate` (disconnected once the client is gone)
+    quit: Receiver<()>,
+}
+
+impl LoopReceivers {
+    fn quit_requested(&self) -> bool {
+        !matches!(self.quit.try_recv(), Err(crossbeam_channel::TryRecvError::Empty))
+    }
+}
+
+/// How a PipeWire session ended
+enum SessionEnd {
+    /// The client was asked to shut down
+    Quit,
+    /// The connection to the daemon was lost
+    Disconnected,
+}
+
+/// State read from PipeWire metadata objects, shared with the event-loop thread
+#[derive(Clone, Default)]
+struct SessionMetadata {
+    /// Graph clock from the "settings" metadata
+    clock: Arc<RwLock<GraphClock>>,
+    /// Default sink/source from the "default" metadata
+    defaults: Arc<RwLock<DefaultNodes>>,
+}
+
+/// Error for a failed step while setting up a PipeWire connection
+fn connect_error(action: &'static str, error: impl std::fmt::Display) -> WavewireError {
+    WavewireError::PipeWireConnect {
+        action,
+        reason: error.to_string(),
+    }
 }
 
 /// PipeWire client wrapper managing audio processing
+///
+/// Holds only thread-safe handles (channels and `Arc<RwLock<..>>` maps) shared with
+/// the event-loop thread. Proxies, listeners and streams stay in that thread's
+/// thread-locals and are dropped there while their core is still alive.
 pub struct PipeWireClient {
     /// Routing graph tracking all devices and connections
     routing_graph: Arc<RwLock<RoutingGraph>>,
-    /// Virtual devices created by wavewire
-    virtual_devices: Arc<RwLock<HashMap<DeviceId, VirtualDevice>>>,
+    /// Graph clock and default devices from the PipeWire metadata
+    metadata: SessionMetadata,
     /// Channel for sending events to UI thread
     event_tx: Option<Sender<AudioEvent>>,
     /// Channel for receiving commands from UI thread
     command_rx: Option<Receiver<AudioCommand>>,
+    /// Command tally shared with the UI thread
+    command_stats: CommandStats,
     /// Thread handle for PipeWire event loop
     event_thread: Option<JoinHandle<()>>,
     /// Channel for signaling event loop thread to quit
@@ -44,19 +127,24 @@ pub struct PipeWireClient {
     /// Mapping from PipeWire global ID to our internal DeviceId
     pw_node_map: Arc<RwLock<HashMap<u32, DeviceId>>>,
     /// Mapping from PipeWire global ID to our internal PortId
-    pw_port_map: Arc<RwLock<HashMap<u32, PortId>>>,
+    pw_port_map: Arc<RwLock<HashMap<u32, (DeviceId, PortId)>>>,
     /// Track if client is activated
     is_activated: bool,
 }
 
 impl PipeWireClient {
     /// Create a new PipeWire client
-    pub fn new(event_tx: Sender<AudioEvent>, command_rx: Receiver<AudioCommand>) -> Result<Self> {
+    pub fn new(
+        event_tx: Sender<AudioEvent>,
+        command_rx: Receiver<AudioCommand>,
+        command_stats: CommandStats,
+    ) -> Result<Self> {
         Ok(Self {
             routing_graph: Arc::new(RwLock::new(RoutingGraph::new())),
-            virtual_devices: Arc::new(RwLock::new(HashMap::new())),
+            metadata: SessionMetadata::default(),
             event_tx: Some(event_tx),
             command_rx: Some(command_rx),
+            command_stats,
             event_thread: None,
             quit_tx: None,
             pw_node_map: Arc::new(RwLock::new(HashMap::new())),
@@ -66,28 +154,37 @@ impl PipeWireClient {
     }
 
     /// Activate the PipeWire client and start audio processing
+    /// Returns `AlreadyStarted` when activated again after `deactivate`: the
+    /// command receiver went to the first event loop thread
     pub fn activate(&mut self) -> Result<()> {
         if self.is_activated {
             return Ok(());
         }
 
+        let event_tx = self
+            .event_tx
+            .as_ref()
+            .ok_or(WavewireError::NotInitialized)?
+            .clone();
+        let command_rx = self
+            .command_rx
+            .take()
+            .ok_or(WavewireError::AlreadyStarted)?;
+
         // Create quit channel
         let (quit_tx, quit_rx) = unbounded();
         self.quit_tx = Some(quit_tx);
+        let receivers = LoopReceivers {
+            commands: command_rx,
+            quit: quit_rx,
+        };
 
         // Clone necessary data for the event loop thread
         let routing_graph = Arc::clone(&self.routing_graph);
+        let metadata = self.metadata.clone();
         let pw_node_map = Arc::clone(&self.pw_node_map);
         let pw_port_map = Arc::clone(&self.pw_port_map);
-        let event_tx = self
-            .event_tx
-            .as_ref()
-            .context("Event sender not initialized")?
-            .clone();
-        let command_rx = self
-            .command_rx
-            .take()
-            .context("Command receiver not initialized")?;
+        let command_stats = self.command_stats.clone();
 
         // Spawn thread to run the PipeWire event loop
         // All PipeWire objects must be created and owned by this thread
@@ -96,253 +193,488 @@ impl PipeWireClient {
             // Initialize PipeWire
             pipewire::init();
 
-            // Create main loop
-            let main_loop = match MainLoopRc::new(None) {
-                Ok(ml) => ml,
-                Err(e) => {
-                    let _ = event_tx.send(AudioEvent::Error {
-                        message: format!("Failed to create PipeWire main loop: {}", e),
-                    });
-                    return;
+            let mut reconnecting = false;
+
+            loop {
+                match Self::run_session(
+                    &routing_graph,
+                    &metadata,
+                    &pw_node_map,
+                    &pw_port_map,
+                    &event_tx,
+                    &receivers,
+                    &command_stats,
+                ) {
+                    Ok(SessionEnd::Quit) => break,
+                    Ok(SessionEnd::Disconnected) => {
+                        crate::debug_log!("[PIPEWIRE] Lost connection to daemon, reconnecting");
+                        // Announced first, so the UI can note what was running
+                        // before the stop events below arrive
+                        let _ = event_tx.send(AudioEvent::Disconnected);
+                        Self::reset_session_state(
+                            &routing_graph,
+                            &pw_node_map,
+                            &pw_port_map,
+                            &event_tx,
+                        );
+                        reconnecting = true;
+                    }
+                    Err(e) if !reconnecting => {
+                        // Initial connection failed - report and give up
+                        let _ = event_tx.send(AudioEvent::Error { message: e.to_string() });
+                        break;
+                    }
+                    Err(e) => {
+                        crate::debug_log!("[PIPEWIRE] Reconnect attempt failed: {}", e);
+                    }
                 }
-            };
 
-            // Create context
-            let context = match ContextRc::new(&main_loop, None) {
-                Ok(ctx) => ctx,
-                Err(e) => {
-                    let _ = event_tx.send(AudioEvent::Error {
-                        message: format!("Failed to create PipeWire context: {}", e),
-                    });
-                    return;
+                if !Self::wait_for_reconnect(&receivers, &event_tx, &command_stats) {
+                    break;
                 }
-            };
+            }
 
-            // Connect to PipeWire daemon
-            let core = match context.connect_rc(None) {
-                Ok(core) => core,
-                Err(e) => {
-                    let _ = event_tx.send(AudioEvent::Error {
-                        message: format!("Failed to connect to PipeWire daemon: {}", e),
-                    });
-                    return;
-                }
-            };
+            // Cleanup (may not be reached if process exits abruptly)
+            // SAFETY: each session drops its proxies and listeners before returning
+            // (clear_session_proxies), so no PipeWire object outlives deinit
+            unsafe {
+                pipewire::deinit();
+            }
+        });
 
-            // Get registry for device discovery
-            let registry = match core.get_registry_rc() {
-                Ok(reg) => reg,
-                Err(e) => {
-                    let _ = event_tx.send(AudioEvent::Error {
-                        message: format!("Failed to get registry: {}", e),
-                    });
-                    return;
-                }
-            };
+        self.event_thread = Some(event_thread);
+        self.is_activated = true;
 
-            // Weak reference to registry for use in closures
-            let registry_weak = registry.downgrade();
-
-            // Clone for all handlers upfront (before creating any closures)
-            let routing_graph_global = Arc::clone(&routing_graph);
-            let pw_node_map_global = Arc::clone(&pw_node_map);
-            let pw_port_map_global = Arc::clone(&pw_port_map);
-            let event_tx_global = event_tx.clone();
-
-            let routing_graph_remove = Arc::clone(&routing_graph);
-            let pw_node_map_remove = Arc::clone(&pw_node_map);
-            let pw_port_map_remove = Arc::clone(&pw_port_map);
-            let event_tx_remove = event_tx.clone();
-
-            let core_cmd = core.clone();
-            let routing_graph_cmd = Arc::clone(&routing_graph);
-            let pw_node_map_cmd = Arc::clone(&pw_node_map);
-            let pw_port_map_cmd = Arc::clone(&pw_port_map);
-            let event_tx_cmd = event_tx.clone();
-            let main_loop_cmd = main_loop.clone();
-
-            // Set up registry listener for device discovery
-            let _registry_listener = registry
-                .add_listener_local()
-                .global(move |obj| {
-                    if let Some(registry) = registry_weak.upgrade() {
-                        Self::handle_registry_object(
-                            &registry,
-                            &routing_graph_global,
-                            &pw_node_map_global,
-                            &pw_port_map_global,
-                            &event_tx_global,
-                            obj,
-                        );
+        Ok(())
+    }
+
+    /// Run one PipeWire connection until its main loop quits
+    /// Returns `PipeWireConnect` if the connection could not be set up
+    fn run_session(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        metadata: &SessionMetadata,
+        pw_node_map: &Arc<RwLock<HashMap<u32, DeviceId>>>,
+        pw_port_map: &Arc<RwLock<HashMap<u32, (DeviceId, PortId)>>>,
+        event_tx: &Sender<AudioEvent>,
+        receivers: &LoopReceivers,
+        command_stats: &CommandStats,
+    ) -> Result<SessionEnd> {
+        let receivers = receivers.clone();
+        let command_stats = command_stats.clone();
+
+        // Create main loop
+        let main_loop = match MainLoopRc::new(None) {
+            Ok(ml) => ml,
+            Err(e) => return Err(connect_error("create PipeWire main loop", e)),
+        };
+
+        // Create context
+        let context = match ContextRc::new(&main_loop, None) {
+            Ok(ctx) => ctx,
+            Err(e) => return Err(connect_error("create PipeWire context", e)),
+        };
+
+        // Connect to PipeWire daemon
+        let core = match context.connect_rc(None) {
+            Ok(core) => core,
+            Err(e) => return Err(connect_error("connect to PipeWire daemon", e)),
+        };
+
+        // Get registry for device discovery
+        let registry = match core.get_registry_rc() {
+            Ok(reg) => reg,
+            Err(e) => return Err(connect_error("get registry", e)),
+        };
+
+        let _ = event_tx.send(AudioEvent::Connected);
+
+        // Detect the daemon going away (EPIPE reported against the core object)
+        let disconnected = Rc::new(Cell::new(false));
+        let disconnected_flag = Rc::clone(&disconnected);
+        let main_loop_weak = main_loop.downgrade();
+        let _core_listener = core
+            .add_listener_local()
+            .error(move |id, _seq, res, message| {
+                crate::debug_log!("[PIPEWIRE] Core error: id={}, res={}, {}", id, res, message);
+                let broken_pipe = std::io::Error::from_raw_os_error(-res).kind()
+                    == std::io::ErrorKind::BrokenPipe;
+                if id == pipewire::core::PW_ID_CORE && broken_pipe {
+                    disconnected_flag.set(true);
+                    if let Some(main_loop) = main_loop_weak.upgrade() {
+                        main_loop.quit();
                     }
-                })
-                .global_remove(move |id| {
-                    Self::handle_registry_remove(
-                        &routing_graph_remove,
-                        &pw_node_map_remove,
-                        &pw_port_map_remove,
-                        &event_tx_remove,
-                        id,
+                }
+            })
+            .register();
+
+        // Weak reference to registry for use in closures
+        let registry_weak = registry.downgrade();
+
+        // Clone for all handlers upfront (before creating any closures)
+        let routing_graph_global = Arc::clone(routing_graph);
+        let metadata_global = metadata.clone();
+        let pw_node_map_global = Arc::clone(pw_node_map);
+        let pw_port_map_global = Arc::clone(pw_port_map);
+        let event_tx_global = event_tx.clone();
+
+        let routing_graph_remove = Arc::clone(routing_graph);
+        let pw_node_map_remove = Arc::clone(pw_node_map);
+        let pw_port_map_remove = Arc::clone(pw_port_map);
+        let event_tx_remove = event_tx.clone();
+
+        let core_cmd = core.clone();
+        let routing_graph_cmd = Arc::clone(routing_graph);
+        let pw_node_map_cmd = Arc::clone(pw_node_map);
+        let event_tx_cmd = event_tx.clone();
+        let main_loop_cmd = main_loop.clone();
+
+        // Set up registry listener for device discovery
+        let _registry_listener = registry
+            .add_listener_local()
+            .global(move |obj| {
+                if let Some(registry) = registry_weak.upgrade() {
+                    Self::handle_registry_object(
+                        &registry,
+                        &routing_graph_global,
+                        &metadata_global,
+                        &pw_node_map_global,
+                        &pw_port_map_global,
+                        &event_tx_global,
+                        obj,
                     );
-                })
-                .register();
-
-            // Set up a timer to poll for commands periodically
-            // This allows us to process commands while the event loop is running
-
-            let timer_source = main_loop
-                .loop_()
-                .add_timer(move |_expirations| {
-                    // Update all active capture streams (generate test data and process FFT)
-                    // Use the CAPTURE_STREAMS from the outer scope (line 184)
-                    CAPTURE_STREAMS.with(|streams| {
-                        let num_streams = streams.borrow().len();
-                        if num_streams > 0 {
-                            // Log occasionally to avoid spam
-                            static mut TIMER_TICK: u32 = 0;
-                            unsafe {
-                                TIMER_TICK += 1;
-                                if TIMER_TICK % 100 == 0 { // Every ~1 second (100 * 10ms)
-                                    crate::debug_log!("[TIMER] Updating {} active stream(s)", num_streams);
-                                }
-                            }
-                        }
+                }
+            })
+            .global_remove(move |id| {
+                Self::handle_registry_remove(
+                    &routing_graph_remove,
+                    &pw_node_map_remove,
+                    &pw_port_map_remove,
+                    &event_tx_remove,
+                    id,
+                );
+            })
+            .register();
+
+        // Set up a timer to poll for commands periodically
+        // This allows us to process commands while the event loop is running
+
+        let timer_source = main_loop
+            .loop_()
+            .add_timer(move |_expirations| {
+                // Asked to shut down (or the client is gone): leave the main loop
+                if receivers.quit_requested() {
+                    crate::debug_log!("[PIPEWIRE] Quit requested, leaving the event loop");
+                    main_loop_cmd.quit();
+                    return;
+                }
 
-                        for stream in streams.borrow_mut().values_mut() {
-                            stream.update();
+                // Update all active capture streams (generate test data and process FFT)
+                // Use the CAPTURE_STREAMS from the outer scope (line 184)
+                CAPTURE_STREAMS.with(|streams| {
+                    let num_streams = streams.borrow().len();
+                    if num_streams > 0 {
+                        // Log occasionally to avoid spam
+                        static mut TIMER_TICK: u32 = 0;
+                        unsafe {
+                            TIMER_TICK += 1;
+                            if TIMER_TICK.is_multiple_of(100) { // Every ~1 second (100 * 10ms)
+                                crate::debug_log!("[TIMER] Updating {} active stream(s)", num_streams);
+                            }
                         }
-                    });
+                    }
 
-                    // Poll for commands (non-blocking)
-                    match command_rx.try_recv() {
-                        Ok(AudioCommand::Connect { source_port, dest_port }) => {
-                            Self::handle_connect_command(
-                                &core_cmd,
-                                &routing_graph_cmd,
-                                &pw_port_map_cmd,
-                                &event_tx_cmd,
-                                &source_port,
-                                &dest_port,
-                            );
-                        }
-                        Ok(AudioCommand::Disconnect { source_port, dest_port }) => {
-                            Self::handle_disconnect_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                &source_port,
-                                &dest_port,
-                            );
-                        }
-                        Ok(AudioCommand::CreateVirtualDevice { .. }) => {
-                            // TODO: Implement virtual device creation
-                        }
-                        Ok(AudioCommand::DestroyVirtualDevice { .. }) => {
-                            // TODO: Implement virtual device destruction
-                        }
-                        Ok(AudioCommand::StartVisualization { device_id, port_id }) => {
-                            Self::handle_start_visualization_command(
-                                &core_cmd,
-                                &routing_graph_cmd,
-                                &pw_node_map_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                                port_id,
-                            );
-                        }
-                        Ok(AudioCommand::StopVisualization { device_id }) => {
-                            Self::handle_stop_visualization_command(
-                                &event_tx_cmd,
-                                device_id,
-                            );
-                        }
-                        Ok(AudioCommand::EnableEq { device_id, settings }) => {
-                            Self::handle_enable_eq_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                                settings,
-                            );
-                        }
-                        Ok(AudioCommand::DisableEq { device_id }) => {
-                            Self::handle_disable_eq_command(&event_tx_cmd, device_id);
-                        }
-                        Ok(AudioCommand::SetEqBand { device_id, band_index, gain_db, q_value }) => {
-                            Self::handle_set_eq_band_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                                band_index,
-                                gain_db,
-                                q_value,
-                            );
-                        }
-                        Ok(AudioCommand::SetEqSettings { device_id, settings }) => {
-                            Self::handle_set_eq_settings_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                                settings,
-                            );
-                        }
-                        Ok(AudioCommand::SetEqBypass { device_id, bypass }) => {
-                            Self::handle_set_eq_bypass_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                                bypass,
-                            );
-                        }
-                        Ok(AudioCommand::ResetEq { device_id }) => {
-                            Self::handle_reset_eq_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                            );
-                        }
-                        Ok(AudioCommand::SetVolume { device_id, settings }) => {
-                            Self::handle_set_volume_command(
-                                &routing_graph_cmd,
-                                &event_tx_cmd,
-                                device_id,
-                                settings,
-                            );
-                        }
-                        Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                            // Command channel closed, quit the loop
-                            main_loop_cmd.quit();
-                        }
-                        Err(crossbeam_channel::TryRecvError::Empty) => {
-                            // No commands, continue
-                        }
+                    for stream in streams.borrow_mut().values_mut() {
+                        stream.update();
+                    }
+                });
+                EQ_TAP_STREAMS.with(|streams| {
+                    for stream in streams.borrow_mut().values_mut() {
+                        stream.update();
                     }
                 });
 
-            // Arm the timer to fire every 10ms
-            timer_source.update_timer(
-                Some(std::time::Duration::from_millis(10)), // Initial delay
-                Some(std::time::Duration::from_millis(10)), // Repeat interval
-            );
+                // Poll for commands (non-blocking)
+                let received = receivers.commands.try_recv();
+                if let Ok(command) = &received {
+                    command_stats.record_handled(command.name());
+                }
+                match received {
+                    Ok(AudioCommand::Connect { source_port, dest_port }) => {
+                        Self::handle_connect_command(
+                            &core_cmd,
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            &source_port,
+                            &dest_port,
+                        );
+                    }
+                    Ok(AudioCommand::Disconnect { source_port, dest_port }) => {
+                        Self::handle_disconnect_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            &source_port,
+                            &dest_port,
+                        );
+                    }
+                    Ok(AudioCommand::CreateVirtualDevice { name, num_inputs, num_outputs }) => {
+                        Self::handle_create_virtual_device_command(
+                            &core_cmd,
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            name,
+                            num_inputs,
+                            num_outputs,
+                        );
+                    }
+                    Ok(AudioCommand::DestroyVirtualDevice { device_id }) => {
+                        Self::handle_destroy_virtual_device_command(&event_tx_cmd, device_id);
+                    }
+                    Ok(AudioCommand::StartVisualization { device_id, port_id }) => {
+                        Self::handle_start_visualization_command(
+                            &routing_graph_cmd,
+                            &pw_node_map_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            port_id,
+                        );
+                    }
+                    Ok(AudioCommand::StopVisualization { device_id }) => {
+                        Self::handle_stop_visualization_command(
+                            &event_tx_cmd,
+                            device_id,
+                        );
+                    }
+                    Ok(AudioCommand::StartEqTap { device_id }) => {
+                        Self::handle_start_eq_tap_command(&event_tx_cmd, device_id);
+                    }
+                    Ok(AudioCommand::StopEqTap { device_id }) => {
+                        Self::handle_stop_eq_tap_command(&event_tx_cmd, device_id);
+                    }
+                    Ok(AudioCommand::EnableEq { device_id, settings }) => {
+                        Self::handle_enable_eq_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            settings,
+                        );
+                    }
+                    Ok(AudioCommand::DisableEq { device_id }) => {
+                        Self::handle_disable_eq_command(&routing_graph_cmd, &event_tx_cmd, device_id);
+                    }
+                    Ok(AudioCommand::SetEqBand { device_id, band_index, gain_db, q_value }) => {
+                        Self::handle_set_eq_band_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            band_index,
+                            gain_db,
+                            q_value,
+                        );
+                    }
+                    Ok(AudioCommand::SetEqSettings { device_id, settings }) => {
+                        Self::handle_set_eq_settings_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            settings,
+                        );
+                    }
+                    Ok(AudioCommand::SetEqBypass { device_id, bypass }) => {
+                        Self::handle_set_eq_bypass_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            bypass,
+                        );
+                    }
+                    Ok(AudioCommand::ResetEq { device_id }) => {
+                        Self::handle_reset_eq_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                        );
+                    }
+                    Ok(AudioCommand::SetVolume { device_id, settings }) => {
+                        Self::handle_set_volume_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            settings,
+                        );
+                    }
+                    Ok(AudioCommand::SetCrossfeed { device_id, settings }) => {
+                        Self::handle_set_crossfeed_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            settings,
+                        );
+                    }
+                    Ok(AudioCommand::SetSaturation { device_id, drive }) => {
+                        Self::handle_set_saturation_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            drive,
+                        );
+                    }
+                    Ok(AudioCommand::SetStereoWidth { device_id, width }) => {
+                        Self::handle_set_stereo_width_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            width,
+                        );
+                    }
+                    Ok(AudioCommand::SetFftSize { device_id, fft_size }) => {
+                        Self::handle_set_fft_size_command(&event_tx_cmd, device_id, fft_size);
+                    }
+                    Ok(AudioCommand::SetRefreshRate { hz }) => {
+                        Self::handle_set_refresh_rate_command(hz);
+                    }
+                    Ok(AudioCommand::SetStereoCapture { enabled }) => {
+                        Self::handle_set_stereo_capture_command(enabled);
+                    }
+                    Ok(AudioCommand::SetWaveform { enabled }) => {
+                        Self::handle_set_waveform_command(enabled);
+                    }
+                    Ok(AudioCommand::SetLevelMeters { enabled }) => {
+                        Self::handle_set_level_meters_command(enabled);
+                    }
+                    Ok(AudioCommand::SetStereoSpectrum { enabled }) => {
+                        Self::handle_set_stereo_spectrum_command(enabled);
+                    }
+                    Ok(AudioCommand::SetIdleDetection { threshold_db, after }) => {
+                        Self::handle_set_idle_detection_command(threshold_db, after);
+                    }
+                    Ok(AudioCommand::SetMonitorPortPatterns { patterns }) => {
+                        Self::handle_set_monitor_port_patterns_command(patterns);
+                    }
+                    Ok(AudioCommand::StartRecording { device_id, path }) => {
+                        Self::handle_start_recording_command(
+                            &routing_graph_cmd,
+                            &event_tx_cmd,
+                            device_id,
+                            path,
+                        );
+                    }
+                    Ok(AudioCommand::StopRecording { device_id }) => {
+                        Self::handle_stop_recording_command(&event_tx_cmd, device_id);
+                    }
+                    Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                        // Command channel closed, quit the loop
+                        main_loop_cmd.quit();
+                    }
+                    Err(crossbeam_channel::TryRecvError::Empty) => {
+                        // No commands, continue
+                    }
+                }
+            });
+
+        // Arm the timer to fire every capture tick (10ms)
+        timer_source.update_timer(
+            Some(CAPTURE_TICK), // Initial delay
+            Some(CAPTURE_TICK), // Repeat interval
+        );
 
-            // Keep objects alive
-            let _context = context;
-            let _registry = registry;
-            let _listener = _registry_listener;
-            let _timer_source = timer_source;
-            let _quit_rx = quit_rx; // Keep alive for future use
+        // Keep objects alive
+        let _context = context;
+        let _registry = registry;
+        let _listener = _registry_listener;
+        let _timer_source = timer_source;
 
-            // Run the main loop (blocks until quit is called)
-            main_loop.run();
+        // Run the main loop (blocks until quit is called or the daemon goes away)
+        main_loop.run();
 
-            // Cleanup (may not be reached if process exits abruptly)
-            unsafe {
-                pipewire::deinit();
+        // Proxies bound on this core must be dropped while the core is still alive
+        Self::clear_session_proxies();
+
+        if disconnected.get() {
+            Ok(SessionEnd::Disconnected)
+        } else {
+            Ok(SessionEnd::Quit)
+        }
+    }
+
+    /// Wait before the next reconnect attempt, rejecting commands meanwhile
+    /// Returns false if the UI has gone away or asked to quit and the thread should exit
+    fn wait_for_reconnect(
+        receivers: &LoopReceivers,
+        event_tx: &Sender<AudioEvent>,
+        command_stats: &CommandStats,
+    ) -> bool {
+        let deadline = std::time::Instant::now() + RECONNECT_DELAY;
+        while std::time::Instant::now() < deadline {
+            if receivers.quit_requested() {
+                return false;
+            }
+            loop {
+                match receivers.commands.try_recv() {
+                    Ok(command) => {
+                        command_stats.record_dropped(command.name());
+                        let _ = event_tx.send(AudioEvent::Error {
+                            message: format!("Not connected to PipeWire, dropped {:?}", command),
+                        });
+                    }
+                    Err(crossbeam_channel::TryRecvError::Empty) => break,
+                    Err(crossbeam_channel::TryRecvError::Disconnected) => return false,
+                }
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        true
+    }
+
+    /// Drop all PipeWire proxies and listeners tied to the current core
+    fn clear_session_proxies() {
+        LISTENERS.with(|listeners| listeners.borrow_mut().clear());
+        NODES.with(|nodes| nodes.borrow_mut().clear());
+        // Virtual nodes don't linger, so they went away with the old connection
+        VIRTUAL_NODES.with(|nodes| nodes.borrow_mut().clear());
+        PORTS.with(|ports| ports.borrow_mut().clear());
+        METADATA.with(|metadata| metadata.borrow_mut().clear());
+        LINKS.with(|links| links.borrow_mut().clear());
+        CONNECTION_TO_LINK.with(|conn_map| conn_map.borrow_mut().clear());
+        PROCESSED_NODES.with(|set| set.borrow_mut().clear());
+        PROCESSED_PORTS.with(|set| set.borrow_mut().clear());
+    }
+
+    /// Forget everything learned from a dead connection so the next session
+    /// re-enumerates from scratch, telling the UI what went away
+    fn reset_session_state(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        pw_node_map: &Arc<RwLock<HashMap<u32, DeviceId>>>,
+        pw_port_map: &Arc<RwLock<HashMap<u32, (DeviceId, PortId)>>>,
+        event_tx: &Sender<AudioEvent>,
+    ) {
+        // JACK clients go through the same daemon, so their streams are dead too
+        CAPTURE_STREAMS.with(|streams| {
+            for (device_id, _stream) in streams.borrow_mut().drain() {
+                let _ = event_tx.send(AudioEvent::VisualizationStopped { device_id });
+            }
+        });
+        EQ_TAP_STREAMS.with(|streams| {
+            for (device_id, _stream) in streams.borrow_mut().drain() {
+                let _ = event_tx.send(AudioEvent::EqTapStopped { device_id });
+            }
+        });
+        PROCESSING_STREAMS.with(|streams| {
+            for (device_id, _stream) in streams.borrow_mut().drain() {
+                let _ = event_tx.send(AudioEvent::EqDisabled { device_id });
             }
         });
 
-        self.event_thread = Some(event_thread);
-        self.is_activated = true;
+        {
+            let mut graph = routing_graph.write().unwrap();
+            let device_ids: Vec<DeviceId> = graph.list_devices().iter().map(|d| d.id).collect();
+            for device_id in device_ids {
+                let _ = event_tx.send(AudioEvent::DeviceRemoved { device_id });
+            }
+            graph.clear();
+        }
 
-        Ok(())
+        pw_node_map.write().unwrap().clear();
+        pw_port_map.write().unwrap().clear();
     }
 
     /// Deactivate the PipeWire client and stop audio processing
@@ -351,14 +683,29 @@ impl PipeWireClient {
             return Ok(());
         }
 
-        // Drop the quit channel sender
-        // The event loop thread will continue running but won't block process exit
-        self.quit_tx.take();
+        // MainLoopRc can't be signaled from another thread, so the loop's command
+        // timer picks this up and quits from inside (dropping the sender does too)
+        if let Some(quit_tx) = self.quit_tx.take() {
+            let _ = quit_tx.send(());
+        }
 
-        // Don't wait for the event thread - PipeWire's MainLoopRc can't be
-        // safely signaled from another thread. The thread will be cleaned up
-        // by the OS when the process exits.
-        self.event_thread.take();
+        // Wait for the thread to finish, but never hang on a stuck loop: past the
+        // timeout it is left behind, to go when the process exits
+        if let Some(event_thread) = self.event_thread.take() {
+            let deadline = std::time::Instant::now() + SHUTDOWN_TIMEOUT;
+            while !event_thread.is_finished() && std::time::Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            if event_thread.is_finished() {
+                let _ = event_thread.join();
+                crate::debug_log!("[PIPEWIRE] Event loop thread stopped");
+            } else {
+                crate::debug_log!(
+                    "[PIPEWIRE] Event loop thread didn't stop within {:?}, leaving it",
+                    SHUTDOWN_TIMEOUT
+                );
+            }
+        }
 
         self.is_activated = false;
         Ok(())
@@ -368,23 +715,12 @@ impl PipeWireClient {
     fn handle_registry_object(
         registry: &pipewire::registry::RegistryRc,
         routing_graph: &Arc<RwLock<RoutingGraph>>,
+        metadata: &SessionMetadata,
         pw_node_map: &Arc<RwLock<HashMap<u32, DeviceId>>>,
-        pw_port_map: &Arc<RwLock<HashMap<u32, PortId>>>,
+        pw_port_map: &Arc<RwLock<HashMap<u32, (DeviceId, PortId)>>>,
         event_tx: &Sender<AudioEvent>,
         obj: &pipewire::registry::GlobalObject<&pipewire::spa::utils::dict::DictRef>,
     ) {
-        // Store objects in thread-local storage so they stay alive for the duration
-        // of the registry listener
-        use std::any::Any;
-        use std::collections::HashSet;
-        thread_local! {
-            static NODES: RefCell<Vec<Node>> = RefCell::new(Vec::new());
-            static PORTS: RefCell<Vec<Port>> = RefCell::new(Vec::new());
-            static LISTENERS: RefCell<Vec<Box<dyn Any>>> = RefCell::new(Vec::new());
-            static PROCESSED_NODES: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
-            static PROCESSED_PORTS: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
-        }
-
         match obj.type_ {
             ObjectType::Node => {
                 // Check if we've already processed this node
@@ -414,6 +750,22 @@ impl PipeWireClient {
                     .info(move |info| {
                         let props = info.props();
 
+                        // Skip wavewire's own virtual devices to avoid duplicates:
+                        // they are added under the name they were created with
+                        if let Some(pw_name) = props
+                            .and_then(|p| p.get("node.name"))
+                            .filter(|name| name.starts_with(VIRTUAL_NODE_PREFIX))
+                        {
+                            Self::register_virtual_node(
+                                &routing_graph,
+                                &pw_node_map,
+                                &event_tx,
+                                global_id,
+                                pw_name,
+                            );
+                            return;
+                        }
+
                         // Extract node name - check multiple properties
                         // Priority: media.name (most specific, used by browser tabs) >
                         //           node.nick > node.description > application.name > node.name
@@ -426,16 +778,22 @@ impl PipeWireClient {
                             .unwrap_or("Unknown Node")
                             .to_string();
 
-                        // Skip wavewire's own virtual devices to avoid duplicates
-                        if node_name.starts_with("wavewire_virtual_") {
-                            return;
-                        }
-
                         // Skip monitor sources - we'll add .monitor programmatically when needed
                         if node_name.ends_with(".monitor") {
                             return;
                         }
 
+                        // Stream nodes (browser tabs, players) remember their application
+                        // so the UI can group them; sinks/sources stay ungrouped
+                        let media_class = props.and_then(|p| p.get("media.class")).map(String::from);
+                        let is_stream = media_class
+                            .as_deref()
+                            .is_some_and(|class| class.starts_with("Stream/"));
+                        let stream_application = props
+                            .and_then(|p| p.get("application.name"))
+                            .filter(|_| is_stream)
+                            .map(String::from);
+
                         // Check if we already have this node mapped to a device
                         let existing_device_id = {
                             let node_map = pw_node_map.read().unwrap();
@@ -443,13 +801,19 @@ impl PipeWireClient {
                         };
 
                         // Only create device if it doesn't already exist
-                        if existing_device_id.is_none() {
+                        let device_id = if let Some(device_id) = existing_device_id {
+                            device_id
+                        } else {
                             let device_id = {
                                 let mut graph = routing_graph.write().unwrap();
                                 let device_id = graph.generate_device_id();
 
-                                let device_info =
+                                let mut device_info =
                                     DeviceInfo::new(device_id, node_name.clone(), DeviceType::Physical);
+                                device_info.application_name = stream_application;
+                                device_info.media_class = media_class;
+                                device_info.node_name =
+                                    props.and_then(|p| p.get("node.name")).map(String::from);
                                 graph.add_device(device_info);
 
                                 device_id
@@ -467,6 +831,17 @@ impl PipeWireClient {
                                 name: node_name,
                                 device_type: DeviceType::Physical,
                             });
+                            device_id
+                        };
+
+                        // Info is sent again whenever the state changes, so this
+                        // tells the UI when a stream starts or stops playing even
+                        // if it isn't being visualized
+                        if is_stream {
+                            let _ = event_tx.send(AudioEvent::StreamStateChanged {
+                                device_id,
+                                running: matches!(info.state(), NodeState::Running),
+                            });
                         }
                     })
                     .register();
@@ -506,6 +881,12 @@ impl PipeWireClient {
                 let listener = port
                     .add_listener_local()
                     .info(move |info| {
+                        // Info comes again when the port's params change; adding it
+                        // twice would leave a ghost behind when it is removed
+                        if pw_port_map.read().unwrap().contains_key(&global_id) {
+                            return;
+                        }
+
                         let props = info.props();
 
                         // Get the parent node ID
@@ -566,9 +947,9 @@ impl PipeWireClient {
                                 port_id
                             };
 
-                            // Track PipeWire port ID -> our PortId
+                            // Track PipeWire port ID -> owning device and our PortId
                             let mut port_map = pw_port_map.write().unwrap();
-                            port_map.insert(global_id, port_id);
+                            port_map.insert(global_id, (device_id, port_id));
                         }
                     })
                     .register();
@@ -605,8 +986,8 @@ impl PipeWireClient {
 
                         // Map PipeWire port IDs to our PortId
                         let port_map = pw_port_map.read().unwrap();
-                        let source_port_id = port_map.get(&output_port_id).copied();
-                        let dest_port_id = port_map.get(&input_port_id).copied();
+                        let source_port_id = port_map.get(&output_port_id).map(|&(_, port_id)| port_id);
+                        let dest_port_id = port_map.get(&input_port_id).map(|&(_, port_id)| port_id);
 
                         if let (Some(source), Some(dest)) = (source_port_id, dest_port_id) {
                             // Add connection to graph
@@ -632,10 +1013,6 @@ impl PipeWireClient {
                             }
 
                             // Store link ID mapping for disconnection
-                            thread_local! {
-                                static LINKS: RefCell<HashMap<u32, Link>> = RefCell::new(HashMap::new());
-                                static CONNECTION_TO_LINK: RefCell<HashMap<(PortId, PortId), u32>> = RefCell::new(HashMap::new());
-                            }
 
                             CONNECTION_TO_LINK.with(|conn_map| {
                                 conn_map.borrow_mut().insert((source, dest), global_id);
@@ -645,15 +1022,52 @@ impl PipeWireClient {
                     .register();
 
                 // Store link and listener to keep them alive
-                thread_local! {
-                    static LINKS: RefCell<HashMap<u32, Link>> = RefCell::new(HashMap::new());
-                }
 
                 LINKS.with(|links| {
                     links.borrow_mut().insert(global_id, link);
                 });
                 LISTENERS.with(|listeners| listeners.borrow_mut().push(Box::new(listener)));
             }
+            ObjectType::Metadata => {
+                // The "settings" metadata carries the graph clock, "default" the
+                // default sink/source; other metadata objects are ignored
+                let name = obj.props.and_then(|props| props.get("metadata.name"));
+                let is_settings = match name {
+                    Some(SETTINGS_METADATA) => true,
+                    Some(DEFAULT_METADATA) => false,
+                    _ => return,
+                };
+
+                let pw_metadata: Metadata = match registry.bind(obj) {
+                    Ok(pw_metadata) => pw_metadata,
+                    Err(_) => return,
+                };
+
+                let metadata = metadata.clone();
+                let listener = pw_metadata
+                    .add_listener_local()
+                    .property(move |subject, key, _type, value| {
+                        // Graph-wide settings and defaults are set on subject 0 (the core)
+                        if subject != pipewire::core::PW_ID_CORE {
+                            return 0;
+                        }
+                        let Some(key) = key else {
+                            return 0;
+                        };
+                        if is_settings {
+                            if metadata.clock.write().unwrap().apply(key, value) {
+                                crate::debug_log!("[PIPEWIRE] Clock setting {} = {:?}", key, value);
+                            }
+                        } else if metadata.defaults.write().unwrap().apply(key, value) {
+                            crate::debug_log!("[PIPEWIRE] Default device {} = {:?}", key, value);
+                        }
+                        0
+                    })
+                    .register();
+
+                METADATA.with(|metadata_list| metadata_list.borrow_mut().push(pw_metadata));
+                LISTENERS.with(|listeners| listeners.borrow_mut().push(Box::new(listener)));
+            }
             _ => {
                 // Ignore other object types for now
             }
@@ -664,7 +1078,7 @@ impl PipeWireClient {
     fn handle_registry_remove(
         routing_graph: &Arc<RwLock<RoutingGraph>>,
         pw_node_map: &Arc<RwLock<HashMap<u32, DeviceId>>>,
-        pw_port_map: &Arc<RwLock<HashMap<u32, PortId>>>,
+        pw_port_map: &Arc<RwLock<HashMap<u32, (DeviceId, PortId)>>>,
         event_tx: &Sender<AudioEvent>,
         id: u32,
     ) {
@@ -682,19 +1096,21 @@ impl PipeWireClient {
         }
 
         // Check if it's a port being removed
-        if let Some(_port_id) = {
+        if let Some((device_id, port_id)) = {
             let mut port_map = pw_port_map.write().unwrap();
             port_map.remove(&id)
         } {
-            // TODO: Remove port from device in routing graph
-            // This requires maintaining port -> device relationships
+            // Nothing to do if the whole device went first
+            let removed = routing_graph.write().unwrap().remove_port(device_id, port_id);
+            if let Some(port) = removed {
+                let _ = event_tx.send(AudioEvent::PortRemoved {
+                    device_id,
+                    port_name: port.pipewire_port_name,
+                });
+            }
         }
 
         // Check if it's a link being removed
-        thread_local! {
-            static LINKS: RefCell<HashMap<u32, Link>> = RefCell::new(HashMap::new());
-            static CONNECTION_TO_LINK: RefCell<HashMap<(PortId, PortId), u32>> = RefCell::new(HashMap::new());
-        }
 
         LINKS.with(|links| {
             if links.borrow_mut().remove(&id).is_some() {
@@ -740,25 +1156,24 @@ impl PipeWireClient {
     fn handle_connect_command(
         core: &pipewire::core::CoreRc,
         routing_graph: &Arc<RwLock<RoutingGraph>>,
-        pw_port_map: &Arc<RwLock<HashMap<u32, PortId>>>,
         event_tx: &Sender<AudioEvent>,
         source_port: &str,
         dest_port: &str,
     ) {
-        // Resolve port names to PortIds
-        let (source_id, dest_id) = {
+        // Resolve port names to PortIds (refusing names shared by several ports)
+        let resolved = {
             let graph = routing_graph.read().unwrap();
-            match (
-                graph.find_port_by_name(source_port),
-                graph.find_port_by_name(dest_port),
-            ) {
-                (Some(src), Some(dst)) => (src, dst),
-                _ => {
-                    let _ = event_tx.send(AudioEvent::Error {
-                        message: format!("Ports not found: {} -> {}", source_port, dest_port),
-                    });
-                    return;
-                }
+            graph
+                .find_port_by_name(source_port)
+                .and_then(|src| Ok((src, graph.find_port_by_name(dest_port)?)))
+        };
+        let (source_id, dest_id) = match resolved {
+            Ok(ids) => ids,
+            Err(e) => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("Cannot connect {} -> {}: {}", source_port, dest_port, e),
+                });
+                return;
             }
         };
 
@@ -806,20 +1221,20 @@ impl PipeWireClient {
         source_port: &str,
         dest_port: &str,
     ) {
-        // Resolve port names to PortIds
-        let (source_id, dest_id) = {
+        // Resolve port names to PortIds (refusing names shared by several ports)
+        let resolved = {
             let graph = routing_graph.read().unwrap();
-            match (
-                graph.find_port_by_name(source_port),
-                graph.find_port_by_name(dest_port),
-            ) {
-                (Some(src), Some(dst)) => (src, dst),
-                _ => {
-                    let _ = event_tx.send(AudioEvent::Error {
-                        message: format!("Ports not found: {} -> {}", source_port, dest_port),
-                    });
-                    return;
-                }
+            graph
+                .find_port_by_name(source_port)
+                .and_then(|src| Ok((src, graph.find_port_by_name(dest_port)?)))
+        };
+        let (source_id, dest_id) = match resolved {
+            Ok(ids) => ids,
+            Err(e) => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("Cannot disconnect {} -> {}: {}", source_port, dest_port, e),
+                });
+                return;
             }
         };
 
@@ -830,10 +1245,6 @@ impl PipeWireClient {
             if graph.remove_connection(&connection) {
                 // Successfully removed from graph
                 // Now remove the Link object from thread-local storage
-                thread_local! {
-                    static LINKS: RefCell<HashMap<u32, Link>> = RefCell::new(HashMap::new());
-                    static CONNECTION_TO_LINK: RefCell<HashMap<(PortId, PortId), u32>> = RefCell::new(HashMap::new());
-                }
 
                 CONNECTION_TO_LINK.with(|conn_map| {
                     if let Some(link_id) = conn_map.borrow_mut().remove(&(source_id, dest_id)) {
@@ -856,9 +1267,133 @@ impl PipeWireClient {
         }
     }
 
+    /// Handle create virtual device command - create a null-sink node
+    /// The device is added once the node shows up in the registry
+    fn handle_create_virtual_device_command(
+        core: &pipewire::core::CoreRc,
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        name: String,
+        num_inputs: usize,
+        num_outputs: usize,
+    ) {
+        let device_id = routing_graph.write().unwrap().generate_device_id();
+        let virtual_device = match VirtualDevice::new(device_id, name, num_inputs, num_outputs) {
+            Ok(virtual_device) => virtual_device,
+            Err(e) => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("Cannot create virtual device: {}", e),
+                });
+                return;
+            }
+        };
+
+        let node_name = virtual_device.node_name();
+        let in_use = VIRTUAL_NODES.with(|nodes| {
+            nodes
+                .borrow()
+                .values()
+                .any(|(existing, _)| existing.node_name() == node_name)
+        });
+        if in_use {
+            let _ = event_tx.send(AudioEvent::Error {
+                message: format!("Virtual device {} already exists", virtual_device.name),
+            });
+            return;
+        }
+
+        let channels = virtual_device.channels().to_string();
+        let position = virtual_device.audio_position();
+        let props = &pipewire::properties::properties! {
+            "factory.name" => "support.null-audio-sink",
+            "node.name" => node_name.as_str(),
+            "node.description" => virtual_device.name.as_str(),
+            "media.class" => virtual_device.media_class(),
+            "audio.channels" => channels.as_str(),
+            "audio.position" => position.as_str(),
+            "monitor.channel-volumes" => "true",
+        };
+
+        match core.create_object::<Node>("adapter", props) {
+            Ok(node) => {
+                crate::debug_log!(
+                    "[VIRTUAL] Created {} ({} in, {} out) as {}",
+                    virtual_device.name,
+                    num_inputs,
+                    num_outputs,
+                    node_name
+                );
+                // The node lives as long as its proxy
+                VIRTUAL_NODES.with(|nodes| {
+                    nodes.borrow_mut().insert(device_id, (virtual_device, node));
+                });
+            }
+            Err(e) => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("Failed to create virtual device {}: {}", virtual_device.name, e),
+                });
+            }
+        }
+    }
+
+    /// Handle destroy virtual device command - drop the node's proxy
+    /// The device is removed once the node leaves the registry
+    fn handle_destroy_virtual_device_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId) {
+        let removed = VIRTUAL_NODES.with(|nodes| nodes.borrow_mut().remove(&device_id));
+        match removed {
+            Some((virtual_device, _node)) => {
+                crate::debug_log!("[VIRTUAL] Destroyed {}", virtual_device.name);
+            }
+            None => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("Cannot destroy virtual device: {} not found", device_id),
+                });
+            }
+        }
+    }
+
+    /// Add a virtual node that appeared in the registry as the device it was created for
+    /// Nodes with the prefix that this session didn't create are left out
+    fn register_virtual_node(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        pw_node_map: &Arc<RwLock<HashMap<u32, DeviceId>>>,
+        event_tx: &Sender<AudioEvent>,
+        global_id: u32,
+        pw_name: &str,
+    ) {
+        if pw_node_map.read().unwrap().contains_key(&global_id) {
+            return;
+        }
+        let Some((device_id, name, media_class)) = VIRTUAL_NODES.with(|nodes| {
+            nodes
+                .borrow()
+                .values()
+                .find(|(virtual_device, _)| virtual_device.node_name() == pw_name)
+                .map(|(virtual_device, _)| {
+                    (virtual_device.id, virtual_device.name.clone(), virtual_device.media_class())
+                })
+        }) else {
+            return;
+        };
+
+        {
+            let mut graph = routing_graph.write().unwrap();
+            let mut device_info = DeviceInfo::new(device_id, name.clone(), DeviceType::Virtual);
+            device_info.media_class = Some(media_class.to_string());
+            device_info.node_name = Some(pw_name.to_string());
+            graph.add_device(device_info);
+        }
+        pw_node_map.write().unwrap().insert(global_id, device_id);
+
+        let _ = event_tx.send(AudioEvent::DeviceAdded {
+            device_id,
+            name,
+            device_type: DeviceType::Virtual,
+        });
+    }
+
     /// Handle start visualization command - create an audio capture stream
     fn handle_start_visualization_command(
-        core: &pipewire::core::CoreRc,
         routing_graph: &Arc<RwLock<RoutingGraph>>,
         _pw_node_map: &Arc<RwLock<HashMap<u32, DeviceId>>>,
         event_tx: &Sender<AudioEvent>,
@@ -868,7 +1403,7 @@ impl PipeWireClient {
         crate::debug_log!("[DEBUG] Start visualization: device_id={:?}, port_id={:?}", device_id, port_id);
 
         // Get device info and port info from routing graph
-        let (node_name, port_direction) = {
+        let (node_name, port_direction, stream_outputs) = {
             let graph = routing_graph.read().unwrap();
             let device = match graph.get_device(device_id) {
                 Some(dev) => dev,
@@ -892,11 +1427,16 @@ impl PipeWireClient {
                 }
             };
 
-            (device.name.clone(), port.direction)
+            // Application streams (browser, game) have no monitor ports:
+            // their own output ports are captured instead
+            let stream_outputs = device.is_stream().then(|| {
+                capture_pair(&device.ports)
+                    .map(|(left, right)| (left.pipewire_port_name.clone(), right.pipewire_port_name.clone()))
+            });
+
+            (device.name.clone(), port.direction, stream_outputs)
         };
 
-        // For output ports, we need to capture from the monitor source
-        use super::types::PortDirection;
         crate::debug_log!("[DEBUG] Device name: '{}', port direction: {:?}", node_name, port_direction);
 
         // With JACK, we just pass the node name - the stream will handle JACK port name mapping
@@ -906,15 +1446,32 @@ impl PipeWireClient {
         // Create the audio capture stream
         crate::debug_log!("[CAPTURE] Device '{}' -> Port direction {:?} -> Capture target: '{}'",
                          node_name, port_direction, target_name);
-        match AudioCaptureStream::new(
-            &(), // JACK doesn't need core reference
-            device_id,
-            port_id,
-            Some(target_name),
-            event_tx.clone(),
-        ) {
-            Ok(stream) => {
+        let stream = match stream_outputs {
+            Some(Some((left, right))) => {
+                AudioCaptureStream::stream_outputs(device_id, port_id, &left, &right, event_tx.clone())
+            }
+            Some(None) => Err(anyhow::anyhow!("stream '{}' has no output ports", node_name)),
+            None => MONITOR_PORT_PATTERNS.with(|patterns| {
+                AudioCaptureStream::new(
+                    &(), // JACK doesn't need core reference
+                    device_id,
+                    port_id,
+                    Some(target_name),
+                    &patterns.borrow(),
+                    event_tx.clone(),
+                )
+            }),
+        };
+        match stream {
+            Ok(mut stream) => {
                 crate::debug_log!("[DEBUG] AudioCaptureStream created successfully for device {:?}", device_id);
+                stream.set_process_interval(PROCESS_INTERVAL.with(Cell::get));
+                stream.set_stereo_capture(STEREO_CAPTURE.with(Cell::get));
+                stream.set_waveform(WAVEFORM.with(Cell::get));
+                stream.set_level_meters(LEVEL_METERS.with(Cell::get));
+                stream.set_stereo_spectrum(STEREO_SPECTRUM.with(Cell::get));
+                let (threshold_db, after) = IDLE_DETECTION.with(Cell::get);
+                stream.set_idle_detection(threshold_db, after);
                 // Store the stream in the SAME thread-local storage that the timer uses (line 184)
                 CAPTURE_STREAMS.with(|streams| {
                     let mut streams_mut = streams.borrow_mut();
@@ -939,9 +1496,27 @@ impl PipeWireClient {
     fn handle_stop_visualization_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId) {
         // Use the CAPTURE_STREAMS from the outer scope (line 184)
         CAPTURE_STREAMS.with(|streams| {
-            if let Some(_stream) = streams.borrow_mut().remove(&device_id) {
+            if let Some(mut stream) = streams.borrow_mut().remove(&device_id) {
+                // Finalize any recording so the UI learns where it was written
+                match stream.stop_recording() {
+                    Ok(Some((path, duration))) => {
+                        let _ = event_tx.send(AudioEvent::RecordingStopped {
+                            device_id,
+                            path,
+                            duration,
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        let _ = event_tx.send(AudioEvent::Error {
+                            message: format!("Failed to finalize recording: {}", e),
+                        });
+                    }
+                }
                 // Stream dropped, PipeWire will clean up
                 let _ = event_tx.send(AudioEvent::VisualizationStopped { device_id });
+                // Without the monitor there's nothing to compare the EQ output against
+                Self::drop_eq_tap(event_tx, device_id);
                 //println!("Visualization stream stopped for device {:?}", device_id);
             } else {
                 let _ = event_tx.send(AudioEvent::Error {
@@ -956,9 +1531,10 @@ impl PipeWireClient {
         routing_graph: &Arc<RwLock<RoutingGraph>>,
         event_tx: &Sender<AudioEvent>,
         device_id: DeviceId,
-        settings: EqSettings,
+        mut settings: EqSettings,
     ) {
         crate::debug_log!("[EQ] Enable EQ for device {:?}", device_id);
+        settings.clamp();
 
         // Get device info and its input ports from routing graph
         let (device_name, device_input_ports) = {
@@ -996,11 +1572,11 @@ impl PipeWireClient {
             for input_port in &device_input_ports {
                 // Find connections where this port is the destination
                 for conn in graph.list_connections() {
-                    if conn.destination == input_port.id {
-                        if let Some(source_port_name) = graph.find_port_name(conn.source) {
-                            connections.push((source_port_name.to_string(), input_port.pipewire_port_name.clone()));
-                            crate::debug_log!("[EQ] Found connection: {} → {}", source_port_name, input_port.pipewire_port_name);
-                        }
+                    if conn.destination == input_port.id
+                        && let Some(source_port_name) = graph.find_port_name(conn.source)
+                    {
+                        connections.push((source_port_name.to_string(), input_port.pipewire_port_name.clone()));
+                        crate::debug_log!("[EQ] Found connection: {} → {}", source_port_name, input_port.pipewire_port_name);
                     }
                 }
             }
@@ -1050,19 +1626,7 @@ impl PipeWireClient {
                     streams.borrow_mut().insert(device_id, stream);
                 });
 
-                // Update routing graph with EQ settings
-                {
-                    let mut graph = routing_graph.write().unwrap();
-                    if let Some(device) = graph.get_device_mut(device_id) {
-                        device.eq_settings = Some(settings.clone());
-                    }
-                }
-
-                // Send success event
-                let _ = event_tx.send(AudioEvent::EqEnabled {
-                    device_id,
-                    settings,
-                });
+                Self::eq_enabled(routing_graph, event_tx, device_id, settings);
             }
             Err(e) => {
                 crate::debug_log!("[EQ] ✗ Failed to create processing stream: {}", e);
@@ -1073,13 +1637,102 @@ impl PipeWireClient {
         }
     }
 
+    /// Record a started EQ stream in the routing graph and tell the UI
+    fn eq_enabled(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        device_id: DeviceId,
+        settings: EqSettings,
+    ) {
+        Self::record_eq_settings(routing_graph, device_id, Some(settings.clone()));
+        let _ = event_tx.send(AudioEvent::EqEnabled { device_id, settings });
+    }
+
+    /// Keep the graph's copy of a device's EQ settings in step with its processing
+    /// stream (None = EQ off), so `list_devices` shows the EQ actually running
+    fn record_eq_settings(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        device_id: DeviceId,
+        settings: Option<EqSettings>,
+    ) {
+        if let Some(device) = routing_graph.write().unwrap().get_device_mut(device_id) {
+            device.eq_settings = settings;
+        }
+    }
+
+    /// Handle start EQ tap command - capture the EQ node's output next to the device's monitor
+    fn handle_start_eq_tap_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId) {
+        let has_eq = PROCESSING_STREAMS.with(|streams| streams.borrow().contains_key(&device_id));
+        let port_id = CAPTURE_STREAMS.with(|streams| streams.borrow().get(&device_id).map(|s| s.port_id()));
+        let port_id = match (has_eq, port_id) {
+            (true, Some(port_id)) => port_id,
+            (false, _) => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No EQ stream found for device {:?}", device_id),
+                });
+                return;
+            }
+            (true, None) => {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No visualization stream found for device {:?}", device_id),
+                });
+                return;
+            }
+        };
+        if EQ_TAP_STREAMS.with(|streams| streams.borrow().contains_key(&device_id)) {
+            return;
+        }
+
+        match AudioCaptureStream::eq_tap(device_id, port_id, event_tx.clone()) {
+            Ok(mut stream) => {
+                stream.set_process_interval(PROCESS_INTERVAL.with(Cell::get));
+                let (threshold_db, after) = IDLE_DETECTION.with(Cell::get);
+                stream.set_idle_detection(threshold_db, after);
+                EQ_TAP_STREAMS.with(|streams| {
+                    streams.borrow_mut().insert(device_id, stream);
+                });
+                let _ = event_tx.send(AudioEvent::EqTapStarted { device_id });
+            }
+            Err(e) => {
+                crate::debug_log!("[EQ] ✗ Failed to tap EQ output: {}", e);
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("Failed to capture EQ output for {:?}: {}", device_id, e),
+                });
+            }
+        }
+    }
+
+    /// Handle stop EQ tap command
+    fn handle_stop_eq_tap_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId) {
+        if !Self::drop_eq_tap(event_tx, device_id) {
+            let _ = event_tx.send(AudioEvent::Error {
+                message: format!("No EQ output capture found for device {:?}", device_id),
+            });
+        }
+    }
+
+    /// Stop capturing a device's EQ output, if it is being captured
+    fn drop_eq_tap(event_tx: &Sender<AudioEvent>, device_id: DeviceId) -> bool {
+        let removed = EQ_TAP_STREAMS.with(|streams| streams.borrow_mut().remove(&device_id));
+        if removed.is_some() {
+            let _ = event_tx.send(AudioEvent::EqTapStopped { device_id });
+        }
+        removed.is_some()
+    }
+
     /// Handle disable EQ command - destroy an audio processing stream
-    fn handle_disable_eq_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId) {
+    fn handle_disable_eq_command(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        device_id: DeviceId,
+    ) {
         crate::debug_log!("[EQ] Disable EQ for device {:?}", device_id);
 
         PROCESSING_STREAMS.with(|streams| {
             if let Some(_stream) = streams.borrow_mut().remove(&device_id) {
                 // Stream dropped, JACK will clean up
+                Self::drop_eq_tap(event_tx, device_id);
+                Self::record_eq_settings(routing_graph, device_id, None);
                 let _ = event_tx.send(AudioEvent::EqDisabled { device_id });
             } else {
                 let _ = event_tx.send(AudioEvent::Error {
@@ -1100,13 +1753,6 @@ impl PipeWireClient {
     ) {
         crate::debug_log!("[EQ] Set band {} for device {:?}: gain={}dB, Q={}", band_index, device_id, gain_db, q_value);
 
-        if band_index >= 10 {
-            let _ = event_tx.send(AudioEvent::Error {
-                message: format!("Invalid band index: {}", band_index),
-            });
-            return;
-        }
-
         // Get current settings from routing graph
         let mut current_settings = {
             let graph = routing_graph.read().unwrap();
@@ -1121,6 +1767,13 @@ impl PipeWireClient {
             }
         };
 
+        if band_index >= current_settings.band_count() {
+            let _ = event_tx.send(AudioEvent::Error {
+                message: format!("Invalid band index: {}", band_index),
+            });
+            return;
+        }
+
         // Update the specific band
         current_settings.set_band(band_index, gain_db, q_value);
 
@@ -1129,13 +1782,7 @@ impl PipeWireClient {
             if let Some(stream) = streams.borrow().get(&device_id) {
                 stream.update_eq(current_settings.clone());
 
-                // Update routing graph
-                {
-                    let mut graph = routing_graph.write().unwrap();
-                    if let Some(device) = graph.get_device_mut(device_id) {
-                        device.eq_settings = Some(current_settings.clone());
-                    }
-                }
+                Self::record_eq_settings(routing_graph, device_id, Some(current_settings.clone()));
 
                 // Send update event
                 let _ = event_tx.send(AudioEvent::EqUpdated {
@@ -1155,21 +1802,16 @@ impl PipeWireClient {
         routing_graph: &Arc<RwLock<RoutingGraph>>,
         event_tx: &Sender<AudioEvent>,
         device_id: DeviceId,
-        settings: EqSettings,
+        mut settings: EqSettings,
     ) {
         crate::debug_log!("[EQ] Set all EQ settings for device {:?}", device_id);
+        settings.clamp();
 
         PROCESSING_STREAMS.with(|streams| {
             if let Some(stream) = streams.borrow().get(&device_id) {
                 stream.update_eq(settings.clone());
 
-                // Update routing graph
-                {
-                    let mut graph = routing_graph.write().unwrap();
-                    if let Some(device) = graph.get_device_mut(device_id) {
-                        device.eq_settings = Some(settings.clone());
-                    }
-                }
+                Self::record_eq_settings(routing_graph, device_id, Some(settings.clone()));
 
                 // Send update event
                 let _ = event_tx.send(AudioEvent::EqUpdated {
@@ -1214,13 +1856,7 @@ impl PipeWireClient {
             if let Some(stream) = streams.borrow().get(&device_id) {
                 stream.update_eq(current_settings.clone());
 
-                // Update routing graph
-                {
-                    let mut graph = routing_graph.write().unwrap();
-                    if let Some(device) = graph.get_device_mut(device_id) {
-                        device.eq_settings = Some(current_settings.clone());
-                    }
-                }
+                Self::record_eq_settings(routing_graph, device_id, Some(current_settings.clone()));
 
                 // Send update event
                 let _ = event_tx.send(AudioEvent::EqUpdated {
@@ -1264,13 +1900,7 @@ impl PipeWireClient {
             if let Some(stream) = streams.borrow().get(&device_id) {
                 stream.update_eq(current_settings.clone());
 
-                // Update routing graph
-                {
-                    let mut graph = routing_graph.write().unwrap();
-                    if let Some(device) = graph.get_device_mut(device_id) {
-                        device.eq_settings = Some(current_settings.clone());
-                    }
-                }
+                Self::record_eq_settings(routing_graph, device_id, Some(current_settings.clone()));
 
                 // Send update event
                 let _ = event_tx.send(AudioEvent::EqUpdated {
@@ -1286,13 +1916,25 @@ impl PipeWireClient {
     }
 
     /// Handle set volume command - update volume settings for a device
+    /// The volume processor lives in the device's processing stream; a device
+    /// without one gets a stream with a flat EQ, so volume works without EQing
     fn handle_set_volume_command(
         routing_graph: &Arc<RwLock<RoutingGraph>>,
         event_tx: &Sender<AudioEvent>,
         device_id: DeviceId,
-        settings: VolumeSettings,
+        mut settings: VolumeSettings,
     ) {
         crate::debug_log!("[VOLUME] Set volume for device {:?}: {:+.1} dB", device_id, settings.gain_db);
+        settings.clamp();
+
+        if !PROCESSING_STREAMS.with(|streams| streams.borrow().contains_key(&device_id)) {
+            crate::debug_log!("[VOLUME] No processing stream for device {:?}, starting one", device_id);
+            Self::handle_enable_eq_command(routing_graph, event_tx, device_id, EqSettings::flat());
+            // Starting it failed and has been reported
+            if !PROCESSING_STREAMS.with(|streams| streams.borrow().contains_key(&device_id)) {
+                return;
+            }
+        }
 
         PROCESSING_STREAMS.with(|streams| {
             if let Some(stream) = streams.borrow().get(&device_id) {
@@ -1319,91 +1961,320 @@ impl PipeWireClient {
         });
     }
 
-    /// Get a reference to the routing graph
-    pub fn routing_graph(&self) -> &Arc<RwLock<RoutingGraph>> {
-        &self.routing_graph
-    }
+    fn handle_set_crossfeed_command(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        device_id: DeviceId,
+        settings: CrossfeedSettings,
+    ) {
+        crate::debug_log!(
+            "[CROSSFEED] Set crossfeed for device {:?}: enabled={}, amount={:.2}, cutoff={}Hz",
+            device_id,
+            settings.enabled,
+            settings.amount,
+            settings.cutoff_hz
+        );
 
-    /// Create a new virtual device
-    pub fn create_virtual_device(
-        &mut self,
-        name: String,
-        num_inputs: usize,
-        num_outputs: usize,
-    ) -> Result<DeviceId> {
-        if !self.is_activated {
-            anyhow::bail!("PipeWire client not activated");
-        }
+        PROCESSING_STREAMS.with(|streams| {
+            if let Some(stream) = streams.borrow().get(&device_id) {
+                stream.update_crossfeed(settings.clone());
 
-        // Generate device ID
-        let device_id = {
-            let mut graph = self.routing_graph.write().unwrap();
-            graph.generate_device_id()
-        };
+                // Update routing graph
+                {
+                    let mut graph = routing_graph.write().unwrap();
+                    if let Some(device) = graph.get_device_mut(device_id) {
+                        device.crossfeed_settings = Some(settings.clone());
+                    }
+                }
 
-        // Create the virtual device
-        let virtual_device = VirtualDevice::new(device_id, name.clone(), num_inputs, num_outputs)?;
+                // Send update event
+                let _ = event_tx.send(AudioEvent::CrossfeedUpdated {
+                    device_id,
+                    settings,
+                });
+            } else {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No crossfeed stream found for device {:?}", device_id),
+                });
+            }
+        });
+    }
 
-        // Add to routing graph
-        {
-            let mut graph = self.routing_graph.write().unwrap();
-            let mut device_info = DeviceInfo::new(device_id, name.clone(), DeviceType::Virtual);
+    fn handle_set_saturation_command(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        device_id: DeviceId,
+        drive: f32,
+    ) {
+        let drive = clamp_saturation_drive(drive);
+        crate::debug_log!("[SATURATION] Set drive for device {:?}: {:.2}", device_id, drive);
 
-            // Add input ports to device info
-            for i in 0..num_inputs {
-                let port_id = graph.generate_port_id();
-                let port_name = format!("input_{}", i);
-                let pw_port_name = format!("wavewire_virtual_{}:{}", name, port_name);
-                device_info.ports.push(PortInfo::new(
-                    port_id,
-                    port_name,
-                    PortDirection::Input,
-                    pw_port_name,
-                ));
+        PROCESSING_STREAMS.with(|streams| {
+            if let Some(stream) = streams.borrow().get(&device_id) {
+                stream.update_saturation(drive);
+
+                // Update routing graph
+                {
+                    let mut graph = routing_graph.write().unwrap();
+                    if let Some(device) = graph.get_device_mut(device_id) {
+                        device.saturation_drive = Some(drive);
+                    }
+                }
+
+                // Send update event
+                let _ = event_tx.send(AudioEvent::SaturationUpdated { device_id, drive });
+            } else {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No saturation stream found for device {:?}", device_id),
+                });
             }
+        });
+    }
 
-            // Add output ports to device info
-            for i in 0..num_outputs {
-                let port_id = graph.generate_port_id();
-                let port_name = format!("output_{}", i);
-                let pw_port_name = format!("wavewire_virtual_{}:{}", name, port_name);
-                device_info.ports.push(PortInfo::new(
-                    port_id,
-                    port_name,
-                    PortDirection::Output,
-                    pw_port_name,
-                ));
+    fn handle_set_stereo_width_command(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        device_id: DeviceId,
+        width: f32,
+    ) {
+        let width = clamp_stereo_width(width);
+        crate::debug_log!("[WIDTH] Set stereo width for device {:?}: {:.2}", device_id, width);
+
+        PROCESSING_STREAMS.with(|streams| {
+            if let Some(stream) = streams.borrow().get(&device_id) {
+                stream.update_width(width);
+
+                // Update routing graph
+                {
+                    let mut graph = routing_graph.write().unwrap();
+                    if let Some(device) = graph.get_device_mut(device_id) {
+                        device.stereo_width = Some(width);
+                    }
+                }
+
+                // Send update event
+                let _ = event_tx.send(AudioEvent::StereoWidthUpdated { device_id, width });
+            } else {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No stereo width stream found for device {:?}", device_id),
+                });
             }
+        });
+    }
 
-            graph.add_device(device_info);
+    /// Handle set refresh rate command - retime all capture streams
+    /// New streams pick up the rate when they are created
+    /// Handle set FFT size command - resize the device's capture streams in place
+    fn handle_set_fft_size_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId, fft_size: usize) {
+        if !is_valid_fft_size(fft_size) {
+            let _ = event_tx.send(AudioEvent::Error {
+                message: format!(
+                    "Invalid FFT size {} (must be a power of two from {} to {})",
+                    fft_size,
+                    FFT_SIZE_RANGE.start(),
+                    FFT_SIZE_RANGE.end()
+                ),
+            });
+            return;
         }
-
-        // Store the virtual device
-        {
-            let mut virtual_devices = self.virtual_devices.write().unwrap();
-            virtual_devices.insert(device_id, virtual_device);
+        crate::debug_log!("[SPECTRUM] FFT size for device {:?} set to {}", device_id, fft_size);
+
+        let mut found = false;
+        for streams in [&CAPTURE_STREAMS, &EQ_TAP_STREAMS] {
+            streams.with(|streams| {
+                if let Some(stream) = streams.borrow_mut().get_mut(&device_id) {
+                    stream.set_fft_size(fft_size);
+                    found = true;
+                }
+            });
         }
 
-        Ok(device_id)
+        if found {
+            let _ = event_tx.send(AudioEvent::FftSizeChanged { device_id, fft_size });
+        } else {
+            let _ = event_tx.send(AudioEvent::Error {
+                message: format!("No visualization stream found for device {:?}", device_id),
+            });
+        }
     }
 
-    /// Destroy a virtual device
-    pub fn destroy_virtual_device(&mut self, device_id: DeviceId) -> Result<()> {
-        // Remove from virtual devices map
-        {
-            let mut virtual_devices = self.virtual_devices.write().unwrap();
-            virtual_devices
-                .remove(&device_id)
-                .context("Virtual device not found")?;
+    fn handle_set_refresh_rate_command(hz: u32) {
+        let interval = Duration::from_secs_f64(1.0 / hz.max(1) as f64);
+        crate::debug_log!("[SPECTRUM] Refresh rate set to {} Hz ({:?})", hz, interval);
+
+        PROCESS_INTERVAL.with(|current| current.set(interval));
+        for streams in [&CAPTURE_STREAMS, &EQ_TAP_STREAMS] {
+            streams.with(|streams| {
+                for stream in streams.borrow_mut().values_mut() {
+                    stream.set_process_interval(interval);
+                }
+            });
         }
+    }
 
-        // Remove from routing graph
-        {
-            let mut graph = self.routing_graph.write().unwrap();
-            graph.remove_device(device_id);
+    /// Handle set stereo capture command - toggle separate L/R buffers on all capture streams
+    /// New streams pick up the setting when they are created
+    fn handle_set_stereo_capture_command(enabled: bool) {
+        crate::debug_log!("[SPECTRUM] Stereo capture {}", if enabled { "enabled" } else { "disabled" });
+
+        STEREO_CAPTURE.with(|current| current.set(enabled));
+        CAPTURE_STREAMS.with(|streams| {
+            for stream in streams.borrow().values() {
+                stream.set_stereo_capture(enabled);
+            }
+        });
+    }
+
+    /// Handle set waveform command - start or stop sending samples from all capture streams
+    /// New streams pick up the setting when they are created
+    fn handle_set_waveform_command(enabled: bool) {
+        crate::debug_log!("[SPECTRUM] Waveform {}", if enabled { "enabled" } else { "disabled" });
+
+        WAVEFORM.with(|current| current.set(enabled));
+        CAPTURE_STREAMS.with(|streams| {
+            for stream in streams.borrow_mut().values_mut() {
+                stream.set_waveform(enabled);
+            }
+        });
+    }
+
+    /// Handle set level meters command - start or stop sending levels from all capture streams
+    /// New streams pick up the setting when they are created
+    fn handle_set_level_meters_command(enabled: bool) {
+        crate::debug_log!("[SPECTRUM] Level meters {}", if enabled { "enabled" } else { "disabled" });
+
+        LEVEL_METERS.with(|current| current.set(enabled));
+        CAPTURE_STREAMS.with(|streams| {
+            for stream in streams.borrow_mut().values_mut() {
+                stream.set_level_meters(enabled);
+            }
+        });
+    }
+
+    /// Handle set stereo spectrum command - toggle per-channel FFTs on all capture streams
+    /// New streams pick up the setting when they are created
+    fn handle_set_stereo_spectrum_command(enabled: bool) {
+        crate::debug_log!("[SPECTRUM] Per-channel spectrum {}", if enabled { "enabled" } else { "disabled" });
+
+        STEREO_SPECTRUM.with(|current| current.set(enabled));
+        CAPTURE_STREAMS.with(|streams| {
+            for stream in streams.borrow_mut().values_mut() {
+                stream.set_stereo_spectrum(enabled);
+            }
+        });
+    }
+
+    /// Handle set idle detection command - reconfigure silence detection on all capture streams
+    /// New streams pick up the setting when they are created
+    fn handle_set_idle_detection_command(threshold_db: f32, after: Option<Duration>) {
+        crate::debug_log!("[SPECTRUM] Idle below {:.1} dBFS after {:?}", threshold_db, after);
+
+        IDLE_DETECTION.with(|current| current.set((threshold_db, after)));
+        for streams in [&CAPTURE_STREAMS, &EQ_TAP_STREAMS] {
+            streams.with(|streams| {
+                for stream in streams.borrow_mut().values_mut() {
+                    stream.set_idle_detection(threshold_db, after);
+                }
+            });
         }
+    }
 
-        Ok(())
+    /// Handle set monitor port patterns command - used by visualizations started from now on
+    fn handle_set_monitor_port_patterns_command(patterns: Vec<MonitorPortPattern>) {
+        crate::debug_log!("[JACK] {} monitor port pattern(s) configured", patterns.len());
+        MONITOR_PORT_PATTERNS.with(|current| *current.borrow_mut() = patterns);
+    }
+
+    /// Handle start recording command - attach a WAV recorder to a capture stream
+    fn handle_start_recording_command(
+        routing_graph: &Arc<RwLock<RoutingGraph>>,
+        event_tx: &Sender<AudioEvent>,
+        device_id: DeviceId,
+        path: Option<PathBuf>,
+    ) {
+        let device_name = {
+            let graph = routing_graph.read().unwrap();
+            match graph.get_device(device_id) {
+                Some(device) => device.name.clone(),
+                None => {
+                    let _ = event_tx.send(AudioEvent::Error {
+                        message: format!("Device {:?} not found", device_id),
+                    });
+                    return;
+                }
+            }
+        };
+
+        CAPTURE_STREAMS.with(|streams| {
+            if let Some(stream) = streams.borrow_mut().get_mut(&device_id) {
+                // A file that can't be created leaves the stream untapped
+                match stream.start_recording(&device_name, path) {
+                    Ok(path) => {
+                        let _ = event_tx.send(AudioEvent::RecordingStarted { device_id, path });
+                    }
+                    Err(e) => {
+                        let _ = event_tx.send(AudioEvent::Error {
+                            message: format!("Failed to start recording: {}", e),
+                        });
+                    }
+                }
+            } else {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No visualization stream found for device {:?}", device_id),
+                });
+            }
+        });
+    }
+
+    /// Handle stop recording command - finalize the WAV file for a capture stream
+    fn handle_stop_recording_command(event_tx: &Sender<AudioEvent>, device_id: DeviceId) {
+        CAPTURE_STREAMS.with(|streams| {
+            if let Some(stream) = streams.borrow_mut().get_mut(&device_id) {
+                match stream.stop_recording() {
+                    Ok(Some((path, duration))) => {
+                        let _ = event_tx.send(AudioEvent::RecordingStopped {
+                            device_id,
+                            path,
+                            duration,
+                        });
+                    }
+                    Ok(None) => {
+                        let _ = event_tx.send(AudioEvent::Error {
+                            message: format!("Device {:?} is not recording", device_id),
+                        });
+                    }
+                    Err(e) => {
+                        let _ = event_tx.send(AudioEvent::Error {
+                            message: format!("Failed to finalize recording: {}", e),
+                        });
+                    }
+                }
+            } else {
+                let _ = event_tx.send(AudioEvent::Error {
+                    message: format!("No visualization stream found for device {:?}", device_id),
+                });
+            }
+        });
+    }
+
+    /// Get a reference to the routing graph
+    pub fn routing_graph(&self) -> &Arc<RwLock<RoutingGraph>> {
+        &self.routing_graph
+    }
+
+    /// Get a reference to the graph clock
+    pub fn graph_clock(&self) -> &Arc<RwLock<GraphClock>> {
+        &self.metadata.clock
+    }
+
+    /// Get a reference to the default sink/source names
+    pub fn default_nodes(&self) -> &Arc<RwLock<DefaultNodes>> {
+        &self.metadata.defaults
+    }
+
+    /// Get a reference to the PipeWire node ID → device map
+    pub fn pw_node_map(&self) -> &Arc<RwLock<HashMap<u32, DeviceId>>> {
+        &self.pw_node_map
     }
 }
 
@@ -1413,3 +2284,96 @@ impl Drop for PipeWireClient {
         let _ = self.deactivate();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activate_reports_missing_channels() {
+        let (event_tx, _event_rx) = unbounded();
+        let (_command_tx, command_rx) = unbounded();
+        let mut client = PipeWireClient::new(event_tx, command_rx, CommandStats::default()).unwrap();
+
+        // The receiver is gone once an earlier activation handed it to its event loop
+        client.command_rx = None;
+        assert!(matches!(client.activate(), Err(WavewireError::AlreadyStarted)));
+        assert!(!client.is_activated);
+        assert!(client.quit_tx.is_none());
+
+        client.event_tx = None;
+        assert!(matches!(client.activate(), Err(WavewireError::NotInitialized)));
+    }
+
+    #[test]
+    fn test_quit_stops_waiting_for_reconnect() {
+        let (event_tx, _event_rx) = unbounded();
+        let (_command_tx, commands) = unbounded();
+        let (quit_tx, quit) = unbounded();
+        let receivers = LoopReceivers { commands, quit };
+        let stats = CommandStats::default();
+
+        quit_tx.send(()).unwrap();
+        let start = std::time::Instant::now();
+        assert!(!PipeWireClient::wait_for_reconnect(&receivers, &event_tx, &stats));
+        assert!(start.elapsed() < RECONNECT_DELAY);
+
+        // A dropped sender means the client is gone, which is a quit as well
+        drop(quit_tx);
+        assert!(!PipeWireClient::wait_for_reconnect(&receivers, &event_tx, &stats));
+    }
+
+    #[test]
+    fn test_eq_state_is_kept_in_the_graph() {
+        let (event_tx, event_rx) = unbounded();
+        let routing_graph = Arc::new(RwLock::new(RoutingGraph::new()));
+        let device_id = routing_graph.write().unwrap().generate_device_id();
+        routing_graph.write().unwrap().add_device(DeviceInfo::new(
+            device_id,
+            String::from("Speakers"),
+            DeviceType::Physical,
+        ));
+        let eq_settings = |graph: &Arc<RwLock<RoutingGraph>>| {
+            graph.read().unwrap().list_devices()[0].eq_settings.clone()
+        };
+
+        let mut settings = EqSettings::default();
+        settings.set_band(3, 4.0, 1.0);
+        PipeWireClient::eq_enabled(&routing_graph, &event_tx, device_id, settings.clone());
+        assert_eq!(eq_settings(&routing_graph), Some(settings.clone()));
+        assert!(matches!(
+            event_rx.try_recv(),
+            Ok(AudioEvent::EqEnabled { settings: sent, .. }) if sent == settings
+        ));
+
+        PipeWireClient::record_eq_settings(&routing_graph, device_id, None);
+        assert_eq!(eq_settings(&routing_graph), None);
+    }
+
+    #[test]
+    fn test_volume_starts_a_processing_stream() {
+        let (event_tx, event_rx) = unbounded();
+        let routing_graph = Arc::new(RwLock::new(RoutingGraph::new()));
+        let device_id = routing_graph.write().unwrap().generate_device_id();
+        routing_graph.write().unwrap().add_device(DeviceInfo::new(
+            device_id,
+            String::from("Speakers"),
+            DeviceType::Physical,
+        ));
+
+        // Without a processing stream one is started for the volume; a device
+        // it can't be started for gets that error once, and no volume update
+        PipeWireClient::handle_set_volume_command(
+            &routing_graph,
+            &event_tx,
+            device_id,
+            VolumeSettings::from_db(-6.0),
+        );
+        assert!(matches!(
+            event_rx.try_recv(),
+            Ok(AudioEvent::Error { message }) if message == "Device 'Speakers' has no input ports"
+        ));
+        assert!(event_rx.try_recv().is_err());
+        assert_eq!(routing_graph.read().unwrap().list_devices()[0].volume_settings, None);
+    }
+}
@@ -0,0 +1,78 @@
+/// Name of the PipeWire metadata object holding the graph clock settings
+pub const SETTINGS_METADATA: &str = "settings";
+
+/// PipeWire graph clock, as published in the "settings" metadata
+/// Zero means the value hasn't been seen (or, for the forced values, isn't forced)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GraphClock {
+    /// Default sample rate (`clock.rate`)
+    pub rate: u32,
+    /// Default quantum in samples (`clock.quantum`)
+    pub quantum: u32,
+    /// Smallest quantum nodes may ask for (`clock.min-quantum`)
+    pub min_quantum: u32,
+    /// Largest quantum nodes may ask for (`clock.max-quantum`)
+    pub max_quantum: u32,
+    /// Rate forced on the graph (`clock.force-rate`)
+    pub force_rate: u32,
+    /// Quantum forced on the graph (`clock.force-quantum`)
+    pub force_quantum: u32,
+}
+
+impl GraphClock {
+    /// Apply one "settings" metadata property
+    /// Returns false for keys that aren't clock settings; a removed key reads as 0
+    pub fn apply(&mut self, key: &str, value: Option<&str>) -> bool {
+        let field = match key {
+            "clock.rate" => &mut self.rate,
+            "clock.quantum" => &mut self.quantum,
+            "clock.min-quantum" => &mut self.min_quantum,
+            "clock.max-quantum" => &mut self.max_quantum,
+            "clock.force-rate" => &mut self.force_rate,
+            "clock.force-quantum" => &mut self.force_quantum,
+            _ => return false,
+        };
+        *field = value.and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+        true
+    }
+
+    /// Sample rate the graph runs at (the forced rate wins)
+    pub fn effective_rate(&self) -> u32 {
+        if self.force_rate > 0 { self.force_rate } else { self.rate }
+    }
+
+    /// Quantum the graph runs at (the forced quantum wins)
+    pub fn effective_quantum(&self) -> u32 {
+        if self.force_quantum > 0 { self.force_quantum } else { self.quantum }
+    }
+
+    /// Duration of one quantum in milliseconds, once both quantum and rate are known
+    pub fn quantum_ms(&self) -> Option<f32> {
+        let (quantum, rate) = (self.effective_quantum(), self.effective_rate());
+        (quantum > 0 && rate > 0).then(|| quantum as f32 * 1000.0 / rate as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_settings_metadata() {
+        let mut clock = GraphClock::default();
+        assert_eq!(clock.quantum_ms(), None);
+
+        assert!(clock.apply("clock.rate", Some("48000")));
+        assert!(clock.apply("clock.quantum", Some("1024")));
+        assert!(!clock.apply("log.level", Some("2")));
+        assert_eq!(clock.quantum_ms(), Some(1024.0 * 1000.0 / 48000.0));
+
+        // A forced quantum overrides the default until it is cleared
+        clock.apply("clock.force-quantum", Some("256"));
+        assert_eq!(clock.effective_quantum(), 256);
+        clock.apply("clock.force-quantum", Some("0"));
+        assert_eq!(clock.effective_quantum(), 1024);
+        clock.apply("clock.quantum", None);
+        assert_eq!(clock.quantum_ms(), None);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// What has happened to the commands of one variant so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandCounts {
+    /// Passed to `send_command`
+    pub sent: u64,
+    /// Taken off the queue by the audio thread
+    pub handled: u64,
+    /// Never reached the audio thread: rejected while busy, coalesced away,
+    /// cancelled, or discarded while disconnected
+    pub dropped: u64,
+}
+
+impl CommandCounts {
+    /// Commands still on their way to the audio thread
+    pub fn in_flight(&self) -> u64 {
+        self.sent.saturating_sub(self.handled + self.dropped)
+    }
+}
+
+/// Per-variant command tally shared by the UI and audio threads
+/// Clones share the same counters
+#[derive(Debug, Clone, Default)]
+pub struct CommandStats {
+    counts: Arc<Mutex<BTreeMap<&'static str, CommandCounts>>>,
+}
+
+impl CommandStats {
+    fn record(&self, name: &'static str, count: impl FnOnce(&mut CommandCounts)) {
+        if let Ok(mut counts) = self.counts.lock() {
+            count(counts.entry(name).or_default());
+        }
+    }
+
+    /// A command was passed to `send_command`
+    pub fn record_sent(&self, name: &'static str) {
+        self.record(name, |counts| counts.sent += 1);
+    }
+
+    /// The audio thread received a command
+    pub fn record_handled(&self, name: &'static str) {
+        self.record(name, |counts| counts.handled += 1);
+    }
+
+    /// A command was given up on before reaching the audio thread
+    pub fn record_dropped(&self, name: &'static str) {
+        self.record(name, |counts| counts.dropped += 1);
+    }
+
+    /// Counts per variant, sorted by name
+    pub fn snapshot(&self) -> Vec<(&'static str, CommandCounts)> {
+        match self.counts.lock() {
+            Ok(counts) => counts.iter().map(|(&name, &counts)| (name, counts)).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_are_shared_between_clones() {
+        let stats = CommandStats::default();
+        let audio_side = stats.clone();
+        stats.record_sent("SetVolume");
+        stats.record_sent("SetVolume");
+        stats.record_sent("SetVolume");
+        stats.record_sent("CreateVirtualDevice");
+        audio_side.record_handled("SetVolume");
+        stats.record_dropped("SetVolume");
+        audio_side.record_handled("CreateVirtualDevice");
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot[0].0, "CreateVirtualDevice");
+        assert_eq!(snapshot[0].1.in_flight(), 0);
+        assert_eq!(snapshot[1].0, "SetVolume");
+        assert_eq!(
+            snapshot[1].1,
+            CommandCounts { sent: 3, handled: 1, dropped: 1 }
+        );
+        assert_eq!(snapshot[1].1.in_flight(), 1);
+    }
+}
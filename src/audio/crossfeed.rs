@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Interaural delay applied to the crossfed signal (seconds)
+#[cfg(any(test, feature = "jack"))]
+const CROSSFEED_DELAY_SECS: f32 = 0.0003;
+
+/// Delay line length; covers the interaural delay up to ~400 kHz
+#[cfg(any(test, feature = "jack"))]
+const MAX_DELAY_SAMPLES: usize = 128;
+
+/// Crossfeed settings for a device (serializable for config)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CrossfeedSettings {
+    pub enabled: bool,
+    pub amount: f32,    // Portion of the opposite channel blended in (0.0 to 1.0)
+    pub cutoff_hz: f32, // Low-pass cutoff of the crossfed signal (300 to 2000 Hz)
+}
+
+impl Default for CrossfeedSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            amount: 0.3,
+            cutoff_hz: 700.0,
+        }
+    }
+}
+
+impl CrossfeedSettings {
+    /// Clamp settings to valid ranges
+    pub fn clamp(&mut self) {
+        self.amount = self.amount.clamp(0.0, 1.0);
+        self.cutoff_hz = self.cutoff_hz.clamp(300.0, 2000.0);
+    }
+
+    /// Whether processing changes the signal at all
+    #[cfg(any(test, feature = "jack"))]
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.amount > 0.0
+    }
+}
+
+/// Real-time crossfeed processor (lives in JACK callback)
+/// Each output channel gets a delayed, low-passed copy of the opposite channel
+/// mixed in, approximating how speakers reach both ears
+#[cfg(any(test, feature = "jack"))]
+pub struct CrossfeedProcessor {
+    settings: CrossfeedSettings,
+    sample_rate: f32,
+    /// One-pole low-pass coefficient for the crossfed signal
+    lowpass_coeff: f32,
+    /// Low-pass filter state per channel
+    lowpass: [f32; 2],
+    /// Delay lines of the low-passed signal per channel
+    delay_lines: [[f32; MAX_DELAY_SAMPLES]; 2],
+    delay_samples: usize,
+    write_pos: usize,
+    needs_update: Arc<AtomicBool>,
+    pending_settings: Arc<Mutex<Option<CrossfeedSettings>>>,
+}
+
+#[cfg(any(test, feature = "jack"))]
+impl CrossfeedProcessor {
+    /// Create a new crossfeed processor with the given sample rate and settings
+    pub fn new(sample_rate: f32, settings: CrossfeedSettings) -> Self {
+        let mut processor = Self {
+            settings,
+            sample_rate,
+            lowpass_coeff: 0.0,
+            lowpass: [0.0; 2],
+            delay_lines: [[0.0; MAX_DELAY_SAMPLES]; 2],
+            delay_samples: 1,
+            write_pos: 0,
+            needs_update: Arc::new(AtomicBool::new(false)),
+            pending_settings: Arc::new(Mutex::new(None)),
+        };
+        processor.configure();
+        processor
+    }
+
+    /// Derive filter and delay parameters from the settings
+    fn configure(&mut self) {
+        let cutoff = self.settings.cutoff_hz.min(self.sample_rate / 2.0);
+        self.lowpass_coeff = 1.0 - (-2.0 * std::f32::consts::PI * cutoff / self.sample_rate).exp();
+        self.delay_samples = ((self.sample_rate * CROSSFEED_DELAY_SECS).round() as usize)
+            .clamp(1, MAX_DELAY_SAMPLES - 1);
+    }
+
+    /// Clear the filter and delay state
+    fn reset(&mut self) {
+        self.lowpass = [0.0; 2];
+        self.delay_lines = [[0.0; MAX_DELAY_SAMPLES]; 2];
+        self.write_pos = 0;
+    }
+
+    /// Process a stereo sample through the crossfeed
+    /// This is the main real-time processing function - must be allocation-free
+    #[inline]
+    pub fn process_sample(&mut self, left: f32, right: f32) -> (f32, f32) {
+        // Check for pending updates (atomic read - very fast)
+        if self.needs_update.load(Ordering::Relaxed) {
+            self.apply_pending_update();
+        }
+
+        // Off or zero amount is an exact passthrough
+        if !self.settings.is_active() {
+            return (left, right);
+        }
+
+        // Low-pass each channel, then delay it on its way to the other side
+        self.lowpass[0] += self.lowpass_coeff * (left - self.lowpass[0]);
+        self.lowpass[1] += self.lowpass_coeff * (right - self.lowpass[1]);
+
+        let read_pos = (self.write_pos + MAX_DELAY_SAMPLES - self.delay_samples) % MAX_DELAY_SAMPLES;
+        let crossfed_left = self.delay_lines[0][read_pos];
+        let crossfed_right = self.delay_lines[1][read_pos];
+        self.delay_lines[0][self.write_pos] = self.lowpass[0];
+        self.delay_lines[1][self.write_pos] = self.lowpass[1];
+        self.write_pos = (self.write_pos + 1) % MAX_DELAY_SAMPLES;
+
+        // Normalize so mono content keeps its level instead of getting louder
+        let amount = self.settings.amount;
+        let scale = 1.0 / (1.0 + amount);
+        (
+            (left + amount * crossfed_right) * scale,
+            (right + amount * crossfed_left) * scale,
+        )
+    }
+
+    /// Apply pending settings update if available (non-blocking)
+    fn apply_pending_update(&mut self) {
+        // Use try_lock to avoid blocking the real-time thread
+        let new_settings = match self.pending_settings.try_lock() {
+            Ok(mut pending) => pending.take(),
+            Err(_) => None,
+        };
+        if let Some(new_settings) = new_settings {
+            // Only switching on or off starts from silence; adjusting amount or
+            // cutoff keeps the state so the change doesn't click
+            let was_active = self.settings.is_active();
+            self.settings = new_settings;
+            self.configure();
+            if self.settings.is_active() != was_active {
+                self.reset();
+            }
+            self.needs_update.store(false, Ordering::Relaxed);
+        }
+        // If lock fails, we'll try again on the next buffer - no big deal
+    }
+
+    /// Get handles for updating settings from another thread
+    pub fn get_update_handles(&self) -> (Arc<AtomicBool>, Arc<Mutex<Option<CrossfeedSettings>>>) {
+        (
+            Arc::clone(&self.needs_update),
+            Arc::clone(&self.pending_settings),
+        )
+    }
+}
+
+/// Helper function to schedule a settings update from another thread
+pub fn update_crossfeed_settings(
+    needs_update: &Arc<AtomicBool>,
+    pending_settings: &Arc<Mutex<Option<CrossfeedSettings>>>,
+    new_settings: CrossfeedSettings,
+) {
+    if let Ok(mut pending) = pending_settings.lock() {
+        *pending = Some(new_settings);
+        needs_update.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(amount: f32) -> CrossfeedSettings {
+        CrossfeedSettings {
+            enabled: true,
+            amount,
+            ..CrossfeedSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_zero_amount_is_unity() {
+        let mut processor = CrossfeedProcessor::new(48000.0, enabled(0.0));
+        for i in 0..256 {
+            let left = (i as f32 * 0.1).sin();
+            let right = (i as f32 * 0.37).cos();
+            assert_eq!(processor.process_sample(left, right), (left, right));
+        }
+    }
+
+    #[test]
+    fn test_disabled_is_unity() {
+        let mut processor = CrossfeedProcessor::new(48000.0, CrossfeedSettings::default());
+        assert_eq!(processor.process_sample(0.5, -0.3), (0.5, -0.3));
+    }
+
+    #[test]
+    fn test_crossfeed_is_delayed_and_blended() {
+        let mut processor = CrossfeedProcessor::new(48000.0, enabled(0.5));
+        // A left-only DC signal starts leaking into the right channel after the delay
+        let delay = (48000.0 * CROSSFEED_DELAY_SECS).round() as usize;
+        let outputs: Vec<(f32, f32)> = (0..2000).map(|_| processor.process_sample(1.0, 0.0)).collect();
+
+        assert!(outputs[..delay].iter().all(|&(_, right)| right == 0.0));
+        assert!(outputs[delay].1 > 0.0);
+        // Settled: left is scaled down, right carries the blended low-passed left
+        let (left, right) = outputs[1999];
+        assert!((left - 1.0 / 1.5).abs() < 1e-4);
+        assert!((right - 0.5 / 1.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_adjusting_keeps_state_and_toggling_clears_it() {
+        let mut processor = CrossfeedProcessor::new(48000.0, enabled(0.5));
+        let (needs_update, pending) = processor.get_update_handles();
+        for _ in 0..2000 {
+            processor.process_sample(1.0, 0.0);
+        }
+
+        // A new amount applies to the crossfed signal already in the delay line
+        update_crossfeed_settings(&needs_update, &pending, enabled(0.25));
+        let (_, right) = processor.process_sample(1.0, 0.0);
+        assert!((right - 0.25 / 1.25).abs() < 1e-3);
+
+        // Off and back on starts again from an empty delay line
+        update_crossfeed_settings(&needs_update, &pending, CrossfeedSettings::default());
+        processor.process_sample(1.0, 0.0);
+        update_crossfeed_settings(&needs_update, &pending, enabled(0.25));
+        assert_eq!(processor.process_sample(1.0, 0.0).1, 0.0);
+    }
+
+    #[test]
+    fn test_settings_serialization() {
+        let settings = enabled(0.4);
+        let serialized = toml::to_string(&settings).unwrap();
+        let deserialized: CrossfeedSettings = toml::from_str(&serialized).unwrap();
+        assert_eq!(settings, deserialized);
+    }
+}
@@ -0,0 +1,60 @@
+/// Name of the PipeWire metadata object holding the default sink and source
+pub const DEFAULT_METADATA: &str = "default";
+
+/// Default devices, as published in the "default" metadata
+/// Holds PipeWire `node.name`s; `None` until the key has been seen (or after it is removed)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DefaultNodes {
+    /// Sink audio is played to (`default.audio.sink`)
+    pub sink: Option<String>,
+    /// Source audio is recorded from (`default.audio.source`)
+    pub source: Option<String>,
+}
+
+impl DefaultNodes {
+    /// Apply one "default" metadata property
+    /// Returns false for keys that aren't default devices
+    pub fn apply(&mut self, key: &str, value: Option<&str>) -> bool {
+        let field = match key {
+            "default.audio.sink" => &mut self.sink,
+            "default.audio.source" => &mut self.source,
+            _ => return false,
+        };
+        *field = value.and_then(node_name);
+        true
+    }
+}
+
+/// Extract the node name from a value like `{ "name": "alsa_output.pci-0000_00_1f.3.analog-stereo" }`
+fn node_name(value: &str) -> Option<String> {
+    let rest = &value[value.find("\"name\"")? + "\"name\"".len()..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start().strip_prefix('"')?;
+    let name = &rest[..rest.find('"')?];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_default_metadata() {
+        let mut defaults = DefaultNodes::default();
+        assert!(defaults.apply(
+            "default.audio.sink",
+            Some(r#"{ "name": "alsa_output.pci-0000_00_1f.3.analog-stereo" }"#)
+        ));
+        assert!(defaults.apply("default.audio.source", Some(r#"{"name":"alsa_input.usb"}"#)));
+        assert!(!defaults.apply("default.configured.audio.sink", Some(r#"{"name":"x"}"#)));
+        assert_eq!(
+            defaults.sink.as_deref(),
+            Some("alsa_output.pci-0000_00_1f.3.analog-stereo")
+        );
+        assert_eq!(defaults.source.as_deref(), Some("alsa_input.usb"));
+
+        // Removed or malformed values clear the default
+        defaults.apply("default.audio.sink", None);
+        defaults.apply("default.audio.source", Some("alsa_input.usb"));
+        assert_eq!(defaults, DefaultNodes::default());
+    }
+}
@@ -1,6 +1,12 @@
-use anyhow::Result;
-
 use super::types::DeviceId;
+use crate::error::{Result, WavewireError};
+
+/// Prefix of the `node.name` of every node wavewire creates, so the registry
+/// can tell them apart from other clients' nodes
+pub const VIRTUAL_NODE_PREFIX: &str = "wavewire_virtual_";
+
+/// Most channels a virtual device can have (PipeWire's SPA_AUDIO_MAX_CHANNELS)
+const MAX_VIRTUAL_CHANNELS: usize = 64;
 
 /// A virtual audio device created by wavewire
 pub struct VirtualDevice {
@@ -22,8 +28,16 @@ impl VirtualDevice {
         num_inputs: usize,
         num_outputs: usize,
     ) -> Result<Self> {
-        // For now, we just store the metadata
-        // Actual PipeWire node/port creation will be implemented later
+        let invalid = |reason: &str| Err(WavewireError::InvalidVirtualDevice(name.clone(), reason.to_string()));
+        if name.trim().is_empty() {
+            return invalid("the name is empty");
+        }
+        if num_inputs == 0 && num_outputs == 0 {
+            return invalid("it needs at least one port");
+        }
+        if num_inputs.max(num_outputs) > MAX_VIRTUAL_CHANNELS {
+            return invalid("too many ports");
+        }
         Ok(Self {
             id,
             name,
@@ -31,4 +45,75 @@ impl VirtualDevice {
             num_outputs,
         })
     }
+
+    /// PipeWire `node.name`: the prefix and the display name, lowercased, with
+    /// anything but letters and digits replaced by '_'
+    pub fn node_name(&self) -> String {
+        let name: String = self
+            .name
+            .trim()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        format!("{}{}", VIRTUAL_NODE_PREFIX, name)
+    }
+
+    /// Channels of the null sink: it has the same number on both sides, so the
+    /// larger count is used and the extra ports on the other side go unused
+    pub fn channels(&self) -> usize {
+        self.num_inputs.max(self.num_outputs)
+    }
+
+    /// `media.class` of the node: a sink when nothing comes out of it, a
+    /// virtual source when nothing goes in, otherwise a duplex device
+    pub fn media_class(&self) -> &'static str {
+        if self.num_outputs == 0 {
+            "Audio/Sink"
+        } else if self.num_inputs == 0 {
+            "Audio/Source/Virtual"
+        } else {
+            "Audio/Duplex"
+        }
+    }
+
+    /// `audio.position` of the node: mono, stereo, or numbered aux channels
+    pub fn audio_position(&self) -> String {
+        match self.channels() {
+            1 => String::from("MONO"),
+            2 => String::from("FL,FR"),
+            channels => (0..channels)
+                .map(|channel| format!("AUX{}", channel))
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_node_properties() {
+        let device = |inputs, outputs| {
+            VirtualDevice::new(DeviceId::new(1), String::from("Game Chat #2"), inputs, outputs).unwrap()
+        };
+        let duplex = device(2, 2);
+        assert_eq!(duplex.node_name(), "wavewire_virtual_game_chat__2");
+        assert_eq!(duplex.media_class(), "Audio/Duplex");
+        assert_eq!(duplex.audio_position(), "FL,FR");
+
+        assert_eq!(device(1, 0).media_class(), "Audio/Sink");
+        assert_eq!(device(1, 0).audio_position(), "MONO");
+        assert_eq!(device(0, 3).media_class(), "Audio/Source/Virtual");
+        assert_eq!(device(0, 3).audio_position(), "AUX0,AUX1,AUX2");
+        assert_eq!(device(4, 1).channels(), 4);
+
+        for (name, inputs, outputs) in [(" ", 2, 2), ("Empty", 0, 0), ("Huge", 65, 0)] {
+            assert!(matches!(
+                VirtualDevice::new(DeviceId::new(1), String::from(name), inputs, outputs),
+                Err(WavewireError::InvalidVirtualDevice(..))
+            ));
+        }
+    }
 }
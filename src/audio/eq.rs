@@ -1,3 +1,4 @@
+#[cfg(any(test, feature = "jack"))]
 use biquad::{Biquad, Coefficients, DirectForm2Transposed, Hertz, Type};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -8,12 +9,110 @@ pub const GRAPHIC_EQ_BANDS: [f32; 10] = [
     31.0, 63.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 20000.0,
 ];
 
+/// 5-band EQ: every other octave of the ISO 266 series
+const FIVE_BAND_EQ: [f32; 5] = [63.0, 250.0, 1000.0, 4000.0, 16000.0];
+
+/// 15-band EQ: ISO 266 two-thirds octave centers
+const FIFTEEN_BAND_EQ: [f32; 15] = [
+    25.0, 40.0, 63.0, 100.0, 160.0, 250.0, 400.0, 630.0, 1000.0, 1600.0, 2500.0, 4000.0,
+    6300.0, 10000.0, 16000.0,
+];
+
+/// 31-band EQ: ISO 266 third octave centers
+const THIRTY_ONE_BAND_EQ: [f32; 31] = [
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0, 5000.0, 6300.0,
+    8000.0, 10000.0, 12500.0, 16000.0, 20000.0,
+];
+
+/// Band counts an EQ can be switched between
+pub const EQ_BAND_COUNTS: [usize; 4] = [5, 10, 15, 31];
+
+/// Center frequencies of an EQ with `band_count` bands (10 for unsupported counts)
+fn band_frequencies(band_count: usize) -> &'static [f32] {
+    match band_count {
+        5 => &FIVE_BAND_EQ,
+        15 => &FIFTEEN_BAND_EQ,
+        31 => &THIRTY_ONE_BAND_EQ,
+        _ => &GRAPHIC_EQ_BANDS,
+    }
+}
+
+/// Largest boost or cut of a band (dB), wherever a gain is set
+pub const MAX_EQ_GAIN_DB: f32 = 24.0;
+
+/// Clamp a band gain to ±MAX_EQ_GAIN_DB
+pub fn clamp_eq_gain(gain_db: f32) -> f32 {
+    gain_db.clamp(-MAX_EQ_GAIN_DB, MAX_EQ_GAIN_DB)
+}
+
+/// Widest and narrowest band Q
+pub const EQ_Q_RANGE: (f32, f32) = (0.5, 5.0);
+
+/// Clamp a band Q to EQ_Q_RANGE
+pub fn clamp_eq_q(q_value: f32) -> f32 {
+    q_value.clamp(EQ_Q_RANGE.0, EQ_Q_RANGE.1)
+}
+
+/// Length of the crossfade between the EQ'd and dry signal when bypass is toggled
+#[cfg(any(test, feature = "jack"))]
+const BYPASS_FADE_SECS: f32 = 0.01;
+
+/// Shape of a band's filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EqFilterType {
+    /// Boosts or cuts around the band's frequency
+    #[default]
+    Peaking,
+    /// Boosts or cuts everything below the band's frequency
+    LowShelf,
+    /// Boosts or cuts everything above the band's frequency
+    HighShelf,
+}
+
+impl EqFilterType {
+    /// Short tag shown next to shelving bands
+    pub fn tag(self) -> Option<&'static str> {
+        match self {
+            EqFilterType::Peaking => None,
+            EqFilterType::LowShelf => Some("LS"),
+            EqFilterType::HighShelf => Some("HS"),
+        }
+    }
+
+    #[cfg(any(test, feature = "jack"))]
+    fn biquad_type(self, gain_db: f32) -> Type<f32> {
+        match self {
+            EqFilterType::Peaking => Type::PeakingEQ(gain_db),
+            EqFilterType::LowShelf => Type::LowShelf(gain_db),
+            EqFilterType::HighShelf => Type::HighShelf(gain_db),
+        }
+    }
+}
+
 /// Parameters for a single EQ band (serializable for config)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EqBandParams {
     pub frequency: f32, // Center frequency (Hz)
     pub gain_db: f32,   // Gain in dB (-24.0 to +24.0)
     pub q_value: f32,   // Q factor (0.5 to 5.0, default 1.41)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool, // Muted bands are flat
+    #[serde(default)]
+    pub soloed: bool, // If any band is soloed, only soloed bands are applied
+    #[serde(default)]
+    pub filter_type: EqFilterType,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// -3 dB bandwidth in octaves of a peaking filter with quality factor `q`
+/// BW = 2·asinh(1 / 2Q) / ln 2, so Q ≈ 1.41 is one octave and higher Q is narrower
+pub fn bandwidth_octaves(q: f32) -> f32 {
+    2.0 * (1.0 / (2.0 * q)).asinh() / std::f32::consts::LN_2
 }
 
 impl Default for EqBandParams {
@@ -22,6 +121,9 @@ impl Default for EqBandParams {
             frequency: 1000.0,
             gain_db: 0.0,
             q_value: 1.41,
+            enabled: true,
+            soloed: false,
+            filter_type: EqFilterType::Peaking,
         }
     }
 }
@@ -31,40 +133,170 @@ impl EqBandParams {
     pub fn new(frequency: f32, gain_db: f32, q_value: f32) -> Self {
         Self {
             frequency,
-            gain_db: gain_db.clamp(-24.0, 24.0),
-            q_value: q_value.clamp(0.5, 5.0),
+            gain_db: clamp_eq_gain(gain_db),
+            q_value: clamp_eq_q(q_value),
+            ..Self::default()
         }
     }
 
     /// Clamp parameters to valid ranges
     pub fn clamp(&mut self) {
-        self.gain_db = self.gain_db.clamp(-24.0, 24.0);
-        self.q_value = self.q_value.clamp(0.5, 5.0);
+        self.gain_db = clamp_eq_gain(self.gain_db);
+        self.q_value = clamp_eq_q(self.q_value);
         self.frequency = self.frequency.clamp(20.0, 20000.0);
     }
+
+    /// Lower and upper -3 dB edges (Hz) of the band, geometrically centered on its frequency
+    pub fn band_edges(&self) -> (f32, f32) {
+        let half_width = 2f32.powf(bandwidth_octaves(self.q_value) / 2.0);
+        (self.frequency / half_width, self.frequency * half_width)
+    }
+
+    /// Numerator and denominator of the RBJ cookbook biquad the processor builds
+    /// None when the band is flat (the filter is an exact identity)
+    fn rbj_coefficients(&self, sample_rate: f32) -> Option<([f64; 3], [f64; 3])> {
+        // The processor can't build a filter at or above Nyquist either
+        if self.gain_db == 0.0 || self.frequency >= sample_rate / 2.0 {
+            return None;
+        }
+
+        let a = 10f64.powf(self.gain_db as f64 / 40.0);
+        let w0 = 2.0 * std::f64::consts::PI * self.frequency as f64 / sample_rate as f64;
+        let alpha = w0.sin() / (2.0 * self.q_value as f64);
+        let cos_w0 = w0.cos();
+        let shelf = 2.0 * a.sqrt() * alpha;
+
+        Some(match self.filter_type {
+            EqFilterType::Peaking => (
+                [1.0 + alpha * a, -2.0 * cos_w0, 1.0 - alpha * a],
+                [1.0 + alpha / a, -2.0 * cos_w0, 1.0 - alpha / a],
+            ),
+            EqFilterType::LowShelf => (
+                [
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 + shelf),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 - shelf),
+                ],
+                [
+                    (a + 1.0) + (a - 1.0) * cos_w0 + shelf,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    (a + 1.0) + (a - 1.0) * cos_w0 - shelf,
+                ],
+            ),
+            EqFilterType::HighShelf => (
+                [
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 + shelf),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 - shelf),
+                ],
+                [
+                    (a + 1.0) - (a - 1.0) * cos_w0 + shelf,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    (a + 1.0) - (a - 1.0) * cos_w0 - shelf,
+                ],
+            ),
+        })
+    }
+
+    /// Magnitude response (dB) of this band's filter at `freq`
+    /// Evaluates the same RBJ cookbook biquad the processor builds, analytically
+    pub fn response_db(&self, freq: f32, sample_rate: f32) -> f32 {
+        let Some(([b0, b1, b2], [a0, a1, a2])) = self.rbj_coefficients(sample_rate) else {
+            return 0.0;
+        };
+
+        // |H(e^jw)|² for a biquad: evaluate numerator and denominator at z = e^jw
+        let w = 2.0 * std::f64::consts::PI * freq as f64 / sample_rate as f64;
+        let power = |c0: f64, c1: f64, c2: f64| {
+            let re = c0 + c1 * w.cos() + c2 * (2.0 * w).cos();
+            let im = c1 * w.sin() + c2 * (2.0 * w).sin();
+            re * re + im * im
+        };
+
+        (10.0 * (power(b0, b1, b2) / power(a0, a1, a2)).log10()) as f32
+    }
+
+    /// Group delay (samples) of this band's filter at `freq`
+    /// Biquads add no fixed latency, only this frequency-dependent phase delay
+    pub fn group_delay_samples(&self, freq: f32, sample_rate: f32) -> f32 {
+        let Some((b, a)) = self.rbj_coefficients(sample_rate) else {
+            return 0.0;
+        };
+
+        // For P(z) = Σ c_k z^-k the group delay is Re(Σ k·c_k·e^-jwk / Σ c_k·e^-jwk),
+        // and a ratio B/A delays by τ_B - τ_A
+        let w = 2.0 * std::f64::consts::PI * freq as f64 / sample_rate as f64;
+        let delay = |c: [f64; 3]| {
+            let (mut re, mut im, mut k_re, mut k_im) = (0.0, 0.0, 0.0, 0.0);
+            for (k, c) in c.iter().enumerate() {
+                let (sin, cos) = (w * k as f64).sin_cos();
+                re += c * cos;
+                im -= c * sin;
+                k_re += k as f64 * c * cos;
+                k_im -= k as f64 * c * sin;
+            }
+            (k_re * re + k_im * im) / (re * re + im * im)
+        };
+        (delay(b) - delay(a)) as f32
+    }
 }
 
 /// Complete EQ settings for a device (serializable)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EqSettings {
-    pub bands: [EqBandParams; 10],
+    pub bands: Vec<EqBandParams>,
     pub bypass: bool,
 }
 
 impl Default for EqSettings {
     fn default() -> Self {
+        Self::with_bands(GRAPHIC_EQ_BANDS.len())
+    }
+}
+
+impl EqSettings {
+    /// Flat EQ with `band_count` bands (one of EQ_BAND_COUNTS, otherwise 10)
+    /// Peaking bands, except for shelves at both ends: a peaking filter at the
+    /// edges leaves everything beyond it untouched
+    pub fn with_bands(band_count: usize) -> Self {
+        let mut bands: Vec<EqBandParams> = band_frequencies(band_count)
+            .iter()
+            .map(|&frequency| EqBandParams {
+                frequency,
+                ..EqBandParams::default()
+            })
+            .collect();
+        bands[0].filter_type = EqFilterType::LowShelf;
+        if let Some(last) = bands.last_mut() {
+            last.filter_type = EqFilterType::HighShelf;
+        }
         Self {
-            bands: GRAPHIC_EQ_BANDS.map(|freq| EqBandParams {
-                frequency: freq,
-                gain_db: 0.0,
-                q_value: 1.41,
-            }),
+            bands,
             bypass: false,
         }
     }
-}
 
-impl EqSettings {
+    pub fn band_count(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// The same curve on `band_count` bands: each new band takes the gain of the
+    /// old band nearest to it (on a log scale)
+    pub fn resampled(&self, band_count: usize) -> Self {
+        let mut resampled = Self::with_bands(band_count);
+        for band in &mut resampled.bands {
+            let nearest = self.bands.iter().min_by(|a, b| {
+                let distance = |other: &EqBandParams| (other.frequency / band.frequency).ln().abs();
+                distance(a).total_cmp(&distance(b))
+            });
+            if let Some(nearest) = nearest {
+                band.gain_db = nearest.gain_db;
+            }
+        }
+        resampled.bypass = self.bypass;
+        resampled
+    }
+
     /// Create a flat EQ (all gains at 0 dB)
     pub fn flat() -> Self {
         Self::default()
@@ -77,30 +309,147 @@ impl EqSettings {
         }
     }
 
+    /// Clamp every band to valid ranges (for settings from a file or another thread)
+    pub fn clamp(&mut self) {
+        for band in &mut self.bands {
+            band.clamp();
+        }
+    }
+
     /// Set a specific band's parameters
     pub fn set_band(&mut self, index: usize, gain_db: f32, q_value: f32) {
-        if index < 10 {
-            self.bands[index].gain_db = gain_db.clamp(-24.0, 24.0);
-            self.bands[index].q_value = q_value.clamp(0.5, 5.0);
+        if let Some(band) = self.bands.get_mut(index) {
+            band.gain_db = clamp_eq_gain(gain_db);
+            band.q_value = clamp_eq_q(q_value);
         }
     }
+
+    /// Whether a band is applied, taking mute and solo into account
+    /// Muting wins over solo; when any band is soloed, only soloed bands are applied
+    pub fn is_band_active(&self, index: usize) -> bool {
+        let any_soloed = self.bands.iter().any(|band| band.soloed);
+        self.bands
+            .get(index)
+            .is_some_and(|band| band.enabled && (band.soloed || !any_soloed))
+    }
+
+    /// Combined magnitude response (dB) of all active bands at `freq`
+    /// Cascaded biquads multiply, so their dB responses add
+    pub fn response_db(&self, freq: f32, sample_rate: f32) -> f32 {
+        if self.bypass {
+            return 0.0;
+        }
+        (0..self.bands.len())
+            .filter(|&index| self.is_band_active(index))
+            .map(|index| self.bands[index].response_db(freq, sample_rate))
+            .sum()
+    }
+
+    /// Combined magnitude response (dB) at each of `freqs`, e.g. a spectrum's bin frequencies
+    pub fn magnitude_response(&self, sample_rate: f32, freqs: &[f32]) -> Vec<f32> {
+        freqs.iter().map(|&freq| self.response_db(freq, sample_rate)).collect()
+    }
+
+    /// Group delay (samples) of the EQ path at `freq`, bypassed or not
+    /// Bypass crossfades to the dry signal while the filters keep running, so this is
+    /// the only timing difference between the two sides of an A/B comparison. The dry
+    /// side isn't delayed to match: the delay depends on frequency, so no single delay
+    /// would line it up
+    pub fn group_delay_samples(&self, freq: f32, sample_rate: f32) -> f32 {
+        (0..self.bands.len())
+            .filter(|&index| self.is_band_active(index))
+            .map(|index| self.bands[index].group_delay_samples(freq, sample_rate))
+            .sum()
+    }
+
+    /// Largest group delay across the audible range as (frequency Hz, delay ms)
+    pub fn max_group_delay(&self, sample_rate: f32) -> (f32, f32) {
+        const POINTS: usize = 200;
+        let (low, high) = (20f32, 20000f32.min(sample_rate / 2.0));
+        (0..POINTS)
+            .map(|i| low * (high / low).powf(i as f32 / (POINTS - 1) as f32))
+            .map(|freq| (freq, self.group_delay_samples(freq, sample_rate) * 1000.0 / sample_rate))
+            .fold((low, 0.0), |max, point| if point.1 > max.1 { point } else { max })
+    }
+}
+
+/// Built-in curves, picked by number in the Filters tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqPreset {
+    Flat,
+    BassBoost,
+    Vocal,
+    Rock,
+    Loudness,
+}
+
+impl EqPreset {
+    /// Every preset, in the order of their numbers
+    pub const ALL: [EqPreset; 5] = [
+        EqPreset::Flat,
+        EqPreset::BassBoost,
+        EqPreset::Vocal,
+        EqPreset::Rock,
+        EqPreset::Loudness,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EqPreset::Flat => "Flat",
+            EqPreset::BassBoost => "Bass boost",
+            EqPreset::Vocal => "Vocal",
+            EqPreset::Rock => "Rock",
+            EqPreset::Loudness => "Loudness",
+        }
+    }
+
+    /// Gain (dB) of each default band
+    fn gains(self) -> [f32; 10] {
+        match self {
+            EqPreset::Flat => [0.0; 10],
+            EqPreset::BassBoost => [6.0, 5.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            // Presence lift, low end pulled back out of the way
+            EqPreset::Vocal => [-2.0, -2.0, -1.0, 0.0, 2.0, 4.0, 4.0, 3.0, 1.0, 0.0],
+            EqPreset::Rock => [4.0, 3.0, 2.0, 0.0, -1.0, -1.0, 1.0, 3.0, 4.0, 4.0],
+            // Both ends raised, as the ear loses them at low volume
+            EqPreset::Loudness => [6.0, 4.0, 2.0, 0.0, -1.0, -1.0, 0.0, 2.0, 4.0, 5.0],
+        }
+    }
+
+    /// The preset's curve on the default ten bands
+    pub fn to_settings(self) -> EqSettings {
+        let mut settings = EqSettings::default();
+        for (index, gain_db) in self.gains().into_iter().enumerate() {
+            let q_value = settings.bands[index].q_value;
+            settings.set_band(index, gain_db, q_value);
+        }
+        settings
+    }
 }
 
 /// Real-time EQ processor (lives in JACK callback)
+#[cfg(any(test, feature = "jack"))]
 pub struct EqProcessor {
-    filters: [DirectForm2Transposed<f32>; 10],
+    filters: Vec<DirectForm2Transposed<f32>>,
     settings: EqSettings,
     sample_rate: f32,
+    /// Share of the EQ'd signal in the output (0 = dry, 1 = EQ'd), ramped on bypass
+    wet_mix: f32,
+    /// `wet_mix` change per sample while crossfading
+    fade_step: f32,
     needs_update: Arc<AtomicBool>,
     pending_settings: Arc<Mutex<Option<EqSettings>>>,
 }
 
+#[cfg(any(test, feature = "jack"))]
 impl EqProcessor {
     /// Create a new EQ processor with the given sample rate and settings
     pub fn new(sample_rate: f32, settings: EqSettings) -> Self {
         let filters = Self::create_filters(sample_rate, &settings);
         Self {
             filters,
+            wet_mix: if settings.bypass { 0.0 } else { 1.0 },
+            fade_step: Self::fade_step(sample_rate),
             settings,
             sample_rate,
             needs_update: Arc::new(AtomicBool::new(false)),
@@ -108,18 +457,34 @@ impl EqProcessor {
         }
     }
 
-    /// Create biquad filters from EQ settings
-    fn create_filters(sr: f32, settings: &EqSettings) -> [DirectForm2Transposed<f32>; 10] {
-        settings.bands.clone().map(|band| {
-            let coeffs = Coefficients::<f32>::from_params(
-                Type::PeakingEQ(band.gain_db),
-                Hertz::<f32>::from_hz(sr).unwrap(),
-                Hertz::<f32>::from_hz(band.frequency).unwrap(),
-                band.q_value,
-            )
-            .unwrap();
-            DirectForm2Transposed::<f32>::new(coeffs)
-        })
+    /// Create biquad filters from EQ settings, one per band
+    fn create_filters(sr: f32, settings: &EqSettings) -> Vec<DirectForm2Transposed<f32>> {
+        (0..settings.bands.len())
+            .map(|index| Self::create_filter(sr, settings, index))
+            .collect()
+    }
+
+    /// Biquad filter of one band
+    /// Muted (or un-soloed) bands get flat 0 dB coefficients so the cascade keeps its shape
+    fn create_filter(sr: f32, settings: &EqSettings, index: usize) -> DirectForm2Transposed<f32> {
+        let band = &settings.bands[index];
+        let gain_db = if settings.is_band_active(index) {
+            band.gain_db
+        } else {
+            0.0
+        };
+        let coeffs = Coefficients::<f32>::from_params(
+            band.filter_type.biquad_type(gain_db),
+            Hertz::<f32>::from_hz(sr).unwrap(),
+            Hertz::<f32>::from_hz(band.frequency).unwrap(),
+            band.q_value,
+        )
+        .unwrap();
+        DirectForm2Transposed::<f32>::new(coeffs)
+    }
+
+    fn fade_step(sample_rate: f32) -> f32 {
+        1.0 / (BYPASS_FADE_SECS * sample_rate).max(1.0)
     }
 
     /// Process a stereo sample through the EQ
@@ -131,12 +496,8 @@ impl EqProcessor {
             self.apply_pending_update();
         }
 
-        // Bypass if enabled
-        if self.settings.bypass {
-            return (left, right);
-        }
-
-        // Cascade through all filters
+        // Cascade through all filters, even when bypassed: the filter state stays
+        // current, so switching back in doesn't start from stale history
         let mut l = left;
         let mut r = right;
         for filter in &mut self.filters {
@@ -144,18 +505,44 @@ impl EqProcessor {
             r = filter.run(r);
         }
 
-        (l, r)
+        // Crossfade instead of flipping, so an A/B switch doesn't click
+        let target = if self.settings.bypass { 0.0 } else { 1.0 };
+        if self.wet_mix != target {
+            self.wet_mix = if target > self.wet_mix {
+                (self.wet_mix + self.fade_step).min(target)
+            } else {
+                (self.wet_mix - self.fade_step).max(target)
+            };
+        }
+        if self.wet_mix == 1.0 {
+            (l, r)
+        } else if self.wet_mix == 0.0 {
+            (left, right)
+        } else {
+            (
+                left + (l - left) * self.wet_mix,
+                right + (r - right) * self.wet_mix,
+            )
+        }
     }
 
     /// Apply pending settings update if available (non-blocking)
     fn apply_pending_update(&mut self) {
         // Use try_lock to avoid blocking the real-time thread
-        if let Ok(mut pending) = self.pending_settings.try_lock() {
-            if let Some(new_settings) = pending.take() {
-                self.settings = new_settings.clone();
+        if let Ok(mut pending) = self.pending_settings.try_lock()
+            && let Some(new_settings) = pending.take()
+        {
+            // A bypass-only change keeps the running filters (and their state)
+            if new_settings.bands.len() != self.filters.len() {
                 self.filters = Self::create_filters(self.sample_rate, &new_settings);
-                self.needs_update.store(false, Ordering::Relaxed);
+            } else if new_settings.bands != self.settings.bands {
+                // Same band count: rebuild in place rather than allocate here
+                for (index, filter) in self.filters.iter_mut().enumerate() {
+                    *filter = Self::create_filter(self.sample_rate, &new_settings, index);
+                }
             }
+            self.settings = new_settings;
+            self.needs_update.store(false, Ordering::Relaxed);
         }
         // If lock fails, we'll try again on the next buffer - no big deal
     }
@@ -168,23 +555,6 @@ impl EqProcessor {
         )
     }
 
-    /// Update sample rate (called when sample rate changes)
-    pub fn update_sample_rate(&mut self, new_sample_rate: f32) {
-        if (self.sample_rate - new_sample_rate).abs() > 0.1 {
-            self.sample_rate = new_sample_rate;
-            self.filters = Self::create_filters(new_sample_rate, &self.settings);
-        }
-    }
-
-    /// Get current settings
-    pub fn settings(&self) -> &EqSettings {
-        &self.settings
-    }
-
-    /// Get current sample rate
-    pub fn sample_rate(&self) -> f32 {
-        self.sample_rate
-    }
 }
 
 /// Helper function to schedule a settings update from another thread
@@ -218,11 +588,11 @@ mod tests {
 
     #[test]
     fn test_eq_band_params_clamping() {
-        let mut params = EqBandParams::new(1000.0, 15.0, 10.0);
+        let mut params = EqBandParams::new(1000.0, 30.0, 10.0);
         assert_eq!(params.gain_db, 24.0); // Clamped to max
         assert_eq!(params.q_value, 5.0); // Clamped to max
 
-        params = EqBandParams::new(1000.0, -20.0, 0.1);
+        params = EqBandParams::new(1000.0, -30.0, 0.1);
         assert_eq!(params.gain_db, -24.0); // Clamped to min
         assert_eq!(params.q_value, 0.5); // Clamped to min
     }
@@ -271,15 +641,176 @@ mod tests {
         assert_eq!(settings.bands[5].q_value, 2.0);
 
         // Test clamping
-        settings.set_band(5, 20.0, 10.0);
+        settings.set_band(5, 30.0, 10.0);
         assert_eq!(settings.bands[5].gain_db, 24.0);
         assert_eq!(settings.bands[5].q_value, 5.0);
     }
 
     #[test]
-    fn test_eq_processor_bypass() {
+    fn test_gain_survives_settings_round_trip() {
+        // +20 dB is in range and kept as is; +30 dB comes back at the clamp
+        let mut settings = EqSettings::default();
+        settings.bands[3].gain_db = 20.0;
+        settings.bands[4].gain_db = 30.0;
+        let mut restored: EqSettings = toml::from_str(&toml::to_string(&settings).unwrap()).unwrap();
+        restored.clamp();
+        restored.set_band(5, 20.0, 1.41);
+
+        let processor = EqProcessor::new(48000.0, restored);
+        let bands = &processor.settings.bands;
+        assert_eq!(bands[3].gain_db, 20.0);
+        assert_eq!(bands[4].gain_db, MAX_EQ_GAIN_DB);
+        assert_eq!(bands[5].gain_db, 20.0);
+    }
+
+    #[test]
+    fn test_eq_response_db() {
+        let mut settings = EqSettings::default();
+        assert_eq!(settings.response_db(1000.0, 48000.0), 0.0);
+
+        settings.set_band(5, 6.0, 1.41);
+        // Full gain at the band's center, falling off away from it
+        assert!((settings.response_db(1000.0, 48000.0) - 6.0).abs() < 0.01);
+        assert!(settings.response_db(100.0, 48000.0).abs() < 0.5);
+        assert!(settings.response_db(10000.0, 48000.0).abs() < 0.5);
+
+        settings.bypass = true;
+        assert_eq!(settings.response_db(1000.0, 48000.0), 0.0);
+    }
+
+    #[test]
+    fn test_magnitude_response_at_bins() {
+        let mut settings = EqSettings::default();
+        settings.set_band(5, 6.0, 1.41);
+        let (_, edge) = settings.bands[5].band_edges();
+        let response = settings.magnitude_response(48000.0, &[1000.0, edge, 10000.0]);
+        assert_eq!(response.len(), 3);
+        assert!((response[0] - 6.0).abs() < 0.01);
+        // Half the gain (in dB) at the band's upper edge
+        assert!((response[1] - 3.0).abs() < 0.3);
+        assert!(response[2].abs() < 0.5);
+        assert!(settings.magnitude_response(48000.0, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_bandwidth_from_q() {
+        assert!((bandwidth_octaves(1.41) - 1.0).abs() < 0.01);
+        assert!((bandwidth_octaves(0.667) - 2.0).abs() < 0.01);
+        assert!(bandwidth_octaves(5.0) < bandwidth_octaves(0.5));
+
+        let (low, high) = EqBandParams::new(1000.0, 6.0, 0.667).band_edges();
+        assert!((low - 500.0).abs() < 5.0);
+        assert!((high - 2000.0).abs() < 20.0);
+        // Edges are symmetric on a log scale
+        assert!((low * high - 1000.0 * 1000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_eq_band_mute_and_solo() {
+        let mut settings = EqSettings::default();
+        settings.bands[2].enabled = false;
+        assert!(!settings.is_band_active(2));
+        assert!(settings.is_band_active(3));
+
+        // Solo leaves only soloed bands active, but a muted band stays muted
+        settings.bands[4].soloed = true;
+        settings.bands[2].soloed = true;
+        assert!(settings.is_band_active(4));
+        assert!(!settings.is_band_active(3));
+        assert!(!settings.is_band_active(2));
+
+        settings.bands[3].gain_db = 6.0;
+        assert_eq!(settings.response_db(250.0, 48000.0), 0.0);
+    }
+
+    #[test]
+    fn test_eq_band_flags_default_when_missing() {
+        let band: EqBandParams =
+            toml::from_str("frequency = 100.0\ngain_db = 3.0\nq_value = 1.0").unwrap();
+        assert!(band.enabled);
+        assert!(!band.soloed);
+    }
+
+    #[test]
+    fn test_shelves_at_the_edges() {
+        let mut settings = EqSettings::default();
+        assert_eq!(settings.bands[0].filter_type, EqFilterType::LowShelf);
+        assert_eq!(settings.bands[9].filter_type, EqFilterType::HighShelf);
+        assert!(settings.bands[1..9].iter().all(|band| band.filter_type == EqFilterType::Peaking));
+
+        // A low shelf boost reaches all the way down, half its gain at the corner
+        settings.set_band(0, 6.0, 0.71);
+        assert!((settings.response_db(5.0, 48000.0) - 6.0).abs() < 0.1);
+        assert!((settings.response_db(31.0, 48000.0) - 3.0).abs() < 0.1);
+        assert!(settings.response_db(1000.0, 48000.0).abs() < 0.1);
+
+        settings.set_band(0, 0.0, 0.71);
+        settings.set_band(9, -6.0, 0.71);
+        assert!(settings.response_db(1000.0, 48000.0).abs() < 0.1);
+        assert!(settings.response_db(23000.0, 48000.0) < -5.0);
+
+        // The processor follows the same curve: a low tone comes out boosted
+        settings.set_band(9, 0.0, 0.71);
+        settings.set_band(0, 6.0, 0.71);
+        let mut processor = EqProcessor::new(48000.0, settings.clone());
+        let peak = (0..48000)
+            .map(|n| (2.0 * std::f32::consts::PI * 10.0 * n as f32 / 48000.0).sin() * 0.1)
+            .map(|sample| processor.process_sample(sample, sample).0.abs())
+            .skip(24000)
+            .fold(0.0, f32::max);
+        assert!((peak / 0.1 - 2.0).abs() < 0.05, "peak {}", peak);
+
+        let restored: EqSettings = toml::from_str(&toml::to_string(&settings).unwrap()).unwrap();
+        assert_eq!(restored, settings);
+    }
+
+    #[test]
+    fn test_presets_stay_in_range() {
+        for preset in EqPreset::ALL {
+            let settings = preset.to_settings();
+            assert_eq!(settings.bands.len(), 10);
+            for band in &settings.bands {
+                assert!((-12.0..=12.0).contains(&band.gain_db), "{} at {} Hz", preset.name(), band.frequency);
+            }
+        }
+        assert_eq!(EqPreset::Flat.to_settings(), EqSettings::flat());
+        assert_eq!(EqPreset::BassBoost.to_settings().bands[0].gain_db, 6.0);
+    }
+
+    #[test]
+    fn test_band_counts() {
+        for count in EQ_BAND_COUNTS {
+            let settings = EqSettings::with_bands(count);
+            assert_eq!(settings.band_count(), count);
+            assert_eq!(settings.bands[0].filter_type, EqFilterType::LowShelf);
+            assert_eq!(settings.bands[count - 1].filter_type, EqFilterType::HighShelf);
+            assert!(settings.bands.windows(2).all(|pair| pair[0].frequency < pair[1].frequency));
+        }
+        assert_eq!(EqSettings::with_bands(7), EqSettings::default());
+
+        // Moving a curve to more bands keeps its shape around the old bands
         let mut settings = EqSettings::default();
+        settings.set_band(1, 6.0, 1.41);
         settings.bypass = true;
+        let fine = settings.resampled(31);
+        assert!(fine.bypass);
+        assert_eq!(fine.bands[5].gain_db, 6.0); // 63 Hz
+        assert_eq!(fine.bands[17].gain_db, 0.0); // 1 kHz
+
+        // The processor follows band count changes
+        let mut processor = EqProcessor::new(48000.0, EqSettings::default());
+        let (flag, pending) = processor.get_update_handles();
+        update_eq_settings(&flag, &pending, EqSettings::with_bands(31));
+        processor.process_sample(0.0, 0.0);
+        assert_eq!(processor.filters.len(), 31);
+    }
+
+    #[test]
+    fn test_eq_processor_bypass() {
+        let settings = EqSettings {
+            bypass: true,
+            ..EqSettings::default()
+        };
         let mut processor = EqProcessor::new(48000.0, settings);
 
         let (l_out, r_out) = processor.process_sample(0.5, -0.3);
@@ -317,25 +848,13 @@ mod tests {
         assert!(flag.load(Ordering::Relaxed));
     }
 
-    #[test]
-    fn test_eq_processor_sample_rate_update() {
-        let mut processor = EqProcessor::new(48000.0, EqSettings::default());
-        assert_eq!(processor.sample_rate(), 48000.0);
-
-        processor.update_sample_rate(44100.0);
-        assert_eq!(processor.sample_rate(), 44100.0);
-
-        // Small changes should be ignored
-        processor.update_sample_rate(44100.05);
-        assert_eq!(processor.sample_rate(), 44100.0);
-    }
-
     #[test]
     fn test_eq_band_params_clamp() {
         let mut params = EqBandParams {
             frequency: 50000.0,
             gain_db: 100.0,
             q_value: 100.0,
+            ..EqBandParams::default()
         };
         params.clamp();
 
@@ -351,4 +870,47 @@ mod tests {
         let deserialized: EqSettings = toml::from_str(&serialized).unwrap();
         assert_eq!(settings, deserialized);
     }
+
+    #[test]
+    fn test_group_delay() {
+        let mut settings = EqSettings::flat();
+        assert_eq!(settings.max_group_delay(48000.0).1, 0.0);
+
+        // A low boost delays its own region most, by well under a period
+        settings.set_band(1, 12.0, 1.41);
+        let at_band = settings.group_delay_samples(63.0, 48000.0);
+        assert!(at_band > 0.0);
+        assert!(at_band > settings.group_delay_samples(5000.0, 48000.0));
+        let (freq, ms) = settings.max_group_delay(48000.0);
+        assert!((30.0..130.0).contains(&freq), "peak delay at {} Hz", freq);
+        assert!(ms > 0.0 && ms < 1000.0 / 63.0);
+    }
+
+    #[test]
+    fn test_bypass_crossfades_without_clicks() {
+        let mut settings = EqSettings::default();
+        for band in &mut settings.bands {
+            band.gain_db = 12.0;
+        }
+        let mut processor = EqProcessor::new(48000.0, settings.clone());
+        let (flag, pending) = processor.get_update_handles();
+        let signal = |n: usize| 0.25 * (2.0 * std::f32::consts::PI * 440.0 * n as f32 / 48000.0).sin();
+
+        let mut output = Vec::new();
+        for n in 0..4800 {
+            if n == 2400 {
+                settings.bypass = true;
+                update_eq_settings(&flag, &pending, settings.clone());
+            }
+            output.push(processor.process_sample(signal(n), signal(n)).0);
+        }
+
+        // The largest step across the switch is no bigger than in steady EQ'd playback
+        let max_step = |range: std::ops::Range<usize>| {
+            range.map(|n| (output[n] - output[n - 1]).abs()).fold(0.0, f32::max)
+        };
+        assert!(max_step(2400..3000) <= max_step(1200..2400) * 1.05);
+        // Once faded, the output is exactly the dry signal
+        assert_eq!(output[4799], signal(4799));
+    }
 }
@@ -0,0 +1,384 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::eq::{EqBandParams, EqFilterType, EqSettings, EQ_BAND_COUNTS, GRAPHIC_EQ_BANDS};
+
+/// Q of a shelf given without one (Butterworth, no overshoot)
+const SHELF_Q: f32 = 0.71;
+
+/// Result of importing a parametric EQ profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqImport {
+    /// Settings with the imported filters snapped onto our bands
+    pub settings: EqSettings,
+    /// Number of filters applied
+    pub imported: usize,
+    /// Filters (or other lines) that couldn't be represented, with the reason
+    pub skipped: Vec<String>,
+    /// Filters applied with values clamped to the band limits
+    pub adjusted: Vec<String>,
+}
+
+impl EqImport {
+    /// One-line report for the status bar
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Imported {} filter{}",
+            self.imported,
+            if self.imported == 1 { "" } else { "s" }
+        );
+        if !self.adjusted.is_empty() {
+            summary.push_str(&format!("; clamped {}", self.adjusted.join(", ")));
+        }
+        if !self.skipped.is_empty() {
+            summary.push_str(&format!("; skipped {}", self.skipped.join(", ")));
+        }
+        summary
+    }
+}
+
+/// A peaking or shelving filter read from the profile
+#[derive(Debug, Clone, PartialEq)]
+struct ProfileFilter {
+    label: String,
+    filter_type: EqFilterType,
+    frequency: f32,
+    gain_db: f32,
+    q_value: f32,
+}
+
+/// Read a parametric EQ text file (AutoEQ ParametricEQ.txt, REW / Equalizer APO export)
+/// A leading `~/` is expanded to the home directory
+/// Fails, leaving the EQ alone, if no filter in it could be applied
+pub fn import_parametric_eq(path: &str) -> Result<EqImport> {
+    let path = expand_home(path);
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let settings = EqSettings::from_autoeq_str(&text)
+        .with_context(|| format!("Nothing to import from {}", path.display()))?;
+    // Parsed once more for the report of what was clamped or skipped
+    Ok(EqImport { settings, ..parse_parametric_eq(&text) })
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => Path::new(path).to_path_buf(),
+    }
+}
+
+impl EqSettings {
+    /// Parse AutoEQ ParametricEQ.txt text into EQ settings (see `parse_parametric_eq`)
+    /// Fails if no filter in it could be applied
+    pub fn from_autoeq_str(text: &str) -> Result<EqSettings> {
+        let import = parse_parametric_eq(text);
+        if import.imported == 0 {
+            if import.skipped.is_empty() {
+                anyhow::bail!("No filters in AutoEQ profile");
+            }
+            anyhow::bail!("No usable filters in AutoEQ profile (skipped {})", import.skipped.join(", "));
+        }
+        Ok(import.settings)
+    }
+}
+
+/// Parse profile text like `Filter 1: ON PK Fc 105 Hz Gain -3.5 dB Q 0.70`
+/// Peaking (PK) and shelving (LS/LSC, HS/HSC) filters map onto our bands. The
+/// EQ gets the fewest bands (10 or more) that fit every filter; each filter takes
+/// the free band nearest its center frequency and retunes it, and when there are
+/// more filters than bands the ones with the largest gain win. The bands are then
+/// put back in ascending frequency order, which nearby filters can otherwise cross
+pub fn parse_parametric_eq(text: &str) -> EqImport {
+    let mut filters = Vec::new();
+    let mut skipped = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(preamp) = line.strip_prefix("Preamp:") {
+            skipped.push(format!("preamp {} (use volume instead)", preamp.trim()));
+            continue;
+        }
+
+        let Some((label, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !label.starts_with("Filter") {
+            continue;
+        }
+
+        match parse_filter(&label, rest) {
+            Ok(Some(filter)) => filters.push(filter),
+            Ok(None) => {}
+            Err(reason) => skipped.push(format!("{} ({})", label, reason)),
+        }
+    }
+
+    let band_count = EQ_BAND_COUNTS
+        .iter()
+        .copied()
+        .find(|&count| count >= GRAPHIC_EQ_BANDS.len() && count >= filters.len())
+        .unwrap_or(EQ_BAND_COUNTS[EQ_BAND_COUNTS.len() - 1]);
+    let mut import = EqImport {
+        settings: EqSettings::with_bands(band_count),
+        imported: 0,
+        skipped,
+        adjusted: Vec::new(),
+    };
+
+    // Strongest filters claim bands first
+    filters.sort_by(|a, b| b.gain_db.abs().total_cmp(&a.gain_db.abs()));
+
+    let band_frequencies: Vec<f32> = import.settings.bands.iter().map(|b| b.frequency).collect();
+    let mut taken = vec![false; band_count];
+    for filter in filters {
+        let Some(band_index) = nearest_free_band(filter.frequency, &band_frequencies, &taken) else {
+            import
+                .skipped
+                .push(format!("{} (only {} bands)", filter.label, taken.len()));
+            continue;
+        };
+        taken[band_index] = true;
+
+        let mut band = EqBandParams {
+            filter_type: filter.filter_type,
+            ..EqBandParams::new(filter.frequency, filter.gain_db, filter.q_value)
+        };
+        band.clamp();
+        if band.frequency != filter.frequency {
+            import.adjusted.push(format!("{} Fc to {:.0} Hz", filter.label, band.frequency));
+        }
+        if band.gain_db != filter.gain_db {
+            import.adjusted.push(format!("{} gain to {:+.1} dB", filter.label, band.gain_db));
+        }
+        if band.q_value != filter.q_value {
+            import.adjusted.push(format!("{} Q to {:.2}", filter.label, band.q_value));
+        }
+
+        import.settings.bands[band_index] = band;
+        import.imported += 1;
+    }
+
+    // A filter can land on a band past a neighbour tuned higher than it
+    import
+        .settings
+        .bands
+        .sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+
+    import
+}
+
+/// Parse the part of a filter line after the label
+/// Returns None for filters that are switched off
+fn parse_filter(label: &str, rest: &str) -> Result<Option<ProfileFilter>, String> {
+    let tokens: Vec<&str> = rest.split_whitespace().collect();
+
+    match tokens.first() {
+        Some(&"ON") => {}
+        Some(&"OFF") => return Ok(None),
+        _ => return Err(String::from("unreadable")),
+    }
+
+    let filter_type = match tokens.get(1).copied().unwrap_or("") {
+        "PK" | "PEQ" => EqFilterType::Peaking,
+        "LS" | "LSC" => EqFilterType::LowShelf,
+        "HS" | "HSC" => EqFilterType::HighShelf,
+        other => return Err(format!("{} filters not supported", other)),
+    };
+
+    // Values follow their keyword: "Fc 105 Hz", "Gain -3.5 dB", "Q 0.70"
+    let value = |key: &str| {
+        tokens
+            .iter()
+            .position(|token| *token == key)
+            .and_then(|i| tokens.get(i + 1))
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| v.is_finite())
+    };
+
+    let frequency = value("Fc").ok_or("missing Fc")?;
+    let gain_db = value("Gain").ok_or("missing gain")?;
+    // Equalizer APO writes plain LS/HS shelves without a Q
+    let q_value = match (value("Q"), filter_type) {
+        (Some(q_value), _) => q_value,
+        (None, EqFilterType::Peaking) => return Err(String::from("missing Q")),
+        (None, _) => SHELF_Q,
+    };
+    if frequency <= 0.0 || q_value <= 0.0 {
+        return Err(String::from("invalid Fc or Q"));
+    }
+
+    Ok(Some(ProfileFilter {
+        label: label.to_string(),
+        filter_type,
+        frequency,
+        gain_db,
+        q_value,
+    }))
+}
+
+/// Index of the untaken band closest to `frequency` (on a log scale)
+fn nearest_free_band(frequency: f32, bands: &[f32], taken: &[bool]) -> Option<usize> {
+    bands
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !taken[*i])
+        .map(|(i, &band)| (i, (frequency / band).ln().abs()))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTOEQ_PROFILE: &str = "\
+Preamp: -6.2 dB
+Filter 1: ON PK Fc 105 Hz Gain -3.5 dB Q 0.70
+Filter 2: ON PK Fc 2200 Hz Gain 4.1 dB Q 1.20
+Filter 3: OFF PK Fc 5000 Hz Gain 2.0 dB Q 1.00
+Filter 4: ON LPQ Fc 20 Hz Q 0.70
+";
+
+    // AutoEQ's newer profiles bracket the peaking filters with shelves
+    const AUTOEQ_SHELF_PROFILE: &str = "\
+Preamp: -5.8 dB
+Filter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70
+Filter 2: ON PK Fc 190 Hz Gain -3.1 dB Q 0.55
+Filter 3: ON PK Fc 3300 Hz Gain 2.4 dB Q 2.10
+Filter 4: ON HSC Fc 10000 Hz Gain -4.0 dB Q 0.70
+";
+
+    #[test]
+    fn test_parse_autoeq_profile() {
+        let import = parse_parametric_eq(AUTOEQ_PROFILE);
+        assert_eq!(import.imported, 2);
+
+        // Filters retune the nearest bands (125 Hz and 2 kHz)
+        assert_eq!(import.settings.bands[2], EqBandParams::new(105.0, -3.5, 0.7));
+        assert_eq!(import.settings.bands[6], EqBandParams::new(2200.0, 4.1, 1.2));
+        assert_eq!(import.settings.bands[0].gain_db, 0.0);
+
+        assert_eq!(
+            import.skipped,
+            vec![
+                String::from("preamp -6.2 dB (use volume instead)"),
+                String::from("Filter 4 (LPQ filters not supported)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rew_spacing() {
+        let import =
+            parse_parametric_eq("Filter  1: ON  PK       Fc   1000.0 Hz  Gain  -2.50 dB  Q  4.000");
+        assert_eq!(import.imported, 1);
+        assert_eq!(import.settings.bands[5], EqBandParams::new(1000.0, -2.5, 4.0));
+    }
+
+    #[test]
+    fn test_extra_filters_keep_strongest() {
+        let profile: String = (1..=33)
+            .map(|i| format!("Filter {i}: ON PK Fc 1000 Hz Gain {} dB Q 1.0\n", i as f32 / 2.0))
+            .collect();
+        let import = parse_parametric_eq(&profile);
+        assert_eq!(import.imported, 31);
+        assert_eq!(
+            import.skipped,
+            vec![
+                String::from("Filter 2 (only 31 bands)"),
+                String::from("Filter 1 (only 31 bands)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_autoeq_shelves() {
+        let settings = EqSettings::from_autoeq_str(AUTOEQ_SHELF_PROFILE).unwrap();
+        assert_eq!(settings.band_count(), 10);
+
+        let low_shelf = &settings.bands[2];
+        assert_eq!(low_shelf.filter_type, EqFilterType::LowShelf);
+        assert_eq!((low_shelf.frequency, low_shelf.gain_db, low_shelf.q_value), (105.0, 5.5, 0.7));
+        assert_eq!(settings.bands[3], EqBandParams::new(190.0, -3.1, 0.55));
+        assert_eq!(settings.bands[7], EqBandParams::new(3300.0, 2.4, 2.1));
+        let high_shelf = &settings.bands[8];
+        assert_eq!(high_shelf.filter_type, EqFilterType::HighShelf);
+        assert_eq!(high_shelf.gain_db, -4.0);
+
+        // Untouched bands stay flat
+        assert_eq!(settings.bands[0].gain_db, 0.0);
+        assert_eq!(settings.bands[9].gain_db, 0.0);
+    }
+
+    #[test]
+    fn test_shelves_without_q() {
+        // Equalizer APO style
+        let settings = EqSettings::from_autoeq_str("Filter 1: ON LS Fc 80 Hz Gain 3 dB").unwrap();
+        assert_eq!(settings.bands[1].filter_type, EqFilterType::LowShelf);
+        assert_eq!(settings.bands[1].q_value, SHELF_Q);
+
+        let import = parse_parametric_eq("Filter 1: ON PK Fc 80 Hz Gain 3 dB");
+        assert_eq!(import.skipped, vec![String::from("Filter 1 (missing Q)")]);
+        assert!(EqSettings::from_autoeq_str("Filter 1: ON PK Fc 80 Hz Gain 3 dB").is_err());
+    }
+
+    #[test]
+    fn test_long_profiles_get_more_bands() {
+        let profile: String = (1..=12)
+            .map(|i| format!("Filter {i}: ON PK Fc {} Hz Gain -1 dB Q 1.0\n", 100 * i))
+            .collect();
+        let settings = EqSettings::from_autoeq_str(&profile).unwrap();
+        assert_eq!(settings.band_count(), 15);
+        assert_eq!(settings.bands.iter().filter(|b| b.gain_db == -1.0).count(), 12);
+    }
+
+    #[test]
+    fn test_bands_stay_in_frequency_order() {
+        // 1200 Hz finds 1 kHz and 2 kHz taken and lands on the 500 Hz band
+        let import = parse_parametric_eq(
+            "Filter 1: ON PK Fc 1000 Hz Gain 6 dB Q 1.0\n\
+             Filter 2: ON PK Fc 1900 Hz Gain 5 dB Q 1.0\n\
+             Filter 3: ON PK Fc 1200 Hz Gain 4 dB Q 1.0",
+        );
+        assert_eq!(import.imported, 3);
+        let frequencies: Vec<f32> = import.settings.bands.iter().map(|b| b.frequency).collect();
+        assert!(frequencies.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", frequencies);
+        assert_eq!(import.settings.bands[4], EqBandParams::new(1000.0, 6.0, 1.0));
+        assert_eq!(import.settings.bands[5], EqBandParams::new(1200.0, 4.0, 1.0));
+        assert_eq!(import.settings.bands[6], EqBandParams::new(1900.0, 5.0, 1.0));
+    }
+
+    #[test]
+    fn test_import_file() {
+        let path = std::env::temp_dir().join(format!("wavewire-autoeq-{}.txt", std::process::id()));
+        std::fs::write(&path, AUTOEQ_PROFILE).unwrap();
+        let import = import_parametric_eq(path.to_str().unwrap()).unwrap();
+        assert_eq!(import.settings, EqSettings::from_autoeq_str(AUTOEQ_PROFILE).unwrap());
+        assert_eq!(import.imported, 2);
+        assert_eq!(import.skipped.len(), 2);
+
+        // Nothing applicable fails instead of flattening the EQ
+        std::fs::write(&path, "Filter 1: ON LPQ Fc 20 Hz Q 0.70").unwrap();
+        let error = import_parametric_eq(path.to_str().unwrap()).unwrap_err();
+        assert!(format!("{:#}", error).contains("skipped Filter 1"), "{:#}", error);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(import_parametric_eq(path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped() {
+        let import = parse_parametric_eq("Filter 1: ON PK Fc 60 Hz Gain 30 dB Q 8");
+        assert_eq!(import.imported, 1);
+        assert_eq!(import.settings.bands[1], EqBandParams::new(60.0, 24.0, 5.0));
+        assert_eq!(
+            import.summary(),
+            "Imported 1 filter; clamped Filter 1 gain to +24.0 dB, Filter 1 Q to 5.00"
+        );
+    }
+}
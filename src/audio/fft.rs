@@ -1,6 +1,20 @@
 use rustfft::{num_complex::Complex, FftPlanner};
 use std::f32::consts::PI;
 
+/// Smallest FFT size that yields a positive-frequency bin; smaller sizes are raised to it
+pub const MIN_FFT_SIZE: usize = 2;
+
+/// FFT sizes that can be chosen at runtime (powers of two only)
+pub const FFT_SIZE_RANGE: std::ops::RangeInclusive<usize> = 256..=16384;
+
+/// Whether `fft_size` can be chosen at runtime: a power of two in `FFT_SIZE_RANGE`
+pub fn is_valid_fft_size(fft_size: usize) -> bool {
+    fft_size.is_power_of_two() && FFT_SIZE_RANGE.contains(&fft_size)
+}
+
+/// Lowest level a bin reports, in dB
+const FLOOR_DB: f32 = -60.0;
+
 /// FFT processor for converting audio samples to frequency spectrum
 pub struct FftProcessor {
     /// FFT size (number of samples to process)
@@ -23,10 +37,14 @@ impl FftProcessor {
     /// Create a new FFT processor
     ///
     /// # Arguments
-    /// * `fft_size` - Size of the FFT (power of 2, typically 2048)
+    /// * `fft_size` - Size of the FFT (typically 2048; any size of at least
+    ///   `MIN_FFT_SIZE` works, but powers of 2 are fastest). Smaller sizes are raised
     /// * `num_bins` - Number of output frequency bins for display (typically 64-128)
     /// * `sample_rate` - Sample rate of the audio source (Hz)
+    #[cfg(any(test, feature = "jack"))]
     pub fn new(fft_size: usize, num_bins: usize, sample_rate: u32) -> Self {
+        let fft_size = fft_size.max(MIN_FFT_SIZE);
+
         // Generate Hann window
         let window = Self::generate_hann_window(fft_size);
 
@@ -44,11 +62,26 @@ impl FftProcessor {
         }
     }
 
+    /// Switch to a new FFT size, keeping the bin count and sample rate
+    /// Regenerates the window and bin edges; the scratch buffer is reallocated,
+    /// so call this from the thread that owns the processor, not per frame
+    pub fn reconfigure(&mut self, fft_size: usize) {
+        let fft_size = fft_size.max(MIN_FFT_SIZE);
+        self.fft_size = fft_size;
+        self.window = Self::generate_hann_window(fft_size);
+        self.bin_edges = Self::generate_log_bin_edges(self.num_bins, self.sample_rate);
+        self.fft_input = vec![Complex::new(0.0, 0.0); fft_size];
+    }
+
     /// Generate a Hann window function
     ///
     /// The Hann window reduces spectral leakage by smoothly tapering the signal
     /// to zero at the edges of the window.
     fn generate_hann_window(size: usize) -> Vec<f32> {
+        // A single sample has no edges to taper
+        if size < 2 {
+            return vec![1.0; size];
+        }
         (0..size)
             .map(|i| {
                 let phase = 2.0 * PI * i as f32 / (size - 1) as f32;
@@ -87,8 +120,8 @@ impl FftProcessor {
     /// - bin_frequencies: Center frequency of each bin in Hz
     pub fn process(&mut self, samples: &[f32]) -> (Vec<f32>, Vec<f32>) {
         if samples.len() < self.fft_size {
-            // Not enough samples, return empty result
-            return (vec![0.0; self.num_bins], self.bin_centers());
+            // Not enough samples (e.g. right after the size grew): report silence
+            return self.silence();
         }
 
         // Take the most recent fft_size samples
@@ -108,7 +141,8 @@ impl FftProcessor {
         // Convert FFT output to magnitudes
         let magnitudes: Vec<f32> = self.fft_input
             .iter()
-            .take(self.fft_size / 2) // Only use positive frequencies
+            // Only use positive frequencies (odd sizes have no Nyquist bin to drop)
+            .take(self.fft_size.div_ceil(2))
             .map(|c| {
                 // Calculate magnitude: sqrt(re^2 + im^2)
                 let mag = c.norm();
@@ -117,7 +151,7 @@ impl FftProcessor {
                 // Convert to dB (with floor to avoid log(0))
                 let db = 20.0 * (normalized.max(1e-10)).log10();
                 // Clamp to reasonable range
-                db.max(-60.0).min(0.0)
+                db.clamp(FLOOR_DB, 0.0)
             })
             .collect();
 
@@ -142,14 +176,14 @@ impl FftProcessor {
             let bin_start = (freq_start / freq_per_bin).floor() as usize;
             let bin_end = (freq_end / freq_per_bin).ceil() as usize;
 
-            // Average the magnitudes in this range
-            let count = (bin_end - bin_start).max(1);
-            let sum: f32 = magnitudes
-                .iter()
-                .skip(bin_start)
-                .take(count)
-                .sum();
-            let avg = sum / count as f32;
+            // Average the magnitudes in this range; a small FFT may not reach it at all
+            let end = bin_end.max(bin_start + 1).min(magnitudes.len());
+            let in_range = magnitudes.get(bin_start..end).unwrap_or(&[]);
+            let avg = if in_range.is_empty() {
+                -60.0
+            } else {
+                in_range.iter().sum::<f32>() / in_range.len() as f32
+            };
 
             binned.push(avg);
         }
@@ -157,6 +191,11 @@ impl FftProcessor {
         binned
     }
 
+    /// Flat spectrum at the floor level, shaped like the output of `process`
+    pub fn silence(&self) -> (Vec<f32>, Vec<f32>) {
+        (vec![FLOOR_DB; self.num_bins], self.bin_centers())
+    }
+
     /// Get the center frequency for each bin
     fn bin_centers(&self) -> Vec<f32> {
         (0..self.num_bins)
@@ -181,13 +220,18 @@ mod tests {
 
     #[test]
     fn test_hann_window() {
-        let window = FftProcessor::generate_hann_window(8);
-        assert_eq!(window.len(), 8);
+        // Odd size, so there is a middle sample for the peak to land on
+        let window = FftProcessor::generate_hann_window(9);
+        assert_eq!(window.len(), 9);
         // First and last values should be close to 0
         assert!(window[0] < 0.01);
-        assert!(window[7] < 0.01);
+        assert!(window[8] < 0.01);
         // Middle value should be close to 1
         assert!((window[4] - 1.0).abs() < 0.01);
+        // And the taper is symmetric
+        for i in 0..4 {
+            assert!((window[i] - window[8 - i]).abs() < 1e-6);
+        }
     }
 
     #[test]
@@ -243,4 +287,61 @@ mod tests {
             peak_freq
         );
     }
+
+    #[test]
+    fn test_reconfigure_fft_size() {
+        let mut processor = FftProcessor::new(2048, 64, 48000);
+        let samples: Vec<f32> = (0..4096)
+            .map(|i| (2.0 * PI * 1000.0 * i as f32 / 48000.0).sin())
+            .collect();
+
+        processor.reconfigure(4096);
+        assert_eq!(processor.fft_size(), 4096);
+        assert_eq!(processor.window.len(), 4096);
+        let (magnitudes, frequencies) = processor.process(&samples);
+        assert_eq!(magnitudes.len(), 64);
+        let peak = (0..64).max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b])).unwrap();
+        assert!((frequencies[peak] - 1000.0).abs() < 150.0);
+
+        // Fewer samples than the new size reads as silence, not full scale
+        processor.reconfigure(8192);
+        assert_eq!(processor.process(&samples).0, vec![FLOOR_DB; 64]);
+
+        assert!(is_valid_fft_size(256) && is_valid_fft_size(16384));
+        assert!(!is_valid_fft_size(128) && !is_valid_fft_size(3000) && !is_valid_fft_size(32768));
+    }
+
+    #[test]
+    fn test_tiny_fft_sizes() {
+        for size in [0, 1, 2] {
+            let mut processor = FftProcessor::new(size, 16, 48000);
+            assert_eq!(processor.fft_size(), size.max(MIN_FFT_SIZE));
+
+            let (magnitudes, frequencies) = processor.process(&[0.5, -0.5]);
+            assert_eq!(magnitudes.len(), 16);
+            assert_eq!(frequencies.len(), 16);
+            assert!(magnitudes.iter().all(|db| db.is_finite() && *db <= 0.0));
+        }
+        assert_eq!(FftProcessor::generate_hann_window(1), vec![1.0]);
+        assert_eq!(FftProcessor::generate_hann_window(2), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_odd_fft_size() {
+        // 1023 = 3 · 11 · 31, nowhere near a power of two
+        let mut processor = FftProcessor::new(1023, 64, 48000);
+        let samples: Vec<f32> = (0..1023)
+            .map(|i| (2.0 * PI * 3000.0 * i as f32 / 48000.0).sin())
+            .collect();
+
+        let (magnitudes, frequencies) = processor.process(&samples);
+        let peak = (0..magnitudes.len())
+            .max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b]))
+            .unwrap();
+        assert!(
+            (frequencies[peak] - 3000.0).abs() < 500.0,
+            "Expected peak near 3 kHz, got {} Hz",
+            frequencies[peak]
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 
+use super::crossfeed::CrossfeedSettings;
 use super::eq::EqSettings;
-use super::types::{DeviceId, DeviceType, PortId, PortInfo};
+use super::types::{DeviceId, DeviceType, PortDirection, PortId, PortInfo};
 use super::volume::VolumeSettings;
 
 /// Information about an audio device
@@ -11,8 +13,19 @@ pub struct DeviceInfo {
     pub name: String,
     pub device_type: DeviceType,
     pub ports: Vec<PortInfo>,
+    /// EQ running on this device (None = EQ off), updated by the audio thread
+    /// whenever EQ is enabled, changed or disabled
     pub eq_settings: Option<EqSettings>,
     pub volume_settings: Option<VolumeSettings>,
+    pub crossfeed_settings: Option<CrossfeedSettings>,
+    pub saturation_drive: Option<f32>,
+    pub stereo_width: Option<f32>,
+    /// Owning application for stream nodes (None for sinks/sources)
+    pub application_name: Option<String>,
+    /// PipeWire media class (e.g. "Audio/Sink", "Stream/Output/Audio")
+    pub media_class: Option<String>,
+    /// PipeWire `node.name`, as referenced by the "default" metadata
+    pub node_name: Option<String>,
 }
 
 impl DeviceInfo {
@@ -24,8 +37,21 @@ impl DeviceInfo {
             ports: Vec::new(),
             eq_settings: None,
             volume_settings: None,
+            crossfeed_settings: None,
+            saturation_drive: None,
+            stereo_width: None,
+            application_name: None,
+            media_class: None,
+            node_name: None,
         }
     }
+
+    /// Whether this is an application stream (browser tab, player) rather than a sink/source
+    pub fn is_stream(&self) -> bool {
+        self.media_class
+            .as_deref()
+            .is_some_and(|class| class.starts_with("Stream/"))
+    }
 }
 
 /// A connection between two ports
@@ -44,6 +70,54 @@ impl Connection {
     }
 }
 
+/// A connection seen from one of a device's ports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortConnection {
+    /// Short name of the device's own port (e.g. "monitor_FL")
+    pub port_label: String,
+    /// PipeWire name of the device's own port
+    pub port_name: String,
+    /// PipeWire name of the port on the other end
+    pub peer_name: String,
+    /// Output: audio flows from the port to the peer; Input: from the peer to the port
+    pub direction: PortDirection,
+}
+
+impl PortConnection {
+    /// (source, destination) PipeWire port names, as used by connect/disconnect
+    pub fn endpoints(&self) -> (&str, &str) {
+        match self.direction {
+            PortDirection::Output => (&self.port_name, &self.peer_name),
+            PortDirection::Input => (&self.peer_name, &self.port_name),
+        }
+    }
+}
+
+/// Why a PipeWire port name didn't resolve to exactly one port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortLookupError {
+    /// No port has this name
+    NotFound { name: String },
+    /// Several ports share the name (e.g. while a device is being re-added)
+    Ambiguous { name: String, ports: Vec<PortId> },
+}
+
+impl fmt::Display for PortLookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortLookupError::NotFound { name } => write!(f, "Port not found: {}", name),
+            PortLookupError::Ambiguous { name, ports } => write!(
+                f,
+                "Port name {} is ambiguous ({} ports share it), try again once devices settle",
+                name,
+                ports.len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PortLookupError {}
+
 /// Graph tracking all audio devices and connections
 pub struct RoutingGraph {
     /// All known devices (physical and virtual)
@@ -91,6 +165,24 @@ impl RoutingGraph {
         self.devices.remove(&device_id)
     }
 
+    /// Remove a port from its device, along with the connections it was part of
+    /// Returns the removed port, or None if the device doesn't have it
+    pub fn remove_port(&mut self, device_id: DeviceId, port_id: PortId) -> Option<PortInfo> {
+        let device = self.devices.get_mut(&device_id)?;
+        let index = device.ports.iter().position(|port| port.id == port_id)?;
+        let port = device.ports.remove(index);
+        self.connections
+            .retain(|conn| conn.source != port_id && conn.destination != port_id);
+        Some(port)
+    }
+
+    /// Remove all devices and connections (ID counters keep counting so
+    /// stale IDs held elsewhere never alias new devices)
+    pub fn clear(&mut self) {
+        self.devices.clear();
+        self.connections.clear();
+    }
+
     /// Get a device by ID
     pub fn get_device(&self, device_id: DeviceId) -> Option<&DeviceInfo> {
         self.devices.get(&device_id)
@@ -131,13 +223,67 @@ impl RoutingGraph {
         self.connections.iter().collect()
     }
 
-    /// Find a port by its PipeWire port name
-    pub fn find_port_by_name(&self, port_name: &str) -> Option<PortId> {
-        self.devices
+    /// Connections of every port of a device, in port order, peers sorted by name
+    /// Connections to ports that are no longer known are left out
+    pub fn device_connections(&self, device_id: DeviceId) -> Vec<PortConnection> {
+        let Some(device) = self.devices.get(&device_id) else {
+            return Vec::new();
+        };
+
+        let mut connections = Vec::new();
+        for port in &device.ports {
+            let mut peers: Vec<&str> = self
+                .get_connections_for_port(port.id)
+                .into_iter()
+                .filter_map(|conn| {
+                    let peer = if conn.source == port.id {
+                        conn.destination
+                    } else {
+                        conn.source
+                    };
+                    self.find_port_name(peer)
+                })
+                .collect();
+            peers.sort_unstable();
+
+            connections.extend(peers.into_iter().map(|peer| PortConnection {
+                port_label: port.name.clone(),
+                port_name: port.pipewire_port_name.clone(),
+                peer_name: peer.to_string(),
+                direction: port.direction,
+            }));
+        }
+        connections
+    }
+
+    /// Find all ports with a PipeWire port name, oldest first
+    /// Names are composed from node and port names, so they aren't guaranteed unique
+    pub fn find_ports_by_name(&self, port_name: &str) -> Vec<PortId> {
+        let mut ports: Vec<PortId> = self
+            .devices
             .values()
             .flat_map(|device| &device.ports)
-            .find(|port| port.pipewire_port_name == port_name)
+            .filter(|port| port.pipewire_port_name == port_name)
             .map(|port| port.id)
+            .collect();
+        ports.sort_by_key(|id| id.0);
+        ports
+    }
+
+    /// Find the single port with a PipeWire port name
+    /// Errors instead of guessing when several ports share the name, so a
+    /// connection never lands on the wrong device
+    pub fn find_port_by_name(&self, port_name: &str) -> Result<PortId, PortLookupError> {
+        match self.find_ports_by_name(port_name).as_slice() {
+            [] => Err(PortLookupError::NotFound {
+                name: port_name.to_string(),
+            }),
+            [port] => Ok(*port),
+            ports => Err(PortLookupError::Ambiguous {
+                name: port_name.to_string(),
+                ports: ports.to_vec(),
+            }),
+        }
     }
 
     /// Find a port name by its PortId
@@ -155,3 +301,111 @@ impl Default for RoutingGraph {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Add a device with one output port named `port_name`
+    fn add_device_with_port(graph: &mut RoutingGraph, name: &str, port_name: &str) -> PortId {
+        let device_id = graph.generate_device_id();
+        let port_id = graph.generate_port_id();
+        let mut device = DeviceInfo::new(device_id, name.to_string(), DeviceType::Physical);
+        device.ports.push(PortInfo::new(
+            port_id,
+            String::from("output_FL"),
+            PortDirection::Output,
+            port_name.to_string(),
+        ));
+        graph.add_device(device);
+        port_id
+    }
+
+    #[test]
+    fn test_find_port_by_name() {
+        let mut graph = RoutingGraph::new();
+        let port = add_device_with_port(&mut graph, "Speakers", "Speakers:output_FL");
+
+        assert_eq!(graph.find_port_by_name("Speakers:output_FL"), Ok(port));
+        assert_eq!(
+            graph.find_port_by_name("Headphones:output_FL"),
+            Err(PortLookupError::NotFound {
+                name: String::from("Headphones:output_FL")
+            })
+        );
+    }
+
+    #[test]
+    fn test_duplicate_port_names_are_ambiguous() {
+        let mut graph = RoutingGraph::new();
+        let old = add_device_with_port(&mut graph, "Speakers", "Speakers:output_FL");
+        let new = add_device_with_port(&mut graph, "Speakers", "Speakers:output_FL");
+
+        assert_eq!(graph.find_ports_by_name("Speakers:output_FL"), vec![old, new]);
+        assert_eq!(
+            graph.find_port_by_name("Speakers:output_FL"),
+            Err(PortLookupError::Ambiguous {
+                name: String::from("Speakers:output_FL"),
+                ports: vec![old, new],
+            })
+        );
+    }
+
+    #[test]
+    fn test_device_connections() {
+        let mut graph = RoutingGraph::new();
+        let source = add_device_with_port(&mut graph, "Player", "Player:output_FL");
+        let speakers_id = graph.generate_device_id();
+        let input = graph.generate_port_id();
+        let mut speakers = DeviceInfo::new(speakers_id, String::from("Speakers"), DeviceType::Physical);
+        speakers.ports.push(PortInfo::new(
+            input,
+            String::from("playback_FL"),
+            PortDirection::Input,
+            String::from("Speakers:playback_FL"),
+        ));
+        graph.add_device(speakers);
+        graph.add_connection(Connection::new(source, input));
+        // Peer that has since disappeared from the graph
+        graph.add_connection(Connection::new(PortId::new(99), input));
+
+        let connections = graph.device_connections(speakers_id);
+        assert_eq!(
+            connections,
+            vec![PortConnection {
+                port_label: String::from("playback_FL"),
+                port_name: String::from("Speakers:playback_FL"),
+                peer_name: String::from("Player:output_FL"),
+                direction: PortDirection::Input,
+            }]
+        );
+        assert_eq!(connections[0].endpoints(), ("Player:output_FL", "Speakers:playback_FL"));
+    }
+
+    #[test]
+    fn test_remove_port() {
+        let mut graph = RoutingGraph::new();
+        let old = add_device_with_port(&mut graph, "Speakers", "Speakers:output_FL");
+        let speakers_id = DeviceId::new(1);
+        let input = add_device_with_port(&mut graph, "Recorder", "Recorder:input_FL");
+        graph.add_connection(Connection::new(old, input));
+
+        let removed = graph.remove_port(speakers_id, old).unwrap();
+        assert_eq!(removed.pipewire_port_name, "Speakers:output_FL");
+        assert!(graph.get_device(speakers_id).unwrap().ports.is_empty());
+        assert!(graph.list_connections().is_empty());
+        assert!(graph.find_port_by_name("Speakers:output_FL").is_err());
+
+        // The port comes back under a new ID without clashing with the stale one
+        let new = graph.generate_port_id();
+        graph.get_device_mut(speakers_id).unwrap().ports.push(PortInfo::new(
+            new,
+            String::from("output_FL"),
+            PortDirection::Output,
+            String::from("Speakers:output_FL"),
+        ));
+        assert_eq!(graph.find_port_by_name("Speakers:output_FL"), Ok(new));
+        assert!(graph.remove_port(speakers_id, old).is_none());
+        assert!(graph.remove_port(DeviceId::new(9), new).is_none());
+    }
+}
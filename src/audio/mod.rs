@@ -1,23 +1,81 @@
 mod client;
+mod clock;
+mod command_stats;
+mod crossfeed;
+mod defaults;
 mod device;
 mod eq;
+mod eq_import;
 mod fft;
 mod graph;
+mod monitor_ports;
+mod pairing;
+#[cfg(feature = "recording")]
+mod recorder;
+mod saturation;
 mod stream;
 mod types;
 mod volume;
+mod width;
 
-pub use eq::{EqBandParams, EqSettings, GRAPHIC_EQ_BANDS};
-pub use graph::DeviceInfo;
-pub use types::{AudioCommand, AudioEvent, DeviceId, DeviceType, PortDirection, PortId, PortInfo, SpectrumData};
-pub use volume::{VolumeSettings, VolumeProcessor, update_volume_settings};
+pub use clock::GraphClock;
+pub use command_stats::{CommandCounts, CommandStats};
+pub use crossfeed::CrossfeedSettings;
+pub use defaults::DefaultNodes;
+pub use eq::{bandwidth_octaves, clamp_eq_gain, clamp_eq_q, EqBandParams, EqPreset, EqSettings, EQ_BAND_COUNTS, GRAPHIC_EQ_BANDS, MAX_EQ_GAIN_DB};
+pub use eq_import::import_parametric_eq;
+pub use fft::FFT_SIZE_RANGE;
+pub use graph::{DeviceInfo, PortConnection, PortLookupError};
+pub use monitor_ports::{default_monitor_port_patterns, MonitorPortPattern};
+pub use pairing::{PairingStrategy, PortPairing};
+pub use saturation::clamp_saturation_drive;
+pub use width::clamp_stereo_width;
+#[cfg(test)]
+pub use graph::RoutingGraph;
+#[cfg(test)]
+pub use monitor_ports::PortMatch;
+pub use stream::{probe_jack_server, DEFAULT_FFT_SIZE, MAX_REFRESH_RATE_HZ};
+pub use types::{AudioCommand, AudioEvent, DeviceId, DeviceType, PortDirection, PortId, PortInfo, SpectrumData, StreamHealth};
+pub use volume::VolumeSettings;
 
-use anyhow::Result;
-use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender, TrySendError};
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
 
 use client::PipeWireClient;
+use crate::error::{Result, WavewireError};
+
+/// Capacity of the UI → audio command channel
+const COMMAND_QUEUE_CAPACITY: usize = 100;
+
+/// Why a command could not be delivered to the audio thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandError {
+    /// The command queue is full (audio thread is stalled); the command was dropped
+    Busy,
+    /// The audio thread has shut down
+    Disconnected,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Busy => write!(f, "Audio engine busy, try again"),
+            CommandError::Disconnected => write!(f, "Audio thread is not running"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
 
 /// Main audio engine managing PipeWire client and routing
+///
+/// Threading: the engine is owned by the UI thread. PipeWire objects are `Rc`-based
+/// and live only on the event-loop thread spawned by `start`; the engine reaches them
+/// solely through the command/event channels and the `Arc<RwLock<..>>` routing graph.
+/// The engine is `Send` (it can be moved to another thread) but not `Sync`: the
+/// held-back command queue is a `RefCell`, so it must not be shared by reference.
 pub struct AudioEngine {
     /// PipeWire client wrapper
     pipewire_client: Option<PipeWireClient>,
@@ -25,22 +83,51 @@ pub struct AudioEngine {
     event_rx: Receiver<AudioEvent>,
     /// Channel for sending commands to audio thread
     command_tx: Sender<AudioCommand>,
+    /// Coalescable commands held back while the command channel is full
+    pending_commands: RefCell<VecDeque<AudioCommand>>,
+    /// Sent/handled/dropped tally per command variant, shared with the audio thread
+    command_stats: CommandStats,
 }
 
+// Compile-time checks of the threading model above: everything crossing a thread
+// boundary must stay Send (a non-Send field added later fails the build here)
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<AudioEngine>();
+    assert_send::<AudioCommand>();
+    assert_send::<AudioEvent>();
+};
+
+// ...and the engine must stay !Sync. A compile_fail doctest would never run in a
+// binary crate, so this uses the ambiguous-impl trick instead: if the engine were
+// Sync, both impls below would apply and the path to `check` would not compile
+const _: () = {
+    trait AmbiguousIfSync<A> {
+        fn check() {}
+    }
+    impl<T: ?Sized> AmbiguousIfSync<()> for T {}
+    struct IsSync;
+    impl<T: ?Sized + Sync> AmbiguousIfSync<IsSync> for T {}
+    let _ = <AudioEngine as AmbiguousIfSync<_>>::check;
+};
+
 impl AudioEngine {
     /// Create a new audio engine
     pub fn new() -> Result<Self> {
         // Create channels for communication between UI and audio threads
         let (event_tx, event_rx) = unbounded(); // Events from audio → UI
-        let (command_tx, command_rx) = bounded(100); // Commands from UI → audio
+        let (command_tx, command_rx) = bounded(COMMAND_QUEUE_CAPACITY); // Commands from UI → audio
 
         // Create PipeWire client with event and command channels
-        let pipewire_client = PipeWireClient::new(event_tx, command_rx)?;
+        let command_stats = CommandStats::default();
+        let pipewire_client = PipeWireClient::new(event_tx, command_rx, command_stats.clone())?;
 
         Ok(Self {
             pipewire_client: Some(pipewire_client),
             event_rx,
             command_tx,
+            pending_commands: RefCell::new(VecDeque::new()),
+            command_stats,
         })
     }
 
@@ -49,7 +136,7 @@ impl AudioEngine {
         if let Some(ref mut pipewire_client) = self.pipewire_client {
             pipewire_client.activate()?;
         } else {
-            anyhow::bail!("PipeWire client not initialized");
+            return Err(WavewireError::NotRunning);
         }
 
         Ok(())
@@ -64,19 +151,114 @@ impl AudioEngine {
     }
 
     /// Poll for events from the audio thread (non-blocking)
+    /// Spectrum updates are coalesced to the latest per device, so a stalled UI
+    /// catches up in one frame instead of replaying stale spectra
     pub fn poll_events(&self) -> Vec<AudioEvent> {
         let mut events = Vec::new();
         while let Ok(event) = self.event_rx.try_recv() {
             events.push(event);
         }
-        events
+        coalesce_spectrum_updates(events)
+    }
+
+    /// Send a command to the audio thread without blocking
+    ///
+    /// If the queue is full, "latest value wins" commands (EQ band, EQ settings,
+    /// volume) are held back and coalesced, then delivered by `flush_pending_commands`.
+    /// Other commands fail with `CommandError::Busy` so the UI can ask the user to retry.
+    pub fn send_command(&self, command: AudioCommand) -> Result<(), CommandError> {
+        let name = command.name();
+        self.command_stats.record_sent(name);
+        let result = self.deliver(command);
+        if result.is_err() {
+            self.command_stats.record_dropped(name);
+        }
+        result
     }
 
-    /// Send a command to the audio thread
-    pub fn send_command(&self, command: AudioCommand) -> Result<()> {
-        self.command_tx
-            .send(command)
-            .map_err(|e| anyhow::anyhow!("Failed to send command: {}", e))
+    fn deliver(&self, command: AudioCommand) -> Result<(), CommandError> {
+        // Keep ordering: anything held back must go out before new commands
+        self.flush_pending_commands()?;
+
+        if !self.pending_commands.borrow().is_empty() {
+            return self.hold_back(command);
+        }
+
+        match self.command_tx.try_send(command) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(command)) => self.hold_back(command),
+            Err(TrySendError::Disconnected(_)) => Err(CommandError::Disconnected),
+        }
+    }
+
+    /// Retry delivery of commands held back while the queue was full
+    /// Call periodically (e.g. once per frame)
+    pub fn flush_pending_commands(&self) -> Result<(), CommandError> {
+        let mut pending = self.pending_commands.borrow_mut();
+        while let Some(command) = pending.pop_front() {
+            match self.command_tx.try_send(command) {
+                Ok(()) => {}
+                Err(TrySendError::Full(command)) => {
+                    pending.push_front(command);
+                    break;
+                }
+                Err(TrySendError::Disconnected(command)) => {
+                    self.command_stats.record_dropped(command.name());
+                    for command in pending.drain(..) {
+                        self.command_stats.record_dropped(command.name());
+                    }
+                    return Err(CommandError::Disconnected);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue a command locally while the channel is full, replacing any queued
+    /// command it supersedes; non-coalescable commands are rejected as busy
+    fn hold_back(&self, command: AudioCommand) -> Result<(), CommandError> {
+        if !command.is_coalescable() {
+            return Err(CommandError::Busy);
+        }
+
+        let mut pending = self.pending_commands.borrow_mut();
+        // Re-append rather than replace in place so ordering against other
+        // held-back commands for the same device is preserved
+        pending.retain(|queued| {
+            let superseded = command.supersedes(queued);
+            if superseded {
+                self.command_stats.record_dropped(queued.name());
+            }
+            !superseded
+        });
+        pending.push_back(command);
+        crate::debug_log!(
+            "[COMMAND] Queue full, holding back {} command(s)",
+            pending.len()
+        );
+        Ok(())
+    }
+
+    /// Drop every command held back while the queue was full
+    /// Returns how many were dropped; commands already in the channel still run
+    pub fn cancel_pending_commands(&self) -> usize {
+        let mut pending = self.pending_commands.borrow_mut();
+        for command in pending.iter() {
+            self.command_stats.record_dropped(command.name());
+        }
+        let count = pending.len();
+        pending.clear();
+        count
+    }
+
+    /// Commands waiting in the channel and held back behind it
+    pub fn queued_commands(&self) -> (usize, usize) {
+        (self.command_tx.len(), self.pending_commands.borrow().len())
+    }
+
+    /// Sent/handled/dropped tally per command variant
+    pub fn command_stats(&self) -> Vec<(&'static str, CommandCounts)> {
+        self.command_stats.snapshot()
     }
 
     /// List all discovered audio devices
@@ -85,30 +267,331 @@ impl AudioEngine {
             let graph = pipewire_client.routing_graph().read().unwrap();
             Ok(graph.list_devices().into_iter().cloned().collect())
         } else {
-            anyhow::bail!("PipeWire client not initialized")
+            Err(WavewireError::NotRunning)
+        }
+    }
+
+    /// Current PipeWire graph clock (quantum and sample rate)
+    /// All zero until the "settings" metadata has been read
+    pub fn graph_clock(&self) -> Result<GraphClock> {
+        if let Some(ref pipewire_client) = self.pipewire_client {
+            Ok(*pipewire_client.graph_clock().read().unwrap())
+        } else {
+            Err(WavewireError::NotRunning)
+        }
+    }
+
+    /// Default sink and source names from the PipeWire "default" metadata
+    /// Both `None` until that metadata has been read
+    pub fn default_nodes(&self) -> Result<DefaultNodes> {
+        if let Some(ref pipewire_client) = self.pipewire_client {
+            Ok(pipewire_client.default_nodes().read().unwrap().clone())
+        } else {
+            Err(WavewireError::NotRunning)
+        }
+    }
+
+    /// Device of the PipeWire node with this global ID
+    /// None if the ID isn't a node wavewire tracks (or hasn't been seen yet)
+    pub fn device_for_node(&self, node_id: u32) -> Result<Option<DeviceId>> {
+        if let Some(ref pipewire_client) = self.pipewire_client {
+            Ok(pipewire_client.pw_node_map().read().unwrap().get(&node_id).copied())
+        } else {
+            Err(WavewireError::NotRunning)
+        }
+    }
+
+    /// Connections of a device's ports to other ports
+    pub fn device_connections(&self, device_id: DeviceId) -> Result<Vec<PortConnection>> {
+        if let Some(ref pipewire_client) = self.pipewire_client {
+            let graph = pipewire_client.routing_graph().read().unwrap();
+            Ok(graph.device_connections(device_id))
+        } else {
+            Err(WavewireError::NotRunning)
+        }
+    }
+
+    /// Whether a link from `source_port` to `dest_port` (PipeWire port names) exists
+    pub fn ports_connected(&self, source_port: &str, dest_port: &str) -> Result<bool> {
+        if let Some(ref pipewire_client) = self.pipewire_client {
+            let graph = pipewire_client.routing_graph().read().unwrap();
+            let source = graph.find_port_by_name(source_port)?;
+            let dest = graph.find_port_by_name(dest_port)?;
+            Ok(graph
+                .get_connections_for_port(source)
+                .iter()
+                .any(|conn| conn.source == source && conn.destination == dest))
+        } else {
+            Err(WavewireError::NotRunning)
+        }
+    }
+
+    /// Link a device's output ports to another device's input ports
+    /// Ports are paired according to `strategy`; the result lists every link requested
+    /// and the ports left unpaired
+    pub fn connect_devices(
+        &self,
+        source: DeviceId,
+        destination: DeviceId,
+        strategy: PairingStrategy,
+    ) -> Result<PortPairing> {
+        let Some(ref pipewire_client) = self.pipewire_client else {
+            return Err(WavewireError::NotRunning);
+        };
+        let pairing = {
+            let graph = pipewire_client.routing_graph().read().unwrap();
+            let ports = |device_id, direction| {
+                graph
+                    .get_device(device_id)
+                    .map(|device| {
+                        device
+                            .ports
+                            .iter()
+                            .filter(|p| p.direction == direction)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    })
+                    .ok_or(WavewireError::DeviceNotFound(device_id))
+            };
+            pairing::pair_ports(
+                &ports(source, PortDirection::Output)?,
+                &ports(destination, PortDirection::Input)?,
+                strategy,
+            )
+        };
+
+        for link in &pairing.links {
+            crate::debug_log!("[CONNECT] {:?}: {}", strategy, link);
+            self.send_command(AudioCommand::Connect {
+                source_port: link.source_port.clone(),
+                dest_port: link.dest_port.clone(),
+            })?;
         }
+        Ok(pairing)
     }
 
-    /// Create a new virtual audio device
+    /// Create a new virtual audio device (a PipeWire null sink)
+    /// `DeviceAdded` follows once the node appears, or `Error` if it can't be created
     pub fn create_virtual_device(
-        &mut self,
+        &self,
         name: String,
         num_inputs: usize,
         num_outputs: usize,
-    ) -> Result<DeviceId> {
-        if let Some(ref mut pipewire_client) = self.pipewire_client {
-            pipewire_client.create_virtual_device(name, num_inputs, num_outputs)
-        } else {
-            anyhow::bail!("PipeWire client not initialized")
+    ) -> Result<()> {
+        if self.pipewire_client.is_none() {
+            return Err(WavewireError::NotRunning);
         }
+        self.send_command(AudioCommand::CreateVirtualDevice {
+            name,
+            num_inputs,
+            num_outputs,
+        })?;
+        Ok(())
     }
 
     /// Destroy a virtual audio device
-    pub fn destroy_virtual_device(&mut self, device_id: DeviceId) -> Result<()> {
-        if let Some(ref mut pipewire_client) = self.pipewire_client {
-            pipewire_client.destroy_virtual_device(device_id)
-        } else {
-            anyhow::bail!("PipeWire client not initialized")
+    /// `DeviceRemoved` follows once the node is gone
+    pub fn destroy_virtual_device(&self, device_id: DeviceId) -> Result<()> {
+        let Some(ref pipewire_client) = self.pipewire_client else {
+            return Err(WavewireError::NotRunning);
+        };
+        let is_virtual = {
+            let graph = pipewire_client.routing_graph().read().unwrap();
+            graph
+                .get_device(device_id)
+                .is_some_and(|device| device.device_type == DeviceType::Virtual)
+        };
+        if !is_virtual {
+            return Err(WavewireError::DeviceNotFound(device_id));
         }
+        self.send_command(AudioCommand::DestroyVirtualDevice { device_id })?;
+        Ok(())
+    }
+}
+
+/// Drop every spectrum update that is followed by a newer one for the same device
+/// (monitor and EQ output spectra are coalesced separately)
+/// The surviving events (all non-spectrum events included) keep their relative order
+fn coalesce_spectrum_updates(events: Vec<AudioEvent>) -> Vec<AudioEvent> {
+    let mut seen = HashSet::new();
+    #[cfg(any(test, feature = "jack"))]
+    let mut seen_eq_taps = HashSet::new();
+    let mut coalesced: Vec<AudioEvent> = events
+        .into_iter()
+        .rev()
+        .filter(|event| match event {
+            AudioEvent::SpectrumUpdate { device_id, .. } => seen.insert(*device_id),
+            #[cfg(any(test, feature = "jack"))]
+            AudioEvent::EqTapSpectrum { device_id, .. } => seen_eq_taps.insert(*device_id),
+            _ => true,
+        })
+        .collect();
+    coalesced.reverse();
+    coalesced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn set_band(band_index: usize, gain_db: f32) -> AudioCommand {
+        AudioCommand::SetEqBand {
+            device_id: DeviceId::new(1),
+            band_index,
+            gain_db,
+            q_value: 1.41,
+        }
+    }
+
+    /// Fill the command channel (the audio thread is never started)
+    fn full_engine() -> AudioEngine {
+        let engine = AudioEngine::new().unwrap();
+        for _ in 0..COMMAND_QUEUE_CAPACITY {
+            engine
+                .send_command(AudioCommand::StopVisualization {
+                    device_id: DeviceId::new(1),
+                })
+                .unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn test_full_queue_rejects_non_coalescable() {
+        let engine = full_engine();
+        let result = engine.send_command(AudioCommand::StopVisualization {
+            device_id: DeviceId::new(1),
+        });
+        assert_eq!(result, Err(CommandError::Busy));
+    }
+
+    #[test]
+    fn test_coalescable_commands_supersede_themselves() {
+        let device_id = DeviceId::new(1);
+        let commands = [
+            set_band(0, 1.0),
+            AudioCommand::SetEqSettings { device_id, settings: EqSettings::default() },
+            AudioCommand::SetVolume { device_id, settings: VolumeSettings::default() },
+            AudioCommand::SetCrossfeed { device_id, settings: CrossfeedSettings::default() },
+            AudioCommand::SetSaturation { device_id, drive: 0.5 },
+            AudioCommand::SetStereoWidth { device_id, width: 1.5 },
+            AudioCommand::StopVisualization { device_id },
+            AudioCommand::SetRefreshRate { hz: 30 },
+        ];
+        for command in &commands {
+            assert_eq!(command.is_coalescable(), command.supersedes(command), "{:?}", command);
+        }
+        assert_eq!(commands.iter().filter(|c| c.is_coalescable()).count(), 6);
+    }
+
+    #[test]
+    fn test_full_queue_coalesces_eq_band() {
+        let engine = full_engine();
+        engine.send_command(set_band(3, 1.0)).unwrap();
+        engine.send_command(set_band(4, 1.0)).unwrap();
+        engine.send_command(set_band(3, 2.0)).unwrap();
+
+        let pending = engine.pending_commands.borrow();
+        assert_eq!(pending.len(), 2);
+        assert!(matches!(
+            pending.back(),
+            Some(AudioCommand::SetEqBand { band_index: 3, gain_db, .. }) if *gain_db == 2.0
+        ));
+    }
+
+    #[test]
+    fn test_command_stats_track_the_queue() {
+        let engine = full_engine();
+        engine.send_command(set_band(3, 1.0)).unwrap();
+        engine.send_command(set_band(3, 2.0)).unwrap();
+        let rejected = engine.send_command(AudioCommand::StopVisualization {
+            device_id: DeviceId::new(1),
+        });
+        assert_eq!(rejected, Err(CommandError::Busy));
+        assert_eq!(engine.queued_commands(), (COMMAND_QUEUE_CAPACITY, 1));
+
+        // The first band change was coalesced away, the rejected stop was dropped
+        let stats: HashMap<_, _> = engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetEqBand"].sent, 2);
+        assert_eq!(stats["SetEqBand"].dropped, 1);
+        assert_eq!(stats["StopVisualization"].dropped, 1);
+        assert_eq!(
+            stats["StopVisualization"].in_flight(),
+            COMMAND_QUEUE_CAPACITY as u64
+        );
+
+        assert_eq!(engine.cancel_pending_commands(), 1);
+        assert_eq!(engine.queued_commands(), (COMMAND_QUEUE_CAPACITY, 0));
+        let stats: HashMap<_, _> = engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetEqBand"].in_flight(), 0);
+    }
+
+    #[test]
+    fn test_engine_errors_are_typed() {
+        let mut engine = AudioEngine::new().unwrap();
+        assert!(matches!(
+            engine.destroy_virtual_device(DeviceId::new(9)),
+            Err(WavewireError::DeviceNotFound(id)) if id == DeviceId::new(9)
+        ));
+
+        engine.stop().unwrap();
+        assert!(matches!(engine.list_devices(), Err(WavewireError::NotRunning)));
+        assert!(matches!(
+            WavewireError::from(CommandError::Disconnected),
+            WavewireError::CommandChannelClosed
+        ));
+    }
+
+    #[test]
+    fn test_coalesce_spectrum_updates() {
+        let data = |sample_rate: u32| {
+            std::sync::Arc::new(SpectrumData {
+                bins: Vec::new(),
+                bins_right: None,
+                frequencies: Vec::new(),
+                sample_rate,
+                timestamp: std::time::Instant::now(),
+            })
+        };
+        let update = |id: u64, sample_rate: u32| AudioEvent::SpectrumUpdate {
+            device_id: DeviceId::new(id),
+            data: data(sample_rate),
+        };
+        let events = vec![
+            update(1, 1),
+            update(2, 1),
+            AudioEvent::Xrun,
+            update(1, 2),
+            // The EQ output of device 1 is a separate spectrum
+            AudioEvent::EqTapSpectrum { device_id: DeviceId::new(1), data: data(2) },
+            AudioEvent::VisualizationStopped { device_id: DeviceId::new(2) },
+            update(1, 3),
+            AudioEvent::Connected,
+        ];
+
+        let summary: Vec<String> = coalesce_spectrum_updates(events)
+            .iter()
+            .map(|event| match event {
+                AudioEvent::SpectrumUpdate { device_id, data } => {
+                    format!("{} #{}", device_id, data.sample_rate)
+                }
+                AudioEvent::EqTapSpectrum { device_id, data } => {
+                    format!("{} post-EQ #{}", device_id, data.sample_rate)
+                }
+                other => format!("{:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "Device(2) #1",
+                "Xrun",
+                "Device(1) post-EQ #2",
+                "VisualizationStopped { device_id: DeviceId(2) }",
+                "Device(1) #3",
+                "Connected",
+            ]
+        );
     }
 }
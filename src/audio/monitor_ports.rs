@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// How a pattern is compared with a port name (always case-insensitive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortMatch {
+    /// The port name ends with the pattern
+    #[default]
+    Suffix,
+    /// The pattern appears anywhere in the port name
+    Substring,
+}
+
+/// Names of a device's left and right monitor ports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorPortPattern {
+    pub left: String,
+    pub right: String,
+    #[serde(default)]
+    pub matching: PortMatch,
+}
+
+#[cfg(any(test, feature = "jack"))]
+impl MonitorPortPattern {
+    fn matches(&self, port_lower: &str, pattern: &str) -> bool {
+        let pattern = pattern.to_lowercase();
+        match self.matching {
+            PortMatch::Suffix => port_lower.ends_with(&pattern),
+            PortMatch::Substring => port_lower.contains(&pattern),
+        }
+    }
+}
+
+/// Patterns tried when none are configured: PipeWire's JACK names
+pub fn default_monitor_port_patterns() -> Vec<MonitorPortPattern> {
+    vec![MonitorPortPattern {
+        left: String::from("monitor_FL"),
+        right: String::from("monitor_FR"),
+        matching: PortMatch::Suffix,
+    }]
+}
+
+/// Monitor ports of `target` among `ports`, trying each pattern in order
+/// Returns the index of the first pattern that finds both ports, and the ports
+#[cfg(any(test, feature = "jack"))]
+pub fn find_monitor_ports<'a>(
+    ports: &'a [String],
+    target: &str,
+    patterns: &[MonitorPortPattern],
+) -> Option<(usize, &'a str, &'a str)> {
+    let target_lower = target.to_lowercase();
+    let candidates: Vec<(&str, String)> = ports
+        .iter()
+        .map(|port| (port.as_str(), port.to_lowercase()))
+        .filter(|(_, lower)| lower.contains(&target_lower))
+        .collect();
+
+    patterns.iter().enumerate().find_map(|(index, pattern)| {
+        let find = |side: &str| {
+            candidates
+                .iter()
+                .find(|(_, lower)| pattern.matches(lower, side))
+                .map(|&(port, _)| port)
+        };
+        Some((index, find(&pattern.left)?, find(&pattern.right)?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_are_tried_in_order() {
+        let ports: Vec<String> = [
+            "Speakers:monitor_FL",
+            "Speakers:monitor_FR",
+            "Headset:Monitor Left",
+            "Headset:Monitor Right",
+        ]
+        .map(String::from)
+        .to_vec();
+        let mut patterns = default_monitor_port_patterns();
+        patterns.push(MonitorPortPattern {
+            left: String::from("monitor left"),
+            right: String::from("monitor right"),
+            matching: PortMatch::Substring,
+        });
+
+        assert_eq!(
+            find_monitor_ports(&ports, "speakers", &patterns),
+            Some((0, "Speakers:monitor_FL", "Speakers:monitor_FR"))
+        );
+        assert_eq!(
+            find_monitor_ports(&ports, "Headset", &patterns),
+            Some((1, "Headset:Monitor Left", "Headset:Monitor Right"))
+        );
+        assert_eq!(find_monitor_ports(&ports, "Headset", &patterns[..1]), None);
+        assert_eq!(find_monitor_ports(&ports, "Speakers", &[]), None);
+    }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::types::{PortDirection, PortInfo};
+
+/// How the output ports of one device are matched to the input ports of another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairingStrategy {
+    /// Match channels by name (FL→FL, FR→FR); unmatched ports are left alone
+    #[default]
+    ByName,
+    /// Match ports by position, ignoring names
+    ByIndex,
+    /// A single source port feeds every destination port (mono into stereo);
+    /// sources with several ports are matched by name
+    MonoFanOut,
+}
+
+impl fmt::Display for PairingStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PairingStrategy::ByName => "by name",
+            PairingStrategy::ByIndex => "by index",
+            PairingStrategy::MonoFanOut => "mono fan-out",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A link to create between two ports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortLink {
+    /// Full PipeWire name of the output port
+    pub source_port: String,
+    /// Full PipeWire name of the input port
+    pub dest_port: String,
+}
+
+impl fmt::Display for PortLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", self.source_port, self.dest_port)
+    }
+}
+
+/// Outcome of pairing two devices' ports
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PortPairing {
+    /// Links to create, in source port order
+    pub links: Vec<PortLink>,
+    /// Source ports that got no link
+    pub unpaired_sources: Vec<String>,
+    /// Destination ports that got no link
+    pub unpaired_destinations: Vec<String>,
+}
+
+/// Channel position of a port from its short name ("monitor_FL" → "FL")
+/// Single-letter and spelled-out left/right are folded into FL/FR
+fn channel(port: &PortInfo) -> String {
+    let suffix = port.name.rsplit(['_', ':']).next().unwrap_or(&port.name);
+    match suffix.to_ascii_uppercase().as_str() {
+        "L" | "LEFT" => String::from("FL"),
+        "R" | "RIGHT" => String::from("FR"),
+        "M" => String::from("MONO"),
+        other => other.to_string(),
+    }
+}
+
+/// Pair `sources` (output ports) with `destinations` (input ports)
+pub fn pair_ports(
+    sources: &[PortInfo],
+    destinations: &[PortInfo],
+    strategy: PairingStrategy,
+) -> PortPairing {
+    let pairs: Vec<(usize, usize)> = match strategy {
+        PairingStrategy::ByIndex => (0..sources.len().min(destinations.len()))
+            .map(|i| (i, i))
+            .collect(),
+        PairingStrategy::MonoFanOut if sources.len() == 1 => {
+            (0..destinations.len()).map(|dest| (0, dest)).collect()
+        }
+        PairingStrategy::ByName | PairingStrategy::MonoFanOut => {
+            let mut taken = vec![false; destinations.len()];
+            sources
+                .iter()
+                .enumerate()
+                .filter_map(|(source, port)| {
+                    let channel = channel(port);
+                    let dest = (0..destinations.len())
+                        .find(|&dest| !taken[dest] && self::channel(&destinations[dest]) == channel)?;
+                    taken[dest] = true;
+                    Some((source, dest))
+                })
+                .collect()
+        }
+    };
+
+    let unpaired = |ports: &[PortInfo], paired: &dyn Fn(usize) -> bool| {
+        ports
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !paired(*i))
+            .map(|(_, port)| port.pipewire_port_name.clone())
+            .collect()
+    };
+    PortPairing {
+        links: pairs
+            .iter()
+            .map(|&(source, dest)| PortLink {
+                source_port: sources[source].pipewire_port_name.clone(),
+                dest_port: destinations[dest].pipewire_port_name.clone(),
+            })
+            .collect(),
+        unpaired_sources: unpaired(sources, &|i| pairs.iter().any(|&(source, _)| source == i)),
+        unpaired_destinations: unpaired(destinations, &|i| pairs.iter().any(|&(_, dest)| dest == i)),
+    }
+}
+
+/// Output ports to capture as (left, right) when visualizing an application stream
+/// A mono stream's single port feeds both sides; otherwise FL/FR are matched by
+/// name, falling back to the first two output ports
+pub fn capture_pair(ports: &[PortInfo]) -> Option<(&PortInfo, &PortInfo)> {
+    let outputs: Vec<&PortInfo> = ports
+        .iter()
+        .filter(|port| port.direction == PortDirection::Output)
+        .collect();
+    let by_channel = |wanted: &str| outputs.iter().copied().find(|&port| channel(port) == wanted);
+    match outputs[..] {
+        [] => None,
+        [mono] => Some((mono, mono)),
+        [first, second, ..] => Some(by_channel("FL").zip(by_channel("FR")).unwrap_or((first, second))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::PortId;
+
+    fn ports(device: &str, names: &[&str], direction: PortDirection) -> Vec<PortInfo> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                PortInfo::new(
+                    PortId::new(i as u64),
+                    name.to_string(),
+                    direction,
+                    format!("{}:{}", device, name),
+                )
+            })
+            .collect()
+    }
+
+    fn links(pairing: &PortPairing) -> Vec<String> {
+        pairing.links.iter().map(|link| link.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pair_by_name() {
+        let sources = ports("Player", &["output_FR", "output_FL", "output_LFE"], PortDirection::Output);
+        let destinations = ports("Speakers", &["playback_FL", "playback_FR"], PortDirection::Input);
+
+        let pairing = pair_ports(&sources, &destinations, PairingStrategy::ByName);
+        assert_eq!(
+            links(&pairing),
+            vec![
+                "Player:output_FR → Speakers:playback_FR",
+                "Player:output_FL → Speakers:playback_FL",
+            ]
+        );
+        assert_eq!(pairing.unpaired_sources, vec!["Player:output_LFE"]);
+        assert!(pairing.unpaired_destinations.is_empty());
+
+        // JACK-style L/R names match FL/FR
+        let jack = ports("Synth", &["out_L", "out_R"], PortDirection::Output);
+        let pairing = pair_ports(&jack, &destinations, PairingStrategy::ByName);
+        assert_eq!(
+            links(&pairing),
+            vec!["Synth:out_L → Speakers:playback_FL", "Synth:out_R → Speakers:playback_FR"]
+        );
+    }
+
+    #[test]
+    fn test_pair_by_index() {
+        let sources = ports("Interface", &["capture_AUX0", "capture_AUX1", "capture_AUX2"], PortDirection::Output);
+        let destinations = ports("Speakers", &["playback_FL", "playback_FR"], PortDirection::Input);
+
+        let pairing = pair_ports(&sources, &destinations, PairingStrategy::ByIndex);
+        assert_eq!(
+            links(&pairing),
+            vec![
+                "Interface:capture_AUX0 → Speakers:playback_FL",
+                "Interface:capture_AUX1 → Speakers:playback_FR",
+            ]
+        );
+        assert_eq!(pairing.unpaired_sources, vec!["Interface:capture_AUX2"]);
+        // Names don't match, so pairing by name links nothing
+        assert!(pair_ports(&sources, &destinations, PairingStrategy::ByName).links.is_empty());
+    }
+
+    #[test]
+    fn test_mono_fan_out() {
+        let mono = ports("Mic", &["capture_MONO"], PortDirection::Output);
+        let destinations = ports("Speakers", &["playback_FL", "playback_FR"], PortDirection::Input);
+
+        let pairing = pair_ports(&mono, &destinations, PairingStrategy::MonoFanOut);
+        assert_eq!(
+            links(&pairing),
+            vec!["Mic:capture_MONO → Speakers:playback_FL", "Mic:capture_MONO → Speakers:playback_FR"]
+        );
+        assert!(pairing.unpaired_sources.is_empty() && pairing.unpaired_destinations.is_empty());
+
+        // A stereo source isn't fanned out
+        let stereo = ports("Player", &["output_FL", "output_FR"], PortDirection::Output);
+        assert_eq!(
+            pair_ports(&stereo, &destinations, PairingStrategy::MonoFanOut),
+            pair_ports(&stereo, &destinations, PairingStrategy::ByName)
+        );
+    }
+
+    #[test]
+    fn test_capture_pair() {
+        let names = |pair: Option<(&PortInfo, &PortInfo)>| {
+            pair.map(|(left, right)| (left.name.clone(), right.name.clone()))
+        };
+        let pair = |names: (&str, &str)| Some((names.0.to_string(), names.1.to_string()));
+
+        let mono = ports("Game", &["output_MONO"], PortDirection::Output);
+        assert_eq!(names(capture_pair(&mono)), pair(("output_MONO", "output_MONO")));
+
+        let stereo = ports("Browser", &["output_FR", "output_FL"], PortDirection::Output);
+        assert_eq!(names(capture_pair(&stereo)), pair(("output_FL", "output_FR")));
+
+        let numbered = ports("Synth", &["out_1", "out_2", "out_3"], PortDirection::Output);
+        assert_eq!(names(capture_pair(&numbered)), pair(("out_1", "out_2")));
+
+        let inputs = ports("Recorder", &["input_FL", "input_FR"], PortDirection::Input);
+        assert!(capture_pair(&inputs).is_none());
+    }
+}
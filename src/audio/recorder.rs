@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Seconds of stereo audio the recording channel can hold before frames are dropped
+const CHANNEL_SECONDS: usize = 2;
+
+/// Writes captured stereo frames to a WAV file on a dedicated writer thread
+/// The real-time callback only pushes frames into a bounded channel
+pub struct WavRecorder {
+    /// Path of the file being written
+    path: PathBuf,
+    /// When recording started
+    started: Instant,
+    /// Writer thread (returns the number of frames written)
+    writer_thread: Option<JoinHandle<Result<u64>>>,
+}
+
+impl WavRecorder {
+    /// Start recording to a new timestamped WAV file in the default recordings directory
+    /// Returns the recorder and the sender the audio callback should feed frames into
+    pub fn start(device_name: &str, sample_rate: u32) -> Result<(Self, Sender<[f32; 2]>)> {
+        let path = recording_dir().join(recording_file_name(device_name));
+        Self::start_at(path, sample_rate)
+    }
+
+    /// Start recording to a specific path
+    pub fn start_at(path: PathBuf, sample_rate: u32) -> Result<(Self, Sender<[f32; 2]>)> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create recording directory: {}", parent.display())
+            })?;
+        }
+
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let writer = hound::WavWriter::create(&path, spec)
+            .with_context(|| format!("Failed to create WAV file: {}", path.display()))?;
+
+        let (frame_tx, frame_rx) = bounded(sample_rate as usize * CHANNEL_SECONDS);
+
+        let writer_thread = thread::Builder::new()
+            .name(String::from("wavewire-recorder"))
+            .spawn(move || write_frames(writer, frame_rx))
+            .context("Failed to spawn recorder thread")?;
+
+        crate::debug_log!("[RECORD] Started recording to {}", path.display());
+
+        Ok((
+            Self {
+                path,
+                started: Instant::now(),
+                writer_thread: Some(writer_thread),
+            },
+            frame_tx,
+        ))
+    }
+
+    /// Path of the file being written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// When recording started
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// Wait for the writer thread to drain and finalize the file
+    /// All senders must be dropped first, otherwise this blocks forever
+    pub fn finish(mut self) -> Result<PathBuf> {
+        if let Some(handle) = self.writer_thread.take() {
+            let frames = handle
+                .join()
+                .map_err(|_| anyhow::anyhow!("Recorder thread panicked"))??;
+            crate::debug_log!(
+                "[RECORD] Finished {} ({} frames)",
+                self.path.display(),
+                frames
+            );
+        }
+        Ok(std::mem::take(&mut self.path))
+    }
+}
+
+/// Writer thread body: drain frames until the sender is dropped, then finalize
+fn write_frames<W>(mut writer: hound::WavWriter<W>, frame_rx: Receiver<[f32; 2]>) -> Result<u64>
+where
+    W: std::io::Write + std::io::Seek,
+{
+    let mut frames = 0u64;
+    for [left, right] in frame_rx.iter() {
+        writer.write_sample(left)?;
+        writer.write_sample(right)?;
+        frames += 1;
+    }
+    writer.finalize()?;
+    Ok(frames)
+}
+
+/// Directory recordings are written to (~/Music/wavewire, falling back to the home directory)
+fn recording_dir() -> PathBuf {
+    dirs::audio_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("wavewire")
+}
+
+/// Build a file name like `wavewire-<device>-<unix seconds>.wav`
+fn recording_file_name(device_name: &str) -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let device: String = device_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("wavewire-{}-{}.wav", device, timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_file_name_sanitized() {
+        let name = recording_file_name("alsa_output.pci/Speakers 1");
+        assert!(name.starts_with("wavewire-alsa_output_pci_Speakers_1-"));
+        assert!(name.ends_with(".wav"));
+    }
+
+    #[test]
+    fn test_recorder_writes_frames() {
+        let path = std::env::temp_dir().join(format!(
+            "wavewire-recorder-test-{}.wav",
+            std::process::id()
+        ));
+        let (recorder, frame_tx) = WavRecorder::start_at(path.clone(), 48000).unwrap();
+        for _ in 0..100 {
+            frame_tx.try_send([0.25, -0.25]).unwrap();
+        }
+        drop(frame_tx);
+        let written = recorder.finish().unwrap();
+
+        let reader = hound::WavReader::open(&written).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 48000);
+        assert_eq!(reader.len(), 200);
+        let _ = std::fs::remove_file(&written);
+    }
+}
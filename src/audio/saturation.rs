@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Waveshaper slope at full drive (tanh(8x) is close to a hard clip)
+#[cfg(any(test, feature = "jack"))]
+const MAX_SHAPE: f32 = 8.0;
+
+/// Clamp a drive amount to its valid range (0.0 = off, 1.0 = heaviest)
+pub fn clamp_saturation_drive(drive: f32) -> f32 {
+    if drive.is_nan() { 0.0 } else { drive.clamp(0.0, 1.0) }
+}
+
+/// Real-time tanh saturation (lives in JACK callback)
+/// The curve is normalized so full scale stays at full scale: quiet material is
+/// pushed up and peaks are rounded off, more so the higher the drive
+#[cfg(any(test, feature = "jack"))]
+pub struct SaturationProcessor {
+    drive: f32,
+    /// tanh slope derived from the drive
+    shape: f32,
+    /// 1 / tanh(shape), keeps ±1.0 at ±1.0
+    normalize: f32,
+    needs_update: Arc<AtomicBool>,
+    pending_drive: Arc<Mutex<Option<f32>>>,
+}
+
+#[cfg(any(test, feature = "jack"))]
+impl SaturationProcessor {
+    /// Create a new saturation processor with the given drive (0.0 to 1.0)
+    pub fn new(drive: f32) -> Self {
+        let mut processor = Self {
+            drive: 0.0,
+            shape: 0.0,
+            normalize: 1.0,
+            needs_update: Arc::new(AtomicBool::new(false)),
+            pending_drive: Arc::new(Mutex::new(None)),
+        };
+        processor.configure(drive);
+        processor
+    }
+
+    fn configure(&mut self, drive: f32) {
+        self.drive = clamp_saturation_drive(drive);
+        self.shape = self.drive * MAX_SHAPE;
+        self.normalize = if self.shape > 0.0 { 1.0 / self.shape.tanh() } else { 1.0 };
+    }
+
+    /// Process a stereo sample through the waveshaper
+    /// This is the main real-time processing function - must be allocation-free
+    #[inline]
+    pub fn process_sample(&mut self, left: f32, right: f32) -> (f32, f32) {
+        // Check for pending updates (atomic read - very fast)
+        if self.needs_update.load(Ordering::Relaxed) {
+            self.apply_pending_update();
+        }
+
+        // Zero drive is an exact passthrough
+        if self.drive == 0.0 {
+            return (left, right);
+        }
+
+        (
+            (left * self.shape).tanh() * self.normalize,
+            (right * self.shape).tanh() * self.normalize,
+        )
+    }
+
+    /// Apply pending drive update if available (non-blocking)
+    fn apply_pending_update(&mut self) {
+        // Use try_lock to avoid blocking the real-time thread
+        let new_drive = match self.pending_drive.try_lock() {
+            Ok(mut pending) => pending.take(),
+            Err(_) => None,
+        };
+        if let Some(new_drive) = new_drive {
+            self.configure(new_drive);
+            self.needs_update.store(false, Ordering::Relaxed);
+        }
+        // If lock fails, we'll try again on the next buffer - no big deal
+    }
+
+    /// Get handles for updating the drive from another thread
+    pub fn get_update_handles(&self) -> (Arc<AtomicBool>, Arc<Mutex<Option<f32>>>) {
+        (
+            Arc::clone(&self.needs_update),
+            Arc::clone(&self.pending_drive),
+        )
+    }
+}
+
+/// Helper function to schedule a drive update from another thread
+pub fn update_saturation_drive(
+    needs_update: &Arc<AtomicBool>,
+    pending_drive: &Arc<Mutex<Option<f32>>>,
+    new_drive: f32,
+) {
+    if let Ok(mut pending) = pending_drive.lock() {
+        *pending = Some(new_drive);
+        needs_update.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_drive_is_bit_exact() {
+        let mut processor = SaturationProcessor::new(0.0);
+        for i in 0..256 {
+            let left = (i as f32 * 0.1).sin() * 1.5;
+            let right = (i as f32 * 0.37).cos();
+            let (out_left, out_right) = processor.process_sample(left, right);
+            assert_eq!(out_left.to_bits(), left.to_bits());
+            assert_eq!(out_right.to_bits(), right.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_drive_rounds_off_peaks() {
+        let mut processor = SaturationProcessor::new(0.5);
+        // Full scale stays put, quieter samples are lifted, overs barely pass full scale
+        let (full, quiet) = processor.process_sample(1.0, 0.25);
+        assert!((full - 1.0).abs() < 1e-6);
+        assert!(quiet > 0.25 && quiet < 1.0);
+        let (over, negative) = processor.process_sample(4.0, -0.25);
+        assert!(over > 1.0 && over < 1.01);
+        assert_eq!(negative, -quiet);
+    }
+
+    #[test]
+    fn test_drive_update_from_another_thread() {
+        let mut processor = SaturationProcessor::new(0.0);
+        let (needs_update, pending_drive) = processor.get_update_handles();
+        update_saturation_drive(&needs_update, &pending_drive, 2.0);
+
+        // Out-of-range drive is clamped to full
+        let (out, _) = processor.process_sample(0.1, 0.0);
+        assert!((out - (0.1 * MAX_SHAPE).tanh() / MAX_SHAPE.tanh()).abs() < 1e-6);
+        assert!(!needs_update.load(Ordering::Relaxed));
+    }
+}
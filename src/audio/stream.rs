@@ -1,14 +1,106 @@
 use anyhow::Result;
 use crossbeam_channel::Sender;
+#[cfg(feature = "jack")]
 use jack::{AudioIn, AudioOut, Client, Port};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use super::crossfeed::CrossfeedSettings;
+#[cfg(feature = "jack")]
+use super::crossfeed::CrossfeedProcessor;
+#[cfg(feature = "jack")]
 use super::eq::EqProcessor;
 use super::fft::FftProcessor;
-use super::types::{AudioEvent, DeviceId, PortId, SpectrumData};
+use super::monitor_ports::MonitorPortPattern;
+#[cfg(feature = "jack")]
+use super::monitor_ports::find_monitor_ports;
+#[cfg(feature = "recording")]
+use super::recorder::WavRecorder;
+#[cfg(feature = "jack")]
+use super::saturation::SaturationProcessor;
+use super::types::{AudioEvent, DeviceId, PortId, SpectrumData, StreamHealth};
+#[cfg(feature = "jack")]
 use super::volume::VolumeProcessor;
+#[cfg(feature = "jack")]
+use super::width::WidthProcessor;
+use crate::error::WavewireError;
+
+/// How often the PipeWire loop updates capture streams
+pub const CAPTURE_TICK: Duration = Duration::from_millis(10);
+
+/// Highest spectrum refresh rate: at most one update per capture tick
+pub const MAX_REFRESH_RATE_HZ: u32 = (1000 / CAPTURE_TICK.as_millis()) as u32;
+
+/// Default interval between spectrum updates (30 Hz)
+pub const DEFAULT_PROCESS_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 30);
+
+/// Default peak level (dBFS) below which a capture stream counts as silent
+pub const DEFAULT_IDLE_THRESHOLD_DB: f32 = -70.0;
+
+/// How often capture streams report their health to the UI
+const HEALTH_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// FFT size capture streams start with
+pub const DEFAULT_FFT_SIZE: usize = 2048;
+
+/// Display bins per spectrum frame
+#[cfg(feature = "jack")]
+const NUM_BINS: usize = 64;
+
+/// Mono sample buffer capacity for an FFT size: at least four FFTs' worth
+fn buffer_capacity(fft_size: usize) -> usize {
+    (4 * fft_size).max(8192)
+}
+
+/// Per-channel buffer capacity when stereo capture is enabled (raised to
+/// the FFT size so the per-channel spectrum always has a full frame)
+const STEREO_BUFFER_CAPACITY: usize = 4096;
+/// Most recent stereo frames considered for each goniometer update
+const STEREO_SCOPE_FRAMES: usize = 1024;
+/// Maximum points sent to the UI per goniometer update
+const STEREO_SCOPE_POINTS: usize = 256;
+/// Most recent mono samples shown per waveform update (~43 ms at 48 kHz)
+const WAVEFORM_SAMPLES: usize = 2048;
+/// Points sent to the UI per waveform update: two braille dots per column
+/// covers a 256 column wide plot
+const WAVEFORM_POINTS: usize = 512;
+/// Sample magnitude counted as a clip (just under 0 dBFS, since a limiter or
+/// float-to-int conversion upstream usually stops a hair short of 1.0)
+#[cfg(any(test, feature = "jack"))]
+const CLIP_LEVEL: f32 = 0.999;
+/// Most recent mono samples measured per level meter update (~43 ms at 48 kHz,
+/// a little more than one update at 30 Hz so no peak falls between updates)
+const LEVEL_SAMPLES: usize = 2048;
+
+/// Open a JACK client without auto-starting a JACK server
+#[cfg(feature = "jack")]
+fn open_jack_client(client_name: &str) -> Result<Client, WavewireError> {
+    let (client, _status) = Client::new(client_name, jack::ClientOptions::NO_START_SERVER)?;
+    Ok(client)
+}
+
+/// JACK client name of a device's EQ node
+#[cfg(feature = "jack")]
+fn eq_client_name(device_id: DeviceId) -> String {
+    format!("wavewire_eq_{}", device_id.0)
+}
+
+/// Create and immediately drop a throwaway JACK client
+/// Returns the server sample rate on success
+#[cfg(feature = "jack")]
+pub fn probe_jack_server() -> Result<usize, WavewireError> {
+    let client = open_jack_client("wavewire_check")?;
+    Ok(client.sample_rate())
+}
+
+/// There is no JACK client to probe without the `jack` feature
+#[cfg(not(feature = "jack"))]
+pub fn probe_jack_server() -> Result<usize, WavewireError> {
+    Err(WavewireError::JackDisabled)
+}
 
 /// Ring buffer for audio samples
 /// Stores incoming audio samples in a circular buffer for FFT processing
@@ -30,6 +122,7 @@ impl RingBuffer {
 
     /// Push samples into the buffer
     /// If capacity is exceeded, oldest samples are dropped
+    #[cfg(any(test, feature = "jack"))]
     pub fn push(&mut self, samples: &[f32]) {
         for &sample in samples {
             if self.buffer.len() >= self.capacity {
@@ -44,6 +137,19 @@ impl RingBuffer {
         self.buffer.len()
     }
 
+    /// Maximum number of samples the buffer holds
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity, keeping the most recent samples that still fit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        let excess = self.buffer.len().saturating_sub(capacity);
+        self.buffer.drain(..excess);
+        self.buffer.reserve(capacity - self.buffer.len());
+        self.capacity = capacity;
+    }
+
     /// Check if the buffer has enough samples for processing
     pub fn has_enough_samples(&self, required: usize) -> bool {
         self.buffer.len() >= required
@@ -58,7 +164,104 @@ impl RingBuffer {
     }
 }
 
+/// Separate left/right sample buffers, only allocated while stereo capture is on
+pub struct StereoBuffers {
+    pub left: RingBuffer,
+    pub right: RingBuffer,
+}
+
+impl StereoBuffers {
+    fn new(capacity: usize) -> Self {
+        Self {
+            left: RingBuffer::new(capacity),
+            right: RingBuffer::new(capacity),
+        }
+    }
+
+    /// Most recent `count` frames as L/R pairs, thinned to at most `max_points`
+    pub fn recent_frames(&self, count: usize, max_points: usize) -> Vec<[f32; 2]> {
+        let left = self.left.peek(count);
+        let right = self.right.peek(count);
+        let frames = left.len().min(right.len());
+        let stride = frames.div_ceil(max_points.max(1)).max(1);
+
+        (0..frames)
+            .step_by(stride)
+            .map(|i| [left[i], right[i]])
+            .collect()
+    }
+}
+
+/// Thin samples down to at most `points`, keeping the largest magnitude of each
+/// stretch so peaks (and clipping) survive the downsampling
+fn waveform_points(samples: &[f32], points: usize) -> Vec<f32> {
+    let stride = samples.len().div_ceil(points.max(1)).max(1);
+    samples
+        .chunks(stride)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .copied()
+                .fold(0.0, |peak: f32, sample| if sample.abs() > peak.abs() { sample } else { peak })
+        })
+        .collect()
+}
+
+/// Downmix any number of channels to mono by averaging them
+/// Dividing by the channel count keeps identical (fully correlated) channels at the
+/// level of a single channel, so the spectrum doesn't jump when the number of
+/// captured channels changes. Extra samples in longer channels are ignored
+#[cfg(any(test, feature = "jack"))]
+fn downmix(channels: &[&[f32]], mono: &mut Vec<f32>) {
+    mono.clear();
+    let Some(frames) = channels.iter().map(|channel| channel.len()).min() else {
+        return;
+    };
+
+    let gain = 1.0 / channels.len() as f32;
+    mono.extend((0..frames).map(|i| channels.iter().map(|channel| channel[i]).sum::<f32>() * gain));
+}
+
+/// Shortest gap between xrun events sent to the UI
+#[cfg(any(test, feature = "jack"))]
+const XRUN_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// When the last xrun event went out; shared by all JACK clients, since every
+/// one of them is told about the same server xruns
+#[cfg(feature = "jack")]
+static LAST_XRUN_EVENT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Whether an xrun at `now` should be reported, given when the last one was
+#[cfg(any(test, feature = "jack"))]
+fn xrun_due(last: &mut Option<Instant>, now: Instant) -> bool {
+    if last.is_some_and(|last| now.saturating_duration_since(last) < XRUN_DEBOUNCE) {
+        return false;
+    }
+    *last = Some(now);
+    true
+}
+
+/// JACK notification handler reporting xruns (at most one event per `XRUN_DEBOUNCE`)
+#[cfg(feature = "jack")]
+struct XrunNotifier {
+    event_tx: Sender<AudioEvent>,
+}
+
+#[cfg(feature = "jack")]
+impl jack::NotificationHandler for XrunNotifier {
+    fn xrun(&mut self, _client: &jack::Client) -> jack::Control {
+        if let Ok(mut last) = LAST_XRUN_EVENT.lock()
+            && xrun_due(&mut last, Instant::now())
+        {
+            crate::debug_log!("[JACK] Xrun");
+            let _ = self.event_tx.send(AudioEvent::Xrun);
+        }
+        jack::Control::Continue
+    }
+}
+
 /// JACK audio processor for handling process callbacks
+#[cfg(feature = "jack")]
 struct JackProcessor {
     /// Left channel input port
     in_left: Port<AudioIn>,
@@ -66,13 +269,20 @@ struct JackProcessor {
     in_right: Port<AudioIn>,
     /// Ring buffer for storing samples (shared with main thread)
     sample_buffer: Arc<Mutex<RingBuffer>>,
+    /// Optional tap feeding stereo frames to a recording writer thread
+    record_tap: Arc<Mutex<Option<Sender<[f32; 2]>>>>,
+    /// Separate L/R buffers (None unless stereo capture is enabled)
+    stereo_buffers: Arc<Mutex<Option<StereoBuffers>>>,
+    /// Set when a sample reaches CLIP_LEVEL; cleared once reported
+    clipped: Arc<AtomicBool>,
 }
 
+#[cfg(feature = "jack")]
 impl jack::ProcessHandler for JackProcessor {
     fn process(&mut self, _client: &jack::Client, ps: &jack::ProcessScope) -> jack::Control {
         use std::cell::Cell;
         thread_local! {
-            static PROCESS_COUNT: Cell<u32> = Cell::new(0);
+            static PROCESS_COUNT: Cell<u32> = const { Cell::new(0) };
         }
 
         PROCESS_COUNT.with(|count| {
@@ -92,12 +302,14 @@ impl jack::ProcessHandler for JackProcessor {
                 );
             }
 
+            // Check the channels before the downmix, which can average a clip away
+            if clips(left_samples) || clips(right_samples) {
+                self.clipped.store(true, Ordering::Relaxed);
+            }
+
             // Convert stereo to mono and push to ring buffer
             let mut mono_samples = Vec::with_capacity(left_samples.len());
-            for i in 0..left_samples.len() {
-                let mono = (left_samples[i] + right_samples[i]) / 2.0;
-                mono_samples.push(mono);
-            }
+            downmix(&[left_samples, right_samples], &mut mono_samples);
 
             // Log audio statistics every 100 callbacks
             if c % 100 == 0 {
@@ -125,12 +337,138 @@ impl jack::ProcessHandler for JackProcessor {
             if let Ok(mut buffer) = self.sample_buffer.lock() {
                 buffer.push(&mono_samples);
             }
+
+            // Keep channels separate for stereo views (skip this cycle if the UI side holds the lock)
+            if let Ok(mut stereo) = self.stereo_buffers.try_lock()
+                && let Some(buffers) = stereo.as_mut()
+            {
+                buffers.left.push(left_samples);
+                buffers.right.push(right_samples);
+            }
+
+            // Feed the recorder if one is attached (never block the RT thread)
+            if let Ok(tap) = self.record_tap.try_lock()
+                && let Some(frame_tx) = tap.as_ref()
+            {
+                for (&left, &right) in left_samples.iter().zip(right_samples) {
+                    // Drop frames rather than block if the writer falls behind
+                    let _ = frame_tx.try_send([left, right]);
+                }
+            }
         });
 
         jack::Control::Continue
     }
 }
 
+/// Where a capture stream takes its signal from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaptureTap {
+    /// The device's monitor ports
+    Monitor,
+    /// The output ports of the device's EQ node
+    #[cfg(feature = "jack")]
+    EqOutput,
+}
+
+/// Running health counters for a capture stream
+struct HealthCounters {
+    /// Start of the current report window
+    window_start: Instant,
+    /// Spectrum updates produced in the current window
+    ffts_in_window: u32,
+    /// Total updates skipped for lack of samples
+    starved_cycles: u64,
+    /// Total updates dropped because the audio thread fell behind
+    dropped_cycles: u64,
+    /// The FFT size grew past the buffered samples; waiting for them to fill up
+    /// again isn't counted as starving
+    refilling: bool,
+}
+
+impl HealthCounters {
+    #[cfg(feature = "jack")]
+    fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            ffts_in_window: 0,
+            starved_cycles: 0,
+            dropped_cycles: 0,
+            refilling: false,
+        }
+    }
+}
+
+/// Tracks how long a capture stream has been silent
+/// Once it has stayed below the threshold for the timeout, the stream idles:
+/// the FFT is skipped until the level comes back above the threshold
+struct IdleDetector {
+    /// Peak level (dBFS) at or below which the signal counts as silent
+    threshold_db: f32,
+    /// Silence needed before idling (None = never idle)
+    after: Option<Duration>,
+    /// When the current stretch of silence started
+    silent_since: Option<Instant>,
+    /// Whether spectrum processing is currently paused
+    idle: bool,
+}
+
+impl IdleDetector {
+    #[cfg(any(test, feature = "jack"))]
+    fn new() -> Self {
+        Self {
+            threshold_db: DEFAULT_IDLE_THRESHOLD_DB,
+            after: None,
+            silent_since: None,
+            idle: false,
+        }
+    }
+
+    fn configure(&mut self, threshold_db: f32, after: Option<Duration>) {
+        self.threshold_db = threshold_db;
+        self.after = after;
+        // Re-evaluated on the next update
+        self.silent_since = None;
+        self.idle = false;
+    }
+
+    /// Feed the peak level of the latest window; returns true while idle
+    fn observe(&mut self, peak_db: f32, now: Instant) -> bool {
+        let Some(after) = self.after else {
+            return false;
+        };
+        if peak_db > self.threshold_db {
+            self.silent_since = None;
+            self.idle = false;
+        } else {
+            let since = *self.silent_since.get_or_insert(now);
+            self.idle = now.duration_since(since) >= after;
+        }
+        self.idle
+    }
+}
+
+/// Peak absolute sample level in dBFS (-inf for digital silence)
+fn peak_db(samples: &[f32]) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    20.0 * peak.log10()
+}
+
+/// Whether any sample reaches CLIP_LEVEL
+#[cfg(any(test, feature = "jack"))]
+fn clips(samples: &[f32]) -> bool {
+    samples.iter().any(|sample| sample.abs() >= CLIP_LEVEL)
+}
+
+/// Peak and RMS level in dBFS from a single pass (-inf for digital silence)
+fn peak_and_rms_db(samples: &[f32]) -> (f32, f32) {
+    let (peak, sum_of_squares) = samples
+        .iter()
+        .fold((0.0f32, 0.0f32), |(peak, sum), sample| (peak.max(sample.abs()), sum + sample * sample));
+    let mean_square = sum_of_squares / samples.len().max(1) as f32;
+    (20.0 * peak.log10(), 10.0 * mean_square.log10())
+}
+
 /// Audio capture stream for visualization using JACK API
 /// Captures audio from monitor ports and buffers samples for FFT processing
 pub struct AudioCaptureStream {
@@ -138,6 +476,8 @@ pub struct AudioCaptureStream {
     device_id: DeviceId,
     /// Port ID this stream is capturing from
     port_id: PortId,
+    /// Signal this stream captures
+    tap: CaptureTap,
     /// Ring buffer for incoming audio samples (thread-safe)
     sample_buffer: Arc<Mutex<RingBuffer>>,
     /// Sample rate of the stream
@@ -146,12 +486,36 @@ pub struct AudioCaptureStream {
     fft_processor: FftProcessor,
     /// Event channel for sending spectrum updates
     event_tx: Sender<AudioEvent>,
-    /// Last FFT processing timestamp
-    last_process_time: Instant,
+    /// Interval between spectrum updates
+    process_interval: Duration,
+    /// When the next spectrum update is due
+    next_process_at: Instant,
+    /// Health counters reported periodically to the UI
+    health: HealthCounters,
+    /// Pauses the FFT while the source is silent
+    idle: IdleDetector,
+    /// Separate L/R buffers shared with the JACK processor (None unless stereo capture is on)
+    stereo_buffers: Arc<Mutex<Option<StereoBuffers>>>,
+    /// Whether the latest samples are sent for the waveform view
+    waveform: bool,
+    /// Whether peak and RMS levels are sent for the level meters
+    level_meters: bool,
+    /// Clip flag shared with the JACK processor
+    clipped: Arc<AtomicBool>,
+    /// Whether the left and right channels get their own FFT (needs stereo capture)
+    stereo_spectrum: bool,
+    /// Recording tap shared with the JACK processor
+    #[cfg(feature = "recording")]
+    record_tap: Arc<Mutex<Option<Sender<[f32; 2]>>>>,
+    /// Active WAV recorder, if recording
+    #[cfg(feature = "recording")]
+    recorder: Option<WavRecorder>,
     /// JACK client (must be kept alive)
-    _jack_client: jack::AsyncClient<(), JackProcessor>,
+    #[cfg(feature = "jack")]
+    jack_client: jack::AsyncClient<XrunNotifier, JackProcessor>,
 }
 
+#[cfg(feature = "jack")]
 impl AudioCaptureStream {
     /// Create a new audio capture stream using JACK API
     pub fn new(
@@ -159,12 +523,9 @@ impl AudioCaptureStream {
         device_id: DeviceId,
         port_id: PortId,
         target_name: Option<String>,
+        monitor_patterns: &[MonitorPortPattern],
         event_tx: Sender<AudioEvent>,
     ) -> Result<Self> {
-        const BUFFER_CAPACITY: usize = 8192;
-        const FFT_SIZE: usize = 2048;
-        const NUM_BINS: usize = 64;
-
         let target = target_name.unwrap_or_else(|| {
             crate::debug_log!("[JACK] WARNING: No target provided");
             String::new()
@@ -176,44 +537,12 @@ impl AudioCaptureStream {
             target
         );
 
-        // Create ring buffer
-        let sample_buffer = Arc::new(Mutex::new(RingBuffer::new(BUFFER_CAPACITY)));
-        crate::debug_log!("[JACK] Ring buffer created with capacity {}", BUFFER_CAPACITY);
-
-        // Create JACK client
         let client_name = format!("wavewire_{}", device_id.0);
-        let (client, _status) =
-            jack::Client::new(&client_name, jack::ClientOptions::NO_START_SERVER)?;
-
-        let sample_rate = client.sample_rate();
-        crate::debug_log!(
-            "[JACK] Client created: {}, sample_rate={}Hz",
-            client_name,
-            sample_rate
-        );
-
-        // Create FFT processor with actual JACK sample rate
-        let fft_processor = FftProcessor::new(FFT_SIZE, NUM_BINS, sample_rate as u32);
-
-        // Register input ports (stereo)
-        let in_left = client.register_port("capture_L", jack::AudioIn::default())?;
-        let in_right = client.register_port("capture_R", jack::AudioIn::default())?;
-        crate::debug_log!("[JACK] Registered input ports: capture_L, capture_R");
-
-        // Create processor with shared buffer
-        let processor = JackProcessor {
-            in_left,
-            in_right,
-            sample_buffer: Arc::clone(&sample_buffer),
-        };
-
-        // Activate the client
-        let async_client = client.activate_async((), processor)?;
-        crate::debug_log!("[JACK] Client activated");
+        let stream = Self::open(&client_name, device_id, port_id, CaptureTap::Monitor, event_tx.clone())?;
 
         // Connect to target ports if specified
         if !target.is_empty() {
-            let client_ref = async_client.as_client();
+            let client_ref = stream.jack_client.as_client();
 
             // Get all output ports (potential monitor sources)
             let all_ports = client_ref.ports(None, None, jack::PortFlags::IS_OUTPUT);
@@ -224,43 +553,18 @@ impl AudioCaptureStream {
                 crate::debug_log!("[JACK]   - {}", port);
             }
 
-            // Find monitor ports that match our target
-            // Look for ports containing the target name and "monitor"
-            let mut left_port = None;
-            let mut right_port = None;
-
-            // Normalize target for case-insensitive matching
-            let target_lower = target.to_lowercase();
-
-            for port_name in all_ports.iter() {
-                let port_name_lower = port_name.to_lowercase();
-
-                // Match ports that contain "monitor"
-                if port_name_lower.contains("monitor") {
-                    // Try multiple matching strategies to find the correct monitor port:
-                    // 1. Check if port name starts with target (works for virtual_, obs_, etc.)
-                    // 2. Check if port name contains target (works for most applications)
-                    // Both checks are case-insensitive to handle variations
-                    let matches_target = port_name_lower.starts_with(&target_lower)
-                        || port_name_lower.contains(&target_lower);
-
-                    if matches_target {
-                        crate::debug_log!("[JACK] Port '{}' matches target '{}' (case-insensitive)", port_name, target);
-
-                        if port_name.ends_with("monitor_FL") || port_name_lower.ends_with("monitor_fl") {
-                            left_port = Some(port_name.clone());
-                            crate::debug_log!("[JACK] Found left monitor port: {}", port_name);
-                        } else if port_name.ends_with("monitor_FR") || port_name_lower.ends_with("monitor_fr") {
-                            right_port = Some(port_name.clone());
-                            crate::debug_log!("[JACK] Found right monitor port: {}", port_name);
-                        }
-                    }
-                }
-            }
-
-            // Try to connect if we found both ports
-            match (&left_port, &right_port) {
-                (Some(left), Some(right)) => {
+            // Find the target's monitor ports, trying each configured pattern in order
+            match find_monitor_ports(&all_ports, &target, monitor_patterns) {
+                Some((index, left, right)) => {
+                    let pattern = &monitor_patterns[index];
+                    crate::debug_log!(
+                        "[JACK] Monitor port pattern #{} ({} / {}, {:?}) matched target '{}'",
+                        index + 1,
+                        pattern.left,
+                        pattern.right,
+                        pattern.matching,
+                        target
+                    );
                     crate::debug_log!("[JACK] Attempting to connect to {} and {}", left, right);
 
                     match client_ref.connect_ports_by_name(left, &format!("{}:capture_L", client_name)) {
@@ -274,9 +578,13 @@ impl AudioCaptureStream {
                         Err(e) => crate::debug_log!("[JACK] ✗ Failed to connect left: {}", e),
                     }
                 }
-                _ => {
-                    crate::debug_log!("[JACK] WARNING: Could not find monitor ports for target: {}", target);
-                    crate::debug_log!("[JACK] Found left: {:?}, Found right: {:?}", left_port, right_port);
+                None => {
+                    crate::debug_log!(
+                        "[JACK] WARNING: None of the {} monitor port pattern(s) matched target: {}",
+                        monitor_patterns.len(),
+                        target
+                    );
+                    crate::debug_log!("[JACK] Add one under [[general.monitor_port_patterns]] in the config");
                     crate::debug_log!("[JACK] You may need to connect manually using Helvum or pw-link");
                 }
             }
@@ -285,126 +593,488 @@ impl AudioCaptureStream {
         // Send event that visualization started
         let _ = event_tx.send(AudioEvent::VisualizationStarted { device_id, port_id });
 
+        Ok(stream)
+    }
+
+    /// Create a capture stream on an application stream's own output ports
+    /// (`left` and `right` may be the same port for a mono stream)
+    pub fn stream_outputs(
+        device_id: DeviceId,
+        port_id: PortId,
+        left: &str,
+        right: &str,
+        event_tx: Sender<AudioEvent>,
+    ) -> Result<Self> {
+        let client_name = format!("wavewire_{}", device_id.0);
+        let stream = Self::open(&client_name, device_id, port_id, CaptureTap::Monitor, event_tx.clone())?;
+
+        let client_ref = stream.jack_client.as_client();
+        for (output, input) in [(left, "capture_L"), (right, "capture_R")] {
+            client_ref.connect_ports_by_name(output, &format!("{}:{}", client_name, input))?;
+        }
+        crate::debug_log!("[JACK] Capturing stream outputs {} / {} for device {:?}", left, right, device_id);
+
+        let _ = event_tx.send(AudioEvent::VisualizationStarted { device_id, port_id });
+        Ok(stream)
+    }
+
+    /// Create a capture stream on the output of a device's EQ node
+    /// Its spectra are sent as `EqTapSpectrum`, so they don't replace the monitor's
+    pub fn eq_tap(device_id: DeviceId, port_id: PortId, event_tx: Sender<AudioEvent>) -> Result<Self> {
+        let client_name = format!("wavewire_eqtap_{}", device_id.0);
+        let stream = Self::open(&client_name, device_id, port_id, CaptureTap::EqOutput, event_tx)?;
+
+        let eq_client = eq_client_name(device_id);
+        let client_ref = stream.jack_client.as_client();
+        for (output, input) in [("out_L", "capture_L"), ("out_R", "capture_R")] {
+            client_ref.connect_ports_by_name(
+                &format!("{}:{}", eq_client, output),
+                &format!("{}:{}", client_name, input),
+            )?;
+        }
+        crate::debug_log!("[JACK] Tapping {}:out_L/out_R for device {:?}", eq_client, device_id);
+
+        Ok(stream)
+    }
+
+    /// Create and activate the JACK client and buffers shared by every capture stream
+    fn open(
+        client_name: &str,
+        device_id: DeviceId,
+        port_id: PortId,
+        tap: CaptureTap,
+        event_tx: Sender<AudioEvent>,
+    ) -> Result<Self> {
+        // Any size works, but rustfft is much faster on powers of two
+        const _: () = assert!(DEFAULT_FFT_SIZE.is_power_of_two(), "FFT size should be a power of two");
+
+        // Create ring buffer
+        let capacity = buffer_capacity(DEFAULT_FFT_SIZE);
+        let sample_buffer = Arc::new(Mutex::new(RingBuffer::new(capacity)));
+        crate::debug_log!("[JACK] Ring buffer created with capacity {}", capacity);
+
+        // Create JACK client
+        let client = open_jack_client(client_name)?;
+
+        let sample_rate = client.sample_rate();
+        crate::debug_log!(
+            "[JACK] Client created: {}, sample_rate={}Hz",
+            client_name,
+            sample_rate
+        );
+
+        // Create FFT processor with actual JACK sample rate
+        let fft_processor = FftProcessor::new(DEFAULT_FFT_SIZE, NUM_BINS, sample_rate as u32);
+
+        // Register input ports (stereo)
+        let in_left = client.register_port("capture_L", jack::AudioIn)?;
+        let in_right = client.register_port("capture_R", jack::AudioIn)?;
+        crate::debug_log!("[JACK] Registered input ports: capture_L, capture_R");
+
+        // Create processor with shared buffer
+        let record_tap = Arc::new(Mutex::new(None));
+        let stereo_buffers = Arc::new(Mutex::new(None));
+        let clipped = Arc::new(AtomicBool::new(false));
+        let processor = JackProcessor {
+            in_left,
+            in_right,
+            sample_buffer: Arc::clone(&sample_buffer),
+            record_tap: Arc::clone(&record_tap),
+            stereo_buffers: Arc::clone(&stereo_buffers),
+            clipped: Arc::clone(&clipped),
+        };
+
+        // Activate the client
+        let notifier = XrunNotifier {
+            event_tx: event_tx.clone(),
+        };
+        let async_client = client.activate_async(notifier, processor)?;
+        crate::debug_log!("[JACK] Client activated");
+
         Ok(Self {
             device_id,
             port_id,
+            tap,
             sample_buffer,
             sample_rate: sample_rate as u32,
             fft_processor,
             event_tx,
-            last_process_time: Instant::now(),
-            _jack_client: async_client,
+            process_interval: DEFAULT_PROCESS_INTERVAL,
+            next_process_at: Instant::now(),
+            health: HealthCounters::new(),
+            idle: IdleDetector::new(),
+            stereo_buffers,
+            waveform: false,
+            level_meters: false,
+            clipped,
+            stereo_spectrum: false,
+            #[cfg(feature = "recording")]
+            record_tap,
+            #[cfg(feature = "recording")]
+            recorder: None,
+            jack_client: async_client,
         })
     }
+}
 
-    /// Get the device ID for this stream
-    pub fn device_id(&self) -> DeviceId {
-        self.device_id
+/// Without the `jack` feature there is nothing to capture with
+#[cfg(not(feature = "jack"))]
+impl AudioCaptureStream {
+    pub fn new(
+        _core: &(),
+        _device_id: DeviceId,
+        _port_id: PortId,
+        _target_name: Option<String>,
+        _monitor_patterns: &[MonitorPortPattern],
+        _event_tx: Sender<AudioEvent>,
+    ) -> Result<Self> {
+        Err(WavewireError::JackDisabled.into())
     }
 
+    pub fn stream_outputs(
+        _device_id: DeviceId,
+        _port_id: PortId,
+        _left: &str,
+        _right: &str,
+        _event_tx: Sender<AudioEvent>,
+    ) -> Result<Self> {
+        Err(WavewireError::JackDisabled.into())
+    }
+
+    pub fn eq_tap(_device_id: DeviceId, _port_id: PortId, _event_tx: Sender<AudioEvent>) -> Result<Self> {
+        Err(WavewireError::JackDisabled.into())
+    }
+}
+
+impl AudioCaptureStream {
     /// Get the port ID for this stream
     pub fn port_id(&self) -> PortId {
         self.port_id
     }
 
-    /// Get the sample rate of this stream
-    pub fn sample_rate(&self) -> u32 {
-        self.sample_rate
+    /// Start recording the captured stereo audio to a WAV file, at `path` or a
+    /// timestamped file named after the device
+    /// Returns the path of the file being written
+    #[cfg(feature = "recording")]
+    pub fn start_recording(&mut self, device_name: &str, path: Option<PathBuf>) -> Result<PathBuf> {
+        if self.recorder.is_some() {
+            anyhow::bail!("Already recording device {:?}", self.device_id);
+        }
+
+        let (recorder, frame_tx) = match path {
+            Some(path) => WavRecorder::start_at(path, self.sample_rate)?,
+            None => WavRecorder::start(device_name, self.sample_rate)?,
+        };
+        *self.record_tap.lock().unwrap() = Some(frame_tx);
+
+        let path = recorder.path().to_path_buf();
+        self.recorder = Some(recorder);
+        Ok(path)
+    }
+
+    /// Stop recording and finalize the WAV file
+    /// Returns the file path and recording length, or None if not recording
+    #[cfg(feature = "recording")]
+    pub fn stop_recording(&mut self) -> Result<Option<(PathBuf, Duration)>> {
+        let Some(recorder) = self.recorder.take() else {
+            return Ok(None);
+        };
+
+        // Detach the tap first so the writer thread sees the channel close
+        self.record_tap.lock().unwrap().take();
+
+        let elapsed = recorder.started().elapsed();
+        let path = recorder.finish()?;
+        Ok(Some((path, elapsed)))
     }
 
-    /// Get access to the sample buffer
-    pub fn sample_buffer(&self) -> &Arc<Mutex<RingBuffer>> {
-        &self.sample_buffer
+    /// Recording is unavailable without the `recording` feature
+    #[cfg(not(feature = "recording"))]
+    pub fn start_recording(&mut self, _device_name: &str, _path: Option<PathBuf>) -> Result<PathBuf> {
+        anyhow::bail!("Recording support not built in (rebuild with --features recording)")
     }
 
-    /// Check if we have enough samples for FFT processing
-    pub fn has_enough_samples(&self, fft_size: usize) -> bool {
-        self.sample_buffer
-            .lock()
-            .unwrap()
-            .has_enough_samples(fft_size)
+    /// Recording is unavailable without the `recording` feature
+    #[cfg(not(feature = "recording"))]
+    pub fn stop_recording(&mut self) -> Result<Option<(PathBuf, Duration)>> {
+        Ok(None)
     }
 
     /// Process buffered audio and send spectrum update
     /// Should be called periodically (e.g., 20-30 Hz)
-    pub fn process_spectrum(&mut self) {
-        // Check if we have enough samples
+    /// Returns false if the buffer didn't hold enough samples yet
+    ///
+    /// While the stream is idle the FFT is skipped; a flat spectrum is sent once
+    /// on going idle and the UI holds it until audio returns
+    pub fn process_spectrum(&mut self) -> bool {
+        // Check and take the samples under one lock, so a buffer resized in
+        // between can't hand the FFT fewer samples than it needs
         let fft_size = self.fft_processor.fft_size();
-        if !self.has_enough_samples(fft_size) {
-            return;
-        }
-
-        // Get samples from buffer
-        let samples = self.sample_buffer.lock().unwrap().peek(fft_size);
-
-        // Run FFT
-        let (bins, frequencies) = self.fft_processor.process(&samples);
+        let samples = {
+            let buffer = self.sample_buffer.lock().unwrap();
+            if !buffer.has_enough_samples(fft_size) {
+                return false;
+            }
+            buffer.peek(fft_size)
+        };
 
-        // Create spectrum data
-        let spectrum_data = SpectrumData {
-            bins: bins.clone(),
-            frequencies,
-            sample_rate: self.sample_rate,
-            timestamp: Instant::now(),
+        let was_idle = self.idle.idle;
+        let mut bins_right = None;
+        let (bins, frequencies) = if self.idle.observe(peak_db(&samples), Instant::now()) {
+            if was_idle {
+                return true;
+            }
+            crate::debug_log!("[SPECTRUM] Device {:?}: Silent, idling", self.device_id);
+            self.fft_processor.silence()
+        } else {
+            if was_idle {
+                crate::debug_log!("[SPECTRUM] Device {:?}: Audio returned, resuming", self.device_id);
+            }
+            // Run FFT, once per channel when the spectrum is split
+            match self.stereo_samples(fft_size) {
+                Some((left, right)) => {
+                    bins_right = Some(self.fft_processor.process(&right).0);
+                    self.fft_processor.process(&left)
+                }
+                None => self.fft_processor.process(&samples),
+            }
         };
 
-        // Diagnostic logging
-        crate::debug_log!(
+        // Diagnostic logging (every frame, so trace only)
+        crate::debug_trace!(
             "[SPECTRUM] Device {:?}: Sending {} bins, sample: [{:.2}, {:.2}, {:.2}]",
             self.device_id,
             bins.len(),
-            bins.get(0).unwrap_or(&-60.0),
+            bins.first().unwrap_or(&-60.0),
             bins.get(32).unwrap_or(&-60.0),
             bins.get(63).unwrap_or(&-60.0)
         );
 
+        // Create spectrum data
+        let spectrum_data = SpectrumData {
+            bins,
+            bins_right,
+            frequencies,
+            sample_rate: self.sample_rate,
+            timestamp: Instant::now(),
+        };
+
         // Send event
-        let send_result = self.event_tx.send(AudioEvent::SpectrumUpdate {
-            device_id: self.device_id,
-            data: spectrum_data,
+        let device_id = self.device_id;
+        let data = Arc::new(spectrum_data);
+        let send_result = self.event_tx.send(match self.tap {
+            CaptureTap::Monitor => AudioEvent::SpectrumUpdate { device_id, data },
+            #[cfg(feature = "jack")]
+            CaptureTap::EqOutput => AudioEvent::EqTapSpectrum { device_id, data },
         });
 
         if let Err(e) = send_result {
             crate::debug_log!("[SPECTRUM] Event send failed: {:?}", e);
         }
+        true
     }
 
-    /// Update the stream (process FFT if enough time has passed)
-    /// Should be called from the audio thread periodically
-    pub fn update(&mut self) {
-        // Process spectrum at ~30 Hz
-        const PROCESS_INTERVAL_MS: u128 = 33; // ~30 Hz
-        let elapsed = self.last_process_time.elapsed().as_millis();
+    /// Latest `count` samples of each channel, if the spectrum is split and
+    /// stereo capture keeps the channels apart
+    fn stereo_samples(&self, count: usize) -> Option<(Vec<f32>, Vec<f32>)> {
+        if !self.stereo_spectrum {
+            return None;
+        }
+        let stereo = self.stereo_buffers.lock().unwrap();
+        let buffers = stereo.as_ref()?;
+        Some((buffers.left.peek(count), buffers.right.peek(count)))
+    }
 
-        let buffer_len = self.sample_buffer.lock().unwrap().len();
-        let fft_size = self.fft_processor.fft_size();
+    /// Enable or disable a separate FFT for each channel
+    /// Only takes effect while stereo capture is on; the mono FFT runs otherwise
+    pub fn set_stereo_spectrum(&mut self, enabled: bool) {
+        self.stereo_spectrum = enabled;
+    }
 
-        // Log periodically (every ~1 second)
-        use std::cell::RefCell;
-        thread_local! {
-            static LAST_LOG: RefCell<Option<Instant>> = RefCell::new(None);
+    /// Enable or disable keeping the left and right channels in separate buffers
+    pub fn set_stereo_capture(&self, enabled: bool) {
+        let capacity = STEREO_BUFFER_CAPACITY.max(self.fft_processor.fft_size());
+        let mut stereo = self.stereo_buffers.lock().unwrap();
+        match (enabled, stereo.is_some()) {
+            (true, false) => *stereo = Some(StereoBuffers::new(capacity)),
+            (false, true) => *stereo = None,
+            _ => {}
         }
-        LAST_LOG.with(|last_log| {
-            let mut last = last_log.borrow_mut();
-            if last.is_none() || last.unwrap().elapsed().as_secs() >= 1 {
-                crate::debug_log!(
-                    "[UPDATE] Buffer: {}/{} samples, FFT needs {} samples",
-                    buffer_len,
-                    8192,
-                    fft_size
-                );
-                *last = Some(Instant::now());
-            }
+    }
+
+    /// Send the latest L/R frames for the goniometer, if stereo capture is on
+    fn send_stereo_frames(&self) {
+        let frames = match self.stereo_buffers.lock().unwrap().as_ref() {
+            Some(buffers) => buffers.recent_frames(STEREO_SCOPE_FRAMES, STEREO_SCOPE_POINTS),
+            None => return,
+        };
+        if frames.is_empty() {
+            return;
+        }
+
+        let _ = self.event_tx.send(AudioEvent::StereoFrames {
+            device_id: self.device_id,
+            frames,
+        });
+    }
+
+    /// Enable or disable sending the latest samples for the waveform view
+    pub fn set_waveform(&mut self, enabled: bool) {
+        self.waveform = enabled;
+    }
+
+    /// Send the latest mono samples for the waveform view, if it is on
+    fn send_waveform(&self) {
+        if !self.waveform {
+            return;
+        }
+        let recent = self.sample_buffer.lock().unwrap().peek(WAVEFORM_SAMPLES);
+        let samples = waveform_points(&recent, WAVEFORM_POINTS);
+        if samples.is_empty() {
+            return;
+        }
+
+        let _ = self.event_tx.send(AudioEvent::WaveformUpdate {
+            device_id: self.device_id,
+            samples,
+        });
+    }
+
+    /// Enable or disable sending peak and RMS levels for the level meters
+    pub fn set_level_meters(&mut self, enabled: bool) {
+        self.level_meters = enabled;
+    }
+
+    /// Send the level of the latest mono samples, if the meters are on
+    /// Only the device's own capture is metered, not an EQ output tap
+    fn send_levels(&self) {
+        if !self.level_meters || self.tap != CaptureTap::Monitor {
+            return;
+        }
+        let recent = self.sample_buffer.lock().unwrap().peek(LEVEL_SAMPLES);
+        if recent.is_empty() {
+            return;
+        }
+
+        let (peak_db, rms_db) = peak_and_rms_db(&recent);
+        let _ = self.event_tx.send(AudioEvent::LevelUpdate {
+            device_id: self.device_id,
+            peak_db,
+            rms_db,
         });
+    }
+
+    /// Report a clip since the last update (always on, unlike the meters)
+    fn send_clip(&self) {
+        if self.tap == CaptureTap::Monitor && self.clipped.swap(false, Ordering::Relaxed) {
+            let _ = self.event_tx.send(AudioEvent::Clipped {
+                device_id: self.device_id,
+            });
+        }
+    }
+
+    /// Configure silence detection: idle once the peak level has stayed at or
+    /// below `threshold_db` for `after` (None = never idle)
+    pub fn set_idle_detection(&mut self, threshold_db: f32, after: Option<Duration>) {
+        self.idle.configure(threshold_db, after);
+    }
+
+    /// Switch to a new FFT size, growing the sample buffer to at least four FFTs
+    /// Buffered samples are kept; if they no longer fill an FFT, updates pause
+    /// until they do (without counting as starved)
+    pub fn set_fft_size(&mut self, fft_size: usize) {
+        self.fft_processor.reconfigure(fft_size);
+        let mut buffer = self.sample_buffer.lock().unwrap();
+        buffer.set_capacity(buffer_capacity(fft_size));
+        self.health.refilling = !buffer.has_enough_samples(fft_size);
+        if let Some(stereo) = self.stereo_buffers.lock().unwrap().as_mut() {
+            let capacity = STEREO_BUFFER_CAPACITY.max(fft_size);
+            stereo.left.set_capacity(capacity);
+            stereo.right.set_capacity(capacity);
+        }
+    }
 
-        if elapsed >= PROCESS_INTERVAL_MS {
-            if buffer_len >= fft_size {
-                crate::debug_log!("[UPDATE] Processing spectrum (buffer has enough samples)");
+    /// Set the interval between spectrum updates
+    pub fn set_process_interval(&mut self, interval: Duration) {
+        self.process_interval = interval;
+        // Don't wait out the old (possibly longer) interval
+        self.next_process_at = self.next_process_at.min(Instant::now() + interval);
+    }
+
+    /// Update the stream (process FFT if the next update is due)
+    /// Should be called from the audio thread periodically
+    ///
+    /// The caller's timer quantizes when this runs (10 ms ticks in the PipeWire
+    /// loop), so updates are scheduled against a running deadline rather than
+    /// "time since last update". Individual gaps snap to tick boundaries, but
+    /// the average rate matches the interval (e.g. 60 Hz alternates 10/20 ms).
+    pub fn update(&mut self) {
+        let now = Instant::now();
+
+        if now >= self.next_process_at {
+            if self.process_spectrum() {
+                self.health.refilling = false;
+                if !self.idle.idle {
+                    self.health.ffts_in_window += 1;
+                }
+            } else if !self.health.refilling {
+                self.health.starved_cycles += 1;
+            }
+            self.send_stereo_frames();
+            self.send_waveform();
+            self.send_levels();
+            self.send_clip();
+
+            self.next_process_at += self.process_interval;
+            // After a stall, resume from now instead of bursting to catch up
+            if self.next_process_at < now {
+                let behind = now.duration_since(self.next_process_at);
+                self.health.dropped_cycles +=
+                    (behind.as_nanos() / self.process_interval.as_nanos().max(1)) as u64 + 1;
+                self.next_process_at = now + self.process_interval;
             }
-            self.process_spectrum();
-            self.last_process_time = Instant::now();
+        }
+
+        // Health is reported for the device's own capture only
+        if self.tap == CaptureTap::Monitor
+            && now.duration_since(self.health.window_start) >= HEALTH_REPORT_INTERVAL
+        {
+            self.report_health(now);
         }
     }
+
+    /// Send a health report for the elapsed window and start a new one
+    fn report_health(&mut self, now: Instant) {
+        let (buffer_len, capacity) = {
+            let buffer = self.sample_buffer.lock().unwrap();
+            (buffer.len(), buffer.capacity())
+        };
+        let elapsed = now.duration_since(self.health.window_start).as_secs_f32();
+
+        let health = StreamHealth {
+            buffer_fill: buffer_len as f32 / capacity.max(1) as f32,
+            fft_rate_hz: self.health.ffts_in_window as f32 / elapsed.max(f32::EPSILON),
+            starved_cycles: self.health.starved_cycles,
+            dropped_cycles: self.health.dropped_cycles,
+            idle: self.idle.idle,
+        };
+
+        crate::debug_log!(
+            "[UPDATE] Buffer: {}/{} samples, FFT needs {} samples, {:.1} updates/s",
+            buffer_len,
+            capacity,
+            self.fft_processor.fft_size(),
+            health.fft_rate_hz
+        );
+
+        let _ = self.event_tx.send(AudioEvent::StreamHealth {
+            device_id: self.device_id,
+            health,
+        });
+
+        self.health.window_start = now;
+        self.health.ffts_in_window = 0;
+    }
 }
 
 impl Drop for AudioCaptureStream {
@@ -413,11 +1083,17 @@ impl Drop for AudioCaptureStream {
             "[JACK] Dropping audio capture stream for device {:?}",
             self.device_id
         );
+
+        // Finalize any in-progress recording before the JACK client goes away
+        if let Err(e) = self.stop_recording() {
+            crate::debug_log!("[RECORD] Failed to finalize recording: {}", e);
+        }
         // JACK client will be automatically deactivated and cleaned up
     }
 }
 
 /// JACK processor for real-time EQ with bidirectional audio
+#[cfg(feature = "jack")]
 struct JackEqProcessor {
     /// Left channel input port
     in_left: Port<AudioIn>,
@@ -429,10 +1105,17 @@ struct JackEqProcessor {
     out_right: Port<AudioOut>,
     /// EQ processor
     eq_processor: EqProcessor,
+    /// Stereo width processor
+    width_processor: WidthProcessor,
+    /// Crossfeed processor
+    crossfeed_processor: CrossfeedProcessor,
+    /// Saturation processor
+    saturation_processor: SaturationProcessor,
     /// Volume processor
     volume_processor: VolumeProcessor,
 }
 
+#[cfg(feature = "jack")]
 impl jack::ProcessHandler for JackEqProcessor {
     fn process(&mut self, _client: &jack::Client, ps: &jack::ProcessScope) -> jack::Control {
         // Get input audio slices
@@ -443,15 +1126,25 @@ impl jack::ProcessHandler for JackEqProcessor {
         let out_left = self.out_left.as_mut_slice(ps);
         let out_right = self.out_right.as_mut_slice(ps);
 
-        // Process each sample through EQ, then volume
+        // Process each sample through EQ, then width, crossfeed, saturation and volume
+        // Width shapes the image of the mix; crossfeed then adapts that image to headphones
         for i in 0..in_left.len() {
             // 1. Apply EQ
             let (mut l, mut r) = self.eq_processor.process_sample(in_left[i], in_right[i]);
 
-            // 2. Apply volume
+            // 2. Apply stereo width
+            (l, r) = self.width_processor.process_sample(l, r);
+
+            // 3. Apply crossfeed
+            (l, r) = self.crossfeed_processor.process_sample(l, r);
+
+            // 4. Apply saturation
+            (l, r) = self.saturation_processor.process_sample(l, r);
+
+            // 5. Apply volume
             (l, r) = self.volume_processor.process_sample(l, r);
 
-            // 3. Write output
+            // 6. Write output
             out_left[i] = l;
             out_right[i] = r;
         }
@@ -465,9 +1158,8 @@ impl jack::ProcessHandler for JackEqProcessor {
 pub struct AudioProcessingStream {
     /// Device ID this stream is processing
     device_id: DeviceId,
-    /// Sample rate of the stream
-    sample_rate: u32,
     /// JACK client (must be kept alive)
+    #[cfg(feature = "jack")]
     _jack_client: jack::AsyncClient<(), JackEqProcessor>,
     /// Update flag for EQ settings (shared with processor)
     eq_update_flag: Arc<std::sync::atomic::AtomicBool>,
@@ -477,8 +1169,21 @@ pub struct AudioProcessingStream {
     volume_update_flag: Arc<std::sync::atomic::AtomicBool>,
     /// Pending settings for volume (shared with processor)
     volume_pending_settings: Arc<Mutex<Option<super::volume::VolumeSettings>>>,
+    /// Update flag for crossfeed settings (shared with processor)
+    crossfeed_update_flag: Arc<std::sync::atomic::AtomicBool>,
+    /// Pending settings for crossfeed (shared with processor)
+    crossfeed_pending_settings: Arc<Mutex<Option<CrossfeedSettings>>>,
+    /// Update flag for saturation drive (shared with processor)
+    saturation_update_flag: Arc<std::sync::atomic::AtomicBool>,
+    /// Pending drive for saturation (shared with processor)
+    saturation_pending_drive: Arc<Mutex<Option<f32>>>,
+    /// Update flag for stereo width (shared with processor)
+    width_update_flag: Arc<std::sync::atomic::AtomicBool>,
+    /// Pending stereo width (shared with processor)
+    width_pending: Arc<Mutex<Option<f32>>>,
 }
 
+#[cfg(feature = "jack")]
 impl AudioProcessingStream {
     /// Create a new audio processing stream with EQ
     pub fn new(
@@ -487,7 +1192,7 @@ impl AudioProcessingStream {
         dest_ports: (String, String),    // (left, right) ports to write to
         eq_settings: super::eq::EqSettings,
     ) -> Result<Self> {
-        let client_name = format!("wavewire_eq_{}", device_id.0);
+        let client_name = eq_client_name(device_id);
 
         crate::debug_log!(
             "[JACK EQ] Creating processing stream for device {:?}",
@@ -497,8 +1202,7 @@ impl AudioProcessingStream {
         crate::debug_log!("[JACK EQ] Dest ports: {:?}", dest_ports);
 
         // Create JACK client
-        let (client, _status) =
-            jack::Client::new(&client_name, jack::ClientOptions::NO_START_SERVER)?;
+        let client = open_jack_client(&client_name)?;
 
         let sample_rate = client.sample_rate();
         crate::debug_log!(
@@ -508,10 +1212,10 @@ impl AudioProcessingStream {
         );
 
         // Register ports (2 inputs, 2 outputs)
-        let in_left = client.register_port("in_L", jack::AudioIn::default())?;
-        let in_right = client.register_port("in_R", jack::AudioIn::default())?;
-        let out_left = client.register_port("out_L", jack::AudioOut::default())?;
-        let out_right = client.register_port("out_R", jack::AudioOut::default())?;
+        let in_left = client.register_port("in_L", jack::AudioIn)?;
+        let in_right = client.register_port("in_R", jack::AudioIn)?;
+        let out_left = client.register_port("out_L", jack::AudioOut)?;
+        let out_right = client.register_port("out_R", jack::AudioOut)?;
         crate::debug_log!("[JACK EQ] Registered 4 ports (2 in, 2 out)");
 
         // Create EQ processor
@@ -522,12 +1226,30 @@ impl AudioProcessingStream {
         let volume_processor = VolumeProcessor::new(super::volume::VolumeSettings::default());
         let (volume_update_flag, volume_pending_settings) = volume_processor.get_update_handles();
 
+        // Create crossfeed processor (off until configured)
+        let crossfeed_processor =
+            CrossfeedProcessor::new(sample_rate as f32, CrossfeedSettings::default());
+        let (crossfeed_update_flag, crossfeed_pending_settings) =
+            crossfeed_processor.get_update_handles();
+
+        // Create saturation processor (zero drive until configured)
+        let saturation_processor = SaturationProcessor::new(0.0);
+        let (saturation_update_flag, saturation_pending_drive) =
+            saturation_processor.get_update_handles();
+
+        // Create stereo width processor (unchanged until configured)
+        let width_processor = WidthProcessor::new(1.0);
+        let (width_update_flag, width_pending) = width_processor.get_update_handles();
+
         let processor = JackEqProcessor {
             in_left,
             in_right,
             out_left,
             out_right,
             eq_processor,
+            width_processor,
+            crossfeed_processor,
+            saturation_processor,
             volume_processor,
         };
 
@@ -622,15 +1344,35 @@ impl AudioProcessingStream {
 
         Ok(Self {
             device_id,
-            sample_rate: sample_rate as u32,
             _jack_client: async_client,
             eq_update_flag,
             eq_pending_settings,
             volume_update_flag,
             volume_pending_settings,
+            crossfeed_update_flag,
+            crossfeed_pending_settings,
+            saturation_update_flag,
+            saturation_pending_drive,
+            width_update_flag,
+            width_pending,
         })
     }
+}
 
+/// Without the `jack` feature there is nothing to process with
+#[cfg(not(feature = "jack"))]
+impl AudioProcessingStream {
+    pub fn new(
+        _device_id: DeviceId,
+        _source_ports: (String, String),
+        _dest_ports: (String, String),
+        _eq_settings: super::eq::EqSettings,
+    ) -> Result<Self> {
+        Err(WavewireError::JackDisabled.into())
+    }
+}
+
+impl AudioProcessingStream {
     /// Update EQ settings from another thread (non-blocking)
     pub fn update_eq(&self, new_settings: super::eq::EqSettings) {
         super::eq::update_eq_settings(&self.eq_update_flag, &self.eq_pending_settings, new_settings);
@@ -641,14 +1383,27 @@ impl AudioProcessingStream {
         super::volume::update_volume_settings(&self.volume_update_flag, &self.volume_pending_settings, new_settings);
     }
 
-    /// Get the device ID for this stream
-    pub fn device_id(&self) -> DeviceId {
-        self.device_id
+    /// Update crossfeed settings from another thread (non-blocking)
+    pub fn update_crossfeed(&self, new_settings: CrossfeedSettings) {
+        super::crossfeed::update_crossfeed_settings(
+            &self.crossfeed_update_flag,
+            &self.crossfeed_pending_settings,
+            new_settings,
+        );
     }
 
-    /// Get the sample rate of this stream
-    pub fn sample_rate(&self) -> u32 {
-        self.sample_rate
+    /// Update saturation drive from another thread (non-blocking)
+    pub fn update_saturation(&self, drive: f32) {
+        super::saturation::update_saturation_drive(
+            &self.saturation_update_flag,
+            &self.saturation_pending_drive,
+            drive,
+        );
+    }
+
+    /// Update stereo width from another thread (non-blocking)
+    pub fn update_width(&self, width: f32) {
+        super::width::update_stereo_width(&self.width_update_flag, &self.width_pending, width);
     }
 }
 
@@ -666,6 +1421,73 @@ impl Drop for AudioProcessingStream {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_downmix_normalizes_by_channel_count() {
+        let signal: &[f32] = &(0..2048)
+            .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 48000.0).sin())
+            .collect::<Vec<f32>>();
+
+        // Identical channels average back to the original signal, whatever their count
+        let mut mono = Vec::new();
+        downmix(&[signal], &mut mono);
+        assert_eq!(mono, signal);
+        let mut summed = Vec::new();
+        downmix(&[signal, signal], &mut summed);
+        assert_eq!(summed, signal);
+        let mut surround = Vec::new();
+        downmix(&[signal; 6], &mut surround);
+        for (a, b) in surround.iter().zip(signal) {
+            assert!((a - b).abs() < 1e-6);
+        }
+
+        // So the spectrum doesn't change with the capture mode
+        let mut fft = FftProcessor::new(2048, 64, 48000);
+        assert_eq!(fft.process(&mono), fft.process(&summed));
+
+        let mut empty = vec![1.0];
+        downmix(&[], &mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_idle_detector() {
+        let start = Instant::now();
+        let secs = |s| start + Duration::from_secs(s);
+        let mut detector = IdleDetector::new();
+        // Disabled until a timeout is configured
+        assert!(!detector.observe(f32::NEG_INFINITY, secs(100)));
+
+        detector.configure(-70.0, Some(Duration::from_secs(5)));
+        assert!(!detector.observe(-90.0, secs(0)));
+        assert!(!detector.observe(-70.0, secs(4)));
+        assert!(detector.observe(f32::NEG_INFINITY, secs(5)));
+        // Resumes on the first window above the threshold, and the timer restarts
+        assert!(!detector.observe(-69.0, secs(6)));
+        assert!(!detector.observe(-90.0, secs(7)));
+        assert!(!detector.observe(-90.0, secs(11)));
+        assert!(detector.observe(-90.0, secs(12)));
+    }
+
+    #[test]
+    fn test_xrun_debounce() {
+        let start = Instant::now();
+        let mut last = None;
+        assert!(xrun_due(&mut last, start));
+        assert!(!xrun_due(&mut last, start + XRUN_DEBOUNCE / 2));
+        assert!(!xrun_due(&mut last, start + XRUN_DEBOUNCE - Duration::from_millis(1)));
+        // Suppressed xruns don't push the next report back
+        assert!(xrun_due(&mut last, start + XRUN_DEBOUNCE));
+        assert_eq!(last, Some(start + XRUN_DEBOUNCE));
+    }
+
+    #[test]
+    fn test_peak_db() {
+        assert_eq!(peak_db(&[0.0, -1.0, 0.5]), 0.0);
+        assert!((peak_db(&[0.01, -0.001]) + 40.0).abs() < 1e-4);
+        assert_eq!(peak_db(&[0.0; 4]), f32::NEG_INFINITY);
+        assert_eq!(peak_db(&[]), f32::NEG_INFINITY);
+    }
+
     #[test]
     fn test_ring_buffer_push() {
         let mut buffer = RingBuffer::new(10);
@@ -698,4 +1520,48 @@ mod tests {
         // Peek should not remove samples
         assert_eq!(buffer.len(), 5);
     }
+
+    #[test]
+    fn test_stereo_recent_frames_thinned() {
+        let mut buffers = StereoBuffers::new(16);
+        let left: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let right: Vec<f32> = (0..10).map(|i| -(i as f32)).collect();
+        buffers.left.push(&left);
+        buffers.right.push(&right);
+
+        let frames = buffers.recent_frames(8, 4);
+        assert_eq!(frames, vec![[2.0, -2.0], [4.0, -4.0], [6.0, -6.0], [8.0, -8.0]]);
+    }
+
+    #[test]
+    fn test_peak_and_rms_levels() {
+        // A full-scale square wave: RMS equals the peak
+        let (peak, rms) = peak_and_rms_db(&[1.0, -1.0, 1.0, -1.0]);
+        assert_eq!((peak, rms), (0.0, 0.0));
+
+        // Half-scale sine: -6 dB peak, 3 dB lower RMS
+        let sine: Vec<f32> = (0..480).map(|i| 0.5 * (i as f32 * std::f32::consts::TAU / 48.0).sin()).collect();
+        let (peak, rms) = peak_and_rms_db(&sine);
+        assert!((peak + 6.02).abs() < 0.01, "{}", peak);
+        assert!((rms + 9.03).abs() < 0.01, "{}", rms);
+
+        assert_eq!(peak_and_rms_db(&[0.0; 8]), (f32::NEG_INFINITY, f32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_clip_detection() {
+        assert!(!clips(&[0.5, -0.998, 0.0]));
+        assert!(clips(&[0.5, -0.999, 0.0]));
+        assert!(clips(&[1.5]));
+        assert!(!clips(&[f32::NAN]));
+        assert!(!clips(&[]));
+    }
+
+    #[test]
+    fn test_waveform_points_keep_peaks() {
+        let samples = [0.1, -0.9, 0.2, 0.3, 1.0, -0.2, 0.0];
+        assert_eq!(waveform_points(&samples, 3), vec![-0.9, 1.0, 0.0]);
+        assert_eq!(waveform_points(&samples[..2], 8), vec![0.1, -0.9]);
+        assert!(waveform_points(&[], 8).is_empty());
+    }
 }
@@ -1,7 +1,11 @@
 use std::fmt;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use super::crossfeed::CrossfeedSettings;
 use super::eq::EqSettings;
+use super::monitor_ports::MonitorPortPattern;
 use super::volume::VolumeSettings;
 
 /// Unique identifier for an audio device
@@ -126,6 +130,15 @@ pub enum AudioCommand {
     StopVisualization {
         device_id: DeviceId,
     },
+    /// Also capture the output of a device's EQ node, next to its monitor
+    /// The device must already be visualized (that capture is the "pre" signal)
+    StartEqTap {
+        device_id: DeviceId,
+    },
+    /// Stop capturing the output of a device's EQ node
+    StopEqTap {
+        device_id: DeviceId,
+    },
     /// Enable EQ for a device
     EnableEq {
         device_id: DeviceId,
@@ -161,11 +174,183 @@ pub enum AudioCommand {
         device_id: DeviceId,
         settings: VolumeSettings,
     },
+    /// Set headphone crossfeed for a device
+    SetCrossfeed {
+        device_id: DeviceId,
+        settings: CrossfeedSettings,
+    },
+    /// Set saturation drive for a device (0.0 = off, 1.0 = heaviest)
+    SetSaturation {
+        device_id: DeviceId,
+        drive: f32,
+    },
+    /// Set stereo width for a device (1.0 = unchanged, 0.0 = mono, >1.0 = wider)
+    SetStereoWidth {
+        device_id: DeviceId,
+        width: f32,
+    },
+    /// Change the FFT size of a device's visualization (a power of two in
+    /// `FFT_SIZE_RANGE`); larger sizes resolve low frequencies better but react slower
+    SetFftSize {
+        device_id: DeviceId,
+        fft_size: usize,
+    },
+    /// Set the spectrum refresh rate for all visualization streams
+    SetRefreshRate {
+        hz: u32,
+    },
+    /// Keep left/right channels separate in all visualization streams
+    SetStereoCapture {
+        enabled: bool,
+    },
+    /// Send the latest samples of all visualization streams for the waveform view
+    SetWaveform {
+        enabled: bool,
+    },
+    /// Send peak and RMS levels of all visualization streams for the level meters
+    SetLevelMeters {
+        enabled: bool,
+    },
+    /// Run a separate FFT for the left and right channels of all visualization
+    /// streams (while stereo capture is on)
+    SetStereoSpectrum {
+        enabled: bool,
+    },
+    /// Idle spectrum processing on visualization streams that stay below
+    /// `threshold_db` (peak dBFS) for `after` (None = never idle)
+    SetIdleDetection {
+        threshold_db: f32,
+        after: Option<Duration>,
+    },
+    /// Monitor port name patterns tried, in order, when a visualization connects
+    /// (takes effect for visualizations started afterwards)
+    SetMonitorPortPatterns {
+        patterns: Vec<MonitorPortPattern>,
+    },
+    /// Start recording a visualized device to a WAV file
+    StartRecording {
+        device_id: DeviceId,
+        /// File to write; None picks a timestamped file in the recordings directory
+        path: Option<PathBuf>,
+    },
+    /// Stop recording a device
+    StopRecording {
+        device_id: DeviceId,
+    },
+}
+
+impl AudioCommand {
+    /// Whether this command makes a queued `other` redundant
+    /// Only coalescable ("latest value wins") commands can supersede
+    pub fn supersedes(&self, other: &AudioCommand) -> bool {
+        match (self, other) {
+            (
+                AudioCommand::SetEqBand {
+                    device_id,
+                    band_index,
+                    ..
+                },
+                AudioCommand::SetEqBand {
+                    device_id: other_device,
+                    band_index: other_band,
+                    ..
+                },
+            ) => device_id == other_device && band_index == other_band,
+            (
+                AudioCommand::SetEqSettings { device_id, .. },
+                AudioCommand::SetEqSettings {
+                    device_id: other_device,
+                    ..
+                },
+            ) => device_id == other_device,
+            (
+                AudioCommand::SetVolume { device_id, .. },
+                AudioCommand::SetVolume {
+                    device_id: other_device,
+                    ..
+                },
+            ) => device_id == other_device,
+            (
+                AudioCommand::SetCrossfeed { device_id, .. },
+                AudioCommand::SetCrossfeed {
+                    device_id: other_device,
+                    ..
+                },
+            ) => device_id == other_device,
+            (
+                AudioCommand::SetSaturation { device_id, .. },
+                AudioCommand::SetSaturation {
+                    device_id: other_device,
+                    ..
+                },
+            ) => device_id == other_device,
+            (
+                AudioCommand::SetStereoWidth { device_id, .. },
+                AudioCommand::SetStereoWidth {
+                    device_id: other_device,
+                    ..
+                },
+            ) => device_id == other_device,
+            _ => false,
+        }
+    }
+
+    /// Variant name, for logs and the command tally
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioCommand::CreateVirtualDevice { .. } => "CreateVirtualDevice",
+            AudioCommand::DestroyVirtualDevice { .. } => "DestroyVirtualDevice",
+            AudioCommand::Connect { .. } => "Connect",
+            AudioCommand::Disconnect { .. } => "Disconnect",
+            AudioCommand::StartVisualization { .. } => "StartVisualization",
+            AudioCommand::StopVisualization { .. } => "StopVisualization",
+            AudioCommand::StartEqTap { .. } => "StartEqTap",
+            AudioCommand::StopEqTap { .. } => "StopEqTap",
+            AudioCommand::EnableEq { .. } => "EnableEq",
+            AudioCommand::DisableEq { .. } => "DisableEq",
+            AudioCommand::SetEqBand { .. } => "SetEqBand",
+            AudioCommand::SetEqSettings { .. } => "SetEqSettings",
+            AudioCommand::SetEqBypass { .. } => "SetEqBypass",
+            AudioCommand::ResetEq { .. } => "ResetEq",
+            AudioCommand::SetVolume { .. } => "SetVolume",
+            AudioCommand::SetCrossfeed { .. } => "SetCrossfeed",
+            AudioCommand::SetSaturation { .. } => "SetSaturation",
+            AudioCommand::SetStereoWidth { .. } => "SetStereoWidth",
+            AudioCommand::SetFftSize { .. } => "SetFftSize",
+            AudioCommand::SetRefreshRate { .. } => "SetRefreshRate",
+            AudioCommand::SetStereoCapture { .. } => "SetStereoCapture",
+            AudioCommand::SetWaveform { .. } => "SetWaveform",
+            AudioCommand::SetLevelMeters { .. } => "SetLevelMeters",
+            AudioCommand::SetStereoSpectrum { .. } => "SetStereoSpectrum",
+            AudioCommand::SetIdleDetection { .. } => "SetIdleDetection",
+            AudioCommand::SetMonitorPortPatterns { .. } => "SetMonitorPortPatterns",
+            AudioCommand::StartRecording { .. } => "StartRecording",
+            AudioCommand::StopRecording { .. } => "StopRecording",
+        }
+    }
+
+    /// Whether this command can be held back and coalesced when the queue is full
+    /// Keep in sync with `supersedes`, which must handle every variant listed here
+    pub fn is_coalescable(&self) -> bool {
+        matches!(
+            self,
+            AudioCommand::SetEqBand { .. }
+                | AudioCommand::SetEqSettings { .. }
+                | AudioCommand::SetVolume { .. }
+                | AudioCommand::SetCrossfeed { .. }
+                | AudioCommand::SetSaturation { .. }
+                | AudioCommand::SetStereoWidth { .. }
+        )
+    }
 }
 
 /// Events sent from audio thread to UI thread
 #[derive(Debug, Clone)]
 pub enum AudioEvent {
+    /// Connected to the PipeWire daemon and registry is available
+    Connected,
+    /// Lost the connection to the PipeWire daemon; reconnecting in the background
+    Disconnected,
     /// A new device was discovered or created
     DeviceAdded {
         device_id: DeviceId,
@@ -174,6 +359,8 @@ pub enum AudioEvent {
     },
     /// A device was removed or destroyed
     DeviceRemoved { device_id: DeviceId },
+    /// One of a device's ports went away (the device itself is still there)
+    PortRemoved { device_id: DeviceId, port_name: String },
     /// A connection was established
     ConnectionEstablished {
         source: String,
@@ -185,10 +372,12 @@ pub enum AudioEvent {
         destination: String,
     },
     /// PipeWire buffer underrun or overrun occurred
+    #[cfg(any(test, feature = "jack"))]
     Xrun,
     /// An error occurred
     Error { message: String },
-    /// Visualization started for a device
+    /// Visualization started for a device (only JACK capture streams start one)
+    #[cfg(feature = "jack")]
     VisualizationStarted {
         device_id: DeviceId,
         port_id: PortId,
@@ -197,10 +386,30 @@ pub enum AudioEvent {
     VisualizationStopped {
         device_id: DeviceId,
     },
+    /// A stream node started (`running`) or stopped processing audio
+    StreamStateChanged {
+        device_id: DeviceId,
+        running: bool,
+    },
     /// Spectrum data update from FFT processing
+    /// Shared so the UI can keep it without copying the bins
     SpectrumUpdate {
         device_id: DeviceId,
-        data: SpectrumData,
+        data: Arc<SpectrumData>,
+    },
+    /// Capture of a device's EQ output started
+    EqTapStarted {
+        device_id: DeviceId,
+    },
+    /// Capture of a device's EQ output stopped
+    EqTapStopped {
+        device_id: DeviceId,
+    },
+    /// Spectrum of a device's EQ output (the "post" signal)
+    #[cfg(any(test, feature = "jack"))]
+    EqTapSpectrum {
+        device_id: DeviceId,
+        data: Arc<SpectrumData>,
     },
     /// EQ was enabled for a device
     EqEnabled {
@@ -221,13 +430,87 @@ pub enum AudioEvent {
         device_id: DeviceId,
         settings: VolumeSettings,
     },
+    /// Crossfeed was updated for a device
+    CrossfeedUpdated {
+        device_id: DeviceId,
+        settings: CrossfeedSettings,
+    },
+    /// Saturation drive was updated for a device
+    SaturationUpdated {
+        device_id: DeviceId,
+        drive: f32,
+    },
+    /// Stereo width was updated for a device
+    StereoWidthUpdated {
+        device_id: DeviceId,
+        width: f32,
+    },
+    /// A device's visualization switched to a new FFT size
+    FftSizeChanged {
+        device_id: DeviceId,
+        fft_size: usize,
+    },
+    /// Recording started for a device
+    RecordingStarted {
+        device_id: DeviceId,
+        path: PathBuf,
+    },
+    /// Recording stopped and the WAV file was finalized
+    RecordingStopped {
+        device_id: DeviceId,
+        path: PathBuf,
+        duration: Duration,
+    },
+    /// Latest left/right sample pairs for the goniometer (stereo capture only)
+    StereoFrames {
+        device_id: DeviceId,
+        frames: Vec<[f32; 2]>,
+    },
+    /// Latest mono samples for the waveform view, thinned to keep their peaks
+    WaveformUpdate {
+        device_id: DeviceId,
+        samples: Vec<f32>,
+    },
+    /// A sample reached 0 dBFS on either channel since the last update
+    Clipped {
+        device_id: DeviceId,
+    },
+    /// Peak and RMS level (dBFS, -inf for digital silence) of the latest mono samples
+    LevelUpdate {
+        device_id: DeviceId,
+        peak_db: f32,
+        rms_db: f32,
+    },
+    /// Periodic health report for a capture stream
+    StreamHealth {
+        device_id: DeviceId,
+        health: StreamHealth,
+    },
+}
+
+/// Capture stream health over the last report interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamHealth {
+    /// Ring buffer fill level (0.0-1.0)
+    pub buffer_fill: f32,
+    /// Spectrum updates actually produced per second
+    pub fft_rate_hz: f32,
+    /// Updates skipped because the buffer held fewer samples than the FFT needs (total)
+    pub starved_cycles: u64,
+    /// Updates dropped because the audio thread fell behind schedule (total)
+    pub dropped_cycles: u64,
+    /// Spectrum processing is paused because the source has gone silent
+    pub idle: bool,
 }
 
 /// Frequency spectrum data for visualization
 #[derive(Debug, Clone)]
 pub struct SpectrumData {
     /// Frequency bin magnitudes in dB (typically 64-128 bins)
+    /// The left channel's when `bins_right` is set, otherwise both channels mixed
     pub bins: Vec<f32>,
+    /// Right channel bin magnitudes in dB, when the channels are analyzed separately
+    pub bins_right: Option<Vec<f32>>,
     /// Corresponding frequencies in Hz for each bin
     pub frequencies: Vec<f32>,
     /// Sample rate of the audio source
@@ -1,12 +1,33 @@
 use serde::{Deserialize, Serialize};
+#[cfg(any(test, feature = "jack"))]
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// Limiter threshold range (dBFS); the default is the top, which a full-scale
+/// input only reaches at maximum gain, so the limiter stays out of the way
+/// until the threshold is lowered
+const LIMITER_THRESHOLD_RANGE: (f32, f32) = (-24.0, 6.0);
+
+/// How far below the threshold the limiter starts bending the signal (dB)
+#[cfg(any(test, feature = "jack"))]
+const LIMITER_KNEE_DB: f32 = 3.0;
+
+fn default_limiter_threshold_db() -> f32 {
+    LIMITER_THRESHOLD_RANGE.1
+}
+
 /// Volume settings for a device (serializable for config)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct VolumeSettings {
     pub gain_linear: f32, // Linear gain multiplier (0.001 to 2.0)
     pub gain_db: f32,     // Gain in dB (-60.0 to +6.0)
+    /// Level (dBFS) the output is limited to after the gain (-24.0 to +6.0)
+    #[serde(default = "default_limiter_threshold_db")]
+    pub limiter_threshold_db: f32,
+    /// Left/right balance from -1.0 (left only) through 0.0 (center) to 1.0 (right only)
+    #[serde(default)]
+    pub balance: f32,
 }
 
 impl Default for VolumeSettings {
@@ -14,6 +35,8 @@ impl Default for VolumeSettings {
         Self {
             gain_linear: 1.0, // Unity gain (0 dB)
             gain_db: 0.0,
+            limiter_threshold_db: default_limiter_threshold_db(),
+            balance: 0.0,
         }
     }
 }
@@ -25,43 +48,96 @@ impl VolumeSettings {
         Self {
             gain_linear: 10f32.powf(clamped_db / 20.0),
             gain_db: clamped_db,
-        }
-    }
-
-    /// Create a new VolumeSettings from linear gain value
-    pub fn from_linear(gain_linear: f32) -> Self {
-        let clamped_linear = gain_linear.clamp(0.001, 2.0);
-        Self {
-            gain_linear: clamped_linear,
-            gain_db: 20.0 * clamped_linear.log10(),
+            ..Self::default()
         }
     }
 
     /// Adjust gain by delta dB
     pub fn adjust_db(&mut self, delta_db: f32) {
-        self.gain_db = (self.gain_db + delta_db).clamp(-60.0, 6.0);
-        self.gain_linear = 10f32.powf(self.gain_db / 20.0);
+        let adjusted = Self::from_db(self.gain_db + delta_db);
+        (self.gain_db, self.gain_linear) = (adjusted.gain_db, adjusted.gain_linear);
     }
 
     /// Clamp settings to valid ranges
     pub fn clamp(&mut self) {
         self.gain_db = self.gain_db.clamp(-60.0, 6.0);
         self.gain_linear = self.gain_linear.clamp(0.001, 2.0);
+        self.limiter_threshold_db = self
+            .limiter_threshold_db
+            .clamp(LIMITER_THRESHOLD_RANGE.0, LIMITER_THRESHOLD_RANGE.1);
+        self.balance = self.balance.clamp(-1.0, 1.0);
+    }
+
+    /// Move the balance by `delta`, stopping at center on the way across it
+    pub fn nudge_balance(&mut self, delta: f32) {
+        // Rounded so repeated steps land exactly on center
+        let nudged = ((self.balance + delta).clamp(-1.0, 1.0) * 100.0).round() / 100.0;
+        let crosses_center = self.balance != 0.0 && nudged.signum() != self.balance.signum();
+        self.balance = if crosses_center { 0.0 } else { nudged };
+    }
+
+    /// Left and right multipliers of the balance (constant power, unity at center)
+    #[cfg(any(test, feature = "jack"))]
+    pub fn balance_gains(&self) -> (f32, f32) {
+        // Center is exact, not a rounding error away from unity
+        if self.balance == 0.0 {
+            return (1.0, 1.0);
+        }
+        let angle = (self.balance.clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+        (angle.cos() * SQRT_2, angle.sin() * SQRT_2)
     }
+
+    /// Linear levels where the limiter's knee starts and the ceiling it never exceeds
+    #[cfg(any(test, feature = "jack"))]
+    fn limiter_levels(&self) -> (f32, f32) {
+        let ceiling = 10f32.powf(self.limiter_threshold_db / 20.0);
+        (ceiling * 10f32.powf(-LIMITER_KNEE_DB / 20.0), ceiling)
+    }
+}
+
+/// Volume times balance, per channel
+#[cfg(any(test, feature = "jack"))]
+fn channel_gains(settings: &VolumeSettings) -> (f32, f32) {
+    let (left, right) = settings.balance_gains();
+    (settings.gain_linear * left, settings.gain_linear * right)
+}
+
+/// Soft-knee limiting of one sample: untouched up to `knee_start`, then bent
+/// smoothly (matching slope at the knee) towards `ceiling`, which it never exceeds
+#[cfg(any(test, feature = "jack"))]
+#[inline]
+fn limit(sample: f32, knee_start: f32, ceiling: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= knee_start {
+        return sample;
+    }
+    let range = ceiling - knee_start;
+    (knee_start + range * ((magnitude - knee_start) / range).tanh()).copysign(sample)
 }
 
 /// Real-time volume processor (lives in JACK callback)
+#[cfg(any(test, feature = "jack"))]
 pub struct VolumeProcessor {
     settings: VolumeSettings,
+    /// Per-channel gain (volume and balance) and linear limiter levels of
+    /// `settings`, worked out once per settings change
+    channel_gains: (f32, f32),
+    knee_start: f32,
+    ceiling: f32,
     needs_update: Arc<AtomicBool>,
     pending_settings: Arc<Mutex<Option<VolumeSettings>>>,
 }
 
+#[cfg(any(test, feature = "jack"))]
 impl VolumeProcessor {
     /// Create a new volume processor with the given settings
     pub fn new(settings: VolumeSettings) -> Self {
+        let (knee_start, ceiling) = settings.limiter_levels();
         Self {
+            channel_gains: channel_gains(&settings),
             settings,
+            knee_start,
+            ceiling,
             needs_update: Arc::new(AtomicBool::new(false)),
             pending_settings: Arc::new(Mutex::new(None)),
         }
@@ -76,18 +152,24 @@ impl VolumeProcessor {
             self.apply_pending_update();
         }
 
-        // Apply gain (simple multiplication)
-        (left * self.settings.gain_linear, right * self.settings.gain_linear)
+        // Apply gain and balance, then keep the result under the limiter threshold
+        let (left_gain, right_gain) = self.channel_gains;
+        (
+            limit(left * left_gain, self.knee_start, self.ceiling),
+            limit(right * right_gain, self.knee_start, self.ceiling),
+        )
     }
 
     /// Apply pending settings update if available (non-blocking)
     fn apply_pending_update(&mut self) {
         // Use try_lock to avoid blocking the real-time thread
-        if let Ok(mut pending) = self.pending_settings.try_lock() {
-            if let Some(new_settings) = pending.take() {
-                self.settings = new_settings;
-                self.needs_update.store(false, Ordering::Relaxed);
-            }
+        if let Ok(mut pending) = self.pending_settings.try_lock()
+            && let Some(new_settings) = pending.take()
+        {
+            (self.knee_start, self.ceiling) = new_settings.limiter_levels();
+            self.channel_gains = channel_gains(&new_settings);
+            self.settings = new_settings;
+            self.needs_update.store(false, Ordering::Relaxed);
         }
         // If lock fails, we'll try again on the next buffer - no big deal
     }
@@ -100,10 +182,6 @@ impl VolumeProcessor {
         )
     }
 
-    /// Get current settings
-    pub fn settings(&self) -> &VolumeSettings {
-        &self.settings
-    }
 }
 
 /// Helper function to schedule a settings update from another thread
@@ -166,10 +244,14 @@ mod tests {
         let mut settings = VolumeSettings {
             gain_db: 100.0,
             gain_linear: 100.0,
+            limiter_threshold_db: 100.0,
+            balance: -3.0,
         };
         settings.clamp();
         assert_eq!(settings.gain_db, 6.0);
         assert_eq!(settings.gain_linear, 2.0);
+        assert_eq!(settings.limiter_threshold_db, 6.0);
+        assert_eq!(settings.balance, -1.0);
     }
 
     #[test]
@@ -189,6 +271,89 @@ mod tests {
         assert!((r_out + 0.6).abs() < 0.01);
     }
 
+    #[test]
+    fn test_limiter_holds_boosted_signal_under_threshold() {
+        // A full-scale sine boosted 12 dB (the gain is pre-multiplied, past the +6 dB
+        // volume range) into a limiter set to -1 dBFS
+        let settings = VolumeSettings {
+            gain_linear: 10f32.powf(12.0 / 20.0),
+            gain_db: 12.0,
+            limiter_threshold_db: -1.0,
+            ..VolumeSettings::default()
+        };
+        let ceiling = 10f32.powf(-1.0 / 20.0);
+        let mut processor = VolumeProcessor::new(settings);
+        let mut loudest: f32 = 0.0;
+        for n in 0..4800 {
+            let sample = (n as f32 * std::f32::consts::TAU * 440.0 / 48000.0).sin();
+            let (left, right) = processor.process_sample(sample, -sample);
+            assert!(left.abs() <= ceiling && right.abs() <= ceiling, "{} {}", left, right);
+            loudest = loudest.max(left.abs());
+        }
+        assert!(loudest > ceiling * 0.99);
+
+        // Quiet material below the knee passes untouched
+        assert_eq!(processor.process_sample(0.1, -0.1), (0.1 * 10f32.powf(0.6), -0.1 * 10f32.powf(0.6)));
+    }
+
+    #[test]
+    fn test_limiter_is_transparent_by_default() {
+        let mut processor = VolumeProcessor::new(VolumeSettings::from_db(6.0));
+        let (left, right) = processor.process_sample(0.5, -0.7);
+        assert_eq!((left, right), (0.5 * processor.settings.gain_linear, -0.7 * processor.settings.gain_linear));
+
+        // Older configs without a threshold get the default
+        let settings: VolumeSettings = toml::from_str("gain_linear = 0.5\ngain_db = -6.0").unwrap();
+        assert_eq!(settings.limiter_threshold_db, 6.0);
+    }
+
+    #[test]
+    fn test_balance_gains() {
+        let gains = |balance: f32| {
+            let settings = VolumeSettings { balance, ..VolumeSettings::default() };
+            let (left, right) = settings.balance_gains();
+            assert!((left * left + right * right - 2.0).abs() < 1e-5, "power at {}", balance);
+            (left, right)
+        };
+
+        let (left, right) = gains(-1.0);
+        assert!((left - SQRT_2).abs() < 1e-6 && right.abs() < 1e-6);
+        assert_eq!(gains(0.0), (1.0, 1.0));
+        let (left, right) = gains(1.0);
+        assert!(left.abs() < 1e-6 && (right - SQRT_2).abs() < 1e-6);
+
+        // Applied by the processor along with the volume
+        let settings = VolumeSettings { balance: 1.0, ..VolumeSettings::from_db(-6.0) };
+        let mut processor = VolumeProcessor::new(settings);
+        let (left, right) = processor.process_sample(0.5, 0.5);
+        assert!(left.abs() < 1e-6);
+        assert!((right - 0.5 * 0.501 * SQRT_2).abs() < 1e-3, "{}", right);
+    }
+
+    #[test]
+    fn test_nudge_balance_stops_at_center() {
+        let mut settings = VolumeSettings::default();
+        for _ in 0..3 {
+            settings.nudge_balance(-0.1);
+        }
+        assert_eq!(settings.balance, -0.3);
+        for _ in 0..3 {
+            settings.nudge_balance(0.1);
+        }
+        assert_eq!(settings.balance, 0.0);
+
+        // From off-grid values, crossing center lands on it
+        settings.balance = -0.05;
+        settings.nudge_balance(0.1);
+        assert_eq!(settings.balance, 0.0);
+        settings.nudge_balance(0.1);
+        assert_eq!(settings.balance, 0.1);
+
+        settings.balance = 0.95;
+        settings.nudge_balance(0.1);
+        assert_eq!(settings.balance, 1.0);
+    }
+
     #[test]
     fn test_volume_processor_update_mechanism() {
         let processor = VolumeProcessor::new(VolumeSettings::default());
@@ -0,0 +1,130 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Widest setting: the side signal at twice its level
+const MAX_WIDTH: f32 = 2.0;
+
+/// Clamp a stereo width to its valid range (0.0 = mono, 1.0 = unchanged, 2.0 = widest)
+pub fn clamp_stereo_width(width: f32) -> f32 {
+    if width.is_nan() { 1.0 } else { width.clamp(0.0, MAX_WIDTH) }
+}
+
+/// Real-time mid/side stereo width (lives in JACK callback)
+/// The mid (L+R) and side (L-R) signals get their own gain: the mid is kept at
+/// unity and the side follows the width, narrowing or widening the stereo image
+#[cfg(any(test, feature = "jack"))]
+pub struct WidthProcessor {
+    width: f32,
+    mid_gain: f32,
+    side_gain: f32,
+    needs_update: Arc<AtomicBool>,
+    pending_width: Arc<Mutex<Option<f32>>>,
+}
+
+#[cfg(any(test, feature = "jack"))]
+impl WidthProcessor {
+    /// Create a new width processor (1.0 leaves the signal unchanged)
+    pub fn new(width: f32) -> Self {
+        let mut processor = Self {
+            width: 1.0,
+            mid_gain: 1.0,
+            side_gain: 1.0,
+            needs_update: Arc::new(AtomicBool::new(false)),
+            pending_width: Arc::new(Mutex::new(None)),
+        };
+        processor.configure(width);
+        processor
+    }
+
+    fn configure(&mut self, width: f32) {
+        self.width = clamp_stereo_width(width);
+        self.mid_gain = 1.0;
+        self.side_gain = self.width;
+    }
+
+    /// Process a stereo sample: M/S encode, mid and side gain, decode
+    /// This is the main real-time processing function - must be allocation-free
+    #[inline]
+    pub fn process_sample(&mut self, left: f32, right: f32) -> (f32, f32) {
+        // Check for pending updates (atomic read - very fast)
+        if self.needs_update.load(Ordering::Relaxed) {
+            self.apply_pending_update();
+        }
+
+        // Unity width is an exact passthrough (encoding and decoding would round)
+        if self.width == 1.0 {
+            return (left, right);
+        }
+
+        let mid = (left + right) * 0.5 * self.mid_gain;
+        let side = (left - right) * 0.5 * self.side_gain;
+        (mid + side, mid - side)
+    }
+
+    /// Apply pending width update if available (non-blocking)
+    fn apply_pending_update(&mut self) {
+        // Use try_lock to avoid blocking the real-time thread
+        let new_width = match self.pending_width.try_lock() {
+            Ok(mut pending) => pending.take(),
+            Err(_) => None,
+        };
+        if let Some(new_width) = new_width {
+            self.configure(new_width);
+            self.needs_update.store(false, Ordering::Relaxed);
+        }
+        // If lock fails, we'll try again on the next buffer - no big deal
+    }
+
+    /// Get handles for updating the width from another thread
+    pub fn get_update_handles(&self) -> (Arc<AtomicBool>, Arc<Mutex<Option<f32>>>) {
+        (
+            Arc::clone(&self.needs_update),
+            Arc::clone(&self.pending_width),
+        )
+    }
+}
+
+/// Helper function to schedule a width update from another thread
+pub fn update_stereo_width(
+    needs_update: &Arc<AtomicBool>,
+    pending_width: &Arc<Mutex<Option<f32>>>,
+    new_width: f32,
+) {
+    if let Ok(mut pending) = pending_width.lock() {
+        *pending = Some(new_width);
+        needs_update.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unity_width_is_bit_exact() {
+        let mut processor = WidthProcessor::new(1.0);
+        for i in 0..256 {
+            let left = (i as f32 * 0.1).sin() * 1.5;
+            let right = (i as f32 * 0.37).cos();
+            let (out_left, out_right) = processor.process_sample(left, right);
+            assert_eq!(out_left.to_bits(), left.to_bits());
+            assert_eq!(out_right.to_bits(), right.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_width_scales_the_side_signal() {
+        // Zero width folds to mono
+        let mut processor = WidthProcessor::new(0.0);
+        assert_eq!(processor.process_sample(1.0, 0.0), (0.5, 0.5));
+
+        // Centered material is untouched at any width, the side signal doubles at 2.0
+        let (needs_update, pending_width) = processor.get_update_handles();
+        update_stereo_width(&needs_update, &pending_width, 5.0);
+        assert_eq!(processor.process_sample(0.5, 0.5), (0.5, 0.5));
+        assert_eq!(processor.process_sample(0.5, -0.5), (1.0, -1.0));
+        assert!(!needs_update.load(Ordering::Relaxed));
+
+        assert_eq!(clamp_stereo_width(f32::NAN), 1.0);
+    }
+}
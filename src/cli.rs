@@ -0,0 +1,175 @@
+use anyhow::{Context, Result};
+
+use crate::audio::MAX_REFRESH_RATE_HZ;
+use crate::config::{validate_profile_name, MAX_FPS};
+
+/// Usage text printed for --help and on argument errors
+pub const USAGE: &str = "\
+Usage: wavewire [OPTIONS]
+
+Options:
+  --check      Verify PipeWire and JACK are reachable, then exit
+  --oneline    Print a one-line spectrum of the default sink for status bars
+  --width <N>  Number of characters in the --oneline spectrum (default 16)
+  --profile <NAME>
+               Load and save config-<NAME>.toml instead of config.toml
+  --fps <N>    Redraw the UI at most N times per second (1-240, default from
+               config, 60)
+  --refresh-rate <HZ>
+               Spectrum update rate (1-100); saved like the 'f' key
+  --visualize-id <ID>
+               Visualize the PipeWire node with this object ID (as shown by
+               pw-cli ls Node), even when its name is ambiguous
+  -h, --help   Print this help and exit";
+
+/// Parsed command-line options
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CliArgs {
+    /// Run the runtime dependency self-test instead of the TUI
+    pub check: bool,
+    /// Print a single-row spectrum to stdout instead of the TUI
+    pub oneline: bool,
+    /// Width of the --oneline spectrum in characters
+    pub width: Option<usize>,
+    /// Named config profile to load and save (None = default config.toml)
+    pub profile: Option<String>,
+    /// UI frame rate limit for this run (None = config's max_fps)
+    pub fps: Option<u32>,
+    /// Spectrum refresh rate in Hz (None = config's refresh_rate_hz)
+    pub refresh_rate: Option<u32>,
+    /// PipeWire node to visualize at startup, by global object ID
+    pub visualize_id: Option<u32>,
+    /// Print usage and exit
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Parse options from the process arguments
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    /// Parse options from an argument list (excluding the program name)
+    pub fn parse_from<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut cli = Self::default();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--check" => cli.check = true,
+                "--oneline" => cli.oneline = true,
+                "--width" => {
+                    let value = args.next().context("--width requires a value")?;
+                    let width = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&w| w > 0)
+                        .with_context(|| format!("Invalid --width: {}", value))?;
+                    cli.width = Some(width);
+                }
+                "--profile" => {
+                    let name = args.next().context("--profile requires a name")?;
+                    validate_profile_name(&name)?;
+                    cli.profile = Some(name);
+                }
+                "--fps" => cli.fps = Some(parse_rate(args.next(), "--fps", MAX_FPS)?),
+                "--refresh-rate" => {
+                    cli.refresh_rate = Some(parse_rate(args.next(), "--refresh-rate", MAX_REFRESH_RATE_HZ)?);
+                }
+                "--visualize-id" => {
+                    let value = args.next().context("--visualize-id requires an object ID")?;
+                    let id = value
+                        .parse::<u32>()
+                        .with_context(|| format!("Invalid --visualize-id: {}", value))?;
+                    cli.visualize_id = Some(id);
+                }
+                "-h" | "--help" => cli.help = true,
+                other => anyhow::bail!("Unknown argument: {}\n\n{}", other, USAGE),
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+/// Parse a per-second rate option in 1..=max
+fn parse_rate(value: Option<String>, option: &str, max: u32) -> Result<u32> {
+    let value = value.with_context(|| format!("{} requires a value", option))?;
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|rate| (1..=max).contains(rate))
+        .with_context(|| format!("Invalid {}: {} (expected 1-{})", option, value, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse_from(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_parse_check() {
+        let cli = parse(&["--check"]).unwrap();
+        assert!(cli.check);
+        assert!(!cli.help);
+    }
+
+    #[test]
+    fn test_parse_oneline_width() {
+        let cli = parse(&["--oneline", "--width", "24"]).unwrap();
+        assert!(cli.oneline);
+        assert_eq!(cli.width, Some(24));
+
+        assert!(parse(&["--width"]).is_err());
+        assert!(parse(&["--width", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let cli = parse(&["--profile", "gaming"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("gaming"));
+
+        assert!(parse(&["--profile"]).is_err());
+        assert!(parse(&["--profile", "../gaming"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_rates() {
+        let cli = parse(&["--fps", "5", "--refresh-rate", "10"]).unwrap();
+        assert_eq!(cli.fps, Some(5));
+        assert_eq!(cli.refresh_rate, Some(10));
+
+        assert!(parse(&["--fps"]).is_err());
+        assert!(parse(&["--fps", "0"]).is_err());
+        assert!(parse(&["--fps", "1000"]).is_err());
+        assert!(parse(&["--refresh-rate", "121"]).is_err());
+        // The capture timer ticks every 10 ms, so 100 Hz is the ceiling
+        assert_eq!(parse(&["--refresh-rate", "100"]).unwrap().refresh_rate, Some(100));
+        assert!(parse(&["--refresh-rate", "101"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_visualize_id() {
+        let cli = parse(&["--visualize-id", "57"]).unwrap();
+        assert_eq!(cli.visualize_id, Some(57));
+
+        assert!(parse(&["--visualize-id"]).is_err());
+        assert!(parse(&["--visualize-id", "Speakers"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_argument() {
+        assert!(parse(&["--bogus"]).is_err());
+    }
+}
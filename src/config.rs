@@ -1,11 +1,16 @@
-use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::audio::{DeviceId, DeviceInfo, EqSettings, VolumeSettings};
+use crate::audio::{
+    default_monitor_port_patterns, CrossfeedSettings, DeviceId, DeviceInfo, EqSettings,
+    MonitorPortPattern, PairingStrategy, VolumeSettings,
+};
 use crate::debug_log;
+use crate::error::{Result, WavewireError};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -15,6 +20,178 @@ pub struct Config {
     pub eq: EqConfig,
     #[serde(default)]
     pub volume: VolumeConfig,
+    #[serde(default)]
+    pub crossfeed: CrossfeedConfig,
+    #[serde(default)]
+    pub saturation: SaturationConfig,
+    #[serde(default)]
+    pub width: WidthConfig,
+    #[serde(default)]
+    pub general: GeneralConfig,
+    /// Whether the first-run key overlay has been dismissed
+    #[serde(default)]
+    pub first_run_seen: bool,
+    /// Action name → key(s), overriding the default bindings (edited by hand)
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, KeyBinding>,
+}
+
+/// General application behavior (edited by hand, never changed by the app)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneralConfig {
+    /// Seconds to wait after the last change before auto-saving
+    /// (default: 2, 0 = only save on exit)
+    #[serde(default = "default_auto_save_delay")]
+    pub auto_save_delay_secs: f64,
+    /// Upper bound on UI redraws per second (default: 60, 1-240)
+    /// Lower it on servers or low-power machines; keys still redraw immediately
+    #[serde(default = "default_max_fps")]
+    pub max_fps: u32,
+    /// How ports are paired when linking two devices on the Routing tab:
+    /// "by_name" (default, FL→FL), "by_index" (positional) or "mono_fan_out"
+    /// (a single source port feeds every destination port)
+    #[serde(default)]
+    pub port_pairing: PairingStrategy,
+    /// Hide application streams that have been silent for this many seconds
+    /// (default: 0 = never). Only streams being visualized are measured;
+    /// hidden streams reappear as soon as they play again
+    #[serde(default)]
+    pub auto_hide_silent_secs: u64,
+    /// Pause spectrum processing for a visualized stream once it has stayed
+    /// below `idle_threshold_db` for this many seconds (default: 10, 0 = never).
+    /// The device keeps visualizing and resumes as soon as audio returns
+    #[serde(default = "default_idle_after")]
+    pub idle_after_secs: u64,
+    /// Peak level in dBFS below which a stream counts as silent (default: -70, -120 to 0)
+    #[serde(default = "default_idle_threshold")]
+    pub idle_threshold_db: f32,
+    /// How a device's monitor ports are recognized when visualizing it, tried in
+    /// order (default: names ending in "monitor_FL" / "monitor_FR"). Each entry has
+    /// `left`, `right` and `matching` = "suffix" (default) or "substring";
+    /// matching ignores case. Add one if visualizations don't auto-connect
+    #[serde(default = "default_monitor_port_patterns")]
+    pub monitor_port_patterns: Vec<MonitorPortPattern>,
+    /// Apply spectrum frames to the display at most once per this many ms, keeping
+    /// only each device's latest (default: 0 = every redraw, up to 1000). Raise it to
+    /// save CPU with many visualized devices; bars then update less smoothly
+    #[serde(default)]
+    pub spectrum_batch_ms: u64,
+}
+
+/// Longest accepted auto-save delay; anything above is treated as a typo
+const MAX_AUTO_SAVE_DELAY_SECS: f64 = 3600.0;
+
+fn default_auto_save_delay() -> f64 {
+    2.0
+}
+
+fn default_idle_after() -> u64 {
+    10
+}
+
+fn default_idle_threshold() -> f32 {
+    -70.0
+}
+
+/// Accepted range for the idle threshold
+const IDLE_THRESHOLD_RANGE_DB: std::ops::RangeInclusive<f32> = -120.0..=0.0;
+
+/// Highest accepted UI frame rate
+pub const MAX_FPS: u32 = 240;
+
+fn default_max_fps() -> u32 {
+    60
+}
+
+/// Time between UI redraws at `fps` frames per second (clamped to 1-240)
+pub fn frame_duration(fps: u32) -> Duration {
+    Duration::from_secs_f64(1.0 / fps.clamp(1, MAX_FPS) as f64)
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Self {
+            auto_save_delay_secs: default_auto_save_delay(),
+            max_fps: default_max_fps(),
+            port_pairing: PairingStrategy::default(),
+            auto_hide_silent_secs: 0,
+            idle_after_secs: default_idle_after(),
+            idle_threshold_db: default_idle_threshold(),
+            monitor_port_patterns: default_monitor_port_patterns(),
+            spectrum_batch_ms: 0,
+        }
+    }
+}
+
+/// Longest accepted spectrum batching window
+const MAX_SPECTRUM_BATCH_MS: u64 = 1000;
+
+impl GeneralConfig {
+    /// Validated auto-save debounce, or None if auto-save is disabled
+    /// Negative, non-finite or absurdly large values fall back to the default
+    pub fn auto_save_delay(&self) -> Option<Duration> {
+        let secs = self.auto_save_delay_secs;
+        if secs == 0.0 {
+            return None;
+        }
+        if !secs.is_finite() || !(0.0..=MAX_AUTO_SAVE_DELAY_SECS).contains(&secs) {
+            debug_log!(
+                "Invalid auto_save_delay_secs {}, using default {}",
+                secs,
+                default_auto_save_delay()
+            );
+            return Some(Duration::from_secs_f64(default_auto_save_delay()));
+        }
+        Some(Duration::from_secs_f64(secs))
+    }
+
+    /// Monitor port patterns to try; an empty list falls back to the default
+    pub fn monitor_port_patterns(&self) -> Vec<MonitorPortPattern> {
+        if self.monitor_port_patterns.is_empty() {
+            default_monitor_port_patterns()
+        } else {
+            self.monitor_port_patterns.clone()
+        }
+    }
+
+    /// How long a stream may stay silent before it is auto-hidden, if enabled
+    pub fn auto_hide_after(&self) -> Option<Duration> {
+        (self.auto_hide_silent_secs > 0).then(|| Duration::from_secs(self.auto_hide_silent_secs))
+    }
+
+    /// Silence threshold (dBFS) and how long a stream must stay below it before
+    /// its spectrum processing idles (None = never). An out-of-range threshold
+    /// falls back to the default
+    pub fn idle_detection(&self) -> (f32, Option<Duration>) {
+        let threshold_db = if IDLE_THRESHOLD_RANGE_DB.contains(&self.idle_threshold_db) {
+            self.idle_threshold_db
+        } else {
+            debug_log!(
+                "Invalid idle_threshold_db {}, using default {}",
+                self.idle_threshold_db,
+                default_idle_threshold()
+            );
+            default_idle_threshold()
+        };
+        let after = (self.idle_after_secs > 0).then(|| Duration::from_secs(self.idle_after_secs));
+        (threshold_db, after)
+    }
+
+    /// How long spectrum frames are batched before reaching the display
+    /// (zero = applied as they arrive), capped so the display can't freeze
+    pub fn spectrum_batch_window(&self) -> Duration {
+        Duration::from_millis(self.spectrum_batch_ms.min(MAX_SPECTRUM_BATCH_MS))
+    }
+
+    /// Time between UI redraws for the configured frame rate
+    /// 0 falls back to the default rather than stopping redraws
+    pub fn frame_duration(&self) -> Duration {
+        if self.max_fps == 0 {
+            debug_log!("Invalid max_fps 0, using default {}", default_max_fps());
+            return frame_duration(default_max_fps());
+        }
+        frame_duration(self.max_fps)
+    }
 }
 
 /// Configuration for spectrum visualization
@@ -25,21 +202,157 @@ pub struct VisualizationConfig {
     /// Spectrum amplification factor (default: 2.0)
     #[serde(default = "default_amplification")]
     pub spectrum_amplification: f32,
+    /// How FFT bins are merged into display groups: "max" (default), "mean" or "rms"
+    #[serde(default)]
+    pub spectrum_aggregation: SpectrumAggregation,
+    /// How levels become bar heights: "linear" (default, amplification multiplies
+    /// the height above the floor) or "perceptual" (amplification expands contrast
+    /// around -30 dB while quiet and loud levels stay in range)
+    #[serde(default)]
+    pub amplitude_transform: AmplitudeTransform,
+    /// Width of each spectrum bar in terminal columns (1-3, default: 1)
+    #[serde(default = "default_bar_width")]
+    pub bar_width: usize,
+    /// Exponent applied to normalized bar heights (0.3-3.0, default: 1.0 = linear dB)
+    #[serde(default = "default_spectrum_gamma")]
+    pub spectrum_gamma: f32,
+    /// Time constant of bars rising to a louder level, in ms (0-500, default: 10)
+    #[serde(default = "default_bar_attack")]
+    pub bar_attack_ms: u64,
+    /// Time constant of bars falling to a quieter level, in ms (0-3000, default: 300)
+    #[serde(default = "default_bar_release")]
+    pub bar_release_ms: u64,
+    /// Spectrum characters: "auto" (default), "braille" or "blocks"
+    /// WAVEWIRE_NO_BRAILLE=1 in the environment forces blocks
+    #[serde(default)]
+    pub glyphs: GlyphMode,
+    /// Spectrum refresh rate in Hz (default: 30, 1-100)
+    #[serde(default = "default_refresh_rate")]
+    pub refresh_rate_hz: u32,
     /// Device names that are hidden from the device list
     #[serde(default)]
     pub hidden_devices: Vec<String>,
+    /// Device names pinned to the top of the device list
+    #[serde(default)]
+    pub favorite_devices: Vec<String>,
+    /// Spectrum colors assigned by device name (others cycle through a default palette)
+    #[serde(default)]
+    pub device_colors: HashMap<String, DeviceColor>,
+    /// dB added to a device's level readouts so they match a reference meter, by
+    /// device name (display only). Without an entry levels are uncalibrated dBFS
+    #[serde(default)]
+    pub calibration_db: HashMap<String, f32>,
+}
+
+// Display and key binding settings. Only the data lives here so the config
+// doesn't depend on the UI; their behavior is implemented in `ui`
+
+/// Strategy for merging several FFT bins into one display group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpectrumAggregation {
+    /// Loudest bin wins (emphasizes narrow peaks)
+    #[default]
+    Max,
+    /// Arithmetic mean of the bin magnitudes in dB
+    Mean,
+    /// Root-mean-square of the linear magnitudes, converted back to dB
+    Rms,
+}
+
+/// How a level in dB becomes a spectrum bar value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmplitudeTransform {
+    /// Height above the floor times the amplification, clipped at the top
+    #[default]
+    Linear,
+    /// S-curve around a reference level: amplification sets the contrast there,
+    /// while the floor and the loudest levels ease into the range instead of clipping
+    Perceptual,
+}
+
+/// Which characters to draw the spectrum with, as configured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphMode {
+    /// Braille unless the terminal looks like it can't show it
+    #[default]
+    Auto,
+    /// Always braille
+    Braille,
+    /// Always block characters
+    Blocks,
+}
+
+/// Colors a device's spectrum can be drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceColor {
+    Cyan,
+    Yellow,
+    Magenta,
+    Green,
+    Red,
+    Blue,
+    White,
+    LightCyan,
+    LightYellow,
+    LightMagenta,
+    LightGreen,
+    LightRed,
+    LightBlue,
+}
+
+/// Keys bound to one action in the config: a single key or a list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
 }
 
 fn default_amplification() -> f32 {
     2.0
 }
 
+fn default_bar_width() -> usize {
+    1
+}
+
+fn default_spectrum_gamma() -> f32 {
+    1.0
+}
+
+fn default_bar_attack() -> u64 {
+    10
+}
+
+fn default_bar_release() -> u64 {
+    300
+}
+
+fn default_refresh_rate() -> u32 {
+    30
+}
+
 impl Default for VisualizationConfig {
     fn default() -> Self {
         Self {
             enabled_devices: Vec::new(),
             spectrum_amplification: default_amplification(),
+            spectrum_aggregation: SpectrumAggregation::default(),
+            amplitude_transform: AmplitudeTransform::default(),
+            bar_width: default_bar_width(),
+            spectrum_gamma: default_spectrum_gamma(),
+            bar_attack_ms: default_bar_attack(),
+            bar_release_ms: default_bar_release(),
+            glyphs: GlyphMode::default(),
+            refresh_rate_hz: default_refresh_rate(),
             hidden_devices: Vec::new(),
+            favorite_devices: Vec::new(),
+            device_colors: HashMap::new(),
+            calibration_db: HashMap::new(),
         }
     }
 }
@@ -60,53 +373,448 @@ pub struct VolumeConfig {
     pub device_settings: HashMap<String, VolumeSettings>,
 }
 
+/// Configuration for headphone crossfeed per device
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CrossfeedConfig {
+    /// Map of device name → crossfeed settings
+    /// Uses device names (not IDs) for persistence across sessions
+    pub device_settings: HashMap<String, CrossfeedSettings>,
+}
+
+/// Configuration for saturation per device
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SaturationConfig {
+    /// Map of device name → drive (0.0 to 1.0)
+    /// Uses device names (not IDs) for persistence across sessions
+    pub device_drive: HashMap<String, f32>,
+}
+
+/// Configuration for stereo width per device
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WidthConfig {
+    /// Map of device name → width (0.0 = mono, 1.0 = unchanged, 2.0 = widest)
+    /// Uses device names (not IDs) for persistence across sessions
+    pub device_width: HashMap<String, f32>,
+}
+
 impl Config {
     /// Create config from current app state
-    /// Extracts device names for all visualized devices
-    pub fn from_visualized_devices(
-        visualized_ids: &HashSet<DeviceId>,
-        all_devices: &[DeviceInfo],
-        spectrum_amplification: f32,
-        hidden_devices: Vec<String>,
-    ) -> Self {
-        let enabled_devices = all_devices
+    /// Extracts device names for all visualized devices; everything else starts at
+    /// its default and is filled in with the `with_*` methods
+    pub fn from_visualized_devices(visualized_ids: &HashSet<DeviceId>, all_devices: &[DeviceInfo]) -> Self {
+        let mut config = Config::default();
+        config.visualization.enabled_devices = all_devices
             .iter()
             .filter(|d| visualized_ids.contains(&d.id))
             .map(|d| d.name.clone())
             .collect();
+        config
+    }
 
-        Config {
-            visualization: VisualizationConfig {
-                enabled_devices,
-                spectrum_amplification,
-                hidden_devices,
-            },
-            eq: EqConfig::default(),
-            volume: VolumeConfig::default(),
+    /// Set the spectrum amplification (dB)
+    pub fn with_spectrum_amplification(mut self, spectrum_amplification: f32) -> Self {
+        self.visualization.spectrum_amplification = spectrum_amplification;
+        self
+    }
+
+    /// Set how bins are merged into bars
+    pub fn with_spectrum_aggregation(mut self, spectrum_aggregation: SpectrumAggregation) -> Self {
+        self.visualization.spectrum_aggregation = spectrum_aggregation;
+        self
+    }
+
+    /// Set the bar width (columns)
+    pub fn with_bar_width(mut self, bar_width: usize) -> Self {
+        self.visualization.bar_width = bar_width;
+        self
+    }
+
+    /// Set the spectrum refresh rate
+    pub fn with_refresh_rate(mut self, refresh_rate_hz: u32) -> Self {
+        self.visualization.refresh_rate_hz = refresh_rate_hz;
+        self
+    }
+
+    /// Set the devices hidden from the device list
+    pub fn with_hidden_devices(mut self, hidden_devices: Vec<String>) -> Self {
+        self.visualization.hidden_devices = hidden_devices;
+        self
+    }
+
+    /// Set the spectrum gamma
+    pub fn with_spectrum_gamma(mut self, spectrum_gamma: f32) -> Self {
+        self.visualization.spectrum_gamma = spectrum_gamma;
+        self
+    }
+
+    /// Set the bar attack and release times (ms)
+    pub fn with_bar_envelope(mut self, (attack_ms, release_ms): (u64, u64)) -> Self {
+        self.visualization.bar_attack_ms = attack_ms;
+        self.visualization.bar_release_ms = release_ms;
+        self
+    }
+
+    /// Set the devices pinned to the top of the list
+    pub fn with_favorites(mut self, favorite_devices: Vec<String>) -> Self {
+        self.visualization.favorite_devices = favorite_devices;
+        self
+    }
+
+    /// Set the spectrum colors assigned to devices
+    pub fn with_device_colors(mut self, device_colors: HashMap<String, DeviceColor>) -> Self {
+        self.visualization.device_colors = device_colors;
+        self
+    }
+
+    /// Set the level calibration offsets of devices
+    pub fn with_calibration(mut self, calibration_db: HashMap<String, f32>) -> Self {
+        self.visualization.calibration_db = calibration_db;
+        self
+    }
+
+    /// Set how levels are turned into bar heights
+    pub fn with_amplitude_transform(mut self, amplitude_transform: AmplitudeTransform) -> Self {
+        self.visualization.amplitude_transform = amplitude_transform;
+        self
+    }
+
+    /// Set the spectrum character set
+    pub fn with_glyphs(mut self, glyphs: GlyphMode) -> Self {
+        self.visualization.glyphs = glyphs;
+        self
+    }
+
+    /// Set per-device EQ curves
+    pub fn with_eq(mut self, device_settings: HashMap<String, EqSettings>) -> Self {
+        self.eq = EqConfig { device_settings };
+        self
+    }
+
+    /// Set per-device volume settings
+    pub fn with_volume(mut self, device_settings: HashMap<String, VolumeSettings>) -> Self {
+        self.volume = VolumeConfig { device_settings };
+        self
+    }
+
+    /// Set per-device crossfeed settings
+    pub fn with_crossfeed(mut self, device_settings: HashMap<String, CrossfeedSettings>) -> Self {
+        self.crossfeed = CrossfeedConfig { device_settings };
+        self
+    }
+
+    /// Set per-device saturation drive
+    pub fn with_saturation(mut self, device_drive: HashMap<String, f32>) -> Self {
+        self.saturation = SaturationConfig { device_drive };
+        self
+    }
+
+    /// Set per-device stereo width
+    pub fn with_width(mut self, device_width: HashMap<String, f32>) -> Self {
+        self.width = WidthConfig { device_width };
+        self
+    }
+
+    /// Set whether the first-run overlay has been dismissed
+    pub fn with_first_run_seen(mut self, first_run_seen: bool) -> Self {
+        self.first_run_seen = first_run_seen;
+        self
+    }
+
+    /// Carry over hand-edited general settings so saving doesn't reset them
+    pub fn with_general(mut self, general: GeneralConfig) -> Self {
+        self.general = general;
+        self
+    }
+
+    /// Carry over hand-edited key bindings so saving doesn't reset them
+    pub fn with_keybindings(mut self, keybindings: BTreeMap<String, KeyBinding>) -> Self {
+        self.keybindings = keybindings;
+        self
+    }
+}
+
+/// What differs between two configs, for logging and feedback after a reload or switch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigDiff {
+    /// Devices that gained visualization
+    pub visualized: Vec<String>,
+    /// Devices that lost visualization
+    pub unvisualized: Vec<String>,
+    /// Devices that became hidden
+    pub hidden: Vec<String>,
+    /// Devices that are no longer hidden
+    pub unhidden: Vec<String>,
+    /// Devices that became favorites
+    pub favorited: Vec<String>,
+    /// Devices that are no longer favorites
+    pub unfavorited: Vec<String>,
+    /// Devices whose EQ curve was added, removed or changed
+    pub eq: Vec<String>,
+    /// Devices whose volume settings changed (no entry counts as unity gain, as
+    /// that is what a switch applies for it)
+    pub volume: Vec<String>,
+    /// Devices whose crossfeed settings changed
+    pub crossfeed: Vec<String>,
+    /// Devices whose saturation drive changed
+    pub saturation: Vec<String>,
+    /// Devices whose stereo width changed
+    pub width: Vec<String>,
+    /// Devices whose level calibration offset changed
+    pub calibration: Vec<String>,
+    /// Scalar settings as (name, old, new)
+    pub settings: Vec<(&'static str, String, String)>,
+}
+
+impl ConfigDiff {
+    /// Compare the config that was in effect with the one replacing it
+    pub fn between(old: &Config, new: &Config) -> Self {
+        let (old_viz, new_viz) = (&old.visualization, &new.visualization);
+        let (visualized, unvisualized) = set_changes(&old_viz.enabled_devices, &new_viz.enabled_devices);
+        let (hidden, unhidden) = set_changes(&old_viz.hidden_devices, &new_viz.hidden_devices);
+        let (favorited, unfavorited) =
+            set_changes(&old_viz.favorite_devices, &new_viz.favorite_devices);
+
+        let mut settings = Vec::new();
+        let mut setting = |name, old: &dyn fmt::Debug, new: &dyn fmt::Debug| {
+            let (old, new) = (format!("{:?}", old), format!("{:?}", new));
+            if old != new {
+                settings.push((name, old, new));
+            }
+        };
+        setting("amplification", &old_viz.spectrum_amplification, &new_viz.spectrum_amplification);
+        setting("aggregation", &old_viz.spectrum_aggregation, &new_viz.spectrum_aggregation);
+        setting("bar width", &old_viz.bar_width, &new_viz.bar_width);
+        setting("gamma", &old_viz.spectrum_gamma, &new_viz.spectrum_gamma);
+        setting("bar attack", &old_viz.bar_attack_ms, &new_viz.bar_attack_ms);
+        setting("bar release", &old_viz.bar_release_ms, &new_viz.bar_release_ms);
+        setting(
+            "amplitude transform",
+            &old_viz.amplitude_transform,
+            &new_viz.amplitude_transform,
+        );
+        setting("glyphs", &old_viz.glyphs, &new_viz.glyphs);
+        setting("refresh rate", &old_viz.refresh_rate_hz, &new_viz.refresh_rate_hz);
+        setting(
+            "auto-save delay",
+            &old.general.auto_save_delay_secs,
+            &new.general.auto_save_delay_secs,
+        );
+        setting("max fps", &old.general.max_fps, &new.general.max_fps);
+        setting("port pairing", &old.general.port_pairing, &new.general.port_pairing);
+        setting(
+            "auto-hide silent streams",
+            &old.general.auto_hide_silent_secs,
+            &new.general.auto_hide_silent_secs,
+        );
+        setting("idle after", &old.general.idle_after_secs, &new.general.idle_after_secs);
+        setting(
+            "idle threshold",
+            &old.general.idle_threshold_db,
+            &new.general.idle_threshold_db,
+        );
+        setting(
+            "monitor port patterns",
+            &old.general.monitor_port_patterns,
+            &new.general.monitor_port_patterns,
+        );
+        setting(
+            "spectrum batch",
+            &old.general.spectrum_batch_ms,
+            &new.general.spectrum_batch_ms,
+        );
+        setting("key bindings", &old.keybindings, &new.keybindings);
+
+        Self {
+            visualized,
+            unvisualized,
+            hidden,
+            unhidden,
+            favorited,
+            unfavorited,
+            eq: changed_devices(&old.eq.device_settings, &new.eq.device_settings),
+            volume: changed_devices(
+                &with_defaults(&old.volume.device_settings, &new.volume.device_settings),
+                &with_defaults(&new.volume.device_settings, &old.volume.device_settings),
+            ),
+            crossfeed: changed_devices(&old.crossfeed.device_settings, &new.crossfeed.device_settings),
+            saturation: changed_devices(&old.saturation.device_drive, &new.saturation.device_drive),
+            width: changed_devices(&old.width.device_width, &new.width.device_width),
+            calibration: changed_devices(&old_viz.calibration_db, &new_viz.calibration_db),
+            settings,
         }
     }
+
+    /// Whether the two configs are equivalent
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+
+        let mut parts = Vec::new();
+        for (label, names) in [
+            ("+viz", &self.visualized),
+            ("-viz", &self.unvisualized),
+            ("+hidden", &self.hidden),
+            ("-hidden", &self.unhidden),
+            ("+favorite", &self.favorited),
+            ("-favorite", &self.unfavorited),
+            ("EQ", &self.eq),
+            ("volume", &self.volume),
+            ("crossfeed", &self.crossfeed),
+            ("saturation", &self.saturation),
+            ("width", &self.width),
+            ("calibration", &self.calibration),
+        ] {
+            if !names.is_empty() {
+                parts.push(format!("{} {}", label, names.join(", ")));
+            }
+        }
+        for (name, old, new) in &self.settings {
+            parts.push(format!("{} {} -> {}", name, old, new));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Names only in `new` and names only in `old`, each sorted
+fn set_changes(old: &[String], new: &[String]) -> (Vec<String>, Vec<String>) {
+    let old: BTreeSet<&String> = old.iter().collect();
+    let new: BTreeSet<&String> = new.iter().collect();
+    (
+        new.difference(&old).map(|name| name.to_string()).collect(),
+        old.difference(&new).map(|name| name.to_string()).collect(),
+    )
+}
+
+/// Sorted device names whose per-device settings were added, removed or changed
+fn changed_devices<T: PartialEq>(old: &HashMap<String, T>, new: &HashMap<String, T>) -> Vec<String> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// `settings` plus a default entry for every device only `other` has
+fn with_defaults<T: Clone + Default>(
+    settings: &HashMap<String, T>,
+    other: &HashMap<String, T>,
+) -> HashMap<String, T> {
+    let mut settings = settings.clone();
+    for name in other.keys() {
+        settings.entry(name.clone()).or_default();
+    }
+    settings
+}
+
+/// Profile whose config lives in the plain `config.toml`
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Whether a name is usable as part of a file name
+fn is_file_name_safe(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Check that a profile name is usable as part of a file name
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if !is_file_name_safe(name) {
+        return Err(WavewireError::InvalidProfileName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Check that an EQ preset name is usable as a file name
+pub fn validate_preset_name(name: &str) -> Result<()> {
+    if !is_file_name_safe(name) {
+        return Err(WavewireError::InvalidPresetName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Directory of saved EQ presets, shared by all profiles
+const PRESETS_DIR: &str = "presets";
+
+/// Config file name for a profile (`config.toml` or `config-<name>.toml`)
+fn profile_file_name(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        String::from("config.toml")
+    } else {
+        format!("config-{}.toml", profile)
+    }
 }
 
 /// Manages configuration file loading and saving
 pub struct ConfigManager {
+    config_dir: PathBuf,
+    profile: String,
     config_path: PathBuf,
 }
 
 impl ConfigManager {
-    /// Create a new ConfigManager with XDG-compliant config directory
-    pub fn new() -> Result<Self> {
+    /// Create a new ConfigManager for a profile in the XDG-compliant config directory
+    pub fn new(profile: &str) -> Result<Self> {
+        validate_profile_name(profile)?;
+
         let config_dir = dirs::config_dir()
-            .context("Failed to get config directory")?
+            .ok_or(WavewireError::NoConfigDir)?
             .join("wavewire");
+        Self::in_dir(config_dir, profile)
+    }
 
+    /// ConfigManager for a profile in `config_dir`
+    fn in_dir(config_dir: PathBuf, profile: &str) -> Result<Self> {
         // Create config directory if it doesn't exist
-        fs::create_dir_all(&config_dir)
-            .context("Failed to create config directory")?;
+        fs::create_dir_all(&config_dir).map_err(|source| WavewireError::ConfigIo {
+            path: config_dir.clone(),
+            source,
+        })?;
 
-        let config_path = config_dir.join("config.toml");
+        let config_path = config_dir.join(profile_file_name(profile));
         debug_log!("Config path: {}", config_path.display());
 
-        Ok(Self { config_path })
+        Ok(Self {
+            config_dir,
+            profile: profile.to_string(),
+            config_path,
+        })
+    }
+
+    /// Name of the profile being loaded and saved
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Load and save a different profile from now on
+    pub fn switch_profile(&mut self, profile: &str) -> Result<()> {
+        validate_profile_name(profile)?;
+        self.profile = profile.to_string();
+        self.config_path = self.config_dir.join(profile_file_name(profile));
+        debug_log!("Switched to profile {} ({})", profile, self.config_path.display());
+        Ok(())
+    }
+
+    /// Names of all profiles with a config file (plus the current one), sorted
+    pub fn list_profiles(&self) -> Vec<String> {
+        let mut profiles: Vec<String> = fs::read_dir(&self.config_dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|file_name| profile_from_file_name(&file_name))
+            .collect();
+        profiles.push(self.profile.clone());
+        profiles.sort();
+        profiles.dedup();
+        profiles
     }
 
     /// Load configuration from disk
@@ -143,19 +851,291 @@ impl ConfigManager {
     /// Save configuration to disk
     /// Uses atomic write (write to temp file, then rename)
     pub fn save(&self, config: &Config) -> Result<()> {
-        let toml_string = toml::to_string_pretty(config)
-            .context("Failed to serialize config")?;
+        let toml_string = toml::to_string_pretty(config)?;
 
         // Write to temp file first
         let temp_path = self.config_path.with_extension("toml.tmp");
-        fs::write(&temp_path, toml_string)
-            .context("Failed to write config to temp file")?;
+        fs::write(&temp_path, toml_string).map_err(|source| WavewireError::ConfigIo {
+            path: temp_path.clone(),
+            source,
+        })?;
 
         // Atomic rename
-        fs::rename(&temp_path, &self.config_path)
-            .context("Failed to rename temp config file")?;
+        fs::rename(&temp_path, &self.config_path).map_err(|source| WavewireError::ConfigIo {
+            path: self.config_path.clone(),
+            source,
+        })?;
 
         debug_log!("Config saved successfully");
         Ok(())
     }
+
+    fn preset_path(&self, name: &str) -> PathBuf {
+        self.config_dir.join(PRESETS_DIR).join(format!("{}.toml", name))
+    }
+
+    /// Save an EQ curve as a named preset, replacing any preset of that name
+    /// Uses atomic write (write to temp file, then rename)
+    pub fn save_eq_preset(&self, name: &str, settings: &EqSettings) -> Result<()> {
+        validate_preset_name(name)?;
+        let presets_dir = self.config_dir.join(PRESETS_DIR);
+        fs::create_dir_all(&presets_dir).map_err(|source| WavewireError::ConfigIo {
+            path: presets_dir.clone(),
+            source,
+        })?;
+
+        let path = self.preset_path(name);
+        let temp_path = path.with_extension("toml.tmp");
+        fs::write(&temp_path, toml::to_string_pretty(settings)?).map_err(|source| {
+            WavewireError::ConfigIo {
+                path: temp_path.clone(),
+                source,
+            }
+        })?;
+        fs::rename(&temp_path, &path).map_err(|source| WavewireError::ConfigIo {
+            path: path.clone(),
+            source,
+        })?;
+
+        debug_log!("EQ preset {} saved to {}", name, path.display());
+        Ok(())
+    }
+
+    /// Load a saved EQ preset
+    pub fn load_eq_preset(&self, name: &str) -> Result<EqSettings> {
+        validate_preset_name(name)?;
+        let path = self.preset_path(name);
+        let contents = fs::read_to_string(&path).map_err(|source| WavewireError::ConfigIo {
+            path: path.clone(),
+            source,
+        })?;
+        toml::from_str(&contents).map_err(|source| WavewireError::InvalidPreset { path, source })
+    }
+
+    /// Names of all saved EQ presets that load, sorted
+    /// Corrupted preset files are skipped (and logged) rather than listed
+    pub fn list_eq_presets(&self) -> Vec<String> {
+        let mut presets: Vec<String> = fs::read_dir(self.config_dir.join(PRESETS_DIR))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|file_name| file_name.strip_suffix(".toml").map(String::from))
+            .filter(|name| is_file_name_safe(name))
+            .filter(|name| match self.load_eq_preset(name) {
+                Ok(_) => true,
+                Err(e) => {
+                    debug_log!("Skipping EQ preset {}: {}", name, e);
+                    false
+                }
+            })
+            .collect();
+        presets.sort();
+        presets
+    }
+}
+
+/// Profile name for a config file name, if it is one
+fn profile_from_file_name(file_name: &str) -> Option<String> {
+    if file_name == "config.toml" {
+        return Some(String::from(DEFAULT_PROFILE));
+    }
+    file_name
+        .strip_prefix("config-")?
+        .strip_suffix(".toml")
+        .filter(|name| validate_profile_name(name).is_ok())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::PortMatch;
+
+    fn delay(secs: f64) -> Option<Duration> {
+        GeneralConfig {
+            auto_save_delay_secs: secs,
+            ..GeneralConfig::default()
+        }
+        .auto_save_delay()
+    }
+
+    #[test]
+    fn test_auto_save_delay_validation() {
+        assert_eq!(GeneralConfig::default().auto_save_delay(), Some(Duration::from_secs(2)));
+        assert_eq!(delay(0.5), Some(Duration::from_millis(500)));
+        assert_eq!(delay(0.0), None);
+
+        // Nonsensical values fall back to the default
+        for secs in [-1.0, f64::NAN, f64::INFINITY, 1e9] {
+            assert_eq!(delay(secs), Some(Duration::from_secs(2)));
+        }
+    }
+
+    #[test]
+    fn test_missing_general_section_uses_default() {
+        let config: Config = toml::from_str("[visualization]\nenabled_devices = []\n").unwrap();
+        assert_eq!(config.general.auto_save_delay(), Some(Duration::from_secs(2)));
+        assert_eq!(config.general.max_fps, 60);
+        assert_eq!(config.general.port_pairing, PairingStrategy::ByName);
+
+        let config: Config = toml::from_str(
+            "[visualization]\nenabled_devices = []\n[general]\nport_pairing = \"mono_fan_out\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.general.port_pairing, PairingStrategy::MonoFanOut);
+    }
+
+    #[test]
+    fn test_idle_detection() {
+        let general = |idle_threshold_db, idle_after_secs| GeneralConfig {
+            idle_threshold_db,
+            idle_after_secs,
+            ..GeneralConfig::default()
+        };
+        assert_eq!(
+            GeneralConfig::default().idle_detection(),
+            (-70.0, Some(Duration::from_secs(10)))
+        );
+        assert_eq!(general(-50.0, 3).idle_detection(), (-50.0, Some(Duration::from_secs(3))));
+        assert_eq!(general(-50.0, 0).idle_detection(), (-50.0, None));
+        for threshold in [6.0, -200.0, f32::NAN] {
+            assert_eq!(general(threshold, 3).idle_detection().0, -70.0);
+        }
+    }
+
+    #[test]
+    fn test_monitor_port_patterns() {
+        let config: Config = toml::from_str(
+            "[visualization]\nenabled_devices = []\n\
+             [[general.monitor_port_patterns]]\nleft = \"out_1\"\nright = \"out_2\"\n\
+             [[general.monitor_port_patterns]]\nleft = \"monitor_FL\"\nright = \"monitor_FR\"\n\
+             matching = \"substring\"\n",
+        )
+        .unwrap();
+        let patterns = config.general.monitor_port_patterns();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!((patterns[0].left.as_str(), patterns[0].matching), ("out_1", PortMatch::Suffix));
+        assert_eq!(patterns[1].matching, PortMatch::Substring);
+
+        // Missing or emptied: the monitor_FL / monitor_FR default
+        let default = default_monitor_port_patterns();
+        assert_eq!(GeneralConfig::default().monitor_port_patterns(), default);
+        let emptied = GeneralConfig { monitor_port_patterns: Vec::new(), ..GeneralConfig::default() };
+        assert_eq!(emptied.monitor_port_patterns(), default);
+    }
+
+    #[test]
+    fn test_keybindings_section() {
+        let config: Config = toml::from_str(
+            "[visualization]\nenabled_devices = []\n[keybindings]\ntoggle_viz = \"V\"\nup = [\"Up\", \"Ctrl-p\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.keybindings["toggle_viz"], KeyBinding::One(String::from("V")));
+        assert_eq!(
+            config.keybindings["up"],
+            KeyBinding::Many(vec![String::from("Up"), String::from("Ctrl-p")])
+        );
+
+        let saved = toml::to_string(&config).unwrap();
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.keybindings, config.keybindings);
+    }
+
+    #[test]
+    fn test_frame_duration() {
+        let general = |max_fps| GeneralConfig {
+            max_fps,
+            ..GeneralConfig::default()
+        };
+        assert_eq!(general(5).frame_duration(), Duration::from_millis(200));
+        assert_eq!(general(0).frame_duration(), frame_duration(60));
+        assert_eq!(general(10_000).frame_duration(), frame_duration(MAX_FPS));
+    }
+
+    #[test]
+    fn test_config_diff() {
+        let mut old = Config::default();
+        old.visualization.enabled_devices = vec![String::from("Speakers"), String::from("Mic")];
+        old.eq.device_settings.insert(String::from("Speakers"), EqSettings::default());
+
+        let mut new = old.clone();
+        assert!(ConfigDiff::between(&old, &new).is_empty());
+        assert_eq!(ConfigDiff::between(&old, &new).to_string(), "no changes");
+
+        new.visualization.enabled_devices = vec![String::from("Speakers"), String::from("Headphones")];
+        new.visualization.spectrum_amplification = 3.0;
+        new.eq.device_settings.get_mut("Speakers").unwrap().bands[0].gain_db = 4.0;
+
+        let diff = ConfigDiff::between(&old, &new);
+        assert_eq!(diff.visualized, vec![String::from("Headphones")]);
+        assert_eq!(diff.unvisualized, vec![String::from("Mic")]);
+        assert_eq!(diff.eq, vec![String::from("Speakers")]);
+        assert_eq!(
+            diff.to_string(),
+            "+viz Headphones; -viz Mic; EQ Speakers; amplification 2.0 -> 3.0"
+        );
+
+        // A volume saved at unity gain is the same as none
+        new = old.clone();
+        new.volume.device_settings.insert(String::from("Mic"), VolumeSettings::default());
+        assert!(ConfigDiff::between(&old, &new).is_empty());
+        new.volume
+            .device_settings
+            .insert(String::from("Mic"), VolumeSettings { gain_linear: 0.5, gain_db: -6.0, ..VolumeSettings::default() });
+        assert_eq!(ConfigDiff::between(&old, &new).to_string(), "volume Mic");
+
+        new = old.clone();
+        new.visualization.calibration_db.insert(String::from("Mic"), 94.0);
+        assert_eq!(ConfigDiff::between(&old, &new).to_string(), "calibration Mic");
+    }
+
+    #[test]
+    fn test_profile_file_names() {
+        assert_eq!(profile_file_name(DEFAULT_PROFILE), "config.toml");
+        assert_eq!(profile_file_name("gaming"), "config-gaming.toml");
+
+        for name in [DEFAULT_PROFILE, "gaming", "music-prod_2"] {
+            assert_eq!(profile_from_file_name(&profile_file_name(name)).as_deref(), Some(name));
+        }
+        assert_eq!(profile_from_file_name("config.toml.bak"), None);
+        assert_eq!(profile_from_file_name("config-gaming.toml.tmp"), None);
+        assert_eq!(profile_from_file_name("config-.toml"), None);
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("streaming").is_ok());
+        assert!(validate_profile_name("music-prod_2").is_ok());
+        for name in ["", "../etc", "my profile", "a/b"] {
+            assert!(matches!(
+                validate_profile_name(name),
+                Err(WavewireError::InvalidProfileName(invalid)) if invalid == name
+            ));
+        }
+    }
+
+    #[test]
+    fn test_eq_presets_round_trip() {
+        let dir = std::env::temp_dir().join(format!("wavewire-presets-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let manager = ConfigManager::in_dir(dir.clone(), DEFAULT_PROFILE).unwrap();
+        assert!(manager.list_eq_presets().is_empty());
+
+        let mut settings = EqSettings::default();
+        settings.set_band(2, -4.5, 2.0);
+        manager.save_eq_preset("podcast", &settings).unwrap();
+        manager.save_eq_preset("bright", &EqSettings::default()).unwrap();
+        assert_eq!(manager.load_eq_preset("podcast").unwrap(), settings);
+
+        // Corrupted files are left out of the list, and fail to load with their path
+        fs::write(dir.join(PRESETS_DIR).join("broken.toml"), "bands = 3").unwrap();
+        assert_eq!(manager.list_eq_presets(), ["bright", "podcast"]);
+        assert!(matches!(manager.load_eq_preset("broken"), Err(WavewireError::InvalidPreset { .. })));
+        assert!(matches!(manager.load_eq_preset("missing"), Err(WavewireError::ConfigIo { .. })));
+        assert!(matches!(
+            manager.save_eq_preset("../escape", &settings),
+            Err(WavewireError::InvalidPresetName(_))
+        ));
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
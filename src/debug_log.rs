@@ -1,34 +1,127 @@
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Debug log file, relative to the current directory
+pub const LOG_PATH: &str = "wavewire-debug.log";
+
+/// Once the log reaches this size it is moved to `<log>.1` (replacing the
+/// previous one) and started over, so at most twice this is kept on disk
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Block size when reading the log backwards for `tail`
+const TAIL_BLOCK_BYTES: u64 = 8 * 1024;
+
+/// Open log file and how much has been written to it
+struct LogFile {
+    file: File,
+    path: PathBuf,
+    len: u64,
+}
+
+impl LogFile {
+    /// Open the log for appending, rotating it first if it is already full
+    fn open(path: &Path) -> io::Result<Self> {
+        if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= MAX_LOG_BYTES) {
+            rotate(path)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            len,
+        })
+    }
+
+    /// Append a line, rotating first if it would take the log past the limit
+    fn write_line(&mut self, line: &str) {
+        // If rotating fails, keep appending to the full log rather than losing lines
+        if self.len + line.len() as u64 > MAX_LOG_BYTES
+            && self.len > 0
+            && let Ok(reopened) = rotate(&self.path).and_then(|_| Self::open(&self.path))
+        {
+            *self = reopened;
+        }
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.len += line.len() as u64;
+        }
+    }
+}
+
+/// Move the log to `<log>.1`, replacing the previous rotated log
+fn rotate(path: &Path) -> io::Result<()> {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    fs::rename(path, rotated)
+}
 
 /// Simple file logger for debugging
 /// Logs to wavewire-debug.log in the current directory
-static LOG_FILE: Mutex<Option<std::fs::File>> = Mutex::new(None);
+static LOG_FILE: Mutex<Option<LogFile>> = Mutex::new(None);
+
+/// Whether `debug_trace!` lines are written (WAVEWIRE_TRACE=1 in the environment)
+static TRACE: AtomicBool = AtomicBool::new(false);
 
 /// Initialize the debug log file
 pub fn init_log() {
-    let file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("wavewire-debug.log")
-        .expect("Failed to open debug log file");
+    let file = LogFile::open(Path::new(LOG_PATH)).expect("Failed to open debug log file");
 
     *LOG_FILE.lock().unwrap() = Some(file);
+    TRACE.store(std::env::var("WAVEWIRE_TRACE").is_ok_and(|value| value == "1"), Ordering::Relaxed);
     log("=== Wavewire Debug Log Started ===");
 }
 
+/// Whether per-frame trace lines are being logged
+pub fn trace_enabled() -> bool {
+    TRACE.load(Ordering::Relaxed)
+}
+
 /// Write a line to the debug log
 pub fn log(msg: &str) {
-    if let Ok(mut guard) = LOG_FILE.lock() {
-        if let Some(file) = guard.as_mut() {
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis();
-            let _ = writeln!(file, "[{}] {}", timestamp, msg);
-        }
+    if let Ok(mut guard) = LOG_FILE.lock()
+        && let Some(log_file) = guard.as_mut()
+    {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        log_file.write_line(&format!("[{}] {}\n", timestamp, msg));
+    }
+}
+
+/// Last `count` lines of the debug log (empty if it can't be read)
+pub fn tail(count: usize) -> Vec<String> {
+    tail_of(Path::new(LOG_PATH), count).unwrap_or_default()
+}
+
+/// Last `count` lines of a file, reading backwards from the end in blocks
+/// until enough lines are in memory instead of reading the whole file
+fn tail_of(path: &Path, count: usize) -> io::Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let mut start = file.metadata()?.len();
+    let mut buffer = Vec::new();
+
+    // `count` newlines past the first one found means `count` complete lines
+    // (the file's final newline included)
+    while start > 0 && buffer.iter().filter(|&&byte| byte == b'\n').count() <= count {
+        let read = TAIL_BLOCK_BYTES.min(start);
+        start -= read;
+        file.seek(SeekFrom::Start(start))?;
+        let mut block = vec![0; read as usize];
+        file.read_exact(&mut block)?;
+        block.extend_from_slice(&buffer);
+        buffer = block;
     }
+
+    let text = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect())
 }
 
 /// Log with formatting
@@ -38,3 +131,58 @@ macro_rules! debug_log {
         $crate::debug_log::log(&format!($($arg)*))
     };
 }
+
+/// Log with formatting, only when tracing is on
+/// For lines written every frame: nothing is formatted while tracing is off
+#[macro_export]
+macro_rules! debug_trace {
+    ($($arg:tt)*) => {
+        if $crate::debug_log::trace_enabled() {
+            $crate::debug_log::log(&format!($($arg)*))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("wavewire-{}-{}.log", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_tail_reads_lines_across_blocks() {
+        let path = temp_log("tail");
+        let lines: Vec<String> = (0..5000).map(|i| format!("line {}", i)).collect();
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+        assert_eq!(tail_of(&path, 3).unwrap(), ["line 4997", "line 4998", "line 4999"]);
+        assert_eq!(tail_of(&path, 2000).unwrap(), lines[3000..]);
+        assert_eq!(tail_of(&path, 10_000).unwrap().len(), 5000);
+        let _ = fs::remove_file(&path);
+        assert!(tail_of(&path, 3).is_err());
+    }
+
+    #[test]
+    fn test_full_log_is_rotated() {
+        let path = temp_log("rotate");
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(".1");
+        let line = "x".repeat(1023) + "\n";
+        let _ = fs::remove_file(&path);
+
+        let mut log_file = LogFile::open(&path).unwrap();
+        for _ in 0..MAX_LOG_BYTES / 1024 {
+            log_file.write_line(&line);
+        }
+        assert_eq!(log_file.len, MAX_LOG_BYTES);
+        log_file.write_line("after rotation\n");
+
+        assert_eq!(log_file.len, 15);
+        assert_eq!(fs::metadata(&rotated).unwrap().len(), MAX_LOG_BYTES);
+        assert_eq!(tail_of(&path, 1).unwrap(), ["after rotation"]);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&rotated);
+    }
+}
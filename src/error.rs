@@ -0,0 +1,86 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::audio::{CommandError, DeviceId, PortLookupError};
+
+/// Result type for the audio engine and config APIs
+pub type Result<T, E = WavewireError> = std::result::Result<T, E>;
+
+/// Errors returned by the audio engine and config manager
+/// Callers can match on these to react to specific failures;
+/// `main` wraps them in `anyhow` for reporting
+#[derive(Debug, Error)]
+pub enum WavewireError {
+    /// A PipeWire main loop, context, core or registry could not be set up
+    #[error("Failed to {action}: {reason}")]
+    PipeWireConnect { action: &'static str, reason: String },
+    /// The engine was used before `start` or after `stop`
+    #[error("Audio engine is not running")]
+    NotRunning,
+    /// The engine was set up without a channel it needs to start
+    #[error("Audio engine is not initialized")]
+    NotInitialized,
+    /// The engine has been started before; its command channel went to that
+    /// event loop, so a new engine is needed to start again
+    #[error("Audio engine was already started")]
+    AlreadyStarted,
+    /// No JACK server could be reached
+    #[cfg(feature = "jack")]
+    #[error("JACK unavailable: {0}")]
+    JackUnavailable(#[from] jack::Error),
+    /// Built without the `jack` feature, so nothing can be captured or processed
+    #[cfg(not(feature = "jack"))]
+    #[error("JACK support not built in (rebuild with --features jack)")]
+    JackDisabled,
+    /// The device does not exist (or is not one of ours)
+    #[error("{0} not found")]
+    DeviceNotFound(DeviceId),
+    /// A PipeWire port name matched no port, or several
+    #[error(transparent)]
+    PortLookup(#[from] PortLookupError),
+    /// The command queue is full (audio thread is stalled)
+    #[error("Audio engine busy, try again")]
+    CommandQueueFull,
+    /// The audio thread has shut down and dropped its end of the channel
+    #[error("Audio thread is not running")]
+    CommandChannelClosed,
+    /// The platform has no config directory
+    #[error("Failed to get config directory")]
+    NoConfigDir,
+    /// Reading or writing a config file failed
+    #[error("Config I/O failed for {}: {source}", path.display())]
+    ConfigIo {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// The config could not be serialized to TOML
+    #[error("Failed to serialize config: {0}")]
+    ConfigSerialize(#[from] toml::ser::Error),
+    /// Profile names end up in file names, so they are restricted
+    #[error("Invalid profile name {0:?} (use letters, digits, '-' and '_')")]
+    InvalidProfileName(String),
+    /// EQ preset names end up in file names too
+    #[error("Invalid preset name {0:?} (use letters, digits, '-' and '_')")]
+    InvalidPresetName(String),
+    /// A saved EQ preset is not valid TOML (or not an EQ curve)
+    #[error("Invalid EQ preset {}: {source}", path.display())]
+    InvalidPreset {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    /// A virtual device was requested with a name or port counts PipeWire can't use
+    #[error("Invalid virtual device {0:?}: {1}")]
+    InvalidVirtualDevice(String, String),
+}
+
+impl From<CommandError> for WavewireError {
+    fn from(error: CommandError) -> Self {
+        match error {
+            CommandError::Busy => WavewireError::CommandQueueFull,
+            CommandError::Disconnected => WavewireError::CommandChannelClosed,
+        }
+    }
+}
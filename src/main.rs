@@ -1,30 +1,74 @@
 use anyhow::Result;
 use ratatui::{backend::TermionBackend, Terminal};
-use std::io;
+use std::io::{self, Write};
 use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::{Mutex, PoisonError};
 use std::thread;
 use std::time::{Duration, Instant};
-use termion::{async_stdin, event::Key, input::TermRead, raw::IntoRawMode};
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::{async_stdin, event::Key, input::TermRead};
 
 mod audio;
 mod ui;
 mod debug_log;
 mod config;
+mod error;
+mod cli;
+mod selftest;
+mod oneline;
+mod support;
 
 use audio::{AudioEngine, AudioEvent};
-use ui::App;
-use config::{Config, ConfigManager};
+use ui::{App, EqPresetRequest, Keymap};
+use config::{Config, ConfigDiff, ConfigManager, DEFAULT_PROFILE};
+use cli::CliArgs;
 
-/// Target frames per second for the UI
-const TARGET_FPS: u64 = 60;
-const FRAME_DURATION: Duration = Duration::from_millis(1000 / TARGET_FPS);
+/// Raw mode guard while the TUI runs; held here so the panic hook can leave raw mode
+static RAW_MODE: Mutex<Option<RawTerminal<io::Stdout>>> = Mutex::new(None);
 
 fn main() -> Result<()> {
+    let cli = match CliArgs::parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    if cli.check {
+        debug_log::init_log();
+        debug_log!("Running self-test");
+        // Force exit for the same reason as below
+        std::process::exit(if selftest::run() { 0 } else { 1 });
+    }
+
+    if cli.oneline {
+        debug_log::init_log();
+        debug_log!("Running one-line spectrum");
+        let width = cli.width.unwrap_or(oneline::DEFAULT_WIDTH);
+        match oneline::run(width) {
+            Ok(()) => std::process::exit(0),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Run the application and get the exit status
-    let result = run_app();
+    install_panic_hook();
+    let profile = cli.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let result = run_app(profile, &cli);
+    restore_terminal();
 
-    // Force exit to avoid waiting for background threads
-    // (PipeWire event loop thread can't be gracefully shut down with MainLoopRc)
+    // Exit explicitly: the audio engine was stopped in run_app, but an event loop
+    // thread that didn't stop in time (see PipeWireClient::deactivate) mustn't keep
+    // the process alive
     match result {
         Ok(()) => std::process::exit(0),
         Err(e) => {
@@ -34,14 +78,14 @@ fn main() -> Result<()> {
     }
 }
 
-fn run_app() -> Result<()> {
+fn run_app(profile: &str, cli: &CliArgs) -> Result<()> {
     // Initialize debug logging
     debug_log::init_log();
     debug_log!("Application starting");
 
-    // Initialize terminal
-    let stdout = io::stdout().into_raw_mode()?;
-    let backend = TermionBackend::new(stdout);
+    // Initialize terminal (main restores it when run_app returns, the panic hook on a panic)
+    *RAW_MODE.lock().unwrap_or_else(PoisonError::into_inner) = Some(io::stdout().into_raw_mode()?);
+    let backend = TermionBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
     terminal.hide_cursor()?;
@@ -51,17 +95,22 @@ fn run_app() -> Result<()> {
     audio_engine.start()?;
 
     // Load configuration
-    let config_manager = ConfigManager::new()?;
-    let config = config_manager.load().unwrap_or_else(|e| {
-        debug_log!("Failed to load config: {}, using defaults", e);
-        Config::default()
-    });
+    let mut config_manager = ConfigManager::new(profile)?;
+    let mut config = load_config(&config_manager);
 
     // Initialize UI app
     let mut app = App::new(config.visualization.spectrum_amplification);
-
-    // Restore hidden devices from config
-    app.restore_hidden_devices(config.visualization.hidden_devices.clone());
+    apply_config(&mut app, &config, &config_manager, &audio_engine);
+    // Only at startup: switching to a profile that predates the flag shouldn't show it again
+    app.set_first_run_seen(config.first_run_seen);
+    if let Some(hz) = cli.refresh_rate {
+        app.set_refresh_rate(hz);
+        let _ = audio_engine.send_command(audio::AudioCommand::SetRefreshRate { hz });
+    }
+    if let Some(node_id) = cli.visualize_id {
+        // Resolved once the registry has reported the node
+        app.queue_node_visualization(node_id);
+    }
 
     // Set up non-blocking input handling
     let input_rx = spawn_input_thread();
@@ -72,48 +121,62 @@ fn run_app() -> Result<()> {
     // Track first iteration for config restoration
     let mut first_iteration = true;
 
+    // Keys redraw right away instead of waiting for the next frame
+    let mut redraw = false;
+
     // Main application loop
     while app.running {
         let now = Instant::now();
         let elapsed = now.duration_since(last_frame);
 
+        // Deliver any commands held back while the command queue was full
+        let _ = audio_engine.flush_pending_commands();
+
         // Poll audio events and update app state
         let audio_events = audio_engine.poll_events();
         let has_device_events = audio_events.iter().any(|e| {
-            matches!(e, AudioEvent::DeviceAdded { .. } | AudioEvent::DeviceRemoved { .. })
+            matches!(
+                e,
+                AudioEvent::DeviceAdded { .. }
+                    | AudioEvent::DeviceRemoved { .. }
+                    | AudioEvent::PortRemoved { .. }
+            )
+        });
+        let has_connection_events = audio_events.iter().any(|e| {
+            matches!(
+                e,
+                AudioEvent::ConnectionEstablished { .. } | AudioEvent::ConnectionBroken { .. }
+            )
         });
         app.handle_audio_events(&audio_events);
+        app.flush_spectrum_batch(now);
+        app.fade_stale_spectra(now);
+        app.update_auto_hide(now);
 
         // Refresh device list if device events occurred
         if has_device_events {
             let _ = app.refresh_devices(&audio_engine);
+            let _ = app.restore_after_reconnect(&audio_engine);
 
             // Restore visualizations from config on first device discovery
             if first_iteration {
-                for device_name in &config.visualization.enabled_devices {
-                    if let Some(device) = app.find_device_by_name(device_name) {
-                        if let Some(port) = device.ports.iter().find(|p| {
-                            use crate::audio::PortDirection;
-                            p.direction == PortDirection::Output
-                        }) {
-                            use crate::audio::AudioCommand;
-                            let _ = audio_engine.send_command(AudioCommand::StartVisualization {
-                                device_id: device.id,
-                                port_id: port.id,
-                            });
-                            debug_log!("Restored visualization for: {}", device_name);
-                        }
-                    }
-                }
+                restore_visualizations(&mut app, &config, &audio_engine);
+                restore_eq_and_volume(&mut app, &config, &audio_engine);
                 first_iteration = false;
             }
+        } else if has_connection_events {
+            app.refresh_connections(&audio_engine);
         }
 
+        // Start any visualizations that were waiting for ports
+        let _ = app.retry_pending_visualizations(&audio_engine);
+
         // Handle keyboard input
         loop {
             match input_rx.try_recv() {
                 Ok(key) => {
                     app.handle_input(key, &mut audio_engine)?;
+                    redraw = true;
                 }
                 Err(TryRecvError::Empty) => break,
                 Err(TryRecvError::Disconnected) => {
@@ -124,15 +187,32 @@ fn run_app() -> Result<()> {
             }
         }
 
+        // Switch config profile if requested from the UI
+        if let Some(profile) = app.take_profile_request()
+            && let Err(e) =
+                switch_profile(&mut app, &mut config_manager, &mut config, &profile, &audio_engine)
+        {
+            debug_log!("Profile switch failed: {}", e);
+            app.set_status_message(format!("Failed to switch profile: {}", e));
+        }
+
+        // Save or load an EQ preset if requested from the UI
+        if let Some(request) = app.take_eq_preset_request() {
+            handle_eq_preset_request(&mut app, &config_manager, request, &audio_engine);
+        }
+
+        // Write a support bundle if requested from the UI
+        if app.take_support_bundle_request() {
+            let message = match write_support_bundle(&app, &audio_engine, &config_manager, &config) {
+                Ok(path) => format!("Support bundle written to {}", path.display()),
+                Err(e) => format!("Failed to write support bundle: {}", e),
+            };
+            app.set_status_message(message);
+        }
+
         // Auto-save config if needed (debounced)
         if app.should_auto_save() {
-            let devices = audio_engine.list_devices().unwrap_or_default();
-            let config = Config::from_visualized_devices(
-                &app.get_visualized_devices(),
-                &devices,
-                app.get_spectrum_amplification(),
-                app.get_hidden_devices(),
-            );
+            let config = current_config(&app, &audio_engine, &config);
             if let Err(e) = config_manager.save(&config) {
                 debug_log!("Auto-save failed: {}", e);
             } else {
@@ -141,49 +221,261 @@ fn run_app() -> Result<()> {
             }
         }
 
-        // Render UI if enough time has passed
-        if elapsed >= FRAME_DURATION {
+        // Render UI if enough time has passed (re-read so profile switches apply)
+        let frame_duration = cli
+            .fps
+            .map_or_else(|| config.general.frame_duration(), config::frame_duration);
+        if elapsed >= frame_duration || redraw {
             terminal.draw(|frame| {
                 app.render(frame, &audio_engine);
             })?;
             last_frame = now;
+            redraw = false;
         } else {
-            // Sleep for remaining time to target FPS
-            let sleep_time = FRAME_DURATION.saturating_sub(elapsed);
-            if sleep_time > Duration::from_millis(1) {
-                thread::sleep(sleep_time);
+            // Wait out the rest of the frame, waking up early for input so keys
+            // stay responsive even at very low frame rates
+            let sleep_time = frame_duration.saturating_sub(elapsed);
+            if sleep_time > Duration::from_millis(1)
+                && let Ok(key) = input_rx.recv_timeout(sleep_time)
+            {
+                app.handle_input(key, &mut audio_engine)?;
+                redraw = true;
             }
         }
     }
 
     // Save configuration before cleanup
-    let devices = audio_engine.list_devices().unwrap_or_default();
-    let final_config = Config::from_visualized_devices(
-        &app.get_visualized_devices(),
-        &devices,
-        app.get_spectrum_amplification(),
-        app.get_hidden_devices(),
-    );
+    let final_config = current_config(&app, &audio_engine, &config);
     if let Err(e) = config_manager.save(&final_config) {
         debug_log!("Failed to save config on exit: {}", e);
     } else {
         debug_log!("Config saved on exit");
     }
 
-    // Cleanup - restore terminal to normal mode
-    terminal.show_cursor()?;
-    terminal.clear()?;
-
-    // Stop audio engine
+    // Stop audio engine (the terminal is restored by main)
     audio_engine.stop()?;
 
-    // Explicitly drop terminal to restore terminal settings
-    // This is critical - it runs the Drop handler that restores from raw mode
-    drop(terminal);
+    Ok(())
+}
+
+/// Leave raw mode, show the cursor and clear the screen (does nothing twice)
+fn restore_terminal() {
+    let raw_mode = RAW_MODE.lock().unwrap_or_else(PoisonError::into_inner).take();
+    let Some(raw_mode) = raw_mode else {
+        return;
+    };
+    // Dropping the guard puts back the terminal settings from before raw mode
+    drop(raw_mode);
+
+    let mut stdout = io::stdout();
+    let _ = write!(
+        stdout,
+        "{}{}{}",
+        termion::cursor::Show,
+        termion::clear::All,
+        termion::cursor::Goto(1, 1)
+    );
+    let _ = stdout.flush();
+}
 
+/// Restore the terminal before a panic message prints, so it lands in a usable shell
+/// and the user's terminal isn't left in raw mode. The panic is also logged, and the
+/// process exits since the TUI can't carry on without its terminal
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        debug_log!("PANIC: {}", info);
+        default_hook(info);
+        std::process::exit(101);
+    }));
+}
+
+/// Load the current profile's config, falling back to defaults
+fn load_config(config_manager: &ConfigManager) -> Config {
+    config_manager.load().unwrap_or_else(|e| {
+        debug_log!("Failed to load config: {}, using defaults", e);
+        Config::default()
+    })
+}
+
+/// Build the config to save from the app state
+/// Hand-edited general settings and key bindings are carried over from the loaded config
+fn current_config(app: &App, audio_engine: &AudioEngine, loaded: &Config) -> Config {
+    let devices = audio_engine.list_devices().unwrap_or_default();
+    Config::from_visualized_devices(app.get_visualized_devices(), &devices)
+        .with_spectrum_amplification(app.get_spectrum_amplification())
+        .with_spectrum_aggregation(app.get_spectrum_aggregation())
+        .with_bar_width(app.get_bar_width())
+        .with_refresh_rate(app.get_refresh_rate())
+        .with_hidden_devices(app.get_hidden_devices())
+        .with_spectrum_gamma(app.get_spectrum_gamma())
+        .with_bar_envelope(app.get_bar_envelope())
+        .with_amplitude_transform(app.get_amplitude_transform())
+        .with_glyphs(app.get_glyph_mode())
+        .with_favorites(app.get_favorite_devices())
+        .with_device_colors(app.get_device_colors())
+        .with_calibration(app.get_calibration())
+        .with_eq(app.get_eq_settings())
+        .with_volume(app.get_volume_settings())
+        .with_crossfeed(app.get_crossfeed_settings())
+        .with_saturation(app.get_saturation_drive())
+        .with_width(app.get_stereo_width())
+        .with_first_run_seen(app.first_run_seen())
+        .with_general(loaded.general.clone())
+        .with_keybindings(loaded.keybindings.clone())
+}
+
+/// Apply a loaded config's settings to the app (visualizations are restored separately)
+fn apply_config(
+    app: &mut App,
+    config: &Config,
+    config_manager: &ConfigManager,
+    audio_engine: &AudioEngine,
+) {
+    app.set_profiles(config_manager.profile(), config_manager.list_profiles());
+    app.set_eq_presets(config_manager.list_eq_presets());
+    app.set_spectrum_amplification(config.visualization.spectrum_amplification);
+    app.restore_hidden_devices(config.visualization.hidden_devices.clone());
+    app.restore_favorite_devices(config.visualization.favorite_devices.clone());
+    app.restore_device_colors(config.visualization.device_colors.clone());
+    app.restore_calibration(config.visualization.calibration_db.clone());
+    app.restore_crossfeed_settings(config.crossfeed.device_settings.clone());
+    app.restore_saturation_drive(config.saturation.device_drive.clone());
+    app.restore_stereo_width(config.width.device_width.clone());
+    app.set_spectrum_aggregation(config.visualization.spectrum_aggregation);
+    app.set_bar_width(config.visualization.bar_width);
+    app.set_spectrum_gamma(config.visualization.spectrum_gamma);
+    app.set_bar_envelope(config.visualization.bar_attack_ms, config.visualization.bar_release_ms);
+    app.set_amplitude_transform(config.visualization.amplitude_transform);
+    app.set_glyph_mode(config.visualization.glyphs);
+    app.set_refresh_rate(config.visualization.refresh_rate_hz);
+    app.set_auto_save_delay(config.general.auto_save_delay());
+    app.set_pairing_strategy(config.general.port_pairing);
+    app.set_auto_hide_after(config.general.auto_hide_after());
+    app.set_spectrum_batch_window(config.general.spectrum_batch_window());
+
+    let (keymap, warnings) = Keymap::new(&config.keybindings);
+    for warning in &warnings {
+        debug_log!("Key bindings: {}", warning);
+    }
+    if let Some(first) = warnings.first() {
+        app.set_status_message(format!("Key bindings: {} (defaults used)", first));
+    }
+    app.set_keymap(keymap);
+    let _ = audio_engine.send_command(audio::AudioCommand::SetRefreshRate {
+        hz: app.get_refresh_rate(),
+    });
+    let (threshold_db, after) = config.general.idle_detection();
+    let _ = audio_engine.send_command(audio::AudioCommand::SetIdleDetection { threshold_db, after });
+    let _ = audio_engine.send_command(audio::AudioCommand::SetMonitorPortPatterns {
+        patterns: config.general.monitor_port_patterns(),
+    });
+}
+
+/// Start visualizing the config's enabled devices
+fn restore_visualizations(app: &mut App, config: &Config, audio_engine: &AudioEngine) {
+    for device_name in &config.visualization.enabled_devices {
+        if let Some(device_id) = app.find_device_by_name(device_name).map(|d| d.id) {
+            // Queued until ports appear if the node was discovered first;
+            // success is logged once the stream actually starts
+            match app.start_visualization(device_id, audio_engine) {
+                Ok(()) => debug_log!("Requested visualization for: {}", device_name),
+                Err(e) => debug_log!("Could not request visualization for {}: {}", device_name, e),
+            }
+        }
+    }
+}
+
+/// Apply the config's EQ curves and volumes to the devices present
+fn restore_eq_and_volume(app: &mut App, config: &Config, audio_engine: &AudioEngine) {
+    if let Err(e) = app.apply_eq_and_volume(
+        &config.eq.device_settings,
+        &config.volume.device_settings,
+        audio_engine,
+    ) {
+        debug_log!("Could not restore EQ and volume: {}", e);
+    }
+}
+
+/// Save the current profile, stop its visualizations and load `profile` in its place
+/// A profile without a config file starts from defaults and is created on the next save
+fn switch_profile(
+    app: &mut App,
+    config_manager: &mut ConfigManager,
+    config: &mut Config,
+    profile: &str,
+    audio_engine: &AudioEngine,
+) -> Result<()> {
+    let previous = current_config(app, audio_engine, config);
+    config_manager.save(&previous)?;
+    app.mark_config_saved();
+
+    // Stop first so a failure leaves the manager on the profile still running
+    app.stop_all_visualizations(audio_engine)?;
+    if let Err(e) = config_manager.switch_profile(profile) {
+        restore_visualizations(app, config, audio_engine);
+        return Err(e.into());
+    }
+
+    *config = load_config(config_manager);
+    apply_config(app, config, config_manager, audio_engine);
+    restore_visualizations(app, config, audio_engine);
+    restore_eq_and_volume(app, config, audio_engine);
+
+    let diff = ConfigDiff::between(&previous, config);
+    app.set_status_message(format!("Switched to profile {} ({})", profile, diff));
+    debug_log!("Switched to profile {}: {}", profile, diff);
     Ok(())
 }
 
+/// Write or read a saved EQ preset, reporting the outcome in the status bar
+fn handle_eq_preset_request(
+    app: &mut App,
+    config_manager: &ConfigManager,
+    request: EqPresetRequest,
+    audio_engine: &AudioEngine,
+) {
+    match request {
+        EqPresetRequest::Save { name, settings } => {
+            let message = match config_manager.save_eq_preset(&name, &settings) {
+                Ok(()) => format!("Saved EQ preset {}", name),
+                Err(e) => format!("Failed to save EQ preset: {}", e),
+            };
+            app.set_status_message(message);
+            app.set_eq_presets(config_manager.list_eq_presets());
+        }
+        EqPresetRequest::Load { device_id, name } => {
+            let loaded = config_manager
+                .load_eq_preset(&name)
+                .map_err(anyhow::Error::from)
+                .and_then(|settings| app.apply_eq_curve(device_id, &name, settings, audio_engine));
+            if let Err(e) = loaded {
+                debug_log!("Failed to load EQ preset {}: {}", name, e);
+                app.set_status_message(format!("Failed to load EQ preset: {}", e));
+            }
+        }
+    }
+}
+
+/// Write a support bundle next to the debug log, returning its path
+fn write_support_bundle(
+    app: &App,
+    audio_engine: &AudioEngine,
+    config_manager: &ConfigManager,
+    config: &Config,
+) -> Result<std::path::PathBuf> {
+    let devices = audio_engine.list_devices()?;
+    let bundle = support::SupportBundle {
+        profile: config_manager.profile(),
+        config: &current_config(app, audio_engine, config),
+        status_history: &app.get_status_history(),
+        devices: &devices,
+        log_tail: &debug_log::tail(support::LOG_TAIL_LINES),
+    };
+    bundle.write(&std::env::current_dir()?)
+}
+
 /// Spawn a thread to handle keyboard input asynchronously
 fn spawn_input_thread() -> Receiver<Key> {
     let (tx, rx) = channel();
@@ -191,11 +483,11 @@ fn spawn_input_thread() -> Receiver<Key> {
     thread::spawn(move || {
         let mut stdin = async_stdin().keys();
         loop {
-            if let Some(Ok(key)) = stdin.next() {
-                if tx.send(key).is_err() {
-                    // Main thread has dropped the receiver, exit
-                    break;
-                }
+            if let Some(Ok(key)) = stdin.next()
+                && tx.send(key).is_err()
+            {
+                // Main thread has dropped the receiver, exit
+                break;
             }
             // Small sleep to prevent busy-waiting
             thread::sleep(Duration::from_millis(10));
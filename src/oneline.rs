@@ -0,0 +1,198 @@
+use anyhow::Result;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::audio::{AudioCommand, AudioEngine, AudioEvent, DeviceId, DeviceInfo, DeviceType, PortId};
+use crate::config::{GlyphMode, SpectrumAggregation};
+use crate::ui::{visualization_port, Glyphs};
+
+/// Default number of characters in the spectrum line
+pub const DEFAULT_WIDTH: usize = 16;
+
+/// How often a line is printed
+const PRINT_INTERVAL: Duration = Duration::from_millis(100);
+/// Spectrum refresh rate requested from the capture stream (matches the print interval)
+const REFRESH_RATE_HZ: u32 = 10;
+/// How long to wait for the default sink to appear
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Print a single-row braille spectrum of the default sink monitor until killed
+/// One line is written per interval so the output can be tailed by tmux/polybar
+/// SIGINT/SIGTERM keep their default behavior and end the process
+pub fn run(width: usize) -> Result<()> {
+    let mut audio_engine = AudioEngine::new()?;
+    audio_engine.start()?;
+
+    let (device_id, port_id) = wait_for_default_sink(&audio_engine)?;
+    audio_engine.send_command(AudioCommand::SetRefreshRate {
+        hz: REFRESH_RATE_HZ,
+    })?;
+    audio_engine.send_command(AudioCommand::StartVisualization { device_id, port_id })?;
+
+    // No config is loaded here; the environment alone decides braille support
+    let glyphs = Glyphs::detect(GlyphMode::Auto);
+    let mut bins: Vec<f32> = Vec::new();
+    let mut next_print = Instant::now();
+    let stdout = io::stdout();
+
+    loop {
+        for event in audio_engine.poll_events() {
+            match event {
+                AudioEvent::SpectrumUpdate { device_id: id, data } if id == device_id => {
+                    bins.clone_from(&data.bins);
+                }
+                AudioEvent::DeviceRemoved { device_id: id } if id == device_id => {
+                    anyhow::bail!("Default sink disappeared");
+                }
+                AudioEvent::Error { message } => {
+                    crate::debug_log!("[ONELINE] Audio error: {}", message);
+                }
+                _ => {}
+            }
+        }
+
+        let now = Instant::now();
+        if now >= next_print {
+            let mut out = stdout.lock();
+            // The reader going away (e.g. status bar reload) is a normal way to stop
+            if writeln!(out, "{}", render_line(&bins, width, glyphs))
+                .and_then(|_| out.flush())
+                .is_err()
+            {
+                return Ok(());
+            }
+
+            // Schedule against a running deadline, resetting after stalls
+            next_print += PRINT_INTERVAL;
+            if next_print < now {
+                next_print = now + PRINT_INTERVAL;
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Wait for device discovery and pick the sink to visualize
+fn wait_for_default_sink(audio_engine: &AudioEngine) -> Result<(DeviceId, PortId)> {
+    let deadline = Instant::now() + DISCOVERY_TIMEOUT;
+
+    loop {
+        // Drain events so the channel doesn't grow while we wait
+        let _ = audio_engine.poll_events();
+
+        let devices = audio_engine.list_devices().unwrap_or_default();
+        let default_sink = audio_engine.default_nodes().unwrap_or_default().sink;
+        let timed_out = Instant::now() >= deadline;
+        if let Some(sink) = pick_default_sink(&devices, default_sink.as_deref(), timed_out) {
+            return Ok(sink);
+        }
+        if timed_out {
+            anyhow::bail!("No sink with a monitor port found");
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Choose the sink whose monitor to visualize
+/// `default_sink` is the `node.name` from the PipeWire "default" metadata. Until
+/// discovery has settled we wait for that sink; after that (no metadata, or the
+/// sink never showed up) fall back to the first Audio/Sink node, then to any
+/// physical device with a monitor port
+fn pick_default_sink(
+    devices: &[DeviceInfo],
+    default_sink: Option<&str>,
+    settled: bool,
+) -> Option<(DeviceId, PortId)> {
+    let physical = || {
+        devices
+            .iter()
+            .filter(|d| d.device_type == DeviceType::Physical)
+            .filter_map(|d| visualization_port(d).map(|port_id| (d, port_id)))
+    };
+
+    let default = default_sink
+        .and_then(|name| physical().find(|(d, _)| d.node_name.as_deref() == Some(name)));
+    if default.is_none() && !settled {
+        return None;
+    }
+
+    default
+        .or_else(|| physical().find(|(d, _)| d.media_class.as_deref() == Some("Audio/Sink")))
+        .or_else(|| physical().next())
+        .map(|(d, port_id)| (d.id, port_id))
+}
+
+/// Collapse spectrum bins (dB) into `width` braille (or block) characters
+/// Each character shows the loudest bin in its frequency group
+fn render_line(bins: &[f32], width: usize, glyphs: Glyphs) -> String {
+    (0..width)
+        .map(|group_idx| {
+            let bin_start = (group_idx * bins.len()) / width;
+            let bin_end = ((group_idx + 1) * bins.len()) / width;
+            let magnitude = SpectrumAggregation::Max.aggregate(&bins[bin_start..bin_end]);
+
+            // Same -60..0 dB range as the full spectrum view, mapped onto the cell's pixels
+            let normalized = (magnitude + 60.0).clamp(0.0, 60.0) / 60.0;
+            let pixels = (normalized * glyphs.pixels_per_row() as f32).round() as usize;
+            glyphs.bar(pixels)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_line_width_and_levels() {
+        let bins = [-60.0, -60.0, -30.0, -30.0, 0.0, 0.0, -100.0, -100.0];
+        let line = render_line(&bins, 4, Glyphs::Braille);
+        assert_eq!(line.chars().count(), 4);
+        assert_eq!(line, "⠀⣤⣿⠀");
+        assert_eq!(render_line(&bins, 4, Glyphs::Blocks), " ▄█ ");
+    }
+
+    #[test]
+    fn test_render_line_without_data_is_blank() {
+        assert_eq!(render_line(&[], 3, Glyphs::Braille), "⠀⠀⠀");
+    }
+
+    #[test]
+    fn test_pick_default_sink_from_metadata() {
+        let sink = |id: u64, node_name: &str| {
+            let mut device =
+                DeviceInfo::new(DeviceId::new(id), format!("Sink {}", id), DeviceType::Physical);
+            device.media_class = Some(String::from("Audio/Sink"));
+            device.node_name = Some(node_name.to_string());
+            device.ports.push(crate::audio::PortInfo::new(
+                PortId::new(10 + id),
+                String::from("monitor_FL"),
+                crate::audio::PortDirection::Output,
+                format!("{}:monitor_FL", node_name),
+            ));
+            device
+        };
+        let devices = [sink(1, "alsa_output.hdmi"), sink(2, "alsa_output.usb")];
+
+        assert_eq!(
+            pick_default_sink(&devices, Some("alsa_output.usb"), false),
+            Some((DeviceId::new(2), PortId::new(12)))
+        );
+        // Without the key, wait for discovery to settle, then take the first sink
+        assert_eq!(pick_default_sink(&devices, None, false), None);
+        assert_eq!(
+            pick_default_sink(&devices, None, true),
+            Some((DeviceId::new(1), PortId::new(11)))
+        );
+        // A default sink that never appears also falls back once settled
+        assert_eq!(pick_default_sink(&devices, Some("bluez_output"), false), None);
+        assert_eq!(
+            pick_default_sink(&devices, Some("bluez_output"), true),
+            Some((DeviceId::new(1), PortId::new(11)))
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::audio::{probe_jack_server, AudioEngine, AudioEvent};
+
+/// How long to wait for PipeWire to connect and report devices
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+/// Extra time to let the registry settle after the first device appears
+const DISCOVERY_SETTLE: Duration = Duration::from_millis(250);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Outcome of a single self-test check
+struct CheckResult {
+    name: &'static str,
+    outcome: Result<String, String>,
+}
+
+impl CheckResult {
+    fn print(&self) {
+        match &self.outcome {
+            Ok(detail) => println!("[PASS] {}: {}", self.name, detail),
+            Err(reason) => println!("[FAIL] {}: {}", self.name, reason),
+        }
+    }
+}
+
+/// Run all runtime dependency checks and print a report
+/// Returns true if every check passed
+pub fn run() -> bool {
+    println!("wavewire self-test");
+
+    let (pipewire, devices) = check_pipewire();
+    let jack = check_jack();
+
+    let results = [pipewire, jack, devices];
+    for result in &results {
+        result.print();
+    }
+
+    let failed = results.iter().filter(|r| r.outcome.is_err()).count();
+    if failed == 0 {
+        println!("All checks passed");
+    } else {
+        println!("{} of {} checks failed", failed, results.len());
+    }
+
+    failed == 0
+}
+
+/// Start the audio engine and wait for the PipeWire connection and device discovery
+fn check_pipewire() -> (CheckResult, CheckResult) {
+    let connection = |outcome| CheckResult {
+        name: "PipeWire connection",
+        outcome,
+    };
+    let discovery = |outcome| CheckResult {
+        name: "Device discovery",
+        outcome,
+    };
+    let skipped = || discovery(Err(String::from("skipped (no PipeWire connection)")));
+
+    let mut audio_engine = match AudioEngine::new().and_then(|mut engine| {
+        engine.start()?;
+        Ok(engine)
+    }) {
+        Ok(engine) => engine,
+        Err(e) => return (connection(Err(e.to_string())), skipped()),
+    };
+
+    let start = Instant::now();
+    let mut deadline = start + DISCOVERY_TIMEOUT;
+    let mut connected_after = None;
+    let mut device_seen = false;
+    let mut error = None;
+
+    while Instant::now() < deadline && error.is_none() {
+        for event in audio_engine.poll_events() {
+            match event {
+                AudioEvent::Connected => connected_after = Some(start.elapsed()),
+                AudioEvent::DeviceAdded { .. } if !device_seen => {
+                    device_seen = true;
+                    deadline = deadline.min(Instant::now() + DISCOVERY_SETTLE);
+                }
+                AudioEvent::Error { message } if connected_after.is_none() => error = Some(message),
+                _ => {}
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    let device_count = audio_engine.list_devices().map(|d| d.len()).unwrap_or(0);
+    let _ = audio_engine.stop();
+
+    let Some(connected_after) = connected_after else {
+        let reason = error.unwrap_or_else(|| {
+            format!("timed out after {}s", DISCOVERY_TIMEOUT.as_secs())
+        });
+        return (connection(Err(reason)), skipped());
+    };
+
+    let connected_in = format!("connected in {}ms", connected_after.as_millis());
+    let devices = if device_count > 0 {
+        discovery(Ok(format!("found {} device(s)", device_count)))
+    } else {
+        discovery(Err(String::from("no audio devices found")))
+    };
+
+    (connection(Ok(connected_in)), devices)
+}
+
+/// Create a throwaway JACK client against the PipeWire JACK server
+fn check_jack() -> CheckResult {
+    let outcome = probe_jack_server()
+        .map(|sample_rate| format!("client created, sample rate {}Hz", sample_rate))
+        .map_err(|e| format!("{} (is pipewire-jack installed?)", e));
+
+    CheckResult {
+        name: "JACK client",
+        outcome,
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::audio::DeviceInfo;
+use crate::config::Config;
+use crate::debug_log;
+
+/// Number of debug log lines included in a support bundle
+pub const LOG_TAIL_LINES: usize = 200;
+
+/// State collected into a support bundle
+pub struct SupportBundle<'a> {
+    /// Config profile in use
+    pub profile: &'a str,
+    /// Config as it would be saved right now
+    pub config: &'a Config,
+    /// Recent status bar messages, oldest first
+    pub status_history: &'a [String],
+    /// All known devices with their ports
+    pub devices: &'a [DeviceInfo],
+    /// Tail of the debug log
+    pub log_tail: &'a [String],
+}
+
+impl SupportBundle<'_> {
+    /// Render the bundle as plain text
+    /// The home directory is replaced with `~` so user names don't leak into reports
+    pub fn render(&self, created_unix_secs: u64) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "=== wavewire support bundle ===");
+        let _ = writeln!(text, "Version: {}", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(text, "Created: {} (unix time)", created_unix_secs);
+        let _ = writeln!(text, "Profile: {}", self.profile);
+
+        let _ = writeln!(text, "\n=== Config ===");
+        match toml::to_string_pretty(self.config) {
+            Ok(config) => text.push_str(&config),
+            Err(e) => {
+                let _ = writeln!(text, "(failed to serialize: {})", e);
+            }
+        }
+
+        let _ = writeln!(text, "\n=== Recent status messages (oldest first) ===");
+        for message in self.status_history {
+            let _ = writeln!(text, "{}", message);
+        }
+
+        let _ = writeln!(text, "\n=== Devices ({}) ===", self.devices.len());
+        for device in self.devices {
+            let _ = write!(text, "{} {} [{}", device.id, device.name, device.device_type);
+            if let Some(media_class) = &device.media_class {
+                let _ = write!(text, ", {}", media_class);
+            }
+            if let Some(application) = &device.application_name {
+                let _ = write!(text, ", app: {}", application);
+            }
+            let _ = writeln!(text, "]");
+            for port in &device.ports {
+                let _ = writeln!(
+                    text,
+                    "    {} {} ({})",
+                    port.id, port.pipewire_port_name, port.direction
+                );
+            }
+        }
+
+        let _ = writeln!(text, "\n=== Debug log (last {} lines) ===", self.log_tail.len());
+        for line in self.log_tail {
+            let _ = writeln!(text, "{}", line);
+        }
+
+        match dirs::home_dir().and_then(|home| home.to_str().map(String::from)) {
+            Some(home) if !home.is_empty() && home != "/" => text.replace(&home, "~"),
+            _ => text,
+        }
+    }
+
+    /// Write the bundle to a timestamped file in `dir`, returning its path
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = dir.join(format!("wavewire-support-{}.txt", created));
+
+        fs::write(&path, self.render(created))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        debug_log!("Support bundle written to {}", path.display());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{DeviceId, DeviceType, PortDirection, PortId, PortInfo};
+
+    #[test]
+    fn test_render_sections() {
+        let mut device = DeviceInfo::new(DeviceId::new(3), String::from("Speakers"), DeviceType::Physical);
+        device.media_class = Some(String::from("Audio/Sink"));
+        device.ports.push(PortInfo::new(
+            PortId::new(7),
+            String::from("monitor_FL"),
+            PortDirection::Output,
+            String::from("Speakers:monitor_FL"),
+        ));
+        let config = Config::default();
+        let status_history = [String::from("EQ enabled"), String::from("Max hold on")];
+        let log_tail = [String::from("[1] Application starting")];
+
+        let text = SupportBundle {
+            profile: "default",
+            config: &config,
+            status_history: &status_history,
+            devices: &[device],
+            log_tail: &log_tail,
+        }
+        .render(1700000000);
+
+        assert!(text.contains("Created: 1700000000 (unix time)"));
+        assert!(text.contains("[visualization]"));
+        assert!(text.contains("EQ enabled\nMax hold on\n"));
+        assert!(text.contains("Device(3) Speakers [Physical, Audio/Sink]\n"));
+        assert!(text.contains("    Port(7) Speakers:monitor_FL (Output)\n"));
+        assert!(text.contains("=== Debug log (last 1 lines) ===\n[1] Application starting\n"));
+    }
+}
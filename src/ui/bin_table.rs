@@ -0,0 +1,94 @@
+use crate::audio::SpectrumData;
+
+/// Column widths; columns are separated by a space
+const INDEX_WIDTH: usize = 5;
+const FREQUENCY_WIDTH: usize = 10;
+const LEVEL_WIDTH: usize = 9;
+
+/// Width that fits every column; narrower tables drop the bin index
+pub const FULL_WIDTH: usize = INDEX_WIDTH + 1 + FREQUENCY_WIDTH + 1 + LEVEL_WIDTH;
+
+/// Bins moved per scroll key press
+pub const SCROLL_STEP: usize = 10;
+
+/// Column titles, aligned with `row` (same wording as the spectrum dump)
+pub fn header(width: usize) -> String {
+    let columns = format!("{:>f$} {:>l$}", "freq (Hz)", "level dB", f = FREQUENCY_WIDTH, l = LEVEL_WIDTH);
+    if width >= FULL_WIDTH {
+        format!("{:>i$} {}", "bin", columns, i = INDEX_WIDTH)
+    } else {
+        columns
+    }
+}
+
+/// One bin: index, center frequency and level, right-aligned under `header`
+pub fn row(index: usize, frequency: f32, level_db: f32, width: usize) -> String {
+    let columns = format!("{:>f$.1} {:>l$.2}", frequency, level_db, f = FREQUENCY_WIDTH, l = LEVEL_WIDTH);
+    if width >= FULL_WIDTH {
+        format!("{:>i$} {}", index, columns, i = INDEX_WIDTH)
+    } else {
+        columns
+    }
+}
+
+/// Largest scroll offset that still fills `rows` lines
+pub fn max_offset(bins: usize, rows: usize) -> usize {
+    bins.saturating_sub(rows)
+}
+
+/// Up to `rows` table lines starting at bin `offset` (clamped so the table stays full)
+/// Levels are shifted by the device's `calibration_db`
+pub fn rows(
+    spectrum: &SpectrumData,
+    offset: usize,
+    rows: usize,
+    width: usize,
+    calibration_db: f32,
+) -> Vec<String> {
+    let offset = offset.min(max_offset(spectrum.bins.len(), rows));
+    spectrum
+        .bins
+        .iter()
+        .zip(&spectrum.frequencies)
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+        .map(|(index, (&level, &frequency))| row(index, frequency, level + calibration_db, width))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_columns_align_and_index_is_dropped_when_narrow() {
+        let header_line = header(FULL_WIDTH);
+        let row_line = row(127, 20480.0, -123.456, FULL_WIDTH);
+        assert_eq!(header_line, "  bin  freq (Hz)  level dB");
+        assert_eq!(row_line, "  127    20480.0   -123.46");
+
+        let narrow = row(3, 93.75, -6.0, FULL_WIDTH - 1);
+        assert_eq!(narrow, "      93.8     -6.00");
+        assert_eq!(narrow.len(), header(FULL_WIDTH - 1).len());
+    }
+
+    #[test]
+    fn test_rows_clamp_to_the_end() {
+        let spectrum = SpectrumData {
+            bins: vec![-10.0, -20.0, -30.0, -40.0],
+            bins_right: None,
+            frequencies: vec![100.0, 200.0, 300.0, 400.0],
+            sample_rate: 48000,
+            timestamp: Instant::now(),
+        };
+        let lines = rows(&spectrum, 100, 3, FULL_WIDTH, 0.0);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].trim_start().starts_with('1'));
+        assert_eq!(rows(&spectrum, 0, 10, FULL_WIDTH, 0.0).len(), 4);
+
+        // Calibration shifts the levels only
+        assert_eq!(rows(&spectrum, 0, 1, FULL_WIDTH, 94.0), ["    0      100.0     84.00"]);
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::Result;
+
+/// System clipboard access (requires the `clipboard` feature)
+/// On Linux the copied text is served by this process, so the handle is kept
+/// alive for the lifetime of the app rather than created per copy
+#[derive(Default)]
+pub struct Clipboard {
+    #[cfg(feature = "clipboard")]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Put text on the system clipboard
+    #[cfg(feature = "clipboard")]
+    pub fn copy(&mut self, text: &str) -> Result<()> {
+        let clipboard = match &mut self.inner {
+            Some(clipboard) => clipboard,
+            inner => inner.insert(arboard::Clipboard::new()?),
+        };
+        clipboard.set_text(text)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    pub fn copy(&mut self, _text: &str) -> Result<()> {
+        anyhow::bail!("built without clipboard support")
+    }
+}
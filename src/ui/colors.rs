@@ -0,0 +1,137 @@
+use ratatui::style::Color;
+
+use crate::config::DeviceColor;
+
+/// Every assignable color, in the order the color key steps through them
+pub const PALETTE: [DeviceColor; 13] = [
+    DeviceColor::Cyan,
+    DeviceColor::Yellow,
+    DeviceColor::Magenta,
+    DeviceColor::Green,
+    DeviceColor::Red,
+    DeviceColor::Blue,
+    DeviceColor::White,
+    DeviceColor::LightCyan,
+    DeviceColor::LightYellow,
+    DeviceColor::LightMagenta,
+    DeviceColor::LightGreen,
+    DeviceColor::LightRed,
+    DeviceColor::LightBlue,
+];
+
+/// Devices without an assigned color cycle through the first six
+const AUTO_COLORS: usize = 6;
+
+impl DeviceColor {
+    /// Color for the `index`-th visualized device when none is assigned
+    pub fn auto(index: usize) -> Self {
+        PALETTE[index % AUTO_COLORS]
+    }
+
+    /// Next color in the palette, or None after the last (back to automatic)
+    pub fn next(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(PALETTE[0]),
+            Some(color) => {
+                let position = PALETTE.iter().position(|&c| c == color).unwrap_or(0);
+                PALETTE.get(position + 1).copied()
+            }
+        }
+    }
+
+    /// The other shade of the same hue (white pairs with light yellow)
+    /// Used for a device's post-EQ spectrum, drawn next to its own
+    pub fn shade(self) -> Self {
+        match self {
+            DeviceColor::Cyan => DeviceColor::LightCyan,
+            DeviceColor::Yellow => DeviceColor::LightYellow,
+            DeviceColor::Magenta => DeviceColor::LightMagenta,
+            DeviceColor::Green => DeviceColor::LightGreen,
+            DeviceColor::Red => DeviceColor::LightRed,
+            DeviceColor::Blue => DeviceColor::LightBlue,
+            DeviceColor::White => DeviceColor::LightYellow,
+            DeviceColor::LightCyan => DeviceColor::Cyan,
+            DeviceColor::LightYellow => DeviceColor::Yellow,
+            DeviceColor::LightMagenta => DeviceColor::Magenta,
+            DeviceColor::LightGreen => DeviceColor::Green,
+            DeviceColor::LightRed => DeviceColor::Red,
+            DeviceColor::LightBlue => DeviceColor::Blue,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            DeviceColor::Cyan => Color::Cyan,
+            DeviceColor::Yellow => Color::Yellow,
+            DeviceColor::Magenta => Color::Magenta,
+            DeviceColor::Green => Color::Green,
+            DeviceColor::Red => Color::Red,
+            DeviceColor::Blue => Color::Blue,
+            DeviceColor::White => Color::White,
+            DeviceColor::LightCyan => Color::LightCyan,
+            DeviceColor::LightYellow => Color::LightYellow,
+            DeviceColor::LightMagenta => Color::LightMagenta,
+            DeviceColor::LightGreen => Color::LightGreen,
+            DeviceColor::LightRed => Color::LightRed,
+            DeviceColor::LightBlue => Color::LightBlue,
+        }
+    }
+
+    /// Legend letter shown next to the device name (lowercase for light shades)
+    pub fn letter(self) -> &'static str {
+        match self {
+            DeviceColor::Cyan => "C",
+            DeviceColor::Yellow => "Y",
+            DeviceColor::Magenta => "M",
+            DeviceColor::Green => "G",
+            DeviceColor::Red => "R",
+            DeviceColor::Blue => "B",
+            DeviceColor::White => "W",
+            DeviceColor::LightCyan => "c",
+            DeviceColor::LightYellow => "y",
+            DeviceColor::LightMagenta => "m",
+            DeviceColor::LightGreen => "g",
+            DeviceColor::LightRed => "r",
+            DeviceColor::LightBlue => "b",
+        }
+    }
+
+    /// Name for status messages
+    pub fn name(self) -> &'static str {
+        match self {
+            DeviceColor::Cyan => "cyan",
+            DeviceColor::Yellow => "yellow",
+            DeviceColor::Magenta => "magenta",
+            DeviceColor::Green => "green",
+            DeviceColor::Red => "red",
+            DeviceColor::Blue => "blue",
+            DeviceColor::White => "white",
+            DeviceColor::LightCyan => "light cyan",
+            DeviceColor::LightYellow => "light yellow",
+            DeviceColor::LightMagenta => "light magenta",
+            DeviceColor::LightGreen => "light green",
+            DeviceColor::LightRed => "light red",
+            DeviceColor::LightBlue => "light blue",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_through_palette_and_back_to_auto() {
+        let mut color = None;
+        let mut steps = 0;
+        loop {
+            color = DeviceColor::next(color);
+            steps += 1;
+            if color.is_none() {
+                break;
+            }
+        }
+        assert_eq!(steps, PALETTE.len() + 1);
+        assert_eq!(DeviceColor::auto(7), DeviceColor::Yellow);
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+use super::SPECTRUM_FLOOR_DB;
+
+/// Meter ballistics for the displayed bars: a rising level is followed with the
+/// attack time, a falling one with the release time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope {
+    pub attack: Duration,
+    pub release: Duration,
+}
+
+/// Share of the way to the new level covered after `elapsed`, for time constant `time`
+/// Based on frame timestamps, so the bars move at the same speed at any refresh rate
+fn coefficient(time: Duration, elapsed: Duration) -> f32 {
+    if time.is_zero() {
+        return 1.0;
+    }
+    1.0 - (-elapsed.as_secs_f32() / time.as_secs_f32()).exp()
+}
+
+/// A device's spectrum as drawn, following its frames through an `Envelope`
+#[derive(Debug, Clone)]
+pub struct SmoothedBins {
+    bins: Vec<f32>,
+    timestamp: Instant,
+}
+
+impl SmoothedBins {
+    /// Start at the first frame's levels
+    pub fn new(bins: &[f32], timestamp: Instant) -> Self {
+        Self {
+            bins: bins.iter().map(|&level| level.max(SPECTRUM_FLOOR_DB)).collect(),
+            timestamp,
+        }
+    }
+
+    /// Move towards a new frame (in place, so no allocation per frame)
+    /// A frame with a different bin count starts over from its levels
+    pub fn update(&mut self, bins: &[f32], timestamp: Instant, envelope: Envelope) {
+        if bins.len() != self.bins.len() {
+            *self = Self::new(bins, timestamp);
+            return;
+        }
+
+        let elapsed = timestamp.saturating_duration_since(self.timestamp);
+        let attack = coefficient(envelope.attack, elapsed);
+        let release = coefficient(envelope.release, elapsed);
+        for (smoothed, &level) in self.bins.iter_mut().zip(bins) {
+            // Silence may arrive as -inf (or NaN); below the floor it all looks the same
+            let level = level.max(SPECTRUM_FLOOR_DB);
+            let step = if level > *smoothed { attack } else { release };
+            *smoothed += (level - *smoothed) * step;
+        }
+        self.timestamp = timestamp;
+    }
+
+    /// Fall towards the floor with the release time, as if silent frames had
+    /// arrived up to `now` (for when a device's frames stop coming)
+    pub fn fade(&mut self, now: Instant, envelope: Envelope) {
+        let release = coefficient(envelope.release, now.saturating_duration_since(self.timestamp));
+        for smoothed in &mut self.bins {
+            *smoothed += (SPECTRUM_FLOOR_DB - *smoothed) * release;
+        }
+        self.timestamp = now;
+    }
+
+    pub fn bins(&self) -> &[f32] {
+        &self.bins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attack_is_faster_than_release() {
+        let envelope = Envelope {
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(300),
+        };
+        let start = Instant::now();
+        let mut smoothed = SmoothedBins::new(&[-60.0, -10.0], start);
+
+        // One time constant covers ~63% of the step either way
+        smoothed.update(&[-10.0, -60.0], start + Duration::from_millis(10), envelope);
+        assert!((smoothed.bins()[0] - (-60.0 + 50.0 * 0.632)).abs() < 0.1);
+        assert!((smoothed.bins()[1] - (-10.0 - 50.0 * 0.033)).abs() < 0.1);
+
+        // Same elapsed time in more, shorter frames ends up at the same level
+        let mut fine = SmoothedBins::new(&[-60.0], start);
+        let mut coarse = fine.clone();
+        for frame in 1..=10 {
+            fine.update(&[-10.0], start + Duration::from_millis(frame * 3), envelope);
+        }
+        coarse.update(&[-10.0], start + Duration::from_millis(30), envelope);
+        assert!((fine.bins()[0] - coarse.bins()[0]).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_silence_and_zero_times() {
+        let instant = Envelope { attack: Duration::ZERO, release: Duration::ZERO };
+        let start = Instant::now();
+        let mut smoothed = SmoothedBins::new(&[f32::NEG_INFINITY, -20.0], start);
+        assert_eq!(smoothed.bins(), [SPECTRUM_FLOOR_DB, -20.0]);
+
+        smoothed.update(&[-30.0, f32::NAN], start + Duration::from_millis(5), instant);
+        assert_eq!(smoothed.bins(), [-30.0, SPECTRUM_FLOOR_DB]);
+
+        // Fading covers the same ground as silent frames would
+        let release = Envelope { attack: Duration::ZERO, release: Duration::from_millis(100) };
+        let mut faded = SmoothedBins::new(&[-20.0], start);
+        let mut silent = faded.clone();
+        faded.fade(start + Duration::from_millis(100), release);
+        silent.update(&[f32::NEG_INFINITY], start + Duration::from_millis(100), release);
+        assert_eq!(faded.bins(), silent.bins());
+
+        // A different bin count starts over
+        smoothed.update(&[-5.0; 3], start, instant);
+        assert_eq!(smoothed.bins(), [-5.0; 3]);
+    }
+}
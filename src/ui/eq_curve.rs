@@ -0,0 +1,71 @@
+use super::goniometer::DOT_BITS;
+
+/// Plot a response curve into braille cells
+/// `values` holds one dB value per dot column (two per cell); 0 dB sits on the
+/// middle of the grid and ±`range_db` reaches the top/bottom edge
+/// Neighbouring points are joined vertically so steep slopes stay connected
+/// Returns `height` rows of `width` cells, each a bitmask of braille dots
+pub fn plot(values: &[f32], range_db: f32, width: usize, height: usize) -> Vec<Vec<u8>> {
+    let mut cells = vec![vec![0u8; width]; height];
+    if width == 0 || height == 0 {
+        return cells;
+    }
+
+    let dots_x = (width * 2).min(values.len());
+    let dots_y = height * 4;
+    let row_for = |db: f32| {
+        let normalized = (1.0 - db.clamp(-range_db, range_db) / range_db) / 2.0;
+        (normalized * (dots_y - 1) as f32).round() as usize
+    };
+
+    let mut previous_row = None;
+    for (x, &db) in values[..dots_x].iter().enumerate() {
+        let row = row_for(db);
+        let (top, bottom) = match previous_row {
+            // Fill back towards the previous point, stopping just short of its row
+            Some(prev) if prev < row => (prev + 1, row),
+            Some(prev) if prev > row => (row, prev - 1),
+            _ => (row, row),
+        };
+        for y in top..=bottom {
+            cells[y / 4][x / 2] |= DOT_BITS[y % 4][x % 2];
+        }
+        previous_row = Some(row);
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rows of cells that have any dot set
+    fn used_rows(cells: &[Vec<u8>]) -> Vec<usize> {
+        (0..cells.len())
+            .filter(|&row| cells[row].iter().any(|&bits| bits != 0))
+            .collect()
+    }
+
+    #[test]
+    fn test_flat_curve_sits_on_center() {
+        let cells = plot(&[0.0; 8], 24.0, 4, 4);
+        // 16 dot rows: 0 dB rounds to dot row 8, the top of cell row 2
+        assert_eq!(used_rows(&cells), vec![2]);
+        assert!(cells[2].iter().all(|&bits| bits == 0x09));
+    }
+
+    #[test]
+    fn test_steep_step_is_joined() {
+        let cells = plot(&[24.0, -24.0], 24.0, 1, 2);
+        // The second dot column runs from just below the top to the bottom
+        assert_eq!(cells[0][0], 0x01 | 0x10 | 0x20 | 0x80);
+        assert_eq!(cells[1][0], 0x08 | 0x10 | 0x20 | 0x80);
+    }
+
+    #[test]
+    fn test_out_of_range_is_clamped() {
+        let cells = plot(&[100.0, -100.0], 24.0, 1, 1);
+        assert_eq!(used_rows(&cells), vec![0]);
+    }
+}
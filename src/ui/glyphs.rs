@@ -0,0 +1,185 @@
+use super::goniometer;
+use crate::config::GlyphMode;
+
+/// Environment variable that forces block rendering when set to anything but "0"
+pub const NO_BRAILLE_ENV: &str = "WAVEWIRE_NO_BRAILLE";
+
+/// Terminals known to lack braille glyphs (the Linux VT console font, old hardware terminals)
+const NO_BRAILLE_TERMS: [&str; 5] = ["linux", "dumb", "vt100", "vt220", "cons25"];
+
+/// Partial block characters, indexed by eighths filled from the bottom
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Character set the spectrum is actually drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyphs {
+    /// 4 vertical dots per cell
+    Braille,
+    /// 8 vertical eighths per cell, available in nearly every font
+    Blocks,
+}
+
+impl Glyphs {
+    /// Resolve a mode against the environment
+    /// Only reads environment variables, so it never stalls startup on a terminal query
+    pub fn detect(mode: GlyphMode) -> Self {
+        let var = |name| std::env::var(name).ok();
+        let locale = var("LC_ALL")
+            .filter(|v| !v.is_empty())
+            .or_else(|| var("LC_CTYPE").filter(|v| !v.is_empty()))
+            .or_else(|| var("LANG"));
+        Self::resolve(
+            mode,
+            var(NO_BRAILLE_ENV).as_deref(),
+            var("TERM").as_deref(),
+            locale.as_deref(),
+        )
+    }
+
+    /// Pick glyphs from the mode, the escape hatch, `TERM` and the locale
+    /// The escape hatch wins over everything, including a configured `braille`
+    pub fn resolve(
+        mode: GlyphMode,
+        no_braille: Option<&str>,
+        term: Option<&str>,
+        locale: Option<&str>,
+    ) -> Self {
+        if no_braille.is_some_and(|v| !v.is_empty() && v != "0") {
+            return Glyphs::Blocks;
+        }
+        match mode {
+            GlyphMode::Braille => Glyphs::Braille,
+            GlyphMode::Blocks => Glyphs::Blocks,
+            GlyphMode::Auto => {
+                let term_lacks_braille = term.is_some_and(|term| NO_BRAILLE_TERMS.contains(&term));
+                // Without a UTF-8 locale nothing outside ASCII renders reliably
+                let utf8 = locale.is_none_or(|locale| {
+                    let locale = locale.to_ascii_lowercase();
+                    locale.contains("utf-8") || locale.contains("utf8")
+                });
+                if term_lacks_braille || !utf8 {
+                    Glyphs::Blocks
+                } else {
+                    Glyphs::Braille
+                }
+            }
+        }
+    }
+
+    /// Vertical resolution of one terminal cell
+    pub fn pixels_per_row(&self) -> usize {
+        match self {
+            Glyphs::Braille => 4,
+            Glyphs::Blocks => 8,
+        }
+    }
+
+    /// Cell with the bottom `filled` pixels lit
+    pub fn bar(&self, filled: usize) -> char {
+        match self {
+            Glyphs::Braille => braille_bar(filled),
+            Glyphs::Blocks => BLOCKS[filled.min(8)],
+        }
+    }
+
+    /// Cell with the top `filled` pixels lit (bars hanging below a baseline)
+    /// Blocks only come in top eighth, top half and full, so they round down
+    pub fn hanging_bar(&self, filled: usize) -> char {
+        match self {
+            Glyphs::Braille => braille_hanging_bar(filled),
+            Glyphs::Blocks => match filled {
+                0 => ' ',
+                1..=3 => '▔',
+                4..=7 => '▀',
+                _ => '█',
+            },
+        }
+    }
+
+    /// Thin horizontal tick at pixel row `pixel` (counted from the bottom) within a cell
+    pub fn tick(&self, pixel: usize) -> char {
+        match self {
+            Glyphs::Braille => {
+                let dot_row = 3 - pixel.min(3);
+                goniometer::braille(goniometer::DOT_BITS[dot_row][0] | goniometer::DOT_BITS[dot_row][1])
+            }
+            Glyphs::Blocks => {
+                if pixel >= 4 {
+                    '▔'
+                } else {
+                    '▁'
+                }
+            }
+        }
+    }
+
+    /// Cell for a braille dot bitmask (goniometer, EQ curve); blocks mark any dot with a bullet
+    pub fn dots(&self, bits: u8) -> char {
+        match self {
+            Glyphs::Braille => goniometer::braille(bits),
+            Glyphs::Blocks if bits == 0 => ' ',
+            Glyphs::Blocks => '•',
+        }
+    }
+}
+
+/// Braille cell with both columns filled from the bottom
+fn braille_bar(filled: usize) -> char {
+    match filled {
+        0 => '⠀',
+        1 => '⣀',
+        2 => '⣤',
+        3 => '⣶',
+        _ => '⣿',
+    }
+}
+
+/// Braille cell with both columns filled from the top
+fn braille_hanging_bar(filled: usize) -> char {
+    match filled {
+        0 => '⠀',
+        1 => '⠉',
+        2 => '⠛',
+        3 => '⠿',
+        _ => '⣿',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UTF8: Option<&str> = Some("en_US.UTF-8");
+
+    #[test]
+    fn test_auto_detection() {
+        let auto = |term, locale| Glyphs::resolve(GlyphMode::Auto, None, term, locale);
+        assert_eq!(auto(Some("xterm-256color"), UTF8), Glyphs::Braille);
+        assert_eq!(auto(None, None), Glyphs::Braille);
+        assert_eq!(auto(Some("linux"), UTF8), Glyphs::Blocks);
+        assert_eq!(auto(Some("xterm-256color"), Some("C")), Glyphs::Blocks);
+        // An explicit mode isn't second-guessed
+        assert_eq!(
+            Glyphs::resolve(GlyphMode::Braille, None, Some("linux"), UTF8),
+            Glyphs::Braille
+        );
+    }
+
+    #[test]
+    fn test_escape_hatch_forces_blocks() {
+        for mode in [GlyphMode::Auto, GlyphMode::Braille] {
+            assert_eq!(Glyphs::resolve(mode, Some("1"), Some("xterm"), UTF8), Glyphs::Blocks);
+        }
+        assert_eq!(
+            Glyphs::resolve(GlyphMode::Auto, Some("0"), Some("xterm"), UTF8),
+            Glyphs::Braille
+        );
+    }
+
+    #[test]
+    fn test_block_levels() {
+        let rendered: String = (0..=8).map(|filled| Glyphs::Blocks.bar(filled)).collect();
+        assert_eq!(rendered, " ▁▂▃▄▅▆▇█");
+        assert_eq!(Glyphs::Braille.bar(2), '⣤');
+    }
+}
@@ -0,0 +1,99 @@
+/// Smallest peak the plot is scaled to, so near-silence doesn't get blown up into noise
+const MIN_SCALE_PEAK: f32 = 0.05;
+
+/// Braille dot bits indexed by [row][column] within a 2x4 cell
+pub const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Plot L/R frames as a goniometer (Lissajous rotated 45°) into braille cells
+/// Mono content draws a vertical line, out-of-phase content a horizontal one
+/// Returns `height` rows of `width` cells, each a bitmask of braille dots
+pub fn plot(frames: &[[f32; 2]], width: usize, height: usize) -> Vec<Vec<u8>> {
+    let mut cells = vec![vec![0u8; width]; height];
+    if width == 0 || height == 0 {
+        return cells;
+    }
+
+    // Scale to the loudest point so quiet material still fills the square
+    let peak = frames
+        .iter()
+        .map(|&[l, r]| mid_side(l, r))
+        .map(|(mid, side)| mid.abs().max(side.abs()))
+        .fold(MIN_SCALE_PEAK, f32::max);
+
+    let dots_x = width * 2;
+    let dots_y = height * 4;
+
+    for &[left, right] in frames {
+        let (mid, side) = mid_side(left, right);
+        // Map -peak..peak onto the dot grid (mid grows upwards)
+        let x = ((side / peak + 1.0) / 2.0 * (dots_x - 1) as f32).round() as usize;
+        let y = ((1.0 - mid / peak) / 2.0 * (dots_y - 1) as f32).round() as usize;
+        let (x, y) = (x.min(dots_x - 1), y.min(dots_y - 1));
+
+        cells[y / 4][x / 2] |= DOT_BITS[y % 4][x % 2];
+    }
+
+    cells
+}
+
+/// Braille character for a cell's dot bitmask
+pub fn braille(bits: u8) -> char {
+    char::from_u32(0x2800 + bits as u32).unwrap_or(' ')
+}
+
+/// Rotate L/R by 45° into mid (L+R) and side (R-L) components
+fn mid_side(left: f32, right: f32) -> (f32, f32) {
+    let scale = std::f32::consts::FRAC_1_SQRT_2;
+    ((left + right) * scale, (right - left) * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Columns of dots (in dot coordinates) that have any point set
+    fn lit_dot_columns(cells: &[Vec<u8>]) -> Vec<usize> {
+        let mut columns = Vec::new();
+        for row in cells {
+            for (cx, &bits) in row.iter().enumerate() {
+                if bits & 0x47 != 0 {
+                    columns.push(cx * 2);
+                }
+                if bits & 0xB8 != 0 {
+                    columns.push(cx * 2 + 1);
+                }
+            }
+        }
+        columns.sort_unstable();
+        columns.dedup();
+        columns
+    }
+
+    #[test]
+    fn test_mono_plots_vertical_line() {
+        let frames: Vec<[f32; 2]> = (-10..=10).map(|i| [i as f32 / 10.0; 2]).collect();
+        let cells = plot(&frames, 8, 4);
+        // All points sit on the center column (side = 0)
+        assert_eq!(lit_dot_columns(&cells), vec![8]);
+    }
+
+    #[test]
+    fn test_out_of_phase_plots_horizontal_line() {
+        let frames: Vec<[f32; 2]> = (-10..=10)
+            .map(|i| [i as f32 / 10.0, -i as f32 / 10.0])
+            .collect();
+        let cells = plot(&frames, 8, 4);
+        // Mid is zero, so only the middle row of cells is used
+        let used_rows: Vec<usize> = (0..cells.len())
+            .filter(|&row| cells[row].iter().any(|&bits| bits != 0))
+            .collect();
+        assert_eq!(used_rows, vec![2]);
+        assert_eq!(lit_dot_columns(&cells).len(), 16);
+    }
+
+    #[test]
+    fn test_braille_char() {
+        assert_eq!(braille(0), '⠀');
+        assert_eq!(braille(0xFF), '⣿');
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use crate::audio::DeviceInfo;
+
+/// One row of the device list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceRow {
+    /// Collapsible header for an application's stream nodes
+    Group {
+        application: String,
+        /// Number of visible streams in the group
+        members: usize,
+        expanded: bool,
+    },
+    /// A device, by index into the flat device list
+    Device {
+        index: usize,
+        /// Whether the device is nested under an application header
+        nested: bool,
+    },
+}
+
+/// Indices of `devices` in display order: favorites first, otherwise discovery order
+pub fn display_order<F>(devices: &[DeviceInfo], is_favorite: F) -> Vec<usize>
+where
+    F: Fn(&DeviceInfo) -> bool,
+{
+    let mut order: Vec<usize> = (0..devices.len()).collect();
+    // Stable, so both partitions keep their discovery order
+    order.sort_by_key(|&index| !is_favorite(&devices[index]));
+    order
+}
+
+/// Build the device list rows over the flat `devices` vec
+/// Without grouping every visible device is a top-level row. With grouping,
+/// stream nodes are nested under a header for their application, placed where
+/// the application's first stream would have been; sinks/sources stay top-level.
+/// Favorites are pinned above everything else (a favorite stream pulls its group up)
+pub fn build_rows<F, G>(
+    devices: &[DeviceInfo],
+    group_by_application: bool,
+    collapsed: &HashSet<String>,
+    is_visible: F,
+    is_favorite: G,
+) -> Vec<DeviceRow>
+where
+    F: Fn(&DeviceInfo) -> bool,
+    G: Fn(&DeviceInfo) -> bool,
+{
+    let visible: Vec<usize> = display_order(devices, is_favorite)
+        .into_iter()
+        .filter(|&index| is_visible(&devices[index]))
+        .collect();
+
+    if !group_by_application {
+        return visible
+            .into_iter()
+            .map(|index| DeviceRow::Device {
+                index,
+                nested: false,
+            })
+            .collect();
+    }
+
+    let mut rows = Vec::new();
+    let mut seen_applications = HashSet::new();
+
+    for &index in &visible {
+        let Some(application) = devices[index].application_name.as_deref() else {
+            rows.push(DeviceRow::Device {
+                index,
+                nested: false,
+            });
+            continue;
+        };
+
+        if !seen_applications.insert(application) {
+            continue;
+        }
+
+        let members: Vec<usize> = visible
+            .iter()
+            .copied()
+            .filter(|&i| devices[i].application_name.as_deref() == Some(application))
+            .collect();
+        let expanded = !collapsed.contains(application);
+
+        rows.push(DeviceRow::Group {
+            application: application.to_string(),
+            members: members.len(),
+            expanded,
+        });
+        if expanded {
+            rows.extend(members.into_iter().map(|index| DeviceRow::Device {
+                index,
+                nested: true,
+            }));
+        }
+    }
+
+    rows
+}
+
+/// First row to show so that `selected` stays inside a window of `visible` rows,
+/// moving the previous `offset` as little as possible and never past the last page
+pub fn scroll_offset(offset: usize, selected: Option<usize>, visible: usize, total: usize) -> usize {
+    let visible = visible.max(1);
+    let offset = match selected {
+        Some(row) if row < offset => row,
+        Some(row) if row >= offset + visible => row + 1 - visible,
+        _ => offset,
+    };
+    offset.min(total.saturating_sub(visible))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{DeviceId, DeviceType};
+
+    fn device(id: u64, name: &str, application: Option<&str>) -> DeviceInfo {
+        let mut device = DeviceInfo::new(DeviceId::new(id), name.to_string(), DeviceType::Physical);
+        device.application_name = application.map(String::from);
+        device
+    }
+
+    fn test_devices() -> Vec<DeviceInfo> {
+        vec![
+            device(1, "Speakers", None),
+            device(2, "YouTube", Some("Firefox")),
+            device(3, "Microphone", None),
+            device(4, "Spotify", Some("Spotify")),
+            device(5, "Twitch", Some("Firefox")),
+        ]
+    }
+
+    #[test]
+    fn test_ungrouped_rows_are_flat() {
+        let devices = test_devices();
+        let rows = build_rows(&devices, false, &HashSet::new(), |_| true, |_| false);
+        assert_eq!(rows.len(), devices.len());
+        assert!(rows
+            .iter()
+            .all(|row| matches!(row, DeviceRow::Device { nested: false, .. })));
+    }
+
+    #[test]
+    fn test_streams_nest_under_application() {
+        let devices = test_devices();
+        let rows = build_rows(&devices, true, &HashSet::new(), |_| true, |_| false);
+        assert_eq!(
+            rows,
+            vec![
+                DeviceRow::Device { index: 0, nested: false },
+                DeviceRow::Group {
+                    application: String::from("Firefox"),
+                    members: 2,
+                    expanded: true,
+                },
+                DeviceRow::Device { index: 1, nested: true },
+                DeviceRow::Device { index: 4, nested: true },
+                DeviceRow::Device { index: 2, nested: false },
+                DeviceRow::Group {
+                    application: String::from("Spotify"),
+                    members: 1,
+                    expanded: true,
+                },
+                DeviceRow::Device { index: 3, nested: true },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapsed_group_hides_members() {
+        let devices = test_devices();
+        let collapsed = HashSet::from([String::from("Firefox")]);
+        // Hidden streams don't count towards the group
+        let rows = build_rows(&devices, true, &collapsed, |d| d.name != "Twitch", |_| false);
+        assert_eq!(
+            rows[1],
+            DeviceRow::Group {
+                application: String::from("Firefox"),
+                members: 1,
+                expanded: false,
+            }
+        );
+        assert_eq!(rows[2], DeviceRow::Device { index: 2, nested: false });
+    }
+
+    #[test]
+    fn test_favorites_are_pinned_in_stable_order() {
+        let devices = test_devices();
+        let favorite = |d: &DeviceInfo| d.name == "Microphone" || d.name == "Twitch";
+        assert_eq!(display_order(&devices, favorite), vec![2, 4, 0, 1, 3]);
+
+        let rows = build_rows(&devices, true, &HashSet::new(), |_| true, favorite);
+        assert_eq!(rows[0], DeviceRow::Device { index: 2, nested: false });
+        // The favorite stream brings its whole group along and leads it
+        assert_eq!(
+            rows[1..4],
+            [
+                DeviceRow::Group {
+                    application: String::from("Firefox"),
+                    members: 2,
+                    expanded: true,
+                },
+                DeviceRow::Device { index: 4, nested: true },
+                DeviceRow::Device { index: 1, nested: true },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scroll_keeps_selection_visible() {
+        // Stays put while the selection is on screen
+        assert_eq!(scroll_offset(0, Some(9), 10, 30), 0);
+        assert_eq!(scroll_offset(5, Some(7), 10, 30), 5);
+        // Follows the selection past either edge
+        assert_eq!(scroll_offset(0, Some(10), 10, 30), 1);
+        assert_eq!(scroll_offset(12, Some(4), 10, 30), 4);
+        // Pulled back when the list shrinks (e.g. devices hidden)
+        assert_eq!(scroll_offset(20, Some(8), 10, 12), 2);
+        assert_eq!(scroll_offset(3, None, 10, 5), 0);
+    }
+}
@@ -0,0 +1,546 @@
+use std::collections::{BTreeMap, HashMap};
+use termion::event::Key;
+
+use crate::config::KeyBinding;
+
+/// Something a key can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Device list
+    Quit,
+    NextTab,
+    PreviousTab,
+    PinEq,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    OpenEq,
+    ApplyEqToAll,
+    Refresh,
+    NewVirtualDevice,
+    DeleteVirtualDevice,
+    ToggleViz,
+    VisualizeNode,
+    CopyPorts,
+    DumpSpectrum,
+    ImportEq,
+    SaveEqPreset,
+    LoadEqPreset,
+    FiltersEqBypass,
+    SupportBundle,
+    SwitchProfile,
+    PauseAll,
+    ToggleRecording,
+    AmplificationDown,
+    AmplificationUp,
+    CycleFocus,
+    NextConnection,
+    PreviousConnection,
+    Disconnect,
+    Link,
+    GammaDown,
+    GammaUp,
+    BarAttackDown,
+    BarAttackUp,
+    BarReleaseDown,
+    BarReleaseUp,
+    BarWidth,
+    ZoomLowDown,
+    ZoomLowUp,
+    ZoomHighDown,
+    ZoomHighUp,
+    ZoomReset,
+    RefreshRate,
+    DifferenceMode,
+    StereoCapture,
+    Goniometer,
+    Waveform,
+    LevelMeters,
+    StereoSpectrum,
+    StreamHealth,
+    CommandStats,
+    CancelCommands,
+    BinTable,
+    BinTableUp,
+    BinTableDown,
+    MaxHold,
+    ResetMaxHold,
+    PeakHold,
+    HideDevice,
+    UndoHide,
+    ShowHidden,
+    GroupByApp,
+    ToggleGroup,
+    Favorite,
+    CycleColor,
+    JumpToDevice,
+    CalibrationDown,
+    CalibrationUp,
+    Crossfeed,
+    CrossfeedAmountDown,
+    CrossfeedAmountUp,
+    CrossfeedCutoffDown,
+    CrossfeedCutoffUp,
+    SaturationDown,
+    SaturationUp,
+    WidthDown,
+    WidthUp,
+    FftSize,
+    VolumeDown,
+    VolumeUp,
+    BalanceLeft,
+    BalanceRight,
+    Help,
+    // EQ mode
+    EqQuit,
+    EqBack,
+    EqGainUp,
+    EqGainDown,
+    EqQDown,
+    EqQUp,
+    EqBandCount,
+    EqMute,
+    EqSolo,
+    EqBypass,
+    EqCompare,
+    EqReset,
+    EqDisable,
+    EqPreviousBand,
+    EqNextBand,
+}
+
+/// Input mode an action belongs to; keys only need to be unique within a mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    DeviceList,
+    Eq,
+}
+
+/// Every action with its name in the `[keybindings]` config section, its mode and default keys
+const ACTIONS: &[(Action, &str, Mode, &[Key])] = &[
+    (Action::Quit, "quit", Mode::DeviceList, &[Key::Char('q'), Key::Esc, Key::Ctrl('c')]),
+    (Action::NextTab, "next_tab", Mode::DeviceList, &[Key::Char('\t')]),
+    (Action::PreviousTab, "previous_tab", Mode::DeviceList, &[Key::BackTab]),
+    (Action::PinEq, "pin_eq", Mode::DeviceList, &[Key::Char('P')]),
+    (Action::Up, "up", Mode::DeviceList, &[Key::Up, Key::Char('k')]),
+    (Action::Down, "down", Mode::DeviceList, &[Key::Down, Key::Char('j')]),
+    (Action::PageUp, "page_up", Mode::DeviceList, &[Key::PageUp]),
+    (Action::PageDown, "page_down", Mode::DeviceList, &[Key::PageDown]),
+    (Action::OpenEq, "open_eq", Mode::DeviceList, &[Key::Char('e')]),
+    (Action::ApplyEqToAll, "apply_eq_to_all", Mode::DeviceList, &[Key::Char('A')]),
+    (Action::Refresh, "refresh", Mode::DeviceList, &[Key::Char('r')]),
+    (Action::NewVirtualDevice, "new_virtual_device", Mode::DeviceList, &[Key::Char('n')]),
+    (Action::DeleteVirtualDevice, "delete_virtual_device", Mode::DeviceList, &[Key::Delete]),
+    (Action::ToggleViz, "toggle_viz", Mode::DeviceList, &[Key::Char(' ')]),
+    (Action::VisualizeNode, "visualize_node", Mode::DeviceList, &[Key::Char('N')]),
+    (Action::CopyPorts, "copy_ports", Mode::DeviceList, &[Key::Char('c')]),
+    (Action::DumpSpectrum, "dump_spectrum", Mode::DeviceList, &[Key::Char('T')]),
+    (Action::ImportEq, "import_eq", Mode::DeviceList, &[Key::Char('I')]),
+    (Action::SaveEqPreset, "save_eq_preset", Mode::DeviceList, &[Key::Char('E')]),
+    (Action::LoadEqPreset, "load_eq_preset", Mode::DeviceList, &[Key::Char(':')]),
+    (Action::FiltersEqBypass, "filters_eq_bypass", Mode::DeviceList, &[Key::Char('b')]),
+    (Action::SupportBundle, "support_bundle", Mode::DeviceList, &[Key::Char('B')]),
+    (Action::SwitchProfile, "switch_profile", Mode::DeviceList, &[Key::Char('L')]),
+    (Action::PauseAll, "pause_all", Mode::DeviceList, &[Key::Char('S')]),
+    (Action::ToggleRecording, "toggle_recording", Mode::DeviceList, &[Key::Char('R')]),
+    (Action::AmplificationDown, "amplification_down", Mode::DeviceList, &[Key::Char('o')]),
+    (Action::AmplificationUp, "amplification_up", Mode::DeviceList, &[Key::Char('p')]),
+    (Action::CycleFocus, "cycle_focus", Mode::DeviceList, &[Key::Char('v')]),
+    (Action::NextConnection, "next_connection", Mode::DeviceList, &[Key::Char('J')]),
+    (Action::PreviousConnection, "previous_connection", Mode::DeviceList, &[Key::Char('K')]),
+    (Action::Disconnect, "disconnect", Mode::DeviceList, &[Key::Char('D')]),
+    (Action::Link, "link", Mode::DeviceList, &[Key::Char('C')]),
+    (Action::GammaDown, "gamma_down", Mode::DeviceList, &[Key::Char('<')]),
+    (Action::GammaUp, "gamma_up", Mode::DeviceList, &[Key::Char('>')]),
+    (Action::BarAttackDown, "bar_attack_down", Mode::DeviceList, &[Key::Char('y')]),
+    (Action::BarAttackUp, "bar_attack_up", Mode::DeviceList, &[Key::Char('Y')]),
+    (Action::BarReleaseDown, "bar_release_down", Mode::DeviceList, &[Key::Char(';')]),
+    (Action::BarReleaseUp, "bar_release_up", Mode::DeviceList, &[Key::Char('\'')]),
+    (Action::BarWidth, "bar_width", Mode::DeviceList, &[Key::Char('w')]),
+    (Action::ZoomLowDown, "zoom_low_down", Mode::DeviceList, &[Key::Char('[')]),
+    (Action::ZoomLowUp, "zoom_low_up", Mode::DeviceList, &[Key::Char(']')]),
+    (Action::ZoomHighDown, "zoom_high_down", Mode::DeviceList, &[Key::Char('{')]),
+    (Action::ZoomHighUp, "zoom_high_up", Mode::DeviceList, &[Key::Char('}')]),
+    (Action::ZoomReset, "zoom_reset", Mode::DeviceList, &[Key::Char('0')]),
+    (Action::RefreshRate, "refresh_rate", Mode::DeviceList, &[Key::Char('f')]),
+    (Action::DifferenceMode, "difference_mode", Mode::DeviceList, &[Key::Char('d')]),
+    (Action::StereoCapture, "stereo_capture", Mode::DeviceList, &[Key::Char('x')]),
+    (Action::Goniometer, "goniometer", Mode::DeviceList, &[Key::Char('g')]),
+    (Action::Waveform, "waveform", Mode::DeviceList, &[Key::Char('W')]),
+    (Action::LevelMeters, "level_meters", Mode::DeviceList, &[Key::Char('l')]),
+    (Action::StereoSpectrum, "stereo_spectrum", Mode::DeviceList, &[Key::Char('G')]),
+    (Action::StreamHealth, "stream_health", Mode::DeviceList, &[Key::Char('i')]),
+    (Action::CommandStats, "command_stats", Mode::DeviceList, &[Key::Char('Q')]),
+    (Action::CancelCommands, "cancel_commands", Mode::DeviceList, &[Key::Char('U')]),
+    (Action::BinTable, "bin_table", Mode::DeviceList, &[Key::Char('t')]),
+    (Action::BinTableUp, "bin_table_up", Mode::DeviceList, &[Key::Ctrl('u')]),
+    (Action::BinTableDown, "bin_table_down", Mode::DeviceList, &[Key::Ctrl('d')]),
+    (Action::MaxHold, "max_hold", Mode::DeviceList, &[Key::Char('m')]),
+    (Action::ResetMaxHold, "reset_max_hold", Mode::DeviceList, &[Key::Char('M')]),
+    (Action::PeakHold, "peak_hold", Mode::DeviceList, &[Key::Char('^')]),
+    (Action::HideDevice, "hide_device", Mode::DeviceList, &[Key::Char('h')]),
+    (Action::UndoHide, "undo_hide", Mode::DeviceList, &[Key::Char('u')]),
+    (Action::ShowHidden, "show_hidden", Mode::DeviceList, &[Key::Char('H')]),
+    (Action::GroupByApp, "group_by_app", Mode::DeviceList, &[Key::Char('a')]),
+    (Action::ToggleGroup, "toggle_group", Mode::DeviceList, &[Key::Char('\n')]),
+    (Action::Favorite, "favorite", Mode::DeviceList, &[Key::Char('*')]),
+    (Action::CycleColor, "cycle_color", Mode::DeviceList, &[Key::Char('O')]),
+    (Action::JumpToDevice, "jump_to_device", Mode::DeviceList, &[Key::Char('/')]),
+    (Action::CalibrationDown, "calibration_down", Mode::DeviceList, &[Key::Char('_')]),
+    (Action::CalibrationUp, "calibration_up", Mode::DeviceList, &[Key::Char('+')]),
+    (Action::Crossfeed, "crossfeed", Mode::DeviceList, &[Key::Char('X')]),
+    (Action::CrossfeedAmountDown, "crossfeed_amount_down", Mode::DeviceList, &[Key::Char('(')]),
+    (Action::CrossfeedAmountUp, "crossfeed_amount_up", Mode::DeviceList, &[Key::Char(')')]),
+    (Action::CrossfeedCutoffDown, "crossfeed_cutoff_down", Mode::DeviceList, &[Key::Char('-')]),
+    (Action::CrossfeedCutoffUp, "crossfeed_cutoff_up", Mode::DeviceList, &[Key::Char('=')]),
+    (Action::SaturationDown, "saturation_down", Mode::DeviceList, &[Key::Char('z')]),
+    (Action::SaturationUp, "saturation_up", Mode::DeviceList, &[Key::Char('Z')]),
+    (Action::WidthDown, "width_down", Mode::DeviceList, &[Key::Char('\\')]),
+    (Action::WidthUp, "width_up", Mode::DeviceList, &[Key::Char('|')]),
+    (Action::FftSize, "fft_size", Mode::DeviceList, &[Key::Char('F')]),
+    (Action::VolumeDown, "volume_down", Mode::DeviceList, &[Key::Char(',')]),
+    (Action::VolumeUp, "volume_up", Mode::DeviceList, &[Key::Char('.')]),
+    (Action::BalanceLeft, "balance_left", Mode::DeviceList, &[Key::Alt(',')]),
+    (Action::BalanceRight, "balance_right", Mode::DeviceList, &[Key::Alt('.')]),
+    (Action::Help, "help", Mode::DeviceList, &[Key::Char('?')]),
+    (Action::EqQuit, "eq_quit", Mode::Eq, &[Key::Char('q'), Key::Ctrl('c')]),
+    (Action::EqBack, "eq_back", Mode::Eq, &[Key::Esc, Key::Char(' '), Key::Backspace]),
+    (Action::EqGainUp, "eq_gain_up", Mode::Eq, &[Key::Char('k')]),
+    (Action::EqGainDown, "eq_gain_down", Mode::Eq, &[Key::Char('j')]),
+    (Action::EqQDown, "eq_q_down", Mode::Eq, &[Key::Char('[')]),
+    (Action::EqQUp, "eq_q_up", Mode::Eq, &[Key::Char(']')]),
+    (Action::EqBandCount, "eq_band_count", Mode::Eq, &[Key::Char('n')]),
+    (Action::EqMute, "eq_mute", Mode::Eq, &[Key::Char('m')]),
+    (Action::EqSolo, "eq_solo", Mode::Eq, &[Key::Char('s')]),
+    (Action::EqBypass, "eq_bypass", Mode::Eq, &[Key::Char('b')]),
+    (Action::EqCompare, "eq_compare", Mode::Eq, &[Key::Char('p')]),
+    (Action::EqReset, "eq_reset", Mode::Eq, &[Key::Char('r')]),
+    (Action::EqDisable, "eq_disable", Mode::Eq, &[Key::Char('x')]),
+    (Action::EqPreviousBand, "eq_previous_band", Mode::Eq, &[Key::Char('h'), Key::Left]),
+    (Action::EqNextBand, "eq_next_band", Mode::Eq, &[Key::Char('l'), Key::Right]),
+];
+
+impl Action {
+    /// Actions on the selected device, which an application header can't take
+    pub fn needs_device(self) -> bool {
+        matches!(
+            self,
+            Action::OpenEq
+                | Action::ToggleViz
+                | Action::ToggleRecording
+                | Action::HideDevice
+                | Action::Favorite
+                | Action::VolumeDown
+                | Action::VolumeUp
+                | Action::BalanceLeft
+                | Action::BalanceRight
+                | Action::CopyPorts
+                | Action::ImportEq
+                | Action::SaveEqPreset
+                | Action::LoadEqPreset
+                | Action::FiltersEqBypass
+                | Action::Crossfeed
+                | Action::CrossfeedAmountDown
+                | Action::CrossfeedAmountUp
+                | Action::CrossfeedCutoffDown
+                | Action::CrossfeedCutoffUp
+                | Action::SaturationDown
+                | Action::SaturationUp
+                | Action::WidthDown
+                | Action::WidthUp
+                | Action::FftSize
+                | Action::DeleteVirtualDevice
+                | Action::DumpSpectrum
+                | Action::Link
+                | Action::CycleColor
+                | Action::CalibrationDown
+                | Action::CalibrationUp
+        )
+    }
+
+    fn entry(self) -> &'static (Action, &'static str, Mode, &'static [Key]) {
+        ACTIONS
+            .iter()
+            .find(|(action, ..)| *action == self)
+            .expect("every action has an entry in ACTIONS")
+    }
+}
+
+impl KeyBinding {
+    fn names(&self) -> &[String] {
+        match self {
+            KeyBinding::One(name) => std::slice::from_ref(name),
+            KeyBinding::Many(names) => names,
+        }
+    }
+}
+
+/// Parse a key name: a single character, a named key ("Space", "Enter", "Tab",
+/// "BackTab", "Esc", "Backspace", arrows, "Home", "End", "PageUp", "PageDown",
+/// "Delete", "Insert", "F1"-"F12") or "Ctrl-x"/"Alt-x"
+/// Named keys are case-insensitive; single characters are not
+pub fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c));
+    }
+
+    let single = |rest: &str| {
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+    let lower = name.to_ascii_lowercase();
+    if let Some(rest) = lower.strip_prefix("ctrl-") {
+        return single(rest).map(Key::Ctrl);
+    }
+    if lower.starts_with("alt-") {
+        return single(&name[4..]).map(Key::Alt);
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=12).contains(&n).then_some(Key::F(n));
+    }
+    let key = match lower.as_str() {
+        "space" => Key::Char(' '),
+        "enter" => Key::Char('\n'),
+        "tab" => Key::Char('\t'),
+        "backtab" | "shift-tab" => Key::BackTab,
+        "esc" | "escape" => Key::Esc,
+        "backspace" => Key::Backspace,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Short label of a key for the status bar
+pub fn key_label(key: Key) -> String {
+    match key {
+        Key::Char(' ') => String::from("Space"),
+        Key::Char('\n') => String::from("Enter"),
+        Key::Char('\t') => String::from("Tab"),
+        Key::Char(c) => c.to_string(),
+        Key::Ctrl(c) => format!("Ctrl-{}", c),
+        Key::Alt(c) => format!("Alt-{}", c),
+        Key::F(n) => format!("F{}", n),
+        Key::Up => String::from("↑"),
+        Key::Down => String::from("↓"),
+        Key::Left => String::from("←"),
+        Key::Right => String::from("→"),
+        Key::Backspace => String::from("⌫"),
+        Key::PageUp => String::from("PgUp"),
+        Key::PageDown => String::from("PgDn"),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Which action each key triggers, per mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Keys of every action, defaults merged with the config
+    bindings: HashMap<Action, Vec<Key>>,
+    /// Reverse lookup per mode
+    actions: HashMap<(Mode, Key), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keybindings]` config section
+    /// A configured action replaces its default keys. Unknown actions, unparsable
+    /// keys and keys claimed by two actions in the same mode are reported in the
+    /// returned warnings, and the affected actions keep their defaults
+    pub fn new(config: &BTreeMap<String, KeyBinding>) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut bindings: HashMap<Action, Vec<Key>> = ACTIONS
+            .iter()
+            .map(|&(action, _, _, keys)| (action, keys.to_vec()))
+            .collect();
+
+        let mut overridden = Vec::new();
+        for (name, binding) in config {
+            let Some(&(action, ..)) = ACTIONS.iter().find(|(_, n, ..)| n == name) else {
+                warnings.push(format!("unknown action '{}'", name));
+                continue;
+            };
+            let keys: Option<Vec<Key>> = binding.names().iter().map(|n| parse_key(n)).collect();
+            match keys {
+                Some(keys) if !keys.is_empty() => {
+                    bindings.insert(action, keys);
+                    overridden.push(action);
+                }
+                _ => warnings.push(format!(
+                    "invalid key in '{}' ({:?}), using the default",
+                    name,
+                    binding.names()
+                )),
+            }
+        }
+
+        // Defaults never collide, so reverting overrides until nothing collides terminates
+        loop {
+            let mut owners: HashMap<(Mode, Key), Vec<Action>> = HashMap::new();
+            for &(action, _, mode, _) in ACTIONS {
+                for &key in &bindings[&action] {
+                    owners.entry((mode, key)).or_default().push(action);
+                }
+            }
+            let clash = owners
+                .into_iter()
+                .filter(|(_, actions)| actions.len() > 1)
+                .find_map(|((_, key), actions)| {
+                    let reverted = *actions.iter().find(|a| overridden.contains(a))?;
+                    Some((key, actions, reverted))
+                });
+            let Some((key, actions, reverted)) = clash else {
+                break;
+            };
+
+            let names: Vec<&str> = actions.iter().map(|a| a.entry().1).collect();
+            warnings.push(format!(
+                "'{}' is bound to {}, using the default for '{}'",
+                key_label(key),
+                names.join(" and "),
+                reverted.entry().1
+            ));
+            bindings.insert(reverted, reverted.entry().3.to_vec());
+            overridden.retain(|&a| a != reverted);
+        }
+
+        let actions = ACTIONS
+            .iter()
+            .flat_map(|&(action, _, mode, _)| {
+                bindings[&action].iter().map(move |&key| ((mode, key), action))
+            })
+            .collect();
+        (Self { bindings, actions }, warnings)
+    }
+
+    /// Action a key triggers in `mode`
+    pub fn action(&self, mode: Mode, key: Key) -> Option<Action> {
+        self.actions.get(&(mode, key)).copied()
+    }
+
+    /// Every action of `mode` with its config name and all of its keys, for the help overlay
+    pub fn bindings(&self, mode: Mode) -> Vec<(Action, &'static str, String)> {
+        ACTIONS
+            .iter()
+            .filter(|&&(_, _, action_mode, _)| action_mode == mode)
+            .map(|&(action, name, ..)| {
+                let keys: Vec<String> = self.bindings[&action].iter().map(|&key| key_label(key)).collect();
+                (action, name, keys.join(" "))
+            })
+            .collect()
+    }
+
+    /// Status bar label for a group of actions: the first key of each, joined by '/'
+    pub fn label(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .filter_map(|action| self.bindings.get(action)?.first())
+            .map(|&key| key_label(key))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(entries: &[(&str, KeyBinding)]) -> BTreeMap<String, KeyBinding> {
+        entries
+            .iter()
+            .map(|(name, binding)| (name.to_string(), binding.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let (keymap, warnings) = Keymap::new(&BTreeMap::new());
+        assert!(warnings.is_empty());
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('k')), Some(Action::Up));
+        assert_eq!(keymap.action(Mode::Eq, Key::Char('k')), Some(Action::EqGainUp));
+        assert_eq!(keymap.action(Mode::Eq, Key::Left), Some(Action::EqPreviousBand));
+        assert_eq!(keymap.action(Mode::DeviceList, Key::F(1)), None);
+        assert_eq!(keymap.label(&[Action::Up, Action::Down]), "↑/↓");
+    }
+
+    #[test]
+    fn test_bindings_list_every_action_of_a_mode() {
+        let (keymap, _) = Keymap::new(&config(&[("help", KeyBinding::One(String::from("F1")))]));
+        let device_list = keymap.bindings(Mode::DeviceList);
+        let eq = keymap.bindings(Mode::Eq);
+        assert_eq!(device_list.len() + eq.len(), ACTIONS.len());
+        assert!(device_list.contains(&(Action::Help, "help", String::from("F1"))));
+        assert!(device_list.contains(&(Action::Quit, "quit", String::from("q Esc Ctrl-c"))));
+        assert!(eq.iter().all(|(action, ..)| action.entry().2 == Mode::Eq));
+    }
+
+    #[test]
+    fn test_parse_key_names() {
+        assert_eq!(parse_key("v"), Some(Key::Char('v')));
+        assert_eq!(parse_key("Space"), Some(Key::Char(' ')));
+        assert_eq!(parse_key("ctrl-x"), Some(Key::Ctrl('x')));
+        assert_eq!(parse_key("Alt-X"), Some(Key::Alt('X')));
+        assert_eq!(parse_key("PageDown"), Some(Key::PageDown));
+        assert_eq!(parse_key("F12"), Some(Key::F(12)));
+        assert_eq!(parse_key("F13"), None);
+        assert_eq!(parse_key("Ctrl-"), None);
+        assert_eq!(parse_key(""), None);
+    }
+
+    #[test]
+    fn test_override_replaces_default_keys() {
+        let (keymap, warnings) = Keymap::new(&config(&[
+            ("toggle_viz", KeyBinding::One(String::from("V"))),
+            ("up", KeyBinding::Many(vec![String::from("Up"), String::from("Ctrl-p")])),
+        ]));
+        assert!(warnings.is_empty());
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('V')), Some(Action::ToggleViz));
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char(' ')), None);
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Ctrl('p')), Some(Action::Up));
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('k')), None);
+    }
+
+    #[test]
+    fn test_bad_bindings_fall_back_to_defaults() {
+        let (keymap, warnings) = Keymap::new(&config(&[
+            ("no_such_action", KeyBinding::One(String::from("z"))),
+            ("refresh", KeyBinding::One(String::from("Hyper-r"))),
+            // 'h' is hide_device's default, so cycle_focus keeps 'v'
+            ("cycle_focus", KeyBinding::One(String::from("h"))),
+            // Same key in different modes is fine
+            ("eq_mute", KeyBinding::One(String::from("v"))),
+        ]));
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('r')), Some(Action::Refresh));
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('h')), Some(Action::HideDevice));
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('v')), Some(Action::CycleFocus));
+        assert_eq!(keymap.action(Mode::Eq, Key::Char('v')), Some(Action::EqMute));
+
+        // Swapping two keys is not a clash
+        let (keymap, warnings) = Keymap::new(&config(&[
+            ("up", KeyBinding::One(String::from("j"))),
+            ("down", KeyBinding::One(String::from("k"))),
+        ]));
+        assert!(warnings.is_empty());
+        assert_eq!(keymap.action(Mode::DeviceList, Key::Char('j')), Some(Action::Up));
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+/// Lowest level the meters show (dBFS)
+pub const FLOOR_DB: f32 = -60.0;
+/// Levels above which the bar turns yellow, then red (dBFS)
+const HOT_DB: f32 = -12.0;
+const CLIPPING_DB: f32 = -3.0;
+/// How long the held peak marker stays up before falling back to the current peak
+const PEAK_HOLD: Duration = Duration::from_millis(1500);
+
+/// Latest peak and RMS level of a device, plus its held peak
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    pub peak_db: f32,
+    pub rms_db: f32,
+    pub held_peak_db: f32,
+    held_at: Instant,
+}
+
+impl Levels {
+    pub fn new(peak_db: f32, rms_db: f32, now: Instant) -> Self {
+        Self {
+            peak_db,
+            rms_db,
+            held_peak_db: peak_db,
+            held_at: now,
+        }
+    }
+
+    /// Take a new reading; the held peak rises at once and falls back after PEAK_HOLD
+    pub fn update(&mut self, peak_db: f32, rms_db: f32, now: Instant) {
+        self.peak_db = peak_db;
+        self.rms_db = rms_db;
+        if peak_db >= self.held_peak_db || now.duration_since(self.held_at) >= PEAK_HOLD {
+            self.held_peak_db = peak_db;
+            self.held_at = now;
+        }
+    }
+}
+
+/// Meter zone a level falls in (green, yellow, red)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Safe,
+    Hot,
+    Clipping,
+}
+
+pub fn zone(level_db: f32) -> Zone {
+    if level_db >= CLIPPING_DB {
+        Zone::Clipping
+    } else if level_db >= HOT_DB {
+        Zone::Hot
+    } else {
+        Zone::Safe
+    }
+}
+
+/// Cells of a `width` wide bar lit for `level_db` (none for silence or NaN)
+pub fn filled_cells(level_db: f32, width: usize) -> usize {
+    if level_db.is_nan() || level_db <= FLOOR_DB {
+        return 0;
+    }
+    let fraction = ((level_db - FLOOR_DB) / -FLOOR_DB).min(1.0);
+    (fraction * width as f32).round() as usize
+}
+
+/// Level a bar reaches once `cell` is lit, for coloring cells by their zone
+pub fn cell_level_db(cell: usize, width: usize) -> f32 {
+    FLOOR_DB - FLOOR_DB * (cell + 1) as f32 / width.max(1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_scale() {
+        assert_eq!(filled_cells(0.0, 20), 20);
+        assert_eq!(filled_cells(6.0, 20), 20);
+        assert_eq!(filled_cells(-30.0, 20), 10);
+        assert_eq!(filled_cells(FLOOR_DB, 20), 0);
+        assert_eq!(filled_cells(f32::NEG_INFINITY, 20), 0);
+        assert_eq!(filled_cells(f32::NAN, 20), 0);
+
+        // The last cell is the 0 dBFS one
+        assert_eq!(cell_level_db(19, 20), 0.0);
+        assert_eq!(zone(cell_level_db(19, 20)), Zone::Clipping);
+        assert_eq!(zone(cell_level_db(16, 20)), Zone::Hot);
+        assert_eq!(zone(cell_level_db(9, 20)), Zone::Safe);
+    }
+
+    #[test]
+    fn test_peak_hold() {
+        let start = Instant::now();
+        let mut levels = Levels::new(-6.0, -12.0, start);
+
+        levels.update(-20.0, -25.0, start + Duration::from_millis(500));
+        assert_eq!(levels.peak_db, -20.0);
+        assert_eq!(levels.held_peak_db, -6.0);
+
+        // A higher peak is held at once; a lower one only once the hold runs out
+        levels.update(-3.0, -10.0, start + Duration::from_millis(600));
+        assert_eq!(levels.held_peak_db, -3.0);
+        levels.update(-20.0, -25.0, start + Duration::from_millis(2000));
+        assert_eq!(levels.held_peak_db, -3.0);
+        levels.update(-20.0, -25.0, start + Duration::from_millis(2100));
+        assert_eq!(levels.held_peak_db, -20.0);
+    }
+}
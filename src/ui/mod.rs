@@ -4,25 +4,199 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Tabs},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Tabs, Wrap},
 };
 use termion::event::Key;
 
-use crate::audio::{AudioCommand, DeviceId, SpectrumData};
-use crate::audio::{AudioEngine, AudioEvent, DeviceInfo, EqSettings, VolumeSettings};
-use std::collections::{HashMap, HashSet};
+mod bin_table;
+mod clipboard;
+mod colors;
+mod envelope;
+mod eq_curve;
+mod glyphs;
+mod goniometer;
+mod groups;
+mod keymap;
+mod level_meter;
+mod pending;
+mod routing;
+mod waveform;
+mod zoom;
+
+use clipboard::Clipboard;
+use envelope::{Envelope, SmoothedBins};
+use groups::{build_rows, display_order, scroll_offset, DeviceRow};
+pub use glyphs::Glyphs;
+use keymap::{Action, Mode};
+use level_meter::Levels;
+pub use keymap::Keymap;
+pub use pending::visualization_port;
+use pending::{PendingOutcome, PendingVisualizations};
+use routing::RoutingRow;
+use zoom::{FrequencyZoom, ZoomEdge};
+
+use crate::audio::{AudioCommand, CommandError, DeviceId, DeviceType, PortDirection, SpectrumData, StreamHealth, MAX_REFRESH_RATE_HZ};
+use crate::audio::{DEFAULT_FFT_SIZE, FFT_SIZE_RANGE};
+use crate::audio::{AudioEngine, AudioEvent, CommandCounts, DeviceInfo, EqSettings, GraphClock, VolumeSettings};
+use crate::audio::{clamp_saturation_drive, clamp_stereo_width, import_parametric_eq, bandwidth_octaves, clamp_eq_gain, clamp_eq_q, CrossfeedSettings, EqBandParams, EqPreset, PairingStrategy, EQ_BAND_COUNTS, GRAPHIC_EQ_BANDS, MAX_EQ_GAIN_DB};
+use crate::config::{validate_preset_name, validate_profile_name, AmplitudeTransform, DeviceColor, GlyphMode, SpectrumAggregation, DEFAULT_PROFILE};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Minimum terminal height for full layout (with device list and tabs)
 /// Below this threshold, only spectrum is displayed
 const MIN_HEIGHT_FOR_FULL_LAYOUT: u16 = 24;
 
+/// Smallest terminal the minimal (spectrum-only) layout can draw bars in
+/// Below this a "terminal too small" message is shown instead
+const MIN_TERMINAL_WIDTH: u16 = 20;
+const MIN_TERMINAL_HEIGHT: u16 = 3;
+
 /// Height reserved for the spectrum visualization in full layout
 const SPECTRUM_HEIGHT: u16 = 24;
 
 /// Width of the left navigation panel (device list)
 const DEVICE_LIST_WIDTH: u16 = 30;
 
+/// Number of recent status messages kept for support bundles
+const STATUS_HISTORY_LEN: usize = 50;
+
+/// Number of recently hidden devices remembered for undo
+const HIDE_UNDO_DEPTH: usize = 10;
+
+/// Maximum width of a spectrum bar in terminal columns
+const MAX_BAR_WIDTH: usize = 3;
+
+/// Range and step of the spectrum gamma (exponent on normalized bar height)
+const MIN_SPECTRUM_GAMMA: f32 = 0.3;
+const MAX_SPECTRUM_GAMMA: f32 = 3.0;
+const SPECTRUM_GAMMA_STEP: f32 = 0.1;
+
+/// Bar attack time range and step (ms; 0 = bars jump straight up)
+const MAX_BAR_ATTACK_MS: u64 = 500;
+const BAR_ATTACK_STEP_MS: u64 = 5;
+/// Bar release time range and step (ms; 0 = bars drop straight down)
+const MAX_BAR_RELEASE_MS: u64 = 3000;
+const BAR_RELEASE_STEP_MS: u64 = 50;
+
+/// Spectrum refresh rates cycled with 'f' (Hz)
+const REFRESH_RATES: [u32; 3] = [15, 30, 60];
+
+/// Difference mode clamps A − B to ±this many dB
+const DIFFERENCE_RANGE_DB: f32 = 24.0;
+
+/// Lowest level shown by the spectrum (and the reset level of max hold)
+const SPECTRUM_FLOOR_DB: f32 = -60.0;
+
+/// Sample rate assumed for EQ calculations until a device reports its own
+const DEFAULT_SAMPLE_RATE: f32 = 48000.0;
+
+/// A stream whose loudest bin stays below this is considered silent
+const SILENCE_THRESHOLD_DB: f32 = -50.0;
+
+/// Columns taken by one band in the Filters tab when bands are laid out side by side
+const EQ_BAND_CELL_WIDTH: usize = 22;
+
+/// Balance change per key press (-1.0 is left only, 1.0 right only)
+const BALANCE_STEP: f32 = 0.1;
+
+/// Width of the level meter column inside its border
+const LEVEL_METER_WIDTH: u16 = 32;
+
+/// Ratio a band's Q changes by per key press
+const EQ_Q_STEP: f32 = 1.25;
+
+/// The EQ response overlay spans ±this many dB (the band gain limit)
+const EQ_CURVE_RANGE_DB: f32 = MAX_EQ_GAIN_DB;
+
+/// Saturation drive change per key press (drive runs 0.0 to 1.0)
+const SATURATION_STEP: f32 = 0.1;
+
+/// Stereo width change per key press (width runs 0.0 to 2.0, 1.0 = unchanged)
+const WIDTH_STEP: f32 = 0.1;
+
+/// Crossfeed amount change per key press (amount runs 0.0 to 1.0)
+const CROSSFEED_AMOUNT_STEP: f32 = 0.05;
+
+/// Crossfeed cutoff change per key press (Hz)
+const CROSSFEED_CUTOFF_STEP: f32 = 100.0;
+
+/// Level calibration change per key press (dB)
+const CALIBRATION_STEP_DB: f32 = 1.0;
+
+/// Largest level calibration offset either way (dB); enough to map dBFS to dB SPL
+const MAX_CALIBRATION_DB: f32 = 150.0;
+
+/// Bars start falling once a device has sent no frame for this long
+const STALE_SPECTRUM_AFTER: Duration = Duration::from_secs(1);
+
+/// How long the CLIP indicator stays lit after a device's last clip
+const CLIP_HOLD: Duration = Duration::from_secs(2);
+
+/// How far held peaks fall per spectrum frame (dB)
+const PEAK_HOLD_DECAY_DB: f32 = 0.5;
+
+/// Raise held levels to any louder bins (a new bin layout starts over)
+fn hold_max(held: &mut Vec<f32>, bins: &[f32]) {
+    if held.len() != bins.len() {
+        *held = bins.to_vec();
+        return;
+    }
+    for (held, &bin) in held.iter_mut().zip(bins) {
+        *held = held.max(bin);
+    }
+}
+
+/// Raise held peaks to any louder bins and let the rest fall by `decay_db`
+/// (a new bin layout starts over)
+fn hold_peak(held: &mut Vec<f32>, bins: &[f32], decay_db: f32) {
+    if held.len() != bins.len() {
+        *held = bins.to_vec();
+        return;
+    }
+    for (held, &bin) in held.iter_mut().zip(bins) {
+        *held = bin.max((*held - decay_db).max(SPECTRUM_FLOOR_DB));
+    }
+}
+
+/// Held levels marked on one spectrum bar (display values, None when not held)
+#[derive(Debug, Clone, Copy, Default)]
+struct BarMarks {
+    max_hold: Option<u64>,
+    peak: Option<u64>,
+}
+
+/// One column of the spectrum: live bar value, its color and the held levels above it
+#[derive(Debug, Clone, Copy)]
+struct BarColumn {
+    value: u64,
+    style: Style,
+    marks: BarMarks,
+}
+
+/// One line per bin pairing its center frequency with its level, under a header
+fn spectrum_table(data: &SpectrumData, calibration_db: f32) -> Vec<String> {
+    let mut lines = vec![bin_table::header(bin_table::FULL_WIDTH)];
+    lines.extend(bin_table::rows(data, 0, data.bins.len(), bin_table::FULL_WIDTH, calibration_db));
+    lines
+}
+
+/// What the EQ preset prompt does with the typed name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EqPresetPrompt {
+    Save,
+    Load,
+}
+
+/// A saved EQ preset to write or read, handled by the main loop (which owns the config)
+#[derive(Debug, Clone, PartialEq)]
+pub enum EqPresetRequest {
+    Save { name: String, settings: EqSettings },
+    Load { device_id: DeviceId, name: String },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeviceTab {
     Routing,
@@ -56,6 +230,173 @@ impl DeviceTab {
     }
 }
 
+impl SpectrumAggregation {
+    /// Merge a slice of bin magnitudes (dB) into a single display magnitude (dB)
+    /// Returns the -60 dB floor for an empty slice
+    pub fn aggregate(&self, bins: &[f32]) -> f32 {
+        if bins.is_empty() {
+            return -60.0;
+        }
+
+        match self {
+            SpectrumAggregation::Max => bins.iter().copied().fold(-60.0, f32::max),
+            SpectrumAggregation::Mean => bins.iter().sum::<f32>() / bins.len() as f32,
+            SpectrumAggregation::Rms => {
+                let mean_power = bins
+                    .iter()
+                    .map(|db| 10f32.powf(db / 10.0))
+                    .sum::<f32>()
+                    / bins.len() as f32;
+                (10.0 * mean_power.log10()).max(-60.0)
+            }
+        }
+    }
+}
+
+/// Level the perceptual curve expands around (the middle of the displayed range)
+const PERCEPTUAL_REFERENCE_DB: f32 = -30.0;
+/// Curve steepness per unit of amplification
+const PERCEPTUAL_STEEPNESS: f32 = 1.5;
+
+impl AmplitudeTransform {
+    /// Map a level (dB) to a bar value from 0 (floor) to 60 (full scale)
+    pub fn apply(&self, level_db: f32, amplification: f32) -> f32 {
+        let range = -SPECTRUM_FLOOR_DB;
+        // A NaN level would otherwise come out of `min` below as full scale
+        if level_db.is_nan() {
+            return 0.0;
+        }
+        let normalized = (level_db - SPECTRUM_FLOOR_DB).clamp(0.0, range);
+        match self {
+            AmplitudeTransform::Linear => (normalized * amplification).min(range),
+            AmplitudeTransform::Perceptual => {
+                let steepness = amplification * PERCEPTUAL_STEEPNESS;
+                let reference = (PERCEPTUAL_REFERENCE_DB - SPECTRUM_FLOOR_DB) / range;
+                let curve = |x: f32| (steepness * (x - reference)).tanh();
+                let (low, high) = (curve(0.0), curve(1.0));
+                if high - low <= f32::EPSILON {
+                    // No amplification left to shape with: fall back to a straight line
+                    return normalized;
+                }
+                (curve(normalized / range) - low) / (high - low) * range
+            }
+        }
+    }
+
+    /// Short name shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            AmplitudeTransform::Linear => "linear",
+            AmplitudeTransform::Perceptual => "perceptual",
+        }
+    }
+}
+
+/// Bar height for a transformed level, kept within 0-60 before the cast
+/// A bad amplification or FFT frame can make the level NaN or out of range;
+/// NaN draws an empty bar instead of whatever the cast would make of it
+fn bar_value(value: f32) -> u64 {
+    if value.is_nan() {
+        return 0;
+    }
+    value.clamp(0.0, -SPECTRUM_FLOOR_DB) as u64
+}
+
+/// Balance as shown to the user: "center", or the side favored and by how much
+fn balance_label(balance: f32) -> String {
+    if balance == 0.0 {
+        String::from("center")
+    } else {
+        format!("{} {:.0}%", if balance < 0.0 { "L" } else { "R" }, balance.abs() * 100.0)
+    }
+}
+
+/// Add one device's braille dot plot to the combined cells
+/// Dots are merged; where devices share a cell, the later one's color wins
+fn overlay_dots(cells: &mut [Vec<(u8, Color)>], plotted: &[Vec<u8>], color: Color) {
+    for (row, plotted_row) in cells.iter_mut().zip(plotted) {
+        for (cell, &bits) in row.iter_mut().zip(plotted_row) {
+            if bits != 0 {
+                *cell = (cell.0 | bits, color);
+            }
+        }
+    }
+}
+
+/// Which device-list actions currently have an effect (drives the status bar hints)
+struct AvailableActions {
+    cancel_commands: bool,
+    scroll_bin_table: bool,
+    goniometer: bool,
+    eq: bool,
+    visualize: bool,
+    pause_all: bool,
+    record: bool,
+    volume: bool,
+    crossfeed: bool,
+    saturation: bool,
+    width: bool,
+    difference: bool,
+    focus: bool,
+    disconnect: bool,
+    link: bool,
+    hide: bool,
+    favorite: bool,
+    color: bool,
+    copy_ports: bool,
+    apply_eq: bool,
+    /// A/B bypass from the Filters tab, when the EQ device has EQ enabled
+    eq_bypass: bool,
+    dump_spectrum: bool,
+    undo_hide: bool,
+    expand: bool,
+    /// Enter patches ports in the Routing tab unless it expands a group
+    patch: bool,
+    delete_virtual: bool,
+}
+
+impl AvailableActions {
+    /// Whether `action` would do anything; actions not tracked here always do
+    fn allows(&self, action: Action) -> bool {
+        match action {
+            Action::OpenEq | Action::ImportEq => self.eq,
+            Action::ApplyEqToAll => self.apply_eq,
+            Action::FiltersEqBypass => self.eq_bypass,
+            Action::ToggleViz => self.visualize,
+            Action::PauseAll => self.pause_all,
+            Action::ToggleRecording | Action::FftSize => self.record,
+            Action::CopyPorts => self.copy_ports,
+            Action::DumpSpectrum => self.dump_spectrum,
+            Action::VolumeDown | Action::VolumeUp | Action::BalanceLeft | Action::BalanceRight => {
+                self.volume
+            }
+            Action::Crossfeed
+            | Action::CrossfeedAmountDown
+            | Action::CrossfeedAmountUp
+            | Action::CrossfeedCutoffDown
+            | Action::CrossfeedCutoffUp => self.crossfeed,
+            Action::SaturationDown | Action::SaturationUp => self.saturation,
+            Action::WidthDown | Action::WidthUp => self.width,
+            Action::DifferenceMode => self.difference,
+            Action::CycleFocus => self.focus,
+            Action::NextConnection | Action::PreviousConnection | Action::Disconnect => {
+                self.disconnect
+            }
+            Action::Link => self.link,
+            Action::HideDevice => self.hide,
+            Action::Favorite => self.favorite,
+            Action::CycleColor | Action::CalibrationDown | Action::CalibrationUp => self.color,
+            Action::UndoHide => self.undo_hide,
+            Action::ToggleGroup => self.expand || self.patch,
+            Action::DeleteVirtualDevice => self.delete_virtual,
+            Action::CancelCommands => self.cancel_commands,
+            Action::BinTableUp | Action::BinTableDown => self.scroll_bin_table,
+            Action::Goniometer | Action::StereoSpectrum => self.goniometer,
+            _ => true,
+        }
+    }
+}
+
 /// Focus mode for UI interaction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum FocusMode {
@@ -70,33 +411,209 @@ pub struct App {
     current_tab: DeviceTab,
     devices: Vec<DeviceInfo>,
     selected_device: usize,
+    /// First device list row on screen
+    device_list_scroll: usize,
+    /// Device list rows that fit in the panel when last drawn (one PageUp/PageDown)
+    device_list_page: usize,
+    /// Ports of the selected device and their connections, shown in the Routing tab
+    connections: Vec<RoutingRow>,
+    /// Device the connections were read for
+    connections_device: Option<DeviceId>,
+    /// Selected row in the Routing tab
+    selected_connection: usize,
+    /// Device whose outputs the next link starts from (Routing tab)
+    link_source: Option<DeviceId>,
+    /// Port the next patch starts from (Routing tab), with its direction
+    patch_source: Option<(String, PortDirection)>,
+    /// How ports are paired when linking two devices
+    pairing_strategy: PairingStrategy,
     status_message: String,
+    /// Recent distinct status messages, oldest first
+    status_history: VecDeque<String>,
     /// Devices currently being visualized
     visualized_devices: HashSet<DeviceId>,
     /// Latest spectrum data per device
-    spectrum_data: HashMap<DeviceId, SpectrumData>,
+    spectrum_data: HashMap<DeviceId, Arc<SpectrumData>>,
+    /// Devices whose EQ output is captured and drawn next to their monitor ("pre"/"post")
+    eq_taps: HashSet<DeviceId>,
+    /// Latest spectrum of each tapped EQ output
+    eq_tap_spectrum: HashMap<DeviceId, Arc<SpectrumData>>,
+    /// Devices that were visualized before pausing all visualization (for resume)
+    paused_visualizations: Vec<DeviceId>,
+    /// System clipboard handle (kept alive so copied text stays available)
+    clipboard: Clipboard,
+    /// Latest capture stream health report per device
+    stream_health: HashMap<DeviceId, StreamHealth>,
+    /// FFT size per visualized device, once changed from the default
+    fft_sizes: HashMap<DeviceId, usize>,
+    /// Whether the stream health overlay is shown
+    show_health: bool,
+    /// PipeWire quantum and sample rate, read from the engine each frame
+    graph_clock: GraphClock,
+    /// Whether the command tally overlay is shown
+    show_commands: bool,
+    /// Whether the key binding overlay is shown
+    show_help: bool,
+    /// Whether the first-run overlay has been dismissed (persisted; shown until then)
+    first_run_seen: bool,
+    /// Per-variant command tally, read from the engine each frame while shown
+    command_stats: Vec<(&'static str, CommandCounts)>,
+    /// Commands in the channel and held back behind it
+    queued_commands: (usize, usize),
+    /// Key → action lookup, defaults merged with the `[keybindings]` config section
+    keymap: Keymap,
     /// Timestamp of last visualization change (for debouncing)
     last_viz_change: Option<Instant>,
     /// Dirty flag indicating unsaved changes
     config_dirty: bool,
+    /// Debounce before auto-saving changes (None = only save on exit)
+    auto_save_delay: Option<Duration>,
     /// Spectrum amplification factor
     pub spectrum_amplification: f32,
+    /// How FFT bins are merged into display groups
+    spectrum_aggregation: SpectrumAggregation,
+    /// How levels are turned into bar heights before gamma is applied
+    amplitude_transform: AmplitudeTransform,
+    /// Width of each spectrum bar in terminal columns (1-3)
+    bar_width: usize,
+    /// Exponent applied to normalized bar heights (<1 lifts quiet detail, >1 compresses peaks)
+    spectrum_gamma: f32,
+    /// Time constants (ms) of the bars rising and falling
+    bar_attack_ms: u64,
+    bar_release_ms: u64,
+    /// Levels drawn per device, following the spectra through the attack/release envelope
+    smoothed_bins: HashMap<DeviceId, SmoothedBins>,
+    /// Configured spectrum character set
+    glyph_mode: GlyphMode,
+    /// Character set resolved from the mode and the terminal
+    glyphs: Glyphs,
+    /// Displayed frequency window of the spectrum
+    frequency_zoom: FrequencyZoom,
+    /// Spectrum refresh rate in Hz
+    refresh_rate_hz: u32,
+    /// Show A − B difference of two visualized devices instead of overlaying them
+    difference_mode: bool,
+    /// Single visualized device shown in the spectrum (None = all devices combined)
+    spectrum_focus: Option<DeviceId>,
+    /// Capture streams keep left/right channels separate
+    stereo_capture: bool,
+    /// Show the goniometer next to the spectrum (requires stereo capture)
+    goniometer: bool,
+    /// Draw the left and right channels' spectra separately (requires stereo capture)
+    stereo_spectrum: bool,
+    /// Show a table of the selected device's bins next to the spectrum
+    show_bin_table: bool,
+    /// First bin shown in the bin table
+    bin_table_scroll: usize,
+    /// Latest L/R frames per device for the goniometer
+    stereo_frames: HashMap<DeviceId, Vec<[f32; 2]>>,
+    /// Draw the waveform instead of the spectrum
+    waveform_view: bool,
+    /// Latest mono samples per device for the waveform view
+    waveforms: HashMap<DeviceId, Vec<f32>>,
+    /// Show peak/RMS level meters next to the spectrum
+    level_meters: bool,
+    /// Latest levels per device for the level meters
+    levels: HashMap<DeviceId, Levels>,
+    /// When each device last clipped
+    last_clip: HashMap<DeviceId, Instant>,
+    /// Track and draw the highest level each bin has reached (no decay)
+    max_hold: bool,
+    /// Highest level per bin per device since max hold was turned on or reset
+    max_hold_bins: HashMap<DeviceId, Vec<f32>>,
+    /// Track and draw recent peaks, falling by `PEAK_HOLD_DECAY_DB` per frame
+    peak_hold: bool,
+    /// Held peak per bin per device
+    peak_hold_bins: HashMap<DeviceId, Vec<f32>>,
     /// Current focus mode
     focus_mode: FocusMode,
-    /// Selected filter option (0 = equalizer)
-    selected_filter: usize,
     /// Selected EQ band (0-9) when in SpectrumEq mode
     selected_eq_band: usize,
+    /// Pin the EQ editor to one device instead of following the selection
+    eq_pinned: bool,
+    /// Device the EQ editor is pinned to (set when entering the Filters tab or EQ mode)
+    eq_pinned_device: Option<DeviceId>,
     /// Devices with EQ enabled
     eq_enabled_devices: HashSet<DeviceId>,
     /// Current EQ settings per device
     eq_settings: HashMap<DeviceId, EqSettings>,
+    /// Path typed into the EQ import prompt (Some while the prompt is open)
+    eq_import_path: Option<String>,
+    /// Import report to show again once the audio thread confirms the settings
+    eq_import_report: Option<(DeviceId, String)>,
+    /// EQ preset prompt and the name typed into it (Some while the prompt is open)
+    eq_preset_prompt: Option<(EqPresetPrompt, String)>,
+    /// Names of the EQ presets saved on disk
+    saved_eq_presets: Vec<String>,
+    /// Preset to save or load, picked up by the main loop
+    eq_preset_request: Option<EqPresetRequest>,
     /// Current volume settings per device
     volume_settings: HashMap<DeviceId, VolumeSettings>,
+    /// Crossfeed settings by device name (persisted, reapplied when EQ starts)
+    crossfeed_settings: HashMap<String, CrossfeedSettings>,
+    /// Saturation drive by device name (persisted, reapplied when EQ starts)
+    saturation_drive: HashMap<String, f32>,
+    /// Stereo width by device name, only when not 1.0 (persisted, reapplied when EQ starts)
+    stereo_width: HashMap<String, f32>,
     /// Device names that are hidden from the device list
     hidden_devices: HashSet<String>,
     /// Whether to show hidden devices (greyed out)
     show_hidden: bool,
+    /// Streams hidden for being silent; not saved, and unhidden when they play again
+    auto_hidden: HashSet<DeviceId>,
+    /// When each stream last produced sound: from its spectrum while visualized,
+    /// otherwise from its PipeWire node state
+    last_active: HashMap<DeviceId, Instant>,
+    /// Stream nodes PipeWire reports as running
+    running_streams: HashSet<DeviceId>,
+    /// Silence before a stream is auto-hidden (None = never)
+    auto_hide_after: Option<Duration>,
+    /// Spectrum frames are applied at most once per this window (zero = as they arrive)
+    spectrum_batch_window: Duration,
+    /// Latest spectrum frame per device waiting for the batching window to pass
+    pending_spectrum: HashMap<DeviceId, Arc<SpectrumData>>,
+    /// When batched spectrum frames were last applied
+    last_spectrum_flush: Instant,
+    /// Device names pinned to the top of the device list
+    favorite_devices: HashSet<String>,
+    /// Spectrum colors assigned by device name
+    device_colors: HashMap<String, DeviceColor>,
+    /// dB added to level readouts by device name, to match a reference meter
+    /// (display only; the bars keep their dBFS scale)
+    calibration_db: HashMap<String, f32>,
+    /// Recently hidden device names, most recent last (for undo)
+    recently_hidden: Vec<String>,
+    /// Nest stream nodes under their application in the device list
+    group_by_application: bool,
+    /// Applications whose group is collapsed
+    collapsed_groups: HashSet<String>,
+    /// Application header under the cursor (None when a device is selected)
+    selected_group: Option<String>,
+    /// Devices currently being recorded, with recording start time
+    recording_devices: HashMap<DeviceId, Instant>,
+    /// Visualization requests waiting for the device's ports to appear
+    pending_visualizations: PendingVisualizations,
+    /// Devices (by name) that were visualized when the PipeWire connection dropped;
+    /// device IDs change on reconnect, so they are matched by name as devices reappear
+    reconnect_visualizations: Vec<String>,
+    /// The PipeWire connection is down and being re-established
+    disconnected: bool,
+    /// Config profile currently loaded
+    profile: String,
+    /// Profiles with a config file, listed in the profile prompt
+    available_profiles: Vec<String>,
+    /// Name typed into the profile prompt (Some while the prompt is open)
+    profile_prompt: Option<String>,
+    /// PipeWire object ID being typed to visualize a node directly (None = prompt closed)
+    node_prompt: Option<String>,
+    /// Type-to-jump is on: letters and digits select the next device starting with them
+    jump_mode: bool,
+    /// Node requested by ID before the registry reported it, with the time of the request
+    pending_node: Option<(u32, Instant)>,
+    /// Profile to switch to, picked up by the main loop
+    requested_profile: Option<String>,
+    /// Support bundle requested, written by the main loop
+    support_bundle_requested: bool,
 }
 
 impl App {
@@ -106,89 +623,230 @@ impl App {
             current_tab: DeviceTab::Routing,
             devices: Vec::new(),
             selected_device: 0,
+            device_list_scroll: 0,
+            device_list_page: 1,
+            connections: Vec::new(),
+            connections_device: None,
+            selected_connection: 0,
+            link_source: None,
+            patch_source: None,
+            pairing_strategy: PairingStrategy::default(),
             status_message: String::from("Starting up..."),
+            status_history: VecDeque::new(),
             visualized_devices: HashSet::new(),
             spectrum_data: HashMap::new(),
+            eq_taps: HashSet::new(),
+            eq_tap_spectrum: HashMap::new(),
+            paused_visualizations: Vec::new(),
+            clipboard: Clipboard::default(),
+            stream_health: HashMap::new(),
+            fft_sizes: HashMap::new(),
+            show_health: false,
+            graph_clock: GraphClock::default(),
+            show_commands: false,
+            show_help: false,
+            first_run_seen: true,
+            command_stats: Vec::new(),
+            queued_commands: (0, 0),
+            keymap: Keymap::default(),
             last_viz_change: None,
             config_dirty: false,
+            auto_save_delay: Some(Duration::from_secs(2)),
             spectrum_amplification,
+            spectrum_aggregation: SpectrumAggregation::default(),
+            amplitude_transform: AmplitudeTransform::default(),
+            bar_width: 1,
+            spectrum_gamma: 1.0,
+            bar_attack_ms: 10,
+            bar_release_ms: 300,
+            smoothed_bins: HashMap::new(),
+            glyph_mode: GlyphMode::Auto,
+            glyphs: Glyphs::Braille,
+            frequency_zoom: FrequencyZoom::default(),
+            refresh_rate_hz: 30,
+            difference_mode: false,
+            spectrum_focus: None,
+            stereo_capture: false,
+            goniometer: false,
+            stereo_spectrum: false,
+            show_bin_table: false,
+            bin_table_scroll: 0,
+            stereo_frames: HashMap::new(),
+            waveform_view: false,
+            waveforms: HashMap::new(),
+            level_meters: false,
+            levels: HashMap::new(),
+            last_clip: HashMap::new(),
+            max_hold: false,
+            max_hold_bins: HashMap::new(),
+            peak_hold: false,
+            peak_hold_bins: HashMap::new(),
             focus_mode: FocusMode::DeviceList,
-            selected_filter: 0,
             selected_eq_band: 0,
+            eq_pinned: false,
+            eq_pinned_device: None,
             eq_enabled_devices: HashSet::new(),
             eq_settings: HashMap::new(),
+            eq_import_path: None,
+            eq_import_report: None,
+            eq_preset_prompt: None,
+            saved_eq_presets: Vec::new(),
+            eq_preset_request: None,
             volume_settings: HashMap::new(),
+            crossfeed_settings: HashMap::new(),
+            saturation_drive: HashMap::new(),
+            stereo_width: HashMap::new(),
             hidden_devices: HashSet::new(),
+            favorite_devices: HashSet::new(),
+            device_colors: HashMap::new(),
+            calibration_db: HashMap::new(),
             show_hidden: false,
+            auto_hidden: HashSet::new(),
+            last_active: HashMap::new(),
+            running_streams: HashSet::new(),
+            auto_hide_after: None,
+            spectrum_batch_window: Duration::ZERO,
+            pending_spectrum: HashMap::new(),
+            last_spectrum_flush: Instant::now(),
+            recently_hidden: Vec::new(),
+            group_by_application: false,
+            collapsed_groups: HashSet::new(),
+            selected_group: None,
+            recording_devices: HashMap::new(),
+            pending_visualizations: PendingVisualizations::default(),
+            reconnect_visualizations: Vec::new(),
+            disconnected: false,
+            profile: String::from(DEFAULT_PROFILE),
+            available_profiles: Vec::new(),
+            profile_prompt: None,
+            node_prompt: None,
+            jump_mode: false,
+            pending_node: None,
+            requested_profile: None,
+            support_bundle_requested: false,
         }
     }
 
     pub fn handle_input(&mut self, key: Key, audio_engine: &mut AudioEngine) -> Result<()> {
-        // Handle input based on focus mode
-        match self.focus_mode {
-            FocusMode::DeviceList => self.handle_device_list_input(key, audio_engine)?,
-            FocusMode::SpectrumEq => self.handle_spectrum_eq_input(key, audio_engine)?,
+        // Any key dismisses the first-run overlay, for good
+        if !self.first_run_seen {
+            self.first_run_seen = true;
+            self.config_dirty = true;
+            self.last_viz_change = Some(Instant::now());
+            return Ok(());
         }
 
-        Ok(())
+        // Prompts take all keys while open, otherwise handle by focus mode
+        let result = if self.eq_import_path.is_some() {
+            self.handle_eq_import_input(key, audio_engine)
+        } else if self.eq_preset_prompt.is_some() {
+            self.handle_eq_preset_input(key);
+            Ok(())
+        } else if self.profile_prompt.is_some() {
+            self.handle_profile_input(key);
+            Ok(())
+        } else if self.node_prompt.is_some() {
+            self.handle_node_input(key, audio_engine)
+        } else if self.jump_mode {
+            self.handle_jump_input(key, audio_engine)
+        } else {
+            match self.focus_mode {
+                FocusMode::DeviceList => self.handle_device_list_input(key, audio_engine),
+                FocusMode::SpectrumEq => self.handle_spectrum_eq_input(key, audio_engine),
+            }
+        };
+
+        // A stalled audio thread shouldn't take the UI down with it
+        let result = match result {
+            Err(e) if e.downcast_ref::<CommandError>().is_some() => {
+                self.status_message = e.to_string();
+                Ok(())
+            }
+            other => other,
+        };
+        // The selection may have moved to another device
+        self.refresh_connections(audio_engine);
+        self.record_status();
+        result
+    }
+
+    /// Remember the status message if it changed since the last one recorded
+    fn record_status(&mut self) {
+        if self.status_history.back() == Some(&self.status_message) {
+            return;
+        }
+        if self.status_history.len() == STATUS_HISTORY_LEN {
+            self.status_history.pop_front();
+        }
+        self.status_history.push_back(self.status_message.clone());
     }
 
     fn handle_device_list_input(&mut self, key: Key, audio_engine: &mut AudioEngine) -> Result<()> {
-        match key {
+        let Some(action) = self.keymap.action(Mode::DeviceList, key) else {
+            // Unbound digits pick a built-in preset on the Filters tab
+            if self.current_tab == DeviceTab::Filters
+                && let Key::Char(c) = key
+                && let Some(preset) = c
+                    .to_digit(10)
+                    .and_then(|number| EqPreset::ALL.get((number as usize).checked_sub(1)?))
+            {
+                self.apply_eq_preset(*preset, audio_engine)?;
+            }
+            return Ok(());
+        };
+
+        // Device actions don't apply to an application header
+        if self.selected_group.is_some() && action.needs_device() {
+            self.status_message = String::from("Select a stream first (Enter: expand/collapse)");
+            return Ok(());
+        }
+
+        match action {
             // Global keys
-            Key::Char('q') | Key::Esc | Key::Ctrl('c') => {
+            Action::Quit => {
                 self.running = false;
             }
-            Key::Char('\t') => {
+            Action::NextTab => {
                 // Cycle through device tabs
                 self.current_tab = self.current_tab.next();
+                self.pin_eq_on_filters_tab();
             }
-            Key::BackTab => {
+            Action::PreviousTab => {
                 // Cycle through device tabs in reverse
                 self.current_tab = self.current_tab.previous();
+                self.pin_eq_on_filters_tab();
             }
-            Key::Up | Key::Char('k') => {
-                // Navigate device list (skip hidden devices if not showing them)
-                if self.selected_device > 0 {
-                    let mut new_index = self.selected_device - 1;
-                    // Skip hidden devices if not showing them
-                    while !self.show_hidden
-                        && new_index > 0
-                        && self.devices.get(new_index).map(|d| self.hidden_devices.contains(&d.name)).unwrap_or(false)
-                    {
-                        new_index -= 1;
-                    }
-                    // Check if the found device is visible or we reached the top
-                    if self.show_hidden
-                        || !self.devices.get(new_index).map(|d| self.hidden_devices.contains(&d.name)).unwrap_or(false)
-                    {
-                        self.selected_device = new_index;
-                    }
+            Action::PinEq => {
+                // Toggle between EQ following the selection and EQ pinned to one device
+                self.eq_pinned = !self.eq_pinned;
+                if self.eq_pinned {
+                    self.eq_pinned_device = self.devices.get(self.selected_device).map(|d| d.id);
+                    self.status_message = format!("EQ pinned to {}", self.eq_device_name());
+                } else {
+                    self.eq_pinned_device = None;
+                    self.status_message = String::from("EQ follows selection");
                 }
             }
-            Key::Down | Key::Char('j') => {
-                // Navigate device list (skip hidden devices if not showing them)
-                if !self.devices.is_empty() && self.selected_device + 1 < self.devices.len() {
-                    let mut new_index = self.selected_device + 1;
-                    // Skip hidden devices if not showing them
-                    while !self.show_hidden
-                        && new_index + 1 < self.devices.len()
-                        && self.devices.get(new_index).map(|d| self.hidden_devices.contains(&d.name)).unwrap_or(false)
-                    {
-                        new_index += 1;
-                    }
-                    // Check if the found device is visible or we reached the bottom
-                    if self.show_hidden
-                        || !self.devices.get(new_index).map(|d| self.hidden_devices.contains(&d.name)).unwrap_or(false)
-                    {
-                        self.selected_device = new_index;
-                    }
-                }
+            Action::Up => {
+                // Navigate device list rows (hidden devices are skipped unless shown)
+                self.move_selection(-1);
+            }
+            Action::Down => {
+                self.move_selection(1);
+            }
+            Action::PageUp => {
+                self.move_selection(-(self.device_list_page as isize));
             }
-            Key::Char('e') => {
+            Action::PageDown => {
+                self.move_selection(self.device_list_page as isize);
+            }
+            Action::OpenEq => {
                 // Enable EQ and jump to EQ mode for selected device
                 if let Some(device) = self.devices.get(self.selected_device) {
                     let device_id = device.id;
+                    if self.eq_pinned {
+                        self.eq_pinned_device = Some(device_id);
+                    }
                     if self.eq_enabled_devices.contains(&device_id) {
                         // EQ already enabled, jump to spectrum mode
                         self.focus_mode = FocusMode::SpectrumEq;
@@ -208,75 +866,400 @@ impl App {
                     }
                 }
             }
-            Key::Char('r') => {
+            Action::ApplyEqToAll => {
+                // Apply the EQ device's curve to all other visualized devices
+                self.apply_eq_to_visualized(audio_engine)?;
+            }
+            Action::Refresh => {
                 // Refresh device list
                 self.refresh_devices(audio_engine)?;
                 self.status_message = String::from("Refreshed device list");
             }
-            Key::Char('n') => {
-                // Create new virtual device (placeholder)
-                self.status_message = String::from("Virtual device creation not yet implemented");
+            Action::NewVirtualDevice => {
+                // Create a stereo virtual device under the first free "Virtual N" name
+                let name = (1..)
+                    .map(|n| format!("Virtual {}", n))
+                    .find(|name| !self.devices.iter().any(|device| &device.name == name))
+                    .unwrap_or_default();
+                audio_engine.create_virtual_device(name.clone(), 2, 2)?;
+                self.status_message = format!("Creating virtual device {}...", name);
+            }
+            Action::DeleteVirtualDevice => {
+                // Only devices wavewire created can be deleted
+                if let Some(device) = self.devices.get(self.selected_device) {
+                    if device.device_type == DeviceType::Virtual {
+                        audio_engine.destroy_virtual_device(device.id)?;
+                        self.status_message = format!("Deleting virtual device {}...", device.name);
+                    } else {
+                        self.status_message = format!("{} is not a virtual device", device.name);
+                    }
+                }
             }
-            Key::Char(' ') => {
+            Action::ToggleViz => {
                 // Toggle visualization for selected device
                 self.toggle_visualization(audio_engine)?;
             }
-            Key::Char('o') => {
+            Action::VisualizeNode => {
+                // Prompt for a PipeWire object ID to visualize
+                self.node_prompt = Some(String::new());
+            }
+            Action::JumpToDevice => {
+                // Letters select devices until Esc (or any other key, which then acts as usual)
+                self.jump_mode = true;
+            }
+            Action::CopyPorts => {
+                // Copy the selected device's PipeWire port names
+                self.copy_port_names();
+            }
+            Action::DumpSpectrum => {
+                // Dump the selected device's spectrum values to the debug log
+                self.dump_spectrum();
+            }
+            Action::ImportEq if self.eq_device().is_some() => {
+                // Ask for a parametric EQ profile to load into the EQ device
+                self.eq_import_path = Some(String::new());
+            }
+            Action::SaveEqPreset => {
+                // Ask for a name to save the EQ device's curve under
+                if self.eq_device_settings().is_some() {
+                    self.eq_preset_prompt = Some((EqPresetPrompt::Save, String::new()));
+                } else {
+                    self.status_message = format!("EQ is not enabled on {}", self.eq_device_name());
+                }
+            }
+            Action::LoadEqPreset => {
+                // Ask for a saved preset to load into the EQ device
+                if self.eq_device_settings().is_none() {
+                    self.status_message = format!("EQ is not enabled on {}", self.eq_device_name());
+                } else if self.saved_eq_presets.is_empty() {
+                    self.status_message = String::from("No saved EQ presets (E saves the current curve)");
+                } else {
+                    self.eq_preset_prompt = Some((EqPresetPrompt::Load, String::new()));
+                }
+            }
+            Action::FiltersEqBypass if self.current_tab == DeviceTab::Filters => {
+                // A/B the EQ device's curve against flat without entering EQ mode
+                if self.eq_device_settings().is_some() {
+                    self.toggle_eq_bypass(audio_engine)?;
+                } else {
+                    self.status_message = format!("EQ is not enabled on {}", self.eq_device_name());
+                }
+            }
+            Action::SupportBundle => {
+                // Write a support bundle (handled by the main loop)
+                self.support_bundle_requested = true;
+            }
+            Action::SwitchProfile => {
+                // Ask for a config profile to switch to (or create)
+                self.profile_prompt = Some(String::new());
+            }
+            Action::PauseAll => {
+                // Stop all visualizations, or resume the previously active set
+                self.toggle_pause_all(audio_engine)?;
+            }
+            Action::ToggleRecording => {
+                // Toggle WAV recording for selected device
+                self.toggle_recording(audio_engine)?;
+            }
+            Action::AmplificationDown => {
                 // Decrease spectrum amplification
                 self.spectrum_amplification = (self.spectrum_amplification - 0.1).max(0.1);
-                self.status_message =
-                    format!("Spectrum amplification: {:.1}", self.spectrum_amplification);
+                self.status_message = self.amplification_message();
                 self.config_dirty = true;
                 self.last_viz_change = Some(Instant::now());
             }
-            Key::Char('p') => {
+            Action::AmplificationUp => {
                 // Increase spectrum amplification
                 self.spectrum_amplification = (self.spectrum_amplification + 0.1).min(10.0);
-                self.status_message =
-                    format!("Spectrum amplification: {:.1}", self.spectrum_amplification);
+                self.status_message = self.amplification_message();
+                self.config_dirty = true;
+                self.last_viz_change = Some(Instant::now());
+            }
+            Action::CycleFocus => {
+                // Cycle the spectrum through each visualized device, then back to combined
+                self.cycle_spectrum_focus();
+            }
+            Action::NextConnection if self.current_tab == DeviceTab::Routing => {
+                // Navigate the selected device's connections
+                self.move_connection_selection(1);
+            }
+            Action::PreviousConnection if self.current_tab == DeviceTab::Routing => {
+                self.move_connection_selection(-1);
+            }
+            Action::Disconnect if self.current_tab == DeviceTab::Routing => {
+                self.disconnect_selected(audio_engine)?;
+            }
+            Action::Link if self.current_tab == DeviceTab::Routing => {
+                // Mark a link source, then link it to the device selected next
+                self.link_devices(audio_engine);
+            }
+            Action::GammaDown => self.adjust_spectrum_gamma(-SPECTRUM_GAMMA_STEP),
+            Action::GammaUp => self.adjust_spectrum_gamma(SPECTRUM_GAMMA_STEP),
+            Action::BarAttackDown => self.adjust_bar_envelope(-(BAR_ATTACK_STEP_MS as i64), 0),
+            Action::BarAttackUp => self.adjust_bar_envelope(BAR_ATTACK_STEP_MS as i64, 0),
+            Action::BarReleaseDown => self.adjust_bar_envelope(0, -(BAR_RELEASE_STEP_MS as i64)),
+            Action::BarReleaseUp => self.adjust_bar_envelope(0, BAR_RELEASE_STEP_MS as i64),
+            Action::BarWidth => {
+                // Cycle spectrum bar width (1-3 columns)
+                self.bar_width = self.bar_width % MAX_BAR_WIDTH + 1;
+                self.status_message = format!("Spectrum bar width: {}", self.bar_width);
+                self.config_dirty = true;
+                self.last_viz_change = Some(Instant::now());
+            }
+            Action::ZoomLowDown => self.adjust_zoom(ZoomEdge::Low, -1.0),
+            Action::ZoomLowUp => self.adjust_zoom(ZoomEdge::Low, 1.0),
+            Action::ZoomHighDown => self.adjust_zoom(ZoomEdge::High, -1.0),
+            Action::ZoomHighUp => self.adjust_zoom(ZoomEdge::High, 1.0),
+            Action::RefreshRate => {
+                // Cycle spectrum refresh rate
+                let next = REFRESH_RATES
+                    .iter()
+                    .position(|&hz| hz == self.refresh_rate_hz)
+                    .map_or(0, |i| (i + 1) % REFRESH_RATES.len());
+                let hz = REFRESH_RATES[next];
+                audio_engine.send_command(AudioCommand::SetRefreshRate { hz })?;
+                self.refresh_rate_hz = hz;
+                self.status_message = format!("Spectrum refresh rate: {} Hz", hz);
                 self.config_dirty = true;
                 self.last_viz_change = Some(Instant::now());
             }
-            Key::Char('h') => {
+            Action::DifferenceMode => {
+                // Toggle A − B difference view (needs exactly two visualized devices)
+                if self.difference_mode {
+                    self.difference_mode = false;
+                    self.status_message = String::from("Difference mode off");
+                } else if self.visualized_devices.len() == 2 {
+                    self.difference_mode = true;
+                    self.status_message = String::from("Difference mode on (A − B)");
+                } else {
+                    self.status_message = format!(
+                        "Difference mode needs exactly 2 visualized devices ({} active)",
+                        self.visualized_devices.len()
+                    );
+                }
+            }
+            Action::StereoCapture => {
+                // Toggle stereo capture (separate L/R buffers in every capture stream)
+                let enabled = !self.stereo_capture;
+                audio_engine.send_command(AudioCommand::SetStereoCapture { enabled })?;
+                self.stereo_capture = enabled;
+                if enabled {
+                    self.status_message = String::from("Stereo capture on (g: goniometer)");
+                } else {
+                    if self.stereo_spectrum {
+                        audio_engine.send_command(AudioCommand::SetStereoSpectrum { enabled: false })?;
+                        self.stereo_spectrum = false;
+                    }
+                    self.goniometer = false;
+                    self.stereo_frames.clear();
+                    self.status_message = String::from("Stereo capture off");
+                }
+            }
+            Action::StereoSpectrum => {
+                // Toggle one spectrum per channel (the audio thread needs the L/R buffers)
+                if !self.stereo_capture {
+                    self.status_message = String::from("Per-channel spectrum needs stereo capture (x)");
+                } else {
+                    let enabled = !self.stereo_spectrum;
+                    audio_engine.send_command(AudioCommand::SetStereoSpectrum { enabled })?;
+                    self.stereo_spectrum = enabled;
+                    self.status_message = if enabled {
+                        String::from("Spectrum per channel (left, right in the lighter shade)")
+                    } else {
+                        String::from("Spectrum of both channels mixed")
+                    };
+                }
+            }
+            Action::Goniometer => {
+                // Toggle the goniometer (only meaningful with separate L/R channels)
+                if !self.stereo_capture {
+                    self.status_message = String::from("Goniometer needs stereo capture (x)");
+                } else {
+                    self.goniometer = !self.goniometer;
+                    self.status_message = if self.goniometer {
+                        String::from("Goniometer on")
+                    } else {
+                        String::from("Goniometer off")
+                    };
+                }
+            }
+            Action::Waveform => {
+                // Toggle between the spectrum and the waveform (samples are only sent while shown)
+                let enabled = !self.waveform_view;
+                audio_engine.send_command(AudioCommand::SetWaveform { enabled })?;
+                self.waveform_view = enabled;
+                if enabled {
+                    self.status_message = String::from("Waveform view");
+                } else {
+                    self.waveforms.clear();
+                    self.status_message = String::from("Spectrum view");
+                }
+            }
+            Action::LevelMeters => {
+                // Toggle the level meters (levels are only sent while shown)
+                let enabled = !self.level_meters;
+                audio_engine.send_command(AudioCommand::SetLevelMeters { enabled })?;
+                self.level_meters = enabled;
+                if enabled {
+                    self.status_message = String::from("Level meters on");
+                } else {
+                    self.levels.clear();
+                    self.status_message = String::from("Level meters off");
+                }
+            }
+            Action::BinTable => {
+                // Toggle the numeric table of the selected device's bins
+                self.show_bin_table = !self.show_bin_table;
+                self.status_message = if self.show_bin_table {
+                    let scroll = self.keymap.label(&[Action::BinTableUp, Action::BinTableDown]);
+                    format!("Bin table on ({}: scroll)", scroll)
+                } else {
+                    String::from("Bin table off")
+                };
+            }
+            Action::BinTableUp => {
+                self.bin_table_scroll = self.bin_table_scroll.saturating_sub(bin_table::SCROLL_STEP);
+            }
+            Action::BinTableDown => {
+                let bins = self
+                    .bin_table_device()
+                    .and_then(|device_id| self.spectrum_data.get(&device_id))
+                    .map_or(0, |spectrum| spectrum.bins.len());
+                self.bin_table_scroll =
+                    (self.bin_table_scroll + bin_table::SCROLL_STEP).min(bins.saturating_sub(1));
+            }
+            Action::StreamHealth => {
+                // Toggle the stream health overlay
+                self.show_health = !self.show_health;
+                self.status_message = if self.show_health {
+                    String::from("Showing stream health (PipeWire quantum, buffer fill, update rate, starved/dropped)")
+                } else {
+                    String::from("Stream health hidden")
+                };
+            }
+            Action::Help => {
+                self.show_help = !self.show_help;
+                self.status_message = if self.show_help {
+                    format!("Showing all key bindings ({}: close)", self.keymap.label(&[Action::Help]))
+                } else {
+                    String::from("Key bindings hidden")
+                };
+            }
+            Action::CommandStats => {
+                // Toggle the command tally overlay
+                self.show_commands = !self.show_commands;
+                self.status_message = if self.show_commands {
+                    String::from("Showing audio commands (sent, handled, in flight, dropped)")
+                } else {
+                    String::from("Audio commands hidden")
+                };
+            }
+            Action::CancelCommands => {
+                // Drop commands held back behind a full queue
+                let cancelled = audio_engine.cancel_pending_commands();
+                self.status_message = if cancelled > 0 {
+                    format!("Cancelled {} held-back command(s)", cancelled)
+                } else {
+                    String::from("No held-back commands to cancel")
+                };
+            }
+            Action::ZoomReset => {
+                // Reset spectrum zoom to the full range
+                self.frequency_zoom.reset();
+                self.status_message = String::from("Spectrum zoom reset to full range");
+            }
+            Action::MaxHold => {
+                // Toggle max hold (starts fresh each time it's turned on)
+                self.max_hold = !self.max_hold;
+                self.max_hold_bins.clear();
+                self.status_message = if self.max_hold {
+                    String::from("Max hold on (M: reset)")
+                } else {
+                    String::from("Max hold off")
+                };
+            }
+            Action::ResetMaxHold => {
+                self.reset_max_hold();
+                self.status_message = String::from("Max hold reset");
+            }
+            Action::PeakHold => {
+                // Toggle peak hold (starts fresh each time it's turned on)
+                self.peak_hold = !self.peak_hold;
+                self.peak_hold_bins.clear();
+                self.status_message = if self.peak_hold {
+                    String::from("Peak hold on")
+                } else {
+                    String::from("Peak hold off")
+                };
+            }
+            Action::HideDevice => {
                 // Hide selected device
                 if let Some(device) = self.devices.get(self.selected_device) {
                     let device_name = device.name.clone();
                     if self.hidden_devices.contains(&device_name) {
                         // Unhide device
                         self.hidden_devices.remove(&device_name);
+                        self.recently_hidden.retain(|name| name != &device_name);
                         self.status_message = format!("Unhidden device: {}", device_name);
                     } else {
                         // Hide device
                         self.hidden_devices.insert(device_name.clone());
-                        self.status_message = format!("Hidden device: {}", device_name);
-
-                        // If not showing hidden devices, move selection to next visible device
-                        if !self.show_hidden {
-                            // Find next visible device
-                            let mut found_visible = false;
-                            for i in (self.selected_device + 1)..self.devices.len() {
-                                if !self.hidden_devices.contains(&self.devices[i].name) {
-                                    self.selected_device = i;
-                                    found_visible = true;
-                                    break;
-                                }
-                            }
-                            // If no visible device found after current, search before
-                            if !found_visible {
-                                for i in (0..self.selected_device).rev() {
-                                    if !self.hidden_devices.contains(&self.devices[i].name) {
-                                        self.selected_device = i;
-                                        break;
-                                    }
-                                }
-                            }
-                        }
+                        self.push_recently_hidden(device_name.clone());
+                        self.status_message =
+                            format!("Hidden device: {} (u: undo)", device_name);
+
+                        self.step_off_hidden_selection();
+                    }
+                    self.config_dirty = true;
+                    self.last_viz_change = Some(Instant::now());
+                }
+            }
+            Action::GroupByApp => {
+                // Toggle grouping stream nodes by application
+                self.group_by_application = !self.group_by_application;
+                self.selected_group = None;
+                self.status_message = if self.group_by_application {
+                    String::from("Grouping streams by application (Enter: expand/collapse)")
+                } else {
+                    String::from("Showing all devices flat")
+                };
+            }
+            Action::ToggleGroup
+                if self.current_tab == DeviceTab::Routing && !self.available_actions().expand =>
+            {
+                // Outside of application groups, Enter patches the selected port
+                self.patch_selected_port(audio_engine)?;
+            }
+            Action::ToggleGroup => {
+                // Expand/collapse the application group under the cursor
+                self.toggle_selected_group();
+            }
+            Action::Favorite => {
+                // Toggle pinning the selected device to the top of the list
+                if let Some(device) = self.devices.get(self.selected_device) {
+                    let device_name = device.name.clone();
+                    if self.favorite_devices.remove(&device_name) {
+                        self.status_message = format!("Unpinned {}", device_name);
+                    } else {
+                        self.status_message = format!("Pinned {} to the top", device_name);
+                        self.favorite_devices.insert(device_name);
                     }
+                    // Selection is by device index, so it follows the device to its new row
                     self.config_dirty = true;
                     self.last_viz_change = Some(Instant::now());
                 }
             }
-            Key::Char('H') => {
+            Action::CycleColor => {
+                // Assign the selected device a spectrum color from the palette
+                self.cycle_device_color();
+            }
+            Action::CalibrationDown => self.adjust_calibration(-CALIBRATION_STEP_DB),
+            Action::CalibrationUp => self.adjust_calibration(CALIBRATION_STEP_DB),
+            Action::UndoHide => {
+                // Unhide the most recently hidden device
+                self.undo_hide();
+            }
+            Action::ShowHidden => {
                 // Toggle showing hidden devices
                 self.show_hidden = !self.show_hidden;
                 self.status_message = if self.show_hidden {
@@ -285,1147 +1268,6226 @@ impl App {
                     String::from("Hiding hidden devices")
                 };
             }
-            Key::Char(',') => {
+            Action::Crossfeed => {
+                // Toggle headphone crossfeed for selected device
+                self.toggle_crossfeed(audio_engine)?;
+            }
+            Action::CrossfeedAmountDown => {
+                self.adjust_crossfeed(-CROSSFEED_AMOUNT_STEP, 0.0, audio_engine)?;
+            }
+            Action::CrossfeedAmountUp => {
+                self.adjust_crossfeed(CROSSFEED_AMOUNT_STEP, 0.0, audio_engine)?;
+            }
+            Action::CrossfeedCutoffDown => {
+                self.adjust_crossfeed(0.0, -CROSSFEED_CUTOFF_STEP, audio_engine)?;
+            }
+            Action::CrossfeedCutoffUp => {
+                self.adjust_crossfeed(0.0, CROSSFEED_CUTOFF_STEP, audio_engine)?;
+            }
+            Action::SaturationDown => {
+                self.adjust_saturation(-SATURATION_STEP, audio_engine)?;
+            }
+            Action::SaturationUp => {
+                self.adjust_saturation(SATURATION_STEP, audio_engine)?;
+            }
+            Action::WidthDown => {
+                self.adjust_stereo_width(-WIDTH_STEP, audio_engine)?;
+            }
+            Action::WidthUp => {
+                self.adjust_stereo_width(WIDTH_STEP, audio_engine)?;
+            }
+            Action::FftSize => {
+                self.cycle_fft_size(audio_engine)?;
+            }
+            Action::VolumeDown => {
                 // Decrease volume
                 self.adjust_volume(-3.0, audio_engine)?;
             }
-            Key::Char('.') => {
+            Action::VolumeUp => {
                 // Increase volume
                 self.adjust_volume(3.0, audio_engine)?;
             }
+            Action::BalanceLeft => {
+                self.adjust_balance(-BALANCE_STEP, audio_engine)?;
+            }
+            Action::BalanceRight => {
+                self.adjust_balance(BALANCE_STEP, audio_engine)?;
+            }
             _ => {}
         }
 
         Ok(())
     }
 
-    fn handle_spectrum_eq_input(&mut self, key: Key, audio_engine: &mut AudioEngine) -> Result<()> {
-        match key {
-            Key::Char('q') | Key::Ctrl('c') => {
-                self.running = false;
-            }
-            Key::Esc | Key::Char(' ') | Key::Backspace => {
-                // Return to device list
-                self.focus_mode = FocusMode::DeviceList;
-                self.status_message = String::from("Returned to device list");
-            }
-            Key::Char('k') => {
-                // Increase gain at selected band (up)
-                self.adjust_eq_gain(1.0, audio_engine)?;
-            }
-            Key::Char('j') => {
-                // Decrease gain at selected band (down)
-                self.adjust_eq_gain(-1.0, audio_engine)?;
-            }
-            Key::Char('h') => {
-                // Move to previous band (left)
-                if self.selected_eq_band > 0 {
-                    self.selected_eq_band -= 1;
-                    self.status_message =
-                        format!("Selected band: {}Hz", self.get_current_band_frequency());
-                }
-            }
-            Key::Char('l') => {
-                // Move to next band (right)
-                if self.selected_eq_band < 9 {
-                    self.selected_eq_band += 1;
-                    self.status_message =
-                        format!("Selected band: {}Hz", self.get_current_band_frequency());
-                }
-            }
-            Key::Left => {
-                // Move to previous band (alternative)
-                if self.selected_eq_band > 0 {
-                    self.selected_eq_band -= 1;
-                    self.status_message =
-                        format!("Selected band: {}Hz", self.get_current_band_frequency());
-                }
-            }
-            Key::Right => {
-                // Move to next band (alternative)
-                if self.selected_eq_band < 9 {
-                    self.selected_eq_band += 1;
-                    self.status_message =
-                        format!("Selected band: {}Hz", self.get_current_band_frequency());
-                }
-            }
-            _ => {}
-        }
-
-        Ok(())
+    /// Rows of the device list as currently displayed
+    fn device_rows(&self) -> Vec<DeviceRow> {
+        build_rows(
+            &self.devices,
+            self.group_by_application,
+            &self.collapsed_groups,
+            |device| self.show_hidden || !self.is_hidden(device),
+            |device| self.favorite_devices.contains(&device.name),
+        )
     }
 
-    fn get_current_band_frequency(&self) -> u32 {
-        const BANDS: [u32; 10] = [31, 63, 125, 250, 500, 1000, 2000, 4000, 8000, 20000];
-        BANDS[self.selected_eq_band]
+    /// Hidden by the user or auto-hidden for being silent
+    fn is_hidden(&self, device: &DeviceInfo) -> bool {
+        self.hidden_devices.contains(&device.name) || self.auto_hidden.contains(&device.id)
     }
 
-    fn enable_eq(&mut self, device_id: DeviceId, audio_engine: &AudioEngine) -> Result<()> {
-        let settings = EqSettings::default();
-        audio_engine.send_command(AudioCommand::EnableEq {
-            device_id,
-            settings: settings.clone(),
-        })?;
-        // Optimistically update state for immediate UI responsiveness
-        self.eq_enabled_devices.insert(device_id);
-        self.eq_settings.insert(device_id, settings.clone());
-        self.status_message = String::from("Enabling EQ...");
-        Ok(())
+    /// If the selected device just got hidden (and hidden devices aren't shown), move
+    /// the selection to the next visible device, or the previous one at the bottom
+    fn step_off_hidden_selection(&mut self) {
+        if self.show_hidden {
+            return;
+        }
+        let current = self.selected_device;
+        let next = self.next_visible_index(current, 1);
+        self.selected_device = if next != current {
+            next
+        } else {
+            self.next_visible_index(current, -1)
+        };
     }
 
-    fn adjust_eq_gain(&mut self, delta: f32, audio_engine: &AudioEngine) -> Result<()> {
-        if let Some(device) = self.devices.get(self.selected_device) {
-            let device_id = device.id;
-            if let Some(settings) = self.eq_settings.get_mut(&device_id) {
-                let current_gain = settings.bands[self.selected_eq_band].gain_db;
-                let new_gain = (current_gain + delta).clamp(-24.0, 24.0);
-                settings.bands[self.selected_eq_band].gain_db = new_gain;
+    /// Take in one spectrum frame: max and peak hold, activity, bar ballistics and the latest data
+    fn apply_spectrum_update(&mut self, device_id: DeviceId, data: &Arc<SpectrumData>) {
+        if self.max_hold {
+            hold_max(self.max_hold_bins.entry(device_id).or_default(), &data.bins);
+        }
+        if self.peak_hold {
+            hold_peak(self.peak_hold_bins.entry(device_id).or_default(), &data.bins, PEAK_HOLD_DECAY_DB);
+        }
+        if data.bins.iter().any(|&level| level > SILENCE_THRESHOLD_DB) {
+            self.last_active.insert(device_id, data.timestamp);
+        }
+        let envelope = self.bar_envelope();
+        self.smoothed_bins
+            .entry(device_id)
+            .and_modify(|smoothed| smoothed.update(&data.bins, data.timestamp, envelope))
+            .or_insert_with(|| SmoothedBins::new(&data.bins, data.timestamp));
+        self.spectrum_data.insert(device_id, Arc::clone(data));
+    }
 
-                audio_engine.send_command(AudioCommand::SetEqBand {
-                    device_id,
-                    band_index: self.selected_eq_band,
-                    gain_db: new_gain,
-                    q_value: settings.bands[self.selected_eq_band].q_value,
-                })?;
+    /// Apply the batched spectrum frames once the batching window has passed
+    /// Call once per loop iteration, before rendering
+    pub fn flush_spectrum_batch(&mut self, now: Instant) {
+        if self.pending_spectrum.is_empty()
+            || now.saturating_duration_since(self.last_spectrum_flush) < self.spectrum_batch_window
+        {
+            return;
+        }
+        for (device_id, data) in std::mem::take(&mut self.pending_spectrum) {
+            self.apply_spectrum_update(device_id, &data);
+        }
+        self.last_spectrum_flush = now;
+    }
 
-                self.status_message =
-                    format!("{}Hz: {:.1}dB", self.get_current_band_frequency(), new_gain);
+    /// Let the bars of devices whose frames stopped coming fall away instead of
+    /// freezing at their last levels (a stalled stream, or a batch that never came)
+    /// Call once per loop iteration, after `flush_spectrum_batch`
+    pub fn fade_stale_spectra(&mut self, now: Instant) {
+        let stale_after = self.spectrum_batch_window + STALE_SPECTRUM_AFTER;
+        let envelope = self.bar_envelope();
+        for (device_id, smoothed) in &mut self.smoothed_bins {
+            if self.spectrum_data.get(device_id).is_none_or(|data| {
+                now.saturating_duration_since(data.timestamp) >= stale_after
+            }) {
+                smoothed.fade(now, envelope);
             }
         }
-        Ok(())
     }
 
-    fn adjust_volume(&mut self, delta_db: f32, audio_engine: &AudioEngine) -> Result<()> {
-        if let Some(device) = self.devices.get(self.selected_device) {
-            let device_id = device.id;
+    /// Hide streams that have been silent longer than the auto-hide delay and
+    /// bring back the ones that are playing again
+    pub fn update_auto_hide(&mut self, now: Instant) {
+        let Some(after) = self.auto_hide_after else {
+            self.auto_hidden.clear();
+            return;
+        };
 
-            // Get current settings or default
-            let mut settings = self.volume_settings
-                .get(&device_id)
-                .cloned()
-                .unwrap_or_default();
+        let (mut hidden, mut unhidden) = (Vec::new(), Vec::new());
+        for device in self.devices.iter().filter(|d| d.is_stream()) {
+            // A running node only says the stream is playing, not that it's audible,
+            // so the spectrum decides for visualized streams
+            if self.running_streams.contains(&device.id)
+                && !self.visualized_devices.contains(&device.id)
+            {
+                self.last_active.insert(device.id, now);
+            }
+            let silent = self
+                .last_active
+                .get(&device.id)
+                .is_some_and(|&active| now.saturating_duration_since(active) >= after);
+            if silent && self.auto_hidden.insert(device.id) {
+                hidden.push(device.name.clone());
+            } else if !silent && self.auto_hidden.remove(&device.id) {
+                unhidden.push(device.name.clone());
+            }
+        }
 
-            // Adjust gain in dB (clamp to -60.0 to +6.0)
-            settings.gain_db = (settings.gain_db + delta_db).clamp(-60.0, 6.0);
+        if !hidden.is_empty() {
+            crate::debug_log!("Auto-hid silent stream(s): {}", hidden.join(", "));
+            self.status_message = format!("Auto-hid {} silent stream(s) (H: show)", hidden.len());
+            if self
+                .devices
+                .get(self.selected_device)
+                .is_some_and(|d| self.auto_hidden.contains(&d.id))
+            {
+                self.step_off_hidden_selection();
+            }
+        }
+        if !unhidden.is_empty() {
+            crate::debug_log!("Stream(s) playing again: {}", unhidden.join(", "));
+            self.status_message = format!("Playing again: {}", unhidden.join(", "));
+        }
+    }
 
-            // Convert to linear: linear = 10^(dB/20)
-            settings.gain_linear = 10f32.powf(settings.gain_db / 20.0);
+    /// Position of the cursor within `rows`
+    fn selected_row(&self, rows: &[DeviceRow]) -> Option<usize> {
+        rows.iter().position(|row| match (row, &self.selected_group) {
+            (DeviceRow::Group { application, .. }, Some(selected)) => application == selected,
+            (DeviceRow::Device { index, .. }, None) => *index == self.selected_device,
+            _ => false,
+        })
+    }
 
-            // Send command
-            audio_engine.send_command(AudioCommand::SetVolume {
-                device_id,
-                settings: settings.clone(),
-            })?;
+    /// Index of the nearest device after (+1) or before (-1) `from` in display order
+    /// that the list shows. Returns `from` if every device in that direction is hidden
+    fn next_visible_index(&self, from: usize, direction: isize) -> usize {
+        let order = display_order(&self.devices, |device| {
+            self.favorite_devices.contains(&device.name)
+        });
+        let Some(position) = order.iter().position(|&i| i == from) else {
+            return from;
+        };
+        let is_visible = |&&i: &&usize| self.show_hidden || !self.is_hidden(&self.devices[i]);
+        let next = if direction < 0 {
+            order[..position].iter().rev().find(is_visible)
+        } else {
+            order[position + 1..].iter().find(is_visible)
+        };
+        next.copied().unwrap_or(from)
+    }
 
-            // Update local state
-            self.volume_settings.insert(device_id, settings.clone());
-            self.config_dirty = true;
-            self.last_viz_change = Some(Instant::now());
+    /// Move the cursor up (-1) or down (+1) one row
+    fn move_selection(&mut self, delta: isize) {
+        let rows = self.device_rows();
+        let Some(last) = rows.len().checked_sub(1) else {
+            return;
+        };
+        let target = match self.selected_row(&rows) {
+            Some(current) => current.saturating_add_signed(delta).min(last),
+            // The selected device isn't listed (hidden while hidden devices aren't shown):
+            // step to its visible neighbour instead of jumping back to the top
+            None => {
+                let neighbour = self.next_visible_index(self.selected_device, delta);
+                rows.iter()
+                    .position(|row| matches!(row, DeviceRow::Device { index, .. } if *index == neighbour))
+                    .unwrap_or(0)
+            }
+        };
 
-            // Show status message
-            self.status_message = format!(
-                "{}: {:+.1} dB ({}%)",
-                device.name,
-                settings.gain_db,
-                (settings.gain_linear * 100.0) as i32
-            );
-        }
-        Ok(())
+        self.select_row(&rows[target]);
     }
 
-    pub fn handle_audio_events(&mut self, events: &[AudioEvent]) {
-        for event in events {
-            match event {
-                AudioEvent::DeviceAdded {
-                    device_id: _,
-                    name,
-                    device_type,
-                } => {
-                    self.status_message = format!("Device added: {} ({:?})", name, device_type);
-                }
-                AudioEvent::DeviceRemoved { device_id } => {
-                    self.status_message = format!("Device removed: {:?}", device_id);
-                }
-                AudioEvent::ConnectionEstablished {
-                    source,
-                    destination,
-                } => {
-                    self.status_message = format!("Connected: {} -> {}", source, destination);
-                }
-                AudioEvent::ConnectionBroken {
-                    source,
-                    destination,
-                } => {
-                    self.status_message = format!("Disconnected: {} -> {}", source, destination);
-                }
-                AudioEvent::Xrun => {
-                    self.status_message = String::from("Audio buffer xrun occurred");
-                }
-                AudioEvent::Error { message } => {
-                    self.status_message = format!("Error: {}", message);
-                }
-                AudioEvent::VisualizationStarted { device_id, port_id } => {
-                    self.visualized_devices.insert(*device_id);
-                    self.last_viz_change = Some(Instant::now());
-                    self.config_dirty = true;
-                    self.status_message = format!(
-                        "Visualization started for device {:?}, port {:?}",
-                        device_id, port_id
-                    );
-                }
-                AudioEvent::VisualizationStopped { device_id } => {
-                    self.visualized_devices.remove(device_id);
-                    self.spectrum_data.remove(device_id);
-                    self.last_viz_change = Some(Instant::now());
-                    self.config_dirty = true;
-                    self.status_message =
-                        format!("Visualization stopped for device {:?}", device_id);
-                }
-                AudioEvent::SpectrumUpdate { device_id, data } => {
-                    crate::debug_log!(
-                        "[UI] SpectrumUpdate received: device={:?}, bins={}, samples=[{:.2}, {:.2}, {:.2}]",
-                        device_id,
-                        data.bins.len(),
-                        data.bins.get(0).unwrap_or(&-60.0),
-                        data.bins.get(32).unwrap_or(&-60.0),
-                        data.bins.get(63).unwrap_or(&-60.0)
-                    );
-                    self.spectrum_data.insert(*device_id, data.clone());
-                }
-                AudioEvent::EqEnabled {
-                    device_id,
-                    settings,
-                } => {
-                    self.eq_enabled_devices.insert(*device_id);
-                    self.eq_settings.insert(*device_id, settings.clone());
-                    let device_name = self
-                        .devices
-                        .iter()
-                        .find(|d| d.id == *device_id)
-                        .map(|d| d.name.as_str())
-                        .unwrap_or("device");
-                    self.status_message = format!(
-                        "EQ enabled! In Helvum: Disconnect sources from '{}', connect them to wavewire_eq, then wavewire_eq to '{}'",
-                        device_name, device_name
-                    );
-                }
-                AudioEvent::EqDisabled { device_id } => {
-                    self.eq_enabled_devices.remove(device_id);
-                    self.eq_settings.remove(device_id);
-                    self.status_message = format!("EQ disabled for device {:?}", device_id);
-                }
-                AudioEvent::EqUpdated {
-                    device_id,
-                    settings,
-                } => {
-                    self.eq_settings.insert(*device_id, settings.clone());
-                    self.status_message = format!("EQ updated for device {:?}", device_id);
-                }
-                AudioEvent::VolumeUpdated {
-                    device_id,
-                    settings,
-                } => {
-                    self.volume_settings.insert(*device_id, settings.clone());
-                }
+    fn select_row(&mut self, row: &DeviceRow) {
+        match row {
+            DeviceRow::Group { application, .. } => {
+                self.selected_group = Some(application.clone());
+            }
+            DeviceRow::Device { index, .. } => {
+                self.selected_group = None;
+                self.selected_device = *index;
             }
         }
     }
 
-    pub fn refresh_devices(&mut self, audio_engine: &AudioEngine) -> Result<()> {
-        self.devices = audio_engine.list_devices()?;
-        if self.selected_device >= self.devices.len() && !self.devices.is_empty() {
-            self.selected_device = self.devices.len() - 1;
+    /// Select the next row after the cursor whose name starts with `letter` (ignoring
+    /// case), wrapping around, so repeated presses cycle through the matches
+    fn jump_to_letter(&mut self, letter: char) {
+        let rows = self.device_rows();
+        let start = self.selected_row(&rows).map_or(0, |row| row + 1);
+        let starts_with_letter = |row: &DeviceRow| {
+            let name = match row {
+                DeviceRow::Group { application, .. } => application.as_str(),
+                DeviceRow::Device { index, .. } => self.devices[*index].name.as_str(),
+            };
+            name.chars()
+                .next()
+                .is_some_and(|first| first.to_lowercase().eq(letter.to_lowercase()))
+        };
+
+        match (0..rows.len())
+            .map(|offset| &rows[(start + offset) % rows.len()])
+            .find(|row| starts_with_letter(row))
+        {
+            Some(row) => self.select_row(row),
+            None => self.status_message = format!("No device starting with '{}'", letter),
         }
-        Ok(())
     }
 
-    fn toggle_visualization(&mut self, audio_engine: &AudioEngine) -> Result<()> {
-        if self.devices.is_empty() {
-            self.status_message = String::from("No devices available");
-            return Ok(());
+    fn handle_jump_input(&mut self, key: Key, audio_engine: &mut AudioEngine) -> Result<()> {
+        match key {
+            Key::Char(c) if c.is_alphanumeric() => {
+                self.jump_to_letter(c);
+                Ok(())
+            }
+            Key::Esc => {
+                self.jump_mode = false;
+                Ok(())
+            }
+            _ => {
+                self.jump_mode = false;
+                self.handle_device_list_input(key, audio_engine)
+            }
         }
+    }
 
-        let device = &self.devices[self.selected_device];
-        let device_id = device.id;
+    /// Expand/collapse the group under the cursor
+    /// On a nested stream, collapses its group and moves the cursor to the header
+    fn toggle_selected_group(&mut self) {
+        if !self.group_by_application {
+            self.status_message = String::from("Press 'a' to group streams by application");
+            return;
+        }
 
-        if self.visualized_devices.contains(&device_id) {
-            // Stop visualization
-            use crate::audio::AudioCommand;
-            audio_engine.send_command(AudioCommand::StopVisualization { device_id })?;
-            self.status_message = format!("Stopping visualization for {}", device.name);
+        let application = match &self.selected_group {
+            Some(application) => application.clone(),
+            None => match self
+                .devices
+                .get(self.selected_device)
+                .and_then(|d| d.application_name.clone())
+            {
+                Some(application) => application,
+                None => return,
+            },
+        };
+
+        if self.collapsed_groups.remove(&application) {
+            self.status_message = format!("Expanded {}", application);
         } else {
-            // Start visualization - pick the first output port (monitor port if available)
-            let port_to_visualize = device.ports.iter().find(|p| {
-                use crate::audio::PortDirection;
-                p.direction == PortDirection::Output
-            });
-
-            if let Some(port) = port_to_visualize {
-                use crate::audio::AudioCommand;
-                audio_engine.send_command(AudioCommand::StartVisualization {
-                    device_id,
-                    port_id: port.id,
-                })?;
-                self.status_message = format!("Starting visualization for {}", device.name);
-            } else {
-                self.status_message = format!("No output port found for {}", device.name);
-            }
+            self.status_message = format!("Collapsed {}", application);
+            self.collapsed_groups.insert(application.clone());
+            self.selected_group = Some(application);
         }
+    }
 
-        Ok(())
+    /// Move one edge of the spectrum zoom window
+    fn adjust_zoom(&mut self, edge: ZoomEdge, direction: f32) {
+        self.frequency_zoom.step_edge(edge, direction);
+        self.status_message = format!("Spectrum zoom: {}", self.frequency_zoom.label());
     }
 
-    pub fn render(&mut self, frame: &mut Frame, audio_engine: &AudioEngine) {
-        // Note: Device list is refreshed via events and manual refresh ('r' key), not on every render
+    /// Remember a hidden device for undo, dropping the oldest entry when full
+    fn push_recently_hidden(&mut self, device_name: String) {
+        self.recently_hidden.retain(|name| name != &device_name);
+        self.recently_hidden.push(device_name);
+        if self.recently_hidden.len() > HIDE_UNDO_DEPTH {
+            self.recently_hidden.remove(0);
+        }
+    }
 
-        let terminal_height = frame.area().height;
+    /// Restore the most recently hidden device and select it
+    fn undo_hide(&mut self) {
+        // Skip entries that were already unhidden some other way
+        while let Some(device_name) = self.recently_hidden.pop() {
+            if !self.hidden_devices.remove(&device_name) {
+                continue;
+            }
 
-        // Responsive layout based on terminal height
-        if terminal_height < MIN_HEIGHT_FOR_FULL_LAYOUT {
-            // Minimal view: spectrum only
-            self.render_minimal_layout(frame);
-        } else {
-            // Full view: device list + tabs + spectrum
-            self.render_full_layout(frame);
+            if let Some(index) = self.devices.iter().position(|d| d.name == device_name) {
+                self.selected_device = index;
+                self.selected_group = None;
+            }
+            self.status_message = format!("Unhidden device: {}", device_name);
+            self.config_dirty = true;
+            self.last_viz_change = Some(Instant::now());
+            return;
         }
-    }
 
-    fn render_minimal_layout(&self, frame: &mut Frame) {
-        // In minimal mode, just show spectrum filling the entire screen
-        self.render_spectrum(frame, frame.area(), false);
+        self.status_message = String::from("No recently hidden devices to restore");
     }
 
-    fn render_full_layout(&mut self, frame: &mut Frame) {
-        // Main vertical split: content area + spectrum at bottom + status bar
-        let main_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),                  // Content area (device list + main content)
-                Constraint::Length(SPECTRUM_HEIGHT), // Spectrum visualization
-                Constraint::Length(3),               // Status bar
-            ])
-            .split(frame.area());
+    fn handle_spectrum_eq_input(&mut self, key: Key, audio_engine: &mut AudioEngine) -> Result<()> {
+        let Some(action) = self.keymap.action(Mode::Eq, key) else {
+            return Ok(());
+        };
+        self.clamp_selected_eq_band();
 
-        // Horizontal split: device list on left + main content on right
-        let content_chunks = Layout::default()
+        match action {
+            Action::EqQuit => {
+                self.running = false;
+            }
+            Action::EqBack => {
+                // Return to device list
+                self.focus_mode = FocusMode::DeviceList;
+                self.status_message = String::from("Returned to device list");
+            }
+            Action::EqGainUp => {
+                // Increase gain at selected band (up)
+                self.adjust_eq_gain(1.0, audio_engine)?;
+            }
+            Action::EqGainDown => {
+                // Decrease gain at selected band (down)
+                self.adjust_eq_gain(-1.0, audio_engine)?;
+            }
+            Action::EqQDown => {
+                // Widen the selected band
+                self.adjust_eq_q(1.0 / EQ_Q_STEP, audio_engine)?;
+            }
+            Action::EqQUp => {
+                // Narrow the selected band
+                self.adjust_eq_q(EQ_Q_STEP, audio_engine)?;
+            }
+            Action::EqBandCount => {
+                // Switch between 5, 10, 15 and 31 bands
+                self.cycle_eq_band_count(audio_engine)?;
+            }
+            Action::EqMute => {
+                // Mute/unmute the selected band
+                self.toggle_eq_band(|band| band.enabled = !band.enabled, audio_engine)?;
+            }
+            Action::EqSolo => {
+                // Solo/unsolo the selected band
+                self.toggle_eq_band(|band| band.soloed = !band.soloed, audio_engine)?;
+            }
+            Action::EqBypass => {
+                // A/B: crossfade between the EQ'd and the dry signal
+                self.toggle_eq_bypass(audio_engine)?;
+            }
+            Action::EqCompare => {
+                // Show the EQ output next to the device's own spectrum
+                self.toggle_eq_compare(audio_engine)?;
+            }
+            Action::EqReset => {
+                // Flatten every band
+                self.reset_eq(audio_engine)?;
+            }
+            Action::EqDisable => {
+                // Take the EQ out of the signal path
+                self.disable_eq(audio_engine)?;
+            }
+            // Move to previous band (left)
+            Action::EqPreviousBand if self.selected_eq_band > 0 => {
+                self.selected_eq_band -= 1;
+                self.status_message =
+                    format!("Selected band: {}Hz", self.get_current_band_frequency());
+            }
+            // Move to next band (right)
+            Action::EqNextBand if self.selected_eq_band + 1 < self.eq_band_count() => {
+                self.selected_eq_band += 1;
+                self.status_message =
+                    format!("Selected band: {}Hz", self.get_current_band_frequency());
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_eq_import_input(&mut self, key: Key, audio_engine: &mut AudioEngine) -> Result<()> {
+        let Some(path) = self.eq_import_path.as_mut() else {
+            return Ok(());
+        };
+
+        match key {
+            Key::Esc | Key::Ctrl('c') => {
+                self.eq_import_path = None;
+                self.status_message = String::from("EQ import cancelled");
+            }
+            Key::Char('\n') => {
+                let path = path.trim().to_string();
+                self.eq_import_path = None;
+                if !path.is_empty() {
+                    self.import_eq(&path, audio_engine)?;
+                }
+            }
+            Key::Backspace => {
+                path.pop();
+            }
+            Key::Char(c) => path.push(c),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_eq_preset_input(&mut self, key: Key) {
+        let Some((prompt, name)) = self.eq_preset_prompt.as_mut() else {
+            return;
+        };
+
+        match key {
+            Key::Esc | Key::Ctrl('c') => {
+                self.eq_preset_prompt = None;
+                self.status_message = String::from("EQ preset cancelled");
+            }
+            Key::Char('\t') if *prompt == EqPresetPrompt::Load => {
+                // Complete to the first saved preset starting with what was typed
+                if let Some(preset) = self.saved_eq_presets.iter().find(|p| p.starts_with(name.as_str())) {
+                    *name = preset.clone();
+                }
+            }
+            Key::Char('\n') => {
+                let prompt = *prompt;
+                let name = name.trim().to_string();
+                self.eq_preset_prompt = None;
+                if name.is_empty() {
+                    return;
+                }
+                if let Err(e) = validate_preset_name(&name) {
+                    self.status_message = e.to_string();
+                    return;
+                }
+                let Some(device_id) = self.eq_device().map(|d| d.id) else {
+                    return;
+                };
+                self.eq_preset_request = match prompt {
+                    EqPresetPrompt::Save => self
+                        .eq_device_settings()
+                        .map(|settings| EqPresetRequest::Save { name, settings: settings.clone() }),
+                    EqPresetPrompt::Load => Some(EqPresetRequest::Load { device_id, name }),
+                };
+            }
+            Key::Backspace => {
+                name.pop();
+            }
+            Key::Char(c) => name.push(c),
+            _ => {}
+        }
+    }
+
+    fn handle_profile_input(&mut self, key: Key) {
+        let Some(name) = self.profile_prompt.as_mut() else {
+            return;
+        };
+
+        match key {
+            Key::Esc | Key::Ctrl('c') => {
+                self.profile_prompt = None;
+                self.status_message = String::from("Profile switch cancelled");
+            }
+            Key::Char('\n') => {
+                let name = name.trim().to_string();
+                self.profile_prompt = None;
+                if name.is_empty() {
+                    return;
+                }
+                if name == self.profile {
+                    self.status_message = format!("Already using profile {}", name);
+                } else if let Err(e) = validate_profile_name(&name) {
+                    self.status_message = e.to_string();
+                } else {
+                    self.status_message = format!("Switching to profile {}...", name);
+                    self.requested_profile = Some(name);
+                }
+            }
+            Key::Backspace => {
+                name.pop();
+            }
+            Key::Char(c) => name.push(c),
+            _ => {}
+        }
+    }
+
+    fn handle_node_input(&mut self, key: Key, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(id) = self.node_prompt.as_mut() else {
+            return Ok(());
+        };
+
+        match key {
+            Key::Esc | Key::Ctrl('c') => {
+                self.node_prompt = None;
+                self.status_message = String::from("Visualize by ID cancelled");
+            }
+            Key::Char('\n') => {
+                let id = id.trim().to_string();
+                self.node_prompt = None;
+                match id.parse::<u32>() {
+                    Ok(node_id) => self.visualize_node(node_id, audio_engine)?,
+                    Err(_) if id.is_empty() => {}
+                    Err(_) => self.status_message = format!("Not a PipeWire object ID: {}", id),
+                }
+            }
+            Key::Backspace => {
+                id.pop();
+            }
+            Key::Char(c) if c.is_ascii_digit() => id.push(c),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Start visualizing the node with this PipeWire global ID, bypassing name matching
+    pub fn visualize_node(&mut self, node_id: u32, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device_id) = audio_engine.device_for_node(node_id)? else {
+            self.status_message = format!("PipeWire object {} is not a known audio node", node_id);
+            return Ok(());
+        };
+
+        self.refresh_devices(audio_engine)?;
+        let Some(device) = self.devices.iter().find(|d| d.id == device_id) else {
+            self.status_message = format!("PipeWire node {} went away", node_id);
+            return Ok(());
+        };
+        // A node without ports yet is queued until they appear
+        if !device.ports.is_empty() && visualization_port(device).is_none() {
+            self.status_message =
+                format!("PipeWire node {} ({}) has no output port to monitor", node_id, device.name);
+            return Ok(());
+        }
+        crate::debug_log!("Visualizing PipeWire node {} as {} ({:?})", node_id, device.name, device_id);
+        self.start_visualization(device_id, audio_engine)
+    }
+
+    /// Visualize a node by ID once the registry has reported it (for the command line,
+    /// which asks before any nodes are known); gives up after the port wait timeout
+    pub fn queue_node_visualization(&mut self, node_id: u32) {
+        self.pending_node = Some((node_id, Instant::now()));
+    }
+
+    /// Load a parametric EQ profile into the EQ device, enabling EQ if needed
+    fn import_eq(&mut self, path: &str, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.eq_device() else {
+            return Ok(());
+        };
+        let device_id = device.id;
+
+        let import = match import_parametric_eq(path) {
+            Ok(import) => import,
+            Err(e) => {
+                self.status_message = format!("EQ import failed: {:#}", e);
+                return Ok(());
+            }
+        };
+
+        let settings = import.settings.clone();
+        let command = if self.eq_enabled_devices.contains(&device_id) {
+            AudioCommand::SetEqSettings {
+                device_id,
+                settings: settings.clone(),
+            }
+        } else {
+            AudioCommand::EnableEq {
+                device_id,
+                settings: settings.clone(),
+            }
+        };
+        let newly_enabled = matches!(command, AudioCommand::EnableEq { .. });
+        audio_engine.send_command(command)?;
+        if newly_enabled {
+            self.send_saved_processing(device_id, audio_engine)?;
+        }
+
+        // Optimistically update state for immediate UI responsiveness
+        self.eq_enabled_devices.insert(device_id);
+        self.eq_settings.insert(device_id, settings);
+        self.status_message = import.summary();
+        self.eq_import_report = Some((device_id, import.summary()));
+        Ok(())
+    }
+
+    /// Replace the EQ device's curve with a built-in preset
+    fn apply_eq_preset(&mut self, preset: EqPreset, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device_id) = self.eq_device().map(|d| d.id) else {
+            return Ok(());
+        };
+        self.apply_eq_curve(device_id, preset.name(), preset.to_settings(), audio_engine)
+    }
+
+    /// Replace a device's EQ curve with a preset's, keeping its A/B state
+    pub fn apply_eq_curve(
+        &mut self,
+        device_id: DeviceId,
+        preset_name: &str,
+        mut settings: EqSettings,
+        audio_engine: &AudioEngine,
+    ) -> Result<()> {
+        let Some(device_name) = self.devices.iter().find(|d| d.id == device_id).map(|d| d.name.clone())
+        else {
+            return Ok(());
+        };
+        let current = self
+            .eq_settings
+            .get(&device_id)
+            .filter(|_| self.eq_enabled_devices.contains(&device_id));
+        let Some(current) = current else {
+            self.status_message = format!("EQ is not enabled on {} (press 'e')", device_name);
+            return Ok(());
+        };
+
+        settings.bypass = current.bypass;
+        audio_engine.send_command(AudioCommand::SetEqSettings {
+            device_id,
+            settings: settings.clone(),
+        })?;
+        self.eq_settings.insert(device_id, settings);
+        self.status_message = format!("{} preset on {}", preset_name, device_name);
+        Ok(())
+    }
+
+    /// Copy the EQ device's curve to every other visualized device that takes EQ,
+    /// enabling EQ on the ones that don't have it yet
+    fn apply_eq_to_visualized(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(source) = self.eq_device() else {
+            return Ok(());
+        };
+        let source_id = source.id;
+        let source_name = source.name.clone();
+        let Some(settings) = self.eq_settings.get(&source_id).cloned() else {
+            self.status_message = format!("EQ is not enabled on {}", source_name);
+            return Ok(());
+        };
+
+        // EQ is inserted in front of input ports, so devices without any are skipped
+        let targets: Vec<DeviceId> = self
+            .devices
+            .iter()
+            .filter(|d| d.id != source_id && self.visualized_devices.contains(&d.id))
+            .filter(|d| d.ports.iter().any(|p| p.direction == PortDirection::Input))
+            .map(|d| d.id)
+            .collect();
+        if targets.is_empty() {
+            self.status_message = String::from("No other visualized devices to apply EQ to");
+            return Ok(());
+        }
+
+        // A full queue only skips that device; the rest still get the curve
+        let mut applied = 0;
+        let mut newly_enabled = 0;
+        for &device_id in &targets {
+            let enabled = self.eq_enabled_devices.contains(&device_id);
+            let command = if enabled {
+                AudioCommand::SetEqSettings {
+                    device_id,
+                    settings: settings.clone(),
+                }
+            } else {
+                AudioCommand::EnableEq {
+                    device_id,
+                    settings: settings.clone(),
+                }
+            };
+            match audio_engine.send_command(command) {
+                Ok(()) => {}
+                Err(CommandError::Busy) => continue,
+                Err(e) => return Err(e.into()),
+            }
+            if !enabled {
+                if let Err(e) = self.send_saved_processing(device_id, audio_engine) {
+                    crate::debug_log!("Could not reapply processing for {:?}: {}", device_id, e);
+                }
+                self.eq_enabled_devices.insert(device_id);
+                newly_enabled += 1;
+            }
+            self.eq_settings.insert(device_id, settings.clone());
+            applied += 1;
+        }
+
+        self.status_message = if applied == targets.len() {
+            format!(
+                "Applied EQ from {} to {} device(s) ({} newly enabled)",
+                source_name, applied, newly_enabled
+            )
+        } else {
+            format!(
+                "Applied EQ from {} to {} of {} device(s) ({} newly enabled); audio engine busy, try again",
+                source_name,
+                applied,
+                targets.len(),
+                newly_enabled
+            )
+        };
+        Ok(())
+    }
+
+    /// Re-pin the EQ editor to the selected device when switching to the Filters tab
+    fn pin_eq_on_filters_tab(&mut self) {
+        if self.eq_pinned && self.current_tab == DeviceTab::Filters {
+            self.eq_pinned_device = self.devices.get(self.selected_device).map(|d| d.id);
+        }
+    }
+
+    /// Device the EQ editor operates on: the pinned device if any, else the selection
+    fn eq_device(&self) -> Option<&DeviceInfo> {
+        self.eq_pinned_device
+            .filter(|_| self.eq_pinned)
+            .and_then(|id| self.devices.iter().find(|d| d.id == id))
+            .or_else(|| self.devices.get(self.selected_device))
+    }
+
+    /// EQ settings of the EQ device, if it has EQ enabled
+    fn eq_device_settings(&self) -> Option<&EqSettings> {
+        let device_id = self.eq_device()?.id;
+        self.eq_settings
+            .get(&device_id)
+            .filter(|_| self.eq_enabled_devices.contains(&device_id))
+    }
+
+    fn eq_device_name(&self) -> &str {
+        self.eq_device().map_or("no device", |d| d.name.as_str())
+    }
+
+    /// Center frequency of the selected band (imported profiles may retune bands)
+    fn get_current_band_frequency(&self) -> u32 {
+        self.eq_device()
+            .and_then(|device| self.eq_settings.get(&device.id))
+            .and_then(|settings| settings.bands.get(self.selected_eq_band))
+            .map_or(GRAPHIC_EQ_BANDS[self.selected_eq_band.min(GRAPHIC_EQ_BANDS.len() - 1)], |band| {
+                band.frequency
+            })
+            .round() as u32
+    }
+
+    /// Number of bands of the EQ device's EQ (the default count before it has one)
+    fn eq_band_count(&self) -> usize {
+        self.eq_device()
+            .and_then(|device| self.eq_settings.get(&device.id))
+            .map_or(GRAPHIC_EQ_BANDS.len(), EqSettings::band_count)
+    }
+
+    /// Keep the selected band within the EQ device's bands (devices can differ in count)
+    fn clamp_selected_eq_band(&mut self) {
+        self.selected_eq_band = self.selected_eq_band.min(self.eq_band_count().saturating_sub(1));
+    }
+
+    /// Switch the EQ device to the next band count, carrying its curve over
+    fn cycle_eq_band_count(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device_id) = self.eq_device().map(|d| d.id) else {
+            return Ok(());
+        };
+        let Some(settings) = self.eq_settings.get(&device_id) else {
+            return Ok(());
+        };
+
+        let next = EQ_BAND_COUNTS
+            .iter()
+            .copied()
+            .find(|&count| count > settings.band_count())
+            .unwrap_or(EQ_BAND_COUNTS[0]);
+        let settings = settings.resampled(next);
+        audio_engine.send_command(AudioCommand::SetEqSettings {
+            device_id,
+            settings: settings.clone(),
+        })?;
+        self.eq_settings.insert(device_id, settings);
+        self.clamp_selected_eq_band();
+        self.status_message = format!(
+            "{}-band EQ (selected band: {}Hz)",
+            next,
+            self.get_current_band_frequency()
+        );
+        Ok(())
+    }
+
+    fn enable_eq(&mut self, device_id: DeviceId, audio_engine: &AudioEngine) -> Result<()> {
+        self.enable_eq_with(device_id, EqSettings::default(), audio_engine)
+    }
+
+    fn enable_eq_with(
+        &mut self,
+        device_id: DeviceId,
+        settings: EqSettings,
+        audio_engine: &AudioEngine,
+    ) -> Result<()> {
+        audio_engine.send_command(AudioCommand::EnableEq {
+            device_id,
+            settings: settings.clone(),
+        })?;
+        self.send_saved_processing(device_id, audio_engine)?;
+        // Optimistically update state for immediate UI responsiveness
+        self.eq_enabled_devices.insert(device_id);
+        self.eq_settings.insert(device_id, settings.clone());
+        self.status_message = String::from("Enabling EQ...");
+        Ok(())
+    }
+
+    /// Reapply a device's saved crossfeed, saturation and width to its newly created EQ stream
+    fn send_saved_processing(&self, device_id: DeviceId, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.iter().find(|d| d.id == device_id) else {
+            return Ok(());
+        };
+        let saved = self
+            .crossfeed_settings
+            .get(&device.name)
+            .filter(|settings| settings.enabled);
+        if let Some(settings) = saved {
+            audio_engine.send_command(AudioCommand::SetCrossfeed {
+                device_id,
+                settings: settings.clone(),
+            })?;
+        }
+        if let Some(&drive) = self.saturation_drive.get(&device.name).filter(|&&drive| drive > 0.0) {
+            audio_engine.send_command(AudioCommand::SetSaturation { device_id, drive })?;
+        }
+        if let Some(&width) = self.stereo_width.get(&device.name) {
+            audio_engine.send_command(AudioCommand::SetStereoWidth { device_id, width })?;
+        }
+        Ok(())
+    }
+
+    fn toggle_crossfeed(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return Ok(());
+        };
+        // Crossfeed runs in the EQ processing stream
+        if !self.eq_enabled_devices.contains(&device.id) {
+            self.status_message = format!("Enable EQ on {} first (e)", device.name);
+            return Ok(());
+        }
+
+        let device_id = device.id;
+        let settings = self.crossfeed_settings.entry(device.name.clone()).or_default();
+        settings.enabled = !settings.enabled;
+        audio_engine.send_command(AudioCommand::SetCrossfeed {
+            device_id,
+            settings: settings.clone(),
+        })?;
+
+        self.status_message = if settings.enabled {
+            format!(
+                "Crossfeed on for {} ({:.0}%, {:.0} Hz)",
+                device.name,
+                settings.amount * 100.0,
+                settings.cutoff_hz
+            )
+        } else {
+            format!("Crossfeed off for {}", device.name)
+        };
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Change the selected device's crossfeed amount and cutoff
+    /// Works while crossfeed is off too, so it can be set up before switching it on
+    fn adjust_crossfeed(
+        &mut self,
+        amount_delta: f32,
+        cutoff_delta: f32,
+        audio_engine: &AudioEngine,
+    ) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return Ok(());
+        };
+        // Crossfeed runs in the EQ processing stream
+        if !self.eq_enabled_devices.contains(&device.id) {
+            self.status_message = format!("Enable EQ on {} first (e)", device.name);
+            return Ok(());
+        }
+
+        let device_id = device.id;
+        let mut settings = self.crossfeed_settings.get(&device.name).cloned().unwrap_or_default();
+        // Round to whole steps so repeated presses land exactly on the limits
+        settings.amount =
+            ((settings.amount + amount_delta) / CROSSFEED_AMOUNT_STEP).round() * CROSSFEED_AMOUNT_STEP;
+        settings.cutoff_hz += cutoff_delta;
+        settings.clamp();
+        audio_engine.send_command(AudioCommand::SetCrossfeed {
+            device_id,
+            settings: settings.clone(),
+        })?;
+
+        self.status_message = format!(
+            "Crossfeed for {}: {:.0}%, {:.0} Hz{}",
+            device.name,
+            settings.amount * 100.0,
+            settings.cutoff_hz,
+            if settings.enabled { "" } else { " (off)" }
+        );
+        self.crossfeed_settings.insert(device.name.clone(), settings);
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Change the selected device's saturation drive by `delta` (0% turns it off)
+    fn adjust_saturation(&mut self, delta: f32, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return Ok(());
+        };
+        // Saturation runs in the EQ processing stream
+        if !self.eq_enabled_devices.contains(&device.id) {
+            self.status_message = format!("Enable EQ on {} first (e)", device.name);
+            return Ok(());
+        }
+
+        let device_id = device.id;
+        let current = self.saturation_drive.get(&device.name).copied().unwrap_or(0.0);
+        // Round to whole steps so repeated presses land exactly on 0%
+        let drive = clamp_saturation_drive(((current + delta) / SATURATION_STEP).round() * SATURATION_STEP);
+        audio_engine.send_command(AudioCommand::SetSaturation { device_id, drive })?;
+        if drive > 0.0 {
+            self.saturation_drive.insert(device.name.clone(), drive);
+            self.status_message = format!("Saturation for {}: {:.0}% drive", device.name, drive * 100.0);
+        } else {
+            self.saturation_drive.remove(&device.name);
+            self.status_message = format!("Saturation off for {}", device.name);
+        }
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Change the selected device's stereo width by `delta` (100% is unchanged)
+    fn adjust_stereo_width(&mut self, delta: f32, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return Ok(());
+        };
+        // Width runs in the EQ processing stream
+        if !self.eq_enabled_devices.contains(&device.id) {
+            self.status_message = format!("Enable EQ on {} first (e)", device.name);
+            return Ok(());
+        }
+
+        let device_id = device.id;
+        let current = self.stereo_width.get(&device.name).copied().unwrap_or(1.0);
+        // Round to whole steps so repeated presses land exactly on 100%
+        let width = clamp_stereo_width(((current + delta) / WIDTH_STEP).round() * WIDTH_STEP);
+        audio_engine.send_command(AudioCommand::SetStereoWidth { device_id, width })?;
+        if width != 1.0 {
+            self.stereo_width.insert(device.name.clone(), width);
+            self.status_message = format!("Stereo width for {}: {:.0}%", device.name, width * 100.0);
+        } else {
+            self.stereo_width.remove(&device.name);
+            self.status_message = format!("Stereo width unchanged for {}", device.name);
+        }
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+        Ok(())
+    }
+
+    /// FFT size of a device's visualization
+    fn fft_size(&self, device_id: DeviceId) -> usize {
+        self.fft_sizes.get(&device_id).copied().unwrap_or(DEFAULT_FFT_SIZE)
+    }
+
+    /// Double the selected visualization's FFT size, wrapping around to the smallest
+    /// The size changes once the audio thread confirms it
+    fn cycle_fft_size(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return Ok(());
+        };
+        if !self.visualized_devices.contains(&device.id) {
+            self.status_message = format!("Visualize {} first (v)", device.name);
+            return Ok(());
+        }
+
+        let device_id = device.id;
+        let doubled = self.fft_size(device_id) * 2;
+        let fft_size = if FFT_SIZE_RANGE.contains(&doubled) {
+            doubled
+        } else {
+            *FFT_SIZE_RANGE.start()
+        };
+        audio_engine.send_command(AudioCommand::SetFftSize { device_id, fft_size })?;
+        Ok(())
+    }
+
+    fn adjust_eq_gain(&mut self, delta: f32, audio_engine: &AudioEngine) -> Result<()> {
+        if let Some(device) = self.eq_device() {
+            let device_id = device.id;
+            if let Some(settings) = self.eq_settings.get_mut(&device_id) {
+                let current_gain = settings.bands[self.selected_eq_band].gain_db;
+                let new_gain = clamp_eq_gain(current_gain + delta);
+                settings.bands[self.selected_eq_band].gain_db = new_gain;
+
+                audio_engine.send_command(AudioCommand::SetEqBand {
+                    device_id,
+                    band_index: self.selected_eq_band,
+                    gain_db: new_gain,
+                    q_value: settings.bands[self.selected_eq_band].q_value,
+                })?;
+
+                self.status_message =
+                    format!("{}Hz: {:.1}dB", self.get_current_band_frequency(), new_gain);
+            }
+        }
+        Ok(())
+    }
+
+    /// Scale the selected band's Q by `factor` (above 1 narrows it)
+    fn adjust_eq_q(&mut self, factor: f32, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device_id) = self.eq_device().map(|d| d.id) else {
+            return Ok(());
+        };
+        let frequency = self.get_current_band_frequency();
+        let Some(settings) = self.eq_settings.get_mut(&device_id) else {
+            return Ok(());
+        };
+
+        let band = &mut settings.bands[self.selected_eq_band];
+        // Rounded so stepping back and forth returns to the same values
+        band.q_value = clamp_eq_q((band.q_value * factor * 100.0).round() / 100.0);
+        audio_engine.send_command(AudioCommand::SetEqBand {
+            device_id,
+            band_index: self.selected_eq_band,
+            gain_db: band.gain_db,
+            q_value: band.q_value,
+        })?;
+
+        self.status_message = format!(
+            "{}Hz: Q {:.2} ({:.1} octaves)",
+            frequency,
+            band.q_value,
+            bandwidth_octaves(band.q_value)
+        );
+        Ok(())
+    }
+
+    /// Flip a mute/solo flag on the selected band and send the full settings
+    /// (solo affects every band, so a single-band update isn't enough)
+    fn toggle_eq_band<F>(&mut self, toggle: F, audio_engine: &AudioEngine) -> Result<()>
+    where
+        F: FnOnce(&mut EqBandParams),
+    {
+        let Some(device_id) = self.eq_device().map(|d| d.id) else {
+            return Ok(());
+        };
+        let frequency = self.get_current_band_frequency();
+        let Some(settings) = self.eq_settings.get_mut(&device_id) else {
+            return Ok(());
+        };
+
+        let band = &mut settings.bands[self.selected_eq_band];
+        toggle(band);
+        let state = match (band.enabled, band.soloed) {
+            (false, _) => "muted",
+            (true, true) => "soloed",
+            (true, false) => "active",
+        };
+
+        audio_engine.send_command(AudioCommand::SetEqSettings {
+            device_id,
+            settings: settings.clone(),
+        })?;
+        self.status_message = format!("{}Hz: {}", frequency, state);
+        Ok(())
+    }
+
+    /// Toggle EQ bypass on the EQ device, reporting the EQ path's delay
+    fn toggle_eq_bypass(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device_id) = self.eq_device().map(|d| d.id) else {
+            return Ok(());
+        };
+        let sample_rate = self.eq_sample_rate(device_id);
+        let Some(settings) = self.eq_settings.get_mut(&device_id) else {
+            return Ok(());
+        };
+
+        settings.bypass = !settings.bypass;
+        audio_engine.send_command(AudioCommand::SetEqBypass {
+            device_id,
+            bypass: settings.bypass,
+        })?;
+        let (freq, delay_ms) = settings.max_group_delay(sample_rate);
+        self.status_message = format!(
+            "{} (EQ path delay up to {:.2} ms at {:.0} Hz, dry path undelayed)",
+            if settings.bypass { "B: EQ bypassed" } else { "A: EQ active" },
+            delay_ms,
+            freq
+        );
+        // Bypass is part of the saved EQ settings
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Set every band of the EQ device's EQ back to 0 dB
+    fn reset_eq(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.eq_device() else {
+            return Ok(());
+        };
+        let (device_id, device_name) = (device.id, device.name.clone());
+        let Some(settings) = self
+            .eq_settings
+            .get_mut(&device_id)
+            .filter(|_| self.eq_enabled_devices.contains(&device_id))
+        else {
+            self.status_message = format!("EQ is not enabled on {}", device_name);
+            return Ok(());
+        };
+
+        audio_engine.send_command(AudioCommand::ResetEq { device_id })?;
+        // Optimistically update state for immediate UI responsiveness
+        settings.reset();
+        self.status_message = format!("Reset EQ on {}", device_name);
+        Ok(())
+    }
+
+    /// Remove the EQ device's EQ; `EqDisabled` clears its settings once it is gone
+    fn disable_eq(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.eq_device() else {
+            return Ok(());
+        };
+        let (device_id, device_name) = (device.id, device.name.clone());
+        if !self.eq_enabled_devices.contains(&device_id) {
+            self.status_message = format!("EQ is not enabled on {}", device_name);
+            return Ok(());
+        }
+
+        audio_engine.send_command(AudioCommand::DisableEq { device_id })?;
+        self.status_message = format!("Disabling EQ on {}", device_name);
+        Ok(())
+    }
+
+    /// Start or stop drawing the EQ device's EQ output ("post") next to its monitor ("pre")
+    fn toggle_eq_compare(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.eq_device() else {
+            return Ok(());
+        };
+        let (device_id, device_name) = (device.id, device.name.clone());
+
+        if self.eq_taps.contains(&device_id) {
+            audio_engine.send_command(AudioCommand::StopEqTap { device_id })?;
+        } else if !self.eq_enabled_devices.contains(&device_id) {
+            self.status_message = format!("Enable EQ on {} to compare pre/post", device_name);
+        } else if !self.visualized_devices.contains(&device_id) {
+            self.status_message = format!("Visualize {} to compare pre/post EQ", device_name);
+        } else {
+            audio_engine.send_command(AudioCommand::StartEqTap { device_id })?;
+            self.status_message = format!("Capturing EQ output of {}...", device_name);
+        }
+        Ok(())
+    }
+
+    /// Sample rate the device's EQ runs at, as reported with its spectrum
+    fn eq_sample_rate(&self, device_id: DeviceId) -> f32 {
+        self.spectrum_data
+            .get(&device_id)
+            .map_or(DEFAULT_SAMPLE_RATE, |data| data.sample_rate as f32)
+    }
+
+    fn adjust_volume(&mut self, delta_db: f32, audio_engine: &AudioEngine) -> Result<()> {
+        if let Some(device) = self.devices.get(self.selected_device) {
+            let device_id = device.id;
+
+            // Get current settings or default
+            let mut settings = self.volume_settings
+                .get(&device_id)
+                .cloned()
+                .unwrap_or_default();
+
+            // Adjust gain in dB (clamped to -60.0 to +6.0)
+            settings.adjust_db(delta_db);
+
+            // Send command
+            audio_engine.send_command(AudioCommand::SetVolume {
+                device_id,
+                settings: settings.clone(),
+            })?;
+
+            // Update local state
+            self.volume_settings.insert(device_id, settings.clone());
+            self.config_dirty = true;
+            self.last_viz_change = Some(Instant::now());
+
+            // Show status message
+            self.status_message = format!(
+                "{}: {:+.1} dB ({}%)",
+                device.name,
+                settings.gain_db,
+                (settings.gain_linear * 100.0) as i32
+            );
+        }
+        Ok(())
+    }
+
+    /// Shift the selected device's left/right balance, pausing at center
+    fn adjust_balance(&mut self, delta: f32, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return Ok(());
+        };
+        let device_id = device.id;
+
+        let mut settings = self.volume_settings.get(&device_id).cloned().unwrap_or_default();
+        settings.nudge_balance(delta);
+        audio_engine.send_command(AudioCommand::SetVolume {
+            device_id,
+            settings: settings.clone(),
+        })?;
+
+        self.status_message = format!("{}: balance {}", device.name, balance_label(settings.balance));
+        self.volume_settings.insert(device_id, settings);
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+        Ok(())
+    }
+
+    pub fn handle_audio_events(&mut self, events: &[AudioEvent]) {
+        for event in events {
+            match event {
+                AudioEvent::Connected => {
+                    self.disconnected = false;
+                    self.status_message = String::from("Connected to PipeWire");
+                }
+                AudioEvent::Disconnected => {
+                    // Remember what was running; the streams are torn down next
+                    let mut names: Vec<String> = self
+                        .sorted_visualized_devices()
+                        .into_iter()
+                        .map(|device_id| self.device_name(device_id))
+                        .collect();
+                    names.retain(|name| !self.reconnect_visualizations.contains(name));
+                    self.reconnect_visualizations.extend(names);
+                    self.disconnected = true;
+                    self.status_message =
+                        String::from("Lost connection to PipeWire, reconnecting...");
+                }
+                AudioEvent::DeviceAdded {
+                    device_id: _,
+                    name,
+                    device_type,
+                } => {
+                    self.status_message = format!("Device added: {} ({:?})", name, device_type);
+                }
+                AudioEvent::DeviceRemoved { device_id } => {
+                    self.status_message = format!("Device removed: {:?}", device_id);
+                }
+                AudioEvent::PortRemoved { device_id, port_name } => {
+                    // The device list is refreshed for this, which drops the port
+                    crate::debug_log!("[EVENT] Port {} of {} removed", port_name, device_id);
+                }
+                AudioEvent::ConnectionEstablished {
+                    source,
+                    destination,
+                } => {
+                    self.status_message = format!("Connected: {} -> {}", source, destination);
+                }
+                AudioEvent::ConnectionBroken {
+                    source,
+                    destination,
+                } => {
+                    self.status_message = format!("Disconnected: {} -> {}", source, destination);
+                }
+                #[cfg(any(test, feature = "jack"))]
+                AudioEvent::Xrun => {
+                    self.status_message = String::from("Audio buffer xrun occurred");
+                }
+                AudioEvent::Error { message } => {
+                    self.status_message = format!("Error: {}", message);
+                }
+                #[cfg(feature = "jack")]
+                AudioEvent::VisualizationStarted { device_id, port_id } => {
+                    crate::debug_log!("Visualization started for: {}", self.device_name(*device_id));
+                    self.visualized_devices.insert(*device_id);
+                    // Silence is timed from when measuring starts
+                    self.last_active.insert(*device_id, Instant::now());
+                    self.last_viz_change = Some(Instant::now());
+                    self.config_dirty = true;
+                    self.status_message = format!(
+                        "Visualization started for device {:?}, port {:?}",
+                        device_id, port_id
+                    );
+                }
+                AudioEvent::VisualizationStopped { device_id } => {
+                    self.visualized_devices.remove(device_id);
+                    // Back to the node state; silence is timed from here
+                    self.last_active.insert(*device_id, Instant::now());
+                    self.auto_hidden.remove(device_id);
+                    self.spectrum_data.remove(device_id);
+                    self.pending_spectrum.remove(device_id);
+                    self.smoothed_bins.remove(device_id);
+                    self.stream_health.remove(device_id);
+                    self.fft_sizes.remove(device_id);
+                    self.stereo_frames.remove(device_id);
+                    self.waveforms.remove(device_id);
+                    self.levels.remove(device_id);
+                    self.last_clip.remove(device_id);
+                    self.max_hold_bins.remove(device_id);
+                    self.peak_hold_bins.remove(device_id);
+                    self.recording_devices.remove(device_id);
+                    // Streams lost with the connection are restored on reconnect,
+                    // so don't save them as stopped
+                    if !self.disconnected {
+                        self.last_viz_change = Some(Instant::now());
+                        self.config_dirty = true;
+                    }
+                    self.status_message =
+                        format!("Visualization stopped for device {:?}", device_id);
+                }
+                AudioEvent::StreamStateChanged { device_id, running } => {
+                    if *running {
+                        self.running_streams.insert(*device_id);
+                    } else if self.running_streams.remove(device_id)
+                        || !self.last_active.contains_key(device_id)
+                    {
+                        // Silence is timed from when it stopped (or was first seen idle)
+                        self.last_active.insert(*device_id, Instant::now());
+                    }
+                }
+                AudioEvent::SpectrumUpdate { device_id, data } => {
+                    // Not logged here: the stream traces every update it sends,
+                    // and formatting a line per frame per device is most of the cost
+                    if self.spectrum_batch_window.is_zero() {
+                        self.apply_spectrum_update(*device_id, data);
+                    } else {
+                        // Latest frame wins until the window passes (see `flush_spectrum_batch`)
+                        self.pending_spectrum.insert(*device_id, Arc::clone(data));
+                    }
+                }
+                AudioEvent::EqTapStarted { device_id } => {
+                    self.eq_taps.insert(*device_id);
+                    self.status_message = format!(
+                        "Comparing {} pre/post EQ",
+                        self.device_name(*device_id)
+                    );
+                }
+                AudioEvent::EqTapStopped { device_id } => {
+                    self.eq_taps.remove(device_id);
+                    self.eq_tap_spectrum.remove(device_id);
+                    self.status_message = format!(
+                        "Stopped comparing {} pre/post EQ",
+                        self.device_name(*device_id)
+                    );
+                }
+                #[cfg(any(test, feature = "jack"))]
+                AudioEvent::EqTapSpectrum { device_id, data } => {
+                    if self.eq_taps.contains(device_id) {
+                        self.eq_tap_spectrum.insert(*device_id, Arc::clone(data));
+                    }
+                }
+                AudioEvent::EqEnabled {
+                    device_id,
+                    settings,
+                } => {
+                    self.eq_enabled_devices.insert(*device_id);
+                    self.eq_settings.insert(*device_id, settings.clone());
+                    let device_name = self
+                        .devices
+                        .iter()
+                        .find(|d| d.id == *device_id)
+                        .map(|d| d.name.as_str())
+                        .unwrap_or("device");
+                    self.status_message = format!(
+                        "EQ enabled! In Helvum: Disconnect sources from '{}', connect them to wavewire_eq, then wavewire_eq to '{}'",
+                        device_name, device_name
+                    );
+                    // Keep the import report visible when EQ was enabled by an import
+                    if let Some((_, report)) =
+                        self.eq_import_report.take_if(|(id, _)| id == device_id)
+                    {
+                        self.status_message = format!("{}. {}", report, self.status_message);
+                    }
+                }
+                AudioEvent::EqDisabled { device_id } => {
+                    self.eq_enabled_devices.remove(device_id);
+                    self.eq_settings.remove(device_id);
+                    self.status_message = format!("EQ disabled for device {:?}", device_id);
+                }
+                AudioEvent::EqUpdated {
+                    device_id,
+                    settings,
+                } => {
+                    self.eq_settings.insert(*device_id, settings.clone());
+                    self.status_message = match self
+                        .eq_import_report
+                        .take_if(|(id, _)| id == device_id)
+                    {
+                        Some((_, report)) => report,
+                        None => format!("EQ updated for device {:?}", device_id),
+                    };
+                }
+                AudioEvent::VolumeUpdated {
+                    device_id,
+                    settings,
+                } => {
+                    self.volume_settings.insert(*device_id, settings.clone());
+                }
+                AudioEvent::CrossfeedUpdated {
+                    device_id,
+                    settings,
+                } => {
+                    if let Some(device) = self.devices.iter().find(|d| d.id == *device_id) {
+                        self.crossfeed_settings
+                            .insert(device.name.clone(), settings.clone());
+                    }
+                }
+                AudioEvent::SaturationUpdated { device_id, drive } => {
+                    if let Some(device) = self.devices.iter().find(|d| d.id == *device_id) {
+                        if *drive > 0.0 {
+                            self.saturation_drive.insert(device.name.clone(), *drive);
+                        } else {
+                            self.saturation_drive.remove(&device.name);
+                        }
+                    }
+                }
+                AudioEvent::FftSizeChanged { device_id, fft_size } => {
+                    self.fft_sizes.insert(*device_id, *fft_size);
+                    self.status_message =
+                        format!("FFT size for {}: {}", self.device_name(*device_id), fft_size);
+                }
+                AudioEvent::StereoWidthUpdated { device_id, width } => {
+                    if let Some(device) = self.devices.iter().find(|d| d.id == *device_id) {
+                        if *width != 1.0 {
+                            self.stereo_width.insert(device.name.clone(), *width);
+                        } else {
+                            self.stereo_width.remove(&device.name);
+                        }
+                    }
+                }
+                AudioEvent::RecordingStarted { device_id, path } => {
+                    self.recording_devices.insert(*device_id, Instant::now());
+                    self.status_message = format!("Recording to {}", path.display());
+                }
+                AudioEvent::RecordingStopped {
+                    device_id,
+                    path,
+                    duration,
+                } => {
+                    self.recording_devices.remove(device_id);
+                    self.status_message = format!(
+                        "Saved {} recording to {}",
+                        Self::format_elapsed(*duration),
+                        path.display()
+                    );
+                }
+                AudioEvent::StereoFrames { device_id, frames } => {
+                    if self.stereo_capture && self.visualized_devices.contains(device_id) {
+                        self.stereo_frames.insert(*device_id, frames.clone());
+                    }
+                }
+                AudioEvent::WaveformUpdate { device_id, samples } => {
+                    if self.waveform_view && self.visualized_devices.contains(device_id) {
+                        self.waveforms.insert(*device_id, samples.clone());
+                    }
+                }
+                AudioEvent::Clipped { device_id } => {
+                    if self.visualized_devices.contains(device_id) {
+                        self.last_clip.insert(*device_id, Instant::now());
+                    }
+                }
+                AudioEvent::LevelUpdate { device_id, peak_db, rms_db } => {
+                    if self.level_meters && self.visualized_devices.contains(device_id) {
+                        let now = Instant::now();
+                        self.levels
+                            .entry(*device_id)
+                            .and_modify(|levels| levels.update(*peak_db, *rms_db, now))
+                            .or_insert_with(|| Levels::new(*peak_db, *rms_db, now));
+                    }
+                }
+                AudioEvent::StreamHealth { device_id, health } => {
+                    // Ignore late reports from a stream that was just stopped
+                    if self.visualized_devices.contains(device_id) {
+                        self.stream_health.insert(*device_id, *health);
+                    }
+                }
+            }
+            self.record_status();
+        }
+    }
+
+    pub fn refresh_devices(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        self.devices = audio_engine.list_devices()?;
+        if self.selected_device >= self.devices.len() && !self.devices.is_empty() {
+            self.selected_device = self.devices.len() - 1;
+        }
+        // Drop the header selection if the application's last stream went away
+        if let Some(application) = &self.selected_group
+            && !self
+                .devices
+                .iter()
+                .any(|d| d.application_name.as_ref() == Some(application))
+        {
+            self.selected_group = None;
+        }
+        self.refresh_connections(audio_engine);
+        Ok(())
+    }
+
+    /// Re-read the selected device's connections from the routing graph
+    /// The connection selection starts over when another device is selected
+    pub fn refresh_connections(&mut self, audio_engine: &AudioEngine) {
+        let device_id = if self.selected_group.is_some() {
+            None
+        } else {
+            self.devices.get(self.selected_device).map(|d| d.id)
+        };
+        let connections = device_id
+            .and_then(|id| audio_engine.device_connections(id).ok())
+            .unwrap_or_default();
+        let ports = device_id
+            .and_then(|id| self.devices.iter().find(|d| d.id == id))
+            .map_or(&[][..], |d| d.ports.as_slice());
+        self.connections = routing::routing_rows(ports, &connections);
+
+        if device_id != self.connections_device {
+            self.connections_device = device_id;
+            self.selected_connection = 0;
+        }
+        self.selected_connection = self
+            .selected_connection
+            .min(self.connections.len().saturating_sub(1));
+    }
+
+    fn move_connection_selection(&mut self, delta: isize) {
+        if self.connections.is_empty() {
+            self.status_message = String::from("No ports for this device");
+            return;
+        }
+        self.selected_connection = self
+            .selected_connection
+            .saturating_add_signed(delta)
+            .min(self.connections.len() - 1);
+    }
+
+    /// Ask the audio thread to remove the selected connection
+    /// The Routing tab updates once PipeWire reports the link gone
+    fn disconnect_selected(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some((source, destination)) = self
+            .connections
+            .get(self.selected_connection)
+            .and_then(RoutingRow::endpoints)
+        else {
+            self.status_message = String::from("No connection selected");
+            return Ok(());
+        };
+        let status = format!("Disconnecting {} -> {}", source, destination);
+
+        audio_engine.send_command(AudioCommand::Disconnect {
+            source_port: source.to_string(),
+            dest_port: destination.to_string(),
+        })?;
+        self.status_message = status;
+        Ok(())
+    }
+
+    /// First press marks the selected port as one end of a patch; the second
+    /// connects it to the port selected then, or disconnects the two if they
+    /// already are (the same port cancels)
+    fn patch_selected_port(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(row) = self.connections.get(self.selected_connection) else {
+            self.status_message = String::from("No ports for this device");
+            return Ok(());
+        };
+        let selected = (row.port_name.clone(), row.direction);
+
+        let Some((first, first_direction)) = self.patch_source.take() else {
+            self.status_message = format!(
+                "Patching from {}: select the other port and press Enter (Enter again cancels)",
+                selected.0
+            );
+            self.patch_source = Some(selected);
+            return Ok(());
+        };
+        if first == selected.0 {
+            self.status_message = String::from("Patch cancelled");
+            return Ok(());
+        }
+        let (source, destination) = match (first_direction, selected.1) {
+            (PortDirection::Output, PortDirection::Input) => (first, selected.0),
+            (PortDirection::Input, PortDirection::Output) => (selected.0, first),
+            (direction, _) => {
+                let kind = if direction == PortDirection::Output { "outputs" } else { "inputs" };
+                self.status_message =
+                    format!("Cannot patch {} to {}: both are {}", first, selected.0, kind);
+                return Ok(());
+            }
+        };
+
+        // A port renamed or removed since it was picked shows up here
+        let connected = match audio_engine.ports_connected(&source, &destination) {
+            Ok(connected) => connected,
+            Err(e) => {
+                self.status_message = format!("Cannot patch {} -> {}: {}", source, destination, e);
+                return Ok(());
+            }
+        };
+        let (command, verb) = if connected {
+            (
+                AudioCommand::Disconnect { source_port: source.clone(), dest_port: destination.clone() },
+                "Disconnecting",
+            )
+        } else {
+            (
+                AudioCommand::Connect { source_port: source.clone(), dest_port: destination.clone() },
+                "Connecting",
+            )
+        };
+        audio_engine.send_command(command)?;
+        self.status_message = format!("{} {} -> {}", verb, source, destination);
+        Ok(())
+    }
+
+    /// First press marks the selected device as the link source; the second links
+    /// its outputs to the selected device's inputs (the same device cancels)
+    fn link_devices(&mut self, audio_engine: &AudioEngine) {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            self.status_message = String::from("No devices available");
+            return;
+        };
+        let destination = device.id;
+
+        let Some(source) = self.link_source.take() else {
+            self.link_source = Some(destination);
+            self.status_message = format!(
+                "Linking from {}: select the destination and press C (C again cancels)",
+                device.name
+            );
+            return;
+        };
+        if source == destination {
+            self.status_message = String::from("Link cancelled");
+            return;
+        }
+
+        let name = |id| {
+            self.devices
+                .iter()
+                .find(|d| d.id == id)
+                .map_or_else(|| id.to_string(), |d| d.name.clone())
+        };
+        let (source_name, destination_name) = (name(source), name(destination));
+        self.status_message =
+            match audio_engine.connect_devices(source, destination, self.pairing_strategy) {
+                Ok(pairing) if pairing.links.is_empty() => format!(
+                    "No ports of {} pair with {} ({})",
+                    source_name, destination_name, self.pairing_strategy
+                ),
+                Ok(pairing) => {
+                    let unpaired = pairing.unpaired_sources.len() + pairing.unpaired_destinations.len();
+                    let mut status = format!(
+                        "Linked {} → {}: {} link(s) ({})",
+                        source_name,
+                        destination_name,
+                        pairing.links.len(),
+                        self.pairing_strategy
+                    );
+                    if unpaired > 0 {
+                        status.push_str(&format!(", {} port(s) unpaired", unpaired));
+                    }
+                    status
+                }
+                Err(e) => format!("Link failed: {}", e),
+            };
+    }
+
+    fn toggle_recording(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            self.status_message = String::from("No devices available");
+            return Ok(());
+        };
+        let device_id = device.id;
+
+        use crate::audio::AudioCommand;
+        if self.recording_devices.contains_key(&device_id) {
+            audio_engine.send_command(AudioCommand::StopRecording { device_id })?;
+            self.status_message = format!("Stopping recording for {}", device.name);
+        } else if self.visualized_devices.contains(&device_id) {
+            audio_engine.send_command(AudioCommand::StartRecording { device_id, path: None })?;
+            self.status_message = format!("Starting recording for {}", device.name);
+        } else {
+            self.status_message =
+                format!("Enable visualization for {} before recording", device.name);
+        }
+
+        Ok(())
+    }
+
+    /// Format a duration as mm:ss
+    /// Whether `device_id` clipped within the last CLIP_HOLD
+    fn is_clipping(&self, device_id: DeviceId) -> bool {
+        self.last_clip
+            .get(&device_id)
+            .is_some_and(|clipped| clipped.elapsed() < CLIP_HOLD)
+    }
+
+    fn clip_indicator() -> Span<'static> {
+        Span::styled(
+            "CLIP",
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    fn format_elapsed(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+
+    fn toggle_visualization(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        if self.devices.is_empty() {
+            self.status_message = String::from("No devices available");
+            return Ok(());
+        }
+
+        let device = &self.devices[self.selected_device];
+        let device_id = device.id;
+
+        if self.visualized_devices.contains(&device_id) {
+            // Stop visualization
+            use crate::audio::AudioCommand;
+            audio_engine.send_command(AudioCommand::StopVisualization { device_id })?;
+            self.status_message = format!("Stopping visualization for {}", device.name);
+        } else if self.pending_visualizations.cancel(device_id) {
+            // Second press while still waiting for ports cancels the request
+            self.status_message = format!("Cancelled visualization for {}", device.name);
+        } else {
+            self.start_visualization(device_id, audio_engine)?;
+        }
+
+        Ok(())
+    }
+
+    /// Start visualizing a device, or queue the request if its ports
+    /// haven't been enumerated yet (nodes often appear before their ports)
+    pub fn start_visualization(
+        &mut self,
+        device_id: DeviceId,
+        audio_engine: &AudioEngine,
+    ) -> Result<()> {
+        let Some(device) = self.devices.iter().find(|d| d.id == device_id) else {
+            return Ok(());
+        };
+
+        // Pick the first output port (monitor port if available)
+        if let Some(port_id) = visualization_port(device) {
+            audio_engine.send_command(AudioCommand::StartVisualization { device_id, port_id })?;
+            self.status_message = format!("Starting visualization for {}", device.name);
+        } else {
+            self.status_message = format!("Waiting for ports on {}...", device.name);
+            self.pending_visualizations.queue(device_id, Instant::now());
+        }
+
+        Ok(())
+    }
+
+    /// Copy the selected device's full PipeWire port names (one per line) to the clipboard
+    /// Without a clipboard, the names are shown in the status bar and written to the log
+    fn copy_port_names(&mut self) {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            self.status_message = String::from("No devices available");
+            return;
+        };
+        if device.ports.is_empty() {
+            self.status_message = format!("{} has no ports", device.name);
+            return;
+        }
+
+        let port_names: Vec<&str> = device
+            .ports
+            .iter()
+            .map(|p| p.pipewire_port_name.as_str())
+            .collect();
+
+        self.status_message = match self.clipboard.copy(&port_names.join("\n")) {
+            Ok(()) => format!(
+                "Copied {} port name(s) of {} to clipboard",
+                port_names.len(),
+                device.name
+            ),
+            Err(e) => {
+                crate::debug_log!("Clipboard unavailable ({}), port names of {}:", e, device.name);
+                for name in &port_names {
+                    crate::debug_log!("  {}", name);
+                }
+                format!("Clipboard unavailable ({}): {}", e, port_names.join(", "))
+            }
+        };
+    }
+
+    /// Write the selected device's current spectrum to the debug log, bin by bin
+    fn dump_spectrum(&mut self) {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            self.status_message = String::from("No devices available");
+            return;
+        };
+        let Some(data) = self.spectrum_data.get(&device.id) else {
+            self.status_message = format!("No spectrum for {} (visualize it first)", device.name);
+            return;
+        };
+
+        crate::debug_log!(
+            "[SPECTRUM] Dump for {} ({:?}): {} bins at {} Hz",
+            device.name,
+            device.id,
+            data.bins.len(),
+            data.sample_rate
+        );
+        for line in spectrum_table(data, self.calibration(device.id)) {
+            crate::debug_log!("  {}", line);
+        }
+        self.status_message = format!(
+            "Dumped spectrum of {} ({} bins) to {}",
+            device.name,
+            data.bins.len(),
+            crate::debug_log::LOG_PATH
+        );
+    }
+
+    /// Stop every capture stream at once, remembering them so a second press resumes
+    fn toggle_pause_all(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        if !self.visualized_devices.is_empty() {
+            // A device only counts as paused once its stop has been queued; if the
+            // queue fills up, the rest keep running and a second press retries them
+            let mut stopped = Vec::new();
+            let mut result = Ok(());
+            for device_id in self.sorted_visualized_devices() {
+                if let Err(e) =
+                    audio_engine.send_command(AudioCommand::StopVisualization { device_id })
+                {
+                    result = Err(e.into());
+                    break;
+                }
+                self.visualized_devices.remove(&device_id);
+                stopped.push(device_id);
+            }
+            if !stopped.is_empty() {
+                self.status_message =
+                    format!("Stopped {} visualization(s) (S: resume)", stopped.len());
+                self.paused_visualizations = stopped;
+                self.last_viz_change = Some(Instant::now());
+                self.config_dirty = true;
+            }
+            return result;
+        } else if !self.paused_visualizations.is_empty() {
+            let device_ids = std::mem::take(&mut self.paused_visualizations);
+            let count = device_ids.len();
+            for (index, &device_id) in device_ids.iter().enumerate() {
+                // Devices that went away while paused are skipped
+                if let Err(e) = self.start_visualization(device_id, audio_engine) {
+                    // Keep the ones not resumed yet for the next press
+                    self.paused_visualizations = device_ids[index..].to_vec();
+                    return Err(e);
+                }
+            }
+            self.status_message = format!("Resuming {} visualization(s)", count);
+        } else {
+            self.status_message = String::from("No visualizations to stop or resume");
+        }
+
+        Ok(())
+    }
+
+    /// Visualized devices in display (and color) order
+    fn sorted_visualized_devices(&self) -> Vec<DeviceId> {
+        let mut device_ids: Vec<DeviceId> = self.visualized_devices.iter().copied().collect();
+        device_ids.sort_by_key(|id| id.0);
+        device_ids
+    }
+
+    /// Device the spectrum is focused on, if it's still visualized
+    fn focused_spectrum_device(&self) -> Option<DeviceId> {
+        self.spectrum_focus
+            .filter(|device_id| self.visualized_devices.contains(device_id))
+    }
+
+    /// Device the bin table lists: the selected device if it's visualized,
+    /// otherwise the focused (or first) visualized device
+    fn bin_table_device(&self) -> Option<DeviceId> {
+        self.devices
+            .get(self.selected_device)
+            .map(|device| device.id)
+            .filter(|device_id| self.selected_group.is_none() && self.visualized_devices.contains(device_id))
+            .or_else(|| self.focused_spectrum_device())
+            .or_else(|| self.sorted_visualized_devices().first().copied())
+    }
+
+    /// Move the spectrum focus to the next visualized device, or back to the combined view
+    fn cycle_spectrum_focus(&mut self) {
+        let device_ids = self.sorted_visualized_devices();
+        self.spectrum_focus = match self.focused_spectrum_device() {
+            None => device_ids.first().copied(),
+            Some(current) => device_ids
+                .iter()
+                .skip_while(|&&device_id| device_id != current)
+                .nth(1)
+                .copied(),
+        };
+
+        self.status_message = match self.spectrum_focus {
+            _ if device_ids.is_empty() => String::from("No visualized devices to focus"),
+            Some(device_id) => format!("Spectrum focus: {}", self.device_name(device_id)),
+            None => String::from("Spectrum: all devices combined"),
+        };
+    }
+
+    /// Change the spectrum gamma by `delta`, staying within the allowed range
+    /// Status line for the amplification, naming the transform it drives
+    fn amplification_message(&self) -> String {
+        format!(
+            "Spectrum amplification: {:.1} ({})",
+            self.spectrum_amplification,
+            self.amplitude_transform.label()
+        )
+    }
+
+    fn adjust_spectrum_gamma(&mut self, delta: f32) {
+        self.set_spectrum_gamma(self.spectrum_gamma + delta);
+        self.status_message = format!("Spectrum gamma: {:.1}", self.spectrum_gamma);
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+    }
+
+    fn adjust_bar_envelope(&mut self, attack_delta_ms: i64, release_delta_ms: i64) {
+        self.set_bar_envelope(
+            self.bar_attack_ms.saturating_add_signed(attack_delta_ms),
+            self.bar_release_ms.saturating_add_signed(release_delta_ms),
+        );
+        self.status_message = format!(
+            "Bar attack: {} ms, release: {} ms",
+            self.bar_attack_ms, self.bar_release_ms
+        );
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+    }
+
+    fn bar_envelope(&self) -> Envelope {
+        Envelope {
+            attack: Duration::from_millis(self.bar_attack_ms),
+            release: Duration::from_millis(self.bar_release_ms),
+        }
+    }
+
+    /// Drop max hold levels back to the spectrum floor
+    fn reset_max_hold(&mut self) {
+        for held in self.max_hold_bins.values_mut() {
+            held.fill(SPECTRUM_FLOOR_DB);
+        }
+    }
+
+    /// Re-request visualizations that were running before the connection dropped
+    /// Call after the device list is refreshed; devices that haven't reappeared yet
+    /// are kept for the next refresh
+    pub fn restore_after_reconnect(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        if self.disconnected || self.reconnect_visualizations.is_empty() {
+            return Ok(());
+        }
+        let names = std::mem::take(&mut self.reconnect_visualizations);
+        for (index, name) in names.iter().enumerate() {
+            let Some(device_id) = self.find_device_by_name(name).map(|d| d.id) else {
+                self.reconnect_visualizations.push(name.clone());
+                continue;
+            };
+            if let Err(e) = self.start_visualization(device_id, audio_engine) {
+                // Keep this one and the rest for the next refresh
+                self.reconnect_visualizations.extend(names[index..].iter().cloned());
+                return Err(e);
+            }
+            crate::debug_log!("Requested visualization after reconnect for: {}", name);
+        }
+        Ok(())
+    }
+
+    /// Stop every capture stream and drop queued or paused ones (e.g. before a profile switch)
+    pub fn stop_all_visualizations(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        // Devices whose stop couldn't be queued stay visualized
+        for device_id in self.sorted_visualized_devices() {
+            audio_engine.send_command(AudioCommand::StopVisualization { device_id })?;
+            self.visualized_devices.remove(&device_id);
+        }
+        self.pending_visualizations.clear();
+        self.paused_visualizations.clear();
+        self.reconnect_visualizations.clear();
+        Ok(())
+    }
+
+    /// Retry queued visualization requests as ports are discovered
+    /// Should be called every frame; does nothing when no requests are queued
+    pub fn retry_pending_visualizations(&mut self, audio_engine: &AudioEngine) -> Result<()> {
+        if let Some((node_id, queued_at)) = self.pending_node
+            && (audio_engine.device_for_node(node_id)?.is_some()
+                || queued_at.elapsed() >= pending::PORT_WAIT_TIMEOUT)
+        {
+            self.pending_node = None;
+            self.visualize_node(node_id, audio_engine)?;
+        }
+
+        if self.pending_visualizations.is_empty() {
+            return Ok(());
+        }
+
+        // Ports don't emit UI events, so re-read the graph while requests are waiting
+        self.refresh_devices(audio_engine)?;
+
+        for outcome in self
+            .pending_visualizations
+            .poll(&self.devices, Instant::now())
+        {
+            match outcome {
+                PendingOutcome::Ready { device_id, port_id } => {
+                    audio_engine
+                        .send_command(AudioCommand::StartVisualization { device_id, port_id })?;
+                    self.status_message =
+                        format!("Starting visualization for {}", self.device_name(device_id));
+                }
+                PendingOutcome::TimedOut { device_id } => {
+                    self.status_message = format!(
+                        "No output port appeared for {} after {}s, giving up",
+                        self.device_name(device_id),
+                        pending::PORT_WAIT_TIMEOUT.as_secs()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Display name for a device ID
+    fn device_name(&self, device_id: DeviceId) -> String {
+        self.devices
+            .iter()
+            .find(|d| d.id == device_id)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| format!("{:?}", device_id))
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, audio_engine: &AudioEngine) {
+        // Note: Device list is refreshed via events and manual refresh ('r' key), not on every render
+        self.graph_clock = audio_engine.graph_clock().unwrap_or_default();
+        if self.show_commands {
+            self.command_stats = audio_engine.command_stats();
+            self.queued_commands = audio_engine.queued_commands();
+        }
+
+        let terminal_area = frame.area();
+        let terminal_height = terminal_area.height;
+
+        // Responsive layout based on terminal height
+        if terminal_area.width < MIN_TERMINAL_WIDTH || terminal_height < MIN_TERMINAL_HEIGHT {
+            // Not even the bare spectrum fits; say so rather than drawing a blank screen
+            self.render_too_small(frame);
+        } else if terminal_height < MIN_HEIGHT_FOR_FULL_LAYOUT {
+            // Minimal view: spectrum only
+            self.render_minimal_layout(frame);
+        } else {
+            // Full view: device list + tabs + spectrum
+            self.render_full_layout(frame);
+        }
+    }
+
+    fn render_too_small(&self, frame: &mut Frame) {
+        let message = format!(
+            "Terminal too small (need at least {}×{})",
+            MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+        );
+        let paragraph = Paragraph::new(message)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, frame.area());
+    }
+
+    fn render_minimal_layout(&self, frame: &mut Frame) {
+        // In minimal mode, just show spectrum filling the entire screen
+        self.render_spectrum(frame, frame.area(), false);
+        if self.show_health {
+            self.render_health_overlay(frame, frame.area());
+        }
+        if self.show_commands {
+            self.render_command_overlay(frame, frame.area());
+        }
+        if self.show_help {
+            self.render_help_overlay(frame, frame.area());
+        }
+        if !self.first_run_seen {
+            self.render_first_run_overlay(frame, frame.area());
+        }
+    }
+
+    fn render_full_layout(&mut self, frame: &mut Frame) {
+        // Main vertical split: content area + spectrum at bottom + status bar
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),                  // Content area (device list + main content)
+                Constraint::Length(SPECTRUM_HEIGHT), // Spectrum visualization
+                Constraint::Length(3),               // Status bar
+            ])
+            .split(frame.area());
+
+        // Horizontal split: device list on left + main content on right
+        let content_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Length(DEVICE_LIST_WIDTH), // Device list
-                Constraint::Min(0),                    // Main content area
+                Constraint::Length(DEVICE_LIST_WIDTH), // Device list
+                Constraint::Min(0),                    // Main content area
+            ])
+            .split(main_chunks[0]);
+
+        // Render device list on the left
+        self.render_device_list(frame, content_chunks[0]);
+
+        // Render main content area on the right (with tabs)
+        self.render_main_content(frame, content_chunks[1]);
+
+        // Render spectrum at the bottom
+        self.render_spectrum(frame, main_chunks[1], true);
+        if self.show_health {
+            self.render_health_overlay(frame, main_chunks[1]);
+        }
+        if self.show_commands {
+            self.render_command_overlay(frame, main_chunks[1]);
+        }
+        if self.show_help {
+            self.render_help_overlay(frame, main_chunks[0]);
+        }
+        if !self.first_run_seen {
+            self.render_first_run_overlay(frame, main_chunks[0]);
+        }
+
+        // Render status bar at the very bottom
+        self.render_status_bar(frame, main_chunks[2]);
+    }
+
+    fn render_device_list(&mut self, frame: &mut Frame, area: Rect) {
+        // Nothing reported yet: the registry is still being read (or PipeWire is slow)
+        if self.devices.is_empty() {
+            let paragraph = Paragraph::new("Discovering audio devices…")
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Devices")
+                        .title_alignment(Alignment::Left),
+                );
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let rows = self.device_rows();
+        let selected_row = self.selected_row(&rows);
+
+        // Scroll over the rows actually listed, so hidden devices don't count
+        let visible = (area.height.saturating_sub(2) as usize).max(1);
+        self.device_list_page = visible;
+        self.device_list_scroll = scroll_offset(self.device_list_scroll, selected_row, visible, rows.len());
+        let title = if rows.len() > visible {
+            format!(
+                "Devices ({}-{} of {})",
+                self.device_list_scroll + 1,
+                (self.device_list_scroll + visible).min(rows.len()),
+                rows.len()
+            )
+        } else {
+            String::from("Devices")
+        };
+
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|row| match row {
+                DeviceRow::Group {
+                    application,
+                    members,
+                    expanded,
+                } => ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if *expanded { "[-]" } else { "[+]" },
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(" "),
+                    Span::styled(
+                        application.as_str(),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(" ({} streams)", members),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])),
+                DeviceRow::Device { index, nested } => {
+                    self.device_list_item(&self.devices[*index], *nested)
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .title_alignment(Alignment::Left),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ");
+
+        frame.render_stateful_widget(
+            list,
+            area,
+            &mut ratatui::widgets::ListState::default()
+                .with_selected(selected_row)
+                .with_offset(self.device_list_scroll),
+        );
+    }
+
+    /// Device list entry for a single device
+    fn device_list_item<'a>(&'a self, device: &'a DeviceInfo, nested: bool) -> ListItem<'a> {
+        let is_hidden = self.is_hidden(device);
+        let device_type = format!("{:?}", device.device_type);
+        let is_visualized = self.visualized_devices.contains(&device.id);
+        let indicator = if is_visualized {
+            "[x]"
+        } else if self.pending_visualizations.contains(device.id) {
+            "[~]"
+        } else {
+            "[ ]"
+        };
+
+        // Grey out hidden devices when showing them
+        let (name_color, indicator_color) = if is_hidden {
+            (Color::DarkGray, Color::DarkGray)
+        } else if is_visualized {
+            (Color::White, Color::Cyan)
+        } else {
+            (Color::White, Color::DarkGray)
+        };
+
+        // Volume indicator
+        let volume_str = if let Some(vol) = self.volume_settings.get(&device.id) {
+            if (vol.gain_db - 0.0).abs() < 0.1 {
+                String::new()  // Don't show if at unity gain
+            } else {
+                format!(" [{:+.1}dB]", vol.gain_db)
+            }
+        } else {
+            String::new()
+        };
+
+        let mut spans = vec![
+            Span::styled(
+                indicator,
+                Style::default().fg(indicator_color),
+            ),
+            Span::raw(" "),
+        ];
+        // Up front, where a narrow list can't cut it off
+        if self.is_clipping(device.id) {
+            spans.push(Self::clip_indicator());
+            spans.push(Span::raw(" "));
+        }
+        if self.favorite_devices.contains(&device.name) {
+            spans.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+        }
+        spans.extend([
+            Span::styled(
+                &device.name,
+                Style::default()
+                    .fg(name_color)
+                    .add_modifier(if is_hidden { Modifier::empty() } else { Modifier::BOLD }),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                format!("({})", device_type),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]);
+
+        if !volume_str.is_empty() {
+            spans.push(Span::styled(
+                volume_str,
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        // Crossfeed only runs while the EQ stream exists
+        if self.eq_enabled_devices.contains(&device.id)
+            && self
+                .crossfeed_settings
+                .get(&device.name)
+                .is_some_and(|settings| settings.enabled)
+        {
+            spans.push(Span::styled(" [XF]", Style::default().fg(Color::Magenta)));
+        }
+        // So does saturation
+        if self.eq_enabled_devices.contains(&device.id)
+            && let Some(drive) = self.saturation_drive.get(&device.name)
+        {
+            spans.push(Span::styled(
+                format!(" [SAT {:.0}%]", drive * 100.0),
+                Style::default().fg(Color::LightRed),
+            ));
+        }
+        // And width
+        if self.eq_enabled_devices.contains(&device.id)
+            && let Some(width) = self.stereo_width.get(&device.name)
+        {
+            spans.push(Span::styled(
+                format!(" [W {:.0}%]", width * 100.0),
+                Style::default().fg(Color::LightBlue),
+            ));
+        }
+
+        // Recording indicator with elapsed time
+        if let Some(started) = self.recording_devices.get(&device.id) {
+            spans.push(Span::styled(
+                format!(" ● REC {}", Self::format_elapsed(started.elapsed())),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        // Streams nested under an application header are indented
+        if nested {
+            spans.insert(0, Span::raw("  "));
+        }
+
+        ListItem::new(Line::from(spans))
+    }
+
+    fn render_main_content(&self, frame: &mut Frame, area: Rect) {
+        // Split main content into tab bar and content area
+        let content_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Tab bar
+                Constraint::Min(0),    // Tab content
+            ])
+            .split(area);
+
+        // Render tab bar
+        self.render_tabs(frame, content_chunks[0]);
+
+        // Render current tab content
+        match self.current_tab {
+            DeviceTab::Routing => self.render_routing_tab(frame, content_chunks[1]),
+            DeviceTab::Filters => self.render_filters_tab(frame, content_chunks[1]),
+        }
+    }
+
+    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
+        let tabs = DeviceTab::all();
+        let titles: Vec<_> = tabs.iter().map(|t| t.title()).collect();
+
+        let selected_index = match self.current_tab {
+            DeviceTab::Routing => 0,
+            DeviceTab::Filters => 1,
+        };
+
+        let device_name = if !self.devices.is_empty() && self.selected_device < self.devices.len() {
+            &self.devices[self.selected_device].name
+        } else {
+            "No device selected"
+        };
+
+        let tabs_widget = Tabs::new(titles)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("wavewire - {}", device_name)),
+            )
+            .select(selected_index)
+            .style(Style::default().fg(Color::White))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            );
+
+        frame.render_widget(tabs_widget, area);
+    }
+
+    fn render_routing_tab(&self, frame: &mut Frame, area: Rect) {
+        if self.connections.is_empty() {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title("Routing")
+                .title_alignment(Alignment::Left);
+
+            let content = if self.devices.is_empty() {
+                "No devices available\n\nPress 'r' to refresh device list"
+            } else {
+                "No ports\n\nThis device has no ports to connect"
+            };
+
+            let paragraph = Paragraph::new(content)
+                .block(block)
+                .alignment(Alignment::Center);
+
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        // Ports on the left, peers on the right; the list scrolls to keep the selection visible
+        let connected = self.connections.iter().filter(|row| row.connection.is_some()).count();
+        let items: Vec<ListItem> = routing::diagram_rows(&self.connections)
+            .into_iter()
+            .map(ListItem::new)
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "Routing ({} connections) - J/K: select  Enter: patch  D: disconnect",
+                        connected
+                    ))
+                    .title_alignment(Alignment::Left),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ");
+
+        frame.render_stateful_widget(
+            list,
+            area,
+            &mut ratatui::widgets::ListState::default().with_selected(Some(self.selected_connection)),
+        );
+    }
+
+    fn render_filters_tab(&self, frame: &mut Frame, area: Rect) {
+        // Name the device being edited, marking it when pinned
+        let mut title = vec![Span::raw("Filters - "), Span::raw(self.eq_device_name())];
+        if self.eq_pinned {
+            title.push(Span::styled(
+                " [pinned]",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ));
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(title))
+            .title_alignment(Alignment::Left);
+
+        if self.devices.is_empty() {
+            let paragraph =
+                Paragraph::new("No devices available\n\nPress 'r' to refresh device list")
+                    .block(block)
+                    .alignment(Alignment::Center);
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Show filter options
+        let Some(device) = self.eq_device() else {
+            return;
+        };
+        let device_id = device.id;
+        let eq_enabled = self.eq_enabled_devices.contains(&device_id);
+
+        let filter_items: Vec<ListItem> = vec![ListItem::new(Line::from(vec![
+            Span::styled("[", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                if eq_enabled { "x" } else { " " },
+                Style::default().fg(if eq_enabled {
+                    Color::Cyan
+                } else {
+                    Color::DarkGray
+                }),
+            ),
+            Span::styled("]", Style::default().fg(Color::DarkGray)),
+            Span::raw(" "),
+            Span::styled(
+                "Equalizer",
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            ),
+            if eq_enabled {
+                Span::styled(
+                    " (Press 'e' in device list to adjust)",
+                    Style::default().fg(Color::DarkGray),
+                )
+            } else {
+                Span::styled(
+                    " (Press 'e' in device list to enable)",
+                    Style::default().fg(Color::DarkGray),
+                )
+            },
+        ]))];
+
+        let list = List::new(filter_items);
+
+        frame.render_widget(list, inner);
+
+        // Show EQ settings if enabled
+        if eq_enabled
+            && let Some(settings) = self.eq_settings.get(&device_id)
+        {
+            // Split the area to show EQ bands
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Filter list
+                    Constraint::Min(0),    // EQ bands display
+                ])
+                .split(inner);
+
+            // Render EQ bands
+            let mut band_lines = vec![
+                Line::from(Span::styled(
+                    "EQ Bands (Press 'e' to adjust):",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+            ];
+
+            let mut band_cells = Vec::with_capacity(settings.band_count());
+            for (i, band) in settings.bands.iter().enumerate() {
+                // Label by the band's own frequency, since imported profiles retune bands
+                let freq_hz = band.frequency.round() as u32;
+                let freq_str = if freq_hz >= 1000 && freq_hz.is_multiple_of(1000) {
+                    format!("{}k", freq_hz / 1000)
+                } else if freq_hz >= 1000 {
+                    format!("{:.1}k", freq_hz as f32 / 1000.0)
+                } else {
+                    format!("{}Hz", freq_hz)
+                };
+
+                let gain_str = format!("{:+.1}dB", band.gain_db);
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{:>5}: ", freq_str),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(
+                        gain_str,
+                        // Inactive (muted or un-soloed) bands are greyed like flat ones,
+                        // as is the whole curve while it's bypassed
+                        Style::default().fg(if settings.bypass
+                            || !settings.is_band_active(i)
+                            || band.gain_db.abs() < 0.1
+                        {
+                            Color::DarkGray
+                        } else if band.gain_db > 0.0 {
+                            Color::Green
+                        } else {
+                            Color::Red
+                        }),
+                    ),
+                ];
+                if let Some(tag) = band.filter_type.tag() {
+                    spans.push(Span::styled(format!(" {}", tag), Style::default().fg(Color::Cyan)));
+                }
+                if !band.enabled {
+                    spans.push(Span::styled(" [M]", Style::default().fg(Color::Red)));
+                }
+                if band.soloed {
+                    spans.push(Span::styled(" [S]", Style::default().fg(Color::Yellow)));
+                }
+                band_cells.push(spans);
+            }
+
+            // Larger EQs are laid out in columns, keeping the list ten lines tall
+            let per_line = settings.band_count().div_ceil(GRAPHIC_EQ_BANDS.len()).max(1);
+            for row in band_cells.chunks(per_line) {
+                let mut spans = Vec::new();
+                for (column, cell) in row.iter().enumerate() {
+                    if column > 0 {
+                        spans.push(Span::raw("   "));
+                    }
+                    let width: usize = cell.iter().map(|span| span.content.chars().count()).sum();
+                    spans.extend(cell.iter().cloned());
+                    if column + 1 < row.len() {
+                        spans.push(Span::raw(" ".repeat(EQ_BAND_CELL_WIDTH.saturating_sub(width))));
+                    }
+                }
+                band_lines.push(Line::from(spans));
+            }
+
+            // Built-in presets, by the number that applies them
+            let mut presets = vec![Span::styled("Presets:", Style::default().fg(Color::Yellow))];
+            for (number, preset) in EqPreset::ALL.iter().enumerate() {
+                presets.push(Span::styled(
+                    format!(" {}", number + 1),
+                    Style::default().fg(Color::Cyan),
+                ));
+                presets.push(Span::raw(format!(" {}", preset.name())));
+            }
+            band_lines.push(Line::from(""));
+            band_lines.push(Line::from(presets));
+            band_lines.push(Line::from(vec![
+                Span::styled("Saved:", Style::default().fg(Color::Yellow)),
+                Span::raw(if self.saved_eq_presets.is_empty() {
+                    String::from(" none")
+                } else {
+                    format!(" {}", self.saved_eq_presets.join(", "))
+                }),
+                Span::styled("  (E: save, :: load)", Style::default().fg(Color::DarkGray)),
+            ]));
+
+            // Peaking filters only shift phase; show by how much so A/B users know
+            let (freq, delay_ms) = settings.max_group_delay(self.eq_sample_rate(device_id));
+            band_lines.push(Line::from(""));
+            band_lines.push(Line::from(vec![
+                Span::styled(
+                    if settings.bypass { "Bypassed (B)" } else { "Active (A)" },
+                    Style::default().fg(if settings.bypass { Color::Yellow } else { Color::Green }),
+                ),
+                Span::styled(
+                    format!(
+                        " - EQ delay up to {:.2} ms at {:.0} Hz vs dry",
+                        delay_ms, freq
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+
+            let paragraph = Paragraph::new(band_lines).alignment(Alignment::Left);
+            frame.render_widget(paragraph, chunks[1]);
+        }
+    }
+
+    fn render_spectrum(&self, frame: &mut Frame, area: Rect, show_borders: bool) {
+        // If no devices are being visualized, show a message
+        if self.visualized_devices.is_empty() {
+            if show_borders {
+                let block = Block::default()
+                    .borders(Borders::ALL)
+                    .title("Frequency Spectrum - Combined View")
+                    .title_alignment(Alignment::Left);
+
+                let paragraph = Paragraph::new(
+                    "No devices visualized\n\nPress Space on a device to start visualization",
+                )
+                .block(block)
+                .alignment(Alignment::Center);
+
+                frame.render_widget(paragraph, area);
+            } else {
+                // In minimal mode, just show a simple message without borders
+                let paragraph = Paragraph::new(
+                    "No devices visualized\n\nPress Space on a device to start visualization",
+                )
+                .alignment(Alignment::Center);
+
+                frame.render_widget(paragraph, area);
+            }
+            return;
+        }
+
+        // Get all visualized devices in a consistent order (or just the focused one)
+        let all_device_ids = self.sorted_visualized_devices();
+        let focused = self.focused_spectrum_device();
+        let device_ids = match focused {
+            Some(device_id) => vec![device_id],
+            None => all_device_ids.clone(),
+        };
+
+        // Check if we have spectrum data for any device
+        let has_data = device_ids
+            .iter()
+            .any(|id| self.spectrum_data.contains_key(id));
+        if !has_data {
+            if show_borders {
+                let block = Block::default()
+                    .borders(Borders::ALL)
+                    .title("Frequency Spectrum - Waiting for data...")
+                    .title_alignment(Alignment::Left);
+
+                frame.render_widget(block, area);
+            }
+            return;
+        }
+
+        // Build the title with device names and colors
+        let device_info: Vec<String> = device_ids
+            .iter()
+            .map(|&device_id| {
+                let name = self
+                    .devices
+                    .iter()
+                    .find(|d| d.id == device_id)
+                    .map(|d| d.name.as_str())
+                    .unwrap_or("Unknown");
+                let color_name = self.get_device_color_name(device_id);
+                let mut info = if self.eq_taps.contains(&device_id) {
+                    let post_color_name = self.device_color(device_id).shade().letter();
+                    format!("[{}] {} pre [{}] post", color_name, name, post_color_name)
+                } else if self.right_channel_bins(device_id).is_some() {
+                    let right_color_name = self.device_color(device_id).shade().letter();
+                    format!("[{}] {} L [{}] R", color_name, name, right_color_name)
+                } else {
+                    format!("[{}] {}", color_name, name)
+                };
+                let calibration = self.calibration(device_id);
+                if calibration != 0.0 {
+                    info.push_str(&format!(" [cal {:+.1} dB]", calibration));
+                }
+                info
+            })
+            .collect();
+
+        // The bin table takes the far right, narrowing (and dropping the index) on small terminals
+        let area = if self.show_bin_table {
+            let border = if show_borders { 2 } else { 0 };
+            let width = (bin_table::FULL_WIDTH as u16 + border).min(area.width / 2);
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(width)])
+                .split(area);
+            self.render_bin_table(frame, chunks[1], show_borders);
+            chunks[0]
+        } else {
+            area
+        };
+
+        // The goniometer takes a square on the right; the spectrum keeps the rest
+        let area = if self.goniometer && self.stereo_capture {
+            let border = if show_borders { 2 } else { 0 };
+            // Braille cells are twice as tall as wide, so a square is 2 columns per row
+            let width = (area.height.saturating_sub(border) * 2 + border).min(area.width / 2);
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(width)])
+                .split(area);
+            self.render_goniometer(frame, chunks[1], &device_ids, show_borders);
+            chunks[0]
+        } else {
+            area
+        };
+
+        // Level meters take a fixed-width column on the right
+        let area = if self.level_meters {
+            let border = if show_borders { 2 } else { 0 };
+            let width = (LEVEL_METER_WIDTH + border).min(area.width / 2);
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(width)])
+                .split(area);
+            self.render_level_meters(frame, chunks[1], &device_ids, show_borders);
+            chunks[0]
+        } else {
+            area
+        };
+
+        if self.waveform_view {
+            let title = format!("Waveform - {}", device_info.join(" | "));
+            self.render_waveform(frame, area, &title, &device_ids, show_borders);
+            return;
+        }
+
+        if self.difference_mode
+            && focused.is_none()
+            && let [device_a, device_b] = device_ids[..]
+        {
+            self.render_difference_spectrum(frame, area, device_a, device_b, show_borders);
+            return;
+        }
+
+        let mut title = format!("Frequency Spectrum - {}", device_info.join(" | "));
+        if self.max_hold {
+            title.push_str(" [max hold]");
+        }
+        if self.peak_hold {
+            title.push_str(" [peak hold]");
+        }
+        if self.amplitude_transform != AmplitudeTransform::Linear {
+            title.push_str(&format!(" [{}]", self.amplitude_transform.label()));
+        }
+        if !self.frequency_zoom.is_full_range() {
+            title.push_str(&format!(" [zoom {}]", self.frequency_zoom.label()));
+        }
+        if let Some(device_id) = focused {
+            let position = all_device_ids.iter().position(|&id| id == device_id).unwrap_or(0);
+            title.push_str(&format!(" [focus {}/{}]", position + 1, all_device_ids.len()));
+        } else if self.difference_mode {
+            title.push_str(" [difference needs exactly 2 devices]");
+        }
+
+        // Render the combined spectrum
+        self.render_combined_spectrum(frame, area, &title, &device_ids, show_borders);
+    }
+
+    /// Waveform of all visualized devices at full scale, one color per device
+    fn render_waveform(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        device_ids: &[DeviceId],
+        show_borders: bool,
+    ) {
+        let inner = if show_borders {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(title.to_string())
+                .title_alignment(Alignment::Left);
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        } else {
+            area
+        };
+
+        let width = inner.width as usize;
+        let height = inner.height as usize;
+
+        let mut cells = vec![vec![(0u8, Color::Reset); width]; height];
+        for device_id in device_ids {
+            let Some(samples) = self.waveforms.get(device_id) else {
+                continue;
+            };
+            let color = self.get_device_color(*device_id);
+            overlay_dots(&mut cells, &waveform::plot(samples, width, height), color);
+        }
+
+        frame.render_widget(Paragraph::new(self.dot_lines(&cells)), inner);
+    }
+
+    /// Braille lines for colored dot cells
+    fn dot_lines(&self, cells: &[Vec<(u8, Color)>]) -> Vec<Line<'static>> {
+        cells
+            .iter()
+            .map(|row| {
+                Line::from(
+                    row.iter()
+                        .map(|&(bits, color)| {
+                            Span::styled(
+                                self.glyphs.dots(bits).to_string(),
+                                Style::default().fg(color),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+
+    /// Goniometer (L/R scatter rotated 45°) of all visualized devices, one color per device
+    fn render_goniometer(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        device_ids: &[DeviceId],
+        show_borders: bool,
+    ) {
+        let inner = if show_borders {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title("Goniometer")
+                .title_alignment(Alignment::Left);
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        } else {
+            area
+        };
+
+        let width = inner.width as usize;
+        let height = inner.height as usize;
+
+        let mut cells = vec![vec![(0u8, Color::Reset); width]; height];
+        for device_id in device_ids {
+            let Some(frames) = self.stereo_frames.get(device_id) else {
+                continue;
+            };
+            let color = self.get_device_color(*device_id);
+            overlay_dots(&mut cells, &goniometer::plot(frames, width, height), color);
+        }
+
+        frame.render_widget(Paragraph::new(self.dot_lines(&cells)), inner);
+    }
+
+    /// Peak and RMS meters of all visualized devices: the device name, then a peak
+    /// bar with its held peak marker and an RMS bar, each with its level in dBFS
+    fn render_level_meters(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        device_ids: &[DeviceId],
+        show_borders: bool,
+    ) {
+        let inner = if show_borders {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title("Levels (dBFS)")
+                .title_alignment(Alignment::Left);
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        } else {
+            area
+        };
+
+        let width = inner.width as usize;
+        // Room for the "RMS " label in front and " -12.3" behind the bar
+        let bar_width = width.saturating_sub(10);
+        let mut lines = Vec::new();
+        for &device_id in device_ids {
+            let clipping = self.is_clipping(device_id);
+            // Leave room for the CLIP indicator after the name
+            let name_width = if clipping { width.saturating_sub(5) } else { width };
+            let name: String = self.device_name(device_id).chars().take(name_width).collect();
+            let mut name_line = vec![Span::styled(name, Style::default().fg(self.get_device_color(device_id)))];
+            if clipping {
+                name_line.push(Span::raw(" "));
+                name_line.push(Self::clip_indicator());
+            }
+            lines.push(Line::from(name_line));
+            let Some(levels) = self.levels.get(&device_id) else {
+                lines.push(Line::from(Span::styled("no level yet", Style::default().fg(Color::DarkGray))));
+                continue;
+            };
+            lines.push(Self::meter_line("Pk", levels.peak_db, Some(levels.held_peak_db), bar_width));
+            lines.push(Self::meter_line("RMS", levels.rms_db, None, bar_width));
+        }
+
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    /// One meter bar, colored by zone, with an optional held peak marker
+    fn meter_line(label: &str, level_db: f32, held_db: Option<f32>, bar_width: usize) -> Line<'static> {
+        let zone_color = |level_db: f32| match level_meter::zone(level_db) {
+            level_meter::Zone::Safe => Color::Green,
+            level_meter::Zone::Hot => Color::Yellow,
+            level_meter::Zone::Clipping => Color::Red,
+        };
+        let filled = level_meter::filled_cells(level_db, bar_width);
+        let held = held_db
+            .map(|held_db| level_meter::filled_cells(held_db, bar_width))
+            .filter(|&held| held > filled);
+
+        let mut spans = vec![Span::styled(format!("{:<3} ", label), Style::default().fg(Color::Cyan))];
+        for cell in 0..bar_width {
+            let color = zone_color(level_meter::cell_level_db(cell, bar_width));
+            let span = if cell < filled {
+                Span::styled("█", Style::default().fg(color))
+            } else if held == Some(cell + 1) {
+                Span::styled("▏", Style::default().fg(color))
+            } else {
+                Span::styled("·", Style::default().fg(Color::DarkGray))
+            };
+            spans.push(span);
+        }
+        let value = if level_db > level_meter::FLOOR_DB {
+            format!(" {:>5.1}", level_db)
+        } else {
+            String::from("  -inf")
+        };
+        spans.push(Span::raw(value));
+        Line::from(spans)
+    }
+
+    /// Scrollable table of one device's bins: index, center frequency and level, updated live
+    fn render_bin_table(&self, frame: &mut Frame, area: Rect, show_borders: bool) {
+        let device_id = self.bin_table_device();
+        let inner = if show_borders {
+            let title = match device_id {
+                Some(device_id) => format!("Bins - {}", self.device_name(device_id)),
+                None => String::from("Bins"),
+            };
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_alignment(Alignment::Left);
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        } else {
+            area
+        };
+
+        let Some(spectrum) = device_id.and_then(|device_id| self.spectrum_data.get(&device_id)) else {
+            frame.render_widget(Paragraph::new("Waiting for data..."), inner);
+            return;
+        };
+
+        let width = inner.width as usize;
+        let rows = (inner.height as usize).saturating_sub(1);
+        let color = device_id.map_or(Color::White, |device_id| self.get_device_color(device_id));
+        let mut lines = vec![Line::from(Span::styled(
+            bin_table::header(width),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))];
+        lines.extend(
+            bin_table::rows(
+                spectrum,
+                self.bin_table_scroll,
+                rows,
+                width,
+                device_id.map_or(0.0, |device_id| self.calibration(device_id)),
+            )
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, Style::default().fg(color)))),
+        );
+
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    /// Small box in the top-right of the spectrum with per-stream health readouts
+    fn render_health_overlay(&self, frame: &mut Frame, area: Rect) {
+        let mut device_ids: Vec<DeviceId> = self.visualized_devices.iter().copied().collect();
+        device_ids.sort_by_key(|id| id.0);
+
+        // The graph quantum sets the latency every stream sees
+        let clock_line = match self.graph_clock.quantum_ms() {
+            Some(quantum_ms) => Line::from(vec![
+                Span::raw(format!(
+                    "PipeWire: {} samples @ {} Hz = {:.1} ms",
+                    self.graph_clock.effective_quantum(),
+                    self.graph_clock.effective_rate(),
+                    quantum_ms
+                )),
+                Span::styled(
+                    if self.graph_clock.force_quantum > 0 { " (forced)" } else { "" },
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]),
+            None => Line::from(Span::styled(
+                "PipeWire: quantum unknown",
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+
+        let mut lines: Vec<Line> = if device_ids.is_empty() {
+            vec![Line::from(Span::styled(
+                "No active streams",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            device_ids
+                .iter()
+                .map(|&device_id| {
+                    let name = Span::styled(
+                        format!("{:.16}: ", self.device_name(device_id)),
+                        Style::default().fg(self.get_device_color(device_id)),
+                    );
+                    let Some(health) = self.stream_health.get(&device_id) else {
+                        return Line::from(vec![name, Span::raw("waiting...")]);
+                    };
+
+                    // Starved/dropped updates are what make the spectrum choppy
+                    let problem_style = if health.starved_cycles + health.dropped_cycles > 0 {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    // An idle stream is still visualizing, just not running the FFT
+                    let rate = if health.idle {
+                        format!("{:>6}", "idle")
+                    } else {
+                        format!("{:>4.1}/s", health.fft_rate_hz)
+                    };
+                    Line::from(vec![
+                        name,
+                        Span::raw(format!("buf {:>3.0}%  {}  ", health.buffer_fill * 100.0, rate)),
+                        Span::styled(
+                            format!(
+                                "starved {} dropped {}",
+                                health.starved_cycles, health.dropped_cycles
+                            ),
+                            problem_style,
+                        ),
+                    ])
+                })
+                .collect()
+        };
+        lines.insert(0, clock_line);
+
+        let width = lines
+            .iter()
+            .map(|line| line.width() as u16 + 2)
+            .max()
+            .unwrap_or(0)
+            .min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay = Rect {
+            x: area.x + area.width - width,
+            y: area.y,
+            width,
+            height,
+        };
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Stream health")
+                .style(Style::default().fg(Color::White)),
+        );
+        frame.render_widget(Clear, overlay);
+        frame.render_widget(paragraph, overlay);
+    }
+
+    /// Small box in the top-left of the spectrum with the per-variant command tally
+    /// In-flight and dropped commands are highlighted: those have not taken
+    /// effect yet, or never will
+    fn render_command_overlay(&self, frame: &mut Frame, area: Rect) {
+        let (in_channel, held_back) = self.queued_commands;
+        let mut lines = vec![Line::from(Span::styled(
+            format!("Queue: {} in channel, {} held back", in_channel, held_back),
+            if held_back > 0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            },
+        ))];
+        if self.command_stats.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No commands sent",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for (name, counts) in &self.command_stats {
+            let problem_style = if counts.in_flight() + counts.dropped > 0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let problems = format!("in flight {} dropped {}", counts.in_flight(), counts.dropped);
+            lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "{:<18} sent {:>4} handled {:>4}  ",
+                    name, counts.sent, counts.handled
+                )),
+                Span::styled(problems, problem_style),
+            ]));
+        }
+
+        let width = lines
+            .iter()
+            .map(|line| line.width() as u16 + 2)
+            .max()
+            .unwrap_or(0)
+            .min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay = Rect {
+            x: area.x,
+            y: area.y,
+            width,
+            height,
+        };
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Audio commands")
+                .style(Style::default().fg(Color::White)),
+        );
+        frame.render_widget(Clear, overlay);
+        frame.render_widget(paragraph, overlay);
+    }
+
+    /// Welcome box shown until the first key press of the first run
+    fn render_first_run_overlay(&self, frame: &mut Frame, area: Rect) {
+        let key = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+        let entry = |actions: &[Action], text: &'static str| {
+            Line::from(vec![
+                Span::styled(format!("{:>7}", self.keymap.label(actions)), key),
+                Span::raw(format!("  {}", text)),
             ])
-            .split(main_chunks[0]);
+        };
+        let lines = vec![
+            Line::from("Wavewire shows and routes your PipeWire audio devices."),
+            Line::from(""),
+            entry(&[Action::Up, Action::Down], "pick a device"),
+            entry(&[Action::ToggleViz], "visualize its spectrum"),
+            entry(&[Action::NextTab], "switch tabs (routing, EQ, filters)"),
+            entry(&[Action::Help], "all key bindings"),
+            entry(&[Action::Quit], "quit"),
+            Line::from(""),
+            Line::styled("Press any key to start", Style::default().fg(Color::DarkGray)),
+        ];
+
+        let width = 48.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Welcome")
+                .style(Style::default().fg(Color::White)),
+        );
+        frame.render_widget(Clear, overlay);
+        frame.render_widget(paragraph, overlay);
+    }
+
+    /// Every key binding from the keymap, flowed into as many columns as the area needs
+    /// Device-list actions that would do nothing right now are greyed out
+    fn render_help_overlay(&self, frame: &mut Frame, area: Rect) {
+        let actions = self.available_actions();
+        let heading = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let mut entries: Vec<(String, Style)> = Vec::new();
+        for (title, mode) in [("Device list", Mode::DeviceList), ("EQ", Mode::Eq)] {
+            let bindings = self.keymap.bindings(mode);
+            let keys_width = bindings.iter().map(|(_, _, keys)| keys.chars().count()).max().unwrap_or(0);
+            entries.push((title.to_string(), heading));
+            for (action, name, keys) in bindings {
+                let style = if mode == Mode::Eq || actions.allows(action) {
+                    Style::default()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                entries.push((format!("{:>width$}  {}", keys, name, width = keys_width), style));
+            }
+        }
+
+        let rows = (area.height.saturating_sub(2) as usize).max(1);
+        let column_width = entries.iter().map(|(text, _)| text.chars().count()).max().unwrap_or(0) + 2;
+        let columns: Vec<&[(String, Style)]> = entries.chunks(rows).collect();
+        let lines: Vec<Line> = (0..rows.min(entries.len()))
+            .map(|row| {
+                Line::from(
+                    columns
+                        .iter()
+                        .filter_map(|column| column.get(row))
+                        .map(|(text, style)| {
+                            Span::styled(format!("{:<width$}", text, width = column_width), *style)
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        let width = ((column_width * columns.len()) as u16 + 2).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Key bindings ({}: close)", self.keymap.label(&[Action::Help])))
+                .style(Style::default().fg(Color::White)),
+        );
+        frame.render_widget(Clear, overlay);
+        frame.render_widget(paragraph, overlay);
+    }
+
+    /// Color slot of a visualized device (its position among all visualized devices)
+    /// Stays the same whether the spectrum shows all devices or focuses on one
+    fn device_color_index(&self, device_id: DeviceId) -> usize {
+        self.visualized_devices
+            .iter()
+            .filter(|id| id.0 < device_id.0)
+            .count()
+    }
+
+    /// The device's assigned color, else the next one in the auto-cycled palette
+    fn device_color(&self, device_id: DeviceId) -> DeviceColor {
+        self.devices
+            .iter()
+            .find(|d| d.id == device_id)
+            .and_then(|d| self.device_colors.get(&d.name))
+            .copied()
+            .unwrap_or_else(|| DeviceColor::auto(self.device_color_index(device_id)))
+    }
+
+    fn get_device_color(&self, device_id: DeviceId) -> Color {
+        self.device_color(device_id).color()
+    }
+
+    fn get_device_color_name(&self, device_id: DeviceId) -> &'static str {
+        self.device_color(device_id).letter()
+    }
+
+    /// Step the selected device's spectrum color through the palette, then back to automatic
+    fn cycle_device_color(&mut self) {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return;
+        };
+        let device_name = device.name.clone();
+        match DeviceColor::next(self.device_colors.get(&device_name).copied()) {
+            Some(color) => {
+                self.status_message = format!("{} color: {}", device_name, color.name());
+                self.device_colors.insert(device_name, color);
+            }
+            None => {
+                self.status_message = format!("{} color: automatic", device_name);
+                self.device_colors.remove(&device_name);
+            }
+        }
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+    }
+
+    /// dB added to the device's level readouts (0 = uncalibrated dBFS)
+    fn calibration(&self, device_id: DeviceId) -> f32 {
+        self.devices
+            .iter()
+            .find(|d| d.id == device_id)
+            .and_then(|d| self.calibration_db.get(&d.name))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Shift the selected device's level readouts, e.g. to match an SPL meter
+    /// Reaching 0 dB drops the entry, leaving the readouts uncalibrated again
+    fn adjust_calibration(&mut self, delta_db: f32) {
+        let Some(device) = self.devices.get(self.selected_device) else {
+            return;
+        };
+        let device_name = device.name.clone();
+        let offset = (self.calibration(device.id) + delta_db).clamp(-MAX_CALIBRATION_DB, MAX_CALIBRATION_DB);
+        if offset == 0.0 {
+            self.status_message = format!("{} calibration: none (levels in dBFS)", device_name);
+            self.calibration_db.remove(&device_name);
+        } else {
+            self.status_message = format!("{} calibration: {:+.1} dB", device_name, offset);
+            self.calibration_db.insert(device_name, offset);
+        }
+        self.config_dirty = true;
+        self.last_viz_change = Some(Instant::now());
+    }
+
+    /// Aggregated magnitude (dB) of one display group for a device
+    /// Groups split `visible_bins` evenly; missing data reads as silence (-60 dB)
+    fn group_magnitude(
+        &self,
+        device_id: DeviceId,
+        group_idx: usize,
+        num_groups: usize,
+        visible_bins: &Range<usize>,
+    ) -> f32 {
+        // Bars follow the attack/release envelope; data set directly has none yet
+        let bins = match self.smoothed_bins.get(&device_id) {
+            Some(smoothed) => smoothed.bins(),
+            None => match self.spectrum_data.get(&device_id) {
+                Some(spectrum) => &spectrum.bins,
+                None => return SPECTRUM_FLOOR_DB,
+            },
+        };
+        self.aggregate_group(bins, group_idx, num_groups, visible_bins)
+    }
+
+    /// Merge the bins of one display group of a bin vector (see `group_magnitude`)
+    fn aggregate_group(
+        &self,
+        bins: &[f32],
+        group_idx: usize,
+        num_groups: usize,
+        visible_bins: &Range<usize>,
+    ) -> f32 {
+        let total_bins = visible_bins.len();
+        let bin_start = visible_bins.start + (group_idx * total_bins) / num_groups;
+        let bin_end = visible_bins.start + ((group_idx + 1) * total_bins) / num_groups;
+
+        let bin_end = bin_end.min(bins.len());
+        let bin_start = bin_start.min(bin_end);
+        self.spectrum_aggregation.aggregate(&bins[bin_start..bin_end])
+    }
+
+    /// Render A minus B per frequency group, centered at 0 dB
+    /// Bars rise where A is louder and fall where A is quieter
+    fn render_difference_spectrum(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        device_a: DeviceId,
+        device_b: DeviceId,
+        show_borders: bool,
+    ) {
+        let color_a = self.get_device_color(device_a);
+        let color_b = self.get_device_color(device_b);
+
+        let inner = if show_borders {
+            let title = Line::from(vec![
+                Span::raw("Difference - A: "),
+                Span::styled(self.device_name(device_a), Style::default().fg(color_a)),
+                Span::raw(" − B: "),
+                Span::styled(self.device_name(device_b), Style::default().fg(color_b)),
+                Span::raw(format!(" (±{:.0} dB)", DIFFERENCE_RANGE_DB)),
+            ]);
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_alignment(Alignment::Left);
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        } else {
+            area
+        };
+
+        let (Some(spectrum_a), Some(_)) = (
+            self.spectrum_data.get(&device_a),
+            self.spectrum_data.get(&device_b),
+        ) else {
+            return;
+        };
+
+        if inner.width == 0 || inner.height < 2 {
+            return;
+        }
+
+        // One bar per group, same width/zoom math as the combined view
+        let visible_bins = self.frequency_zoom.bin_range(&spectrum_a.frequencies);
+        let num_slots = inner.width as usize / self.bar_width;
+        let num_groups = num_slots.min(visible_bins.len());
+        if num_groups == 0 {
+            return;
+        }
+
+        // Upper half shows A louder, lower half shows A quieter
+        let up_rows = inner.height / 2;
+        let down_rows = inner.height - up_rows;
+        let center_y = inner.y + up_rows;
+
+        // Zero line
+        for x in inner.x..inner.x + inner.width {
+            if let Some(cell) = frame.buffer_mut().cell_mut((x, center_y)) {
+                cell.set_symbol("─");
+                cell.set_style(Style::default().fg(Color::DarkGray));
+            }
+        }
+
+        let pixels_per_row = self.glyphs.pixels_per_row();
+        for slot in 0..num_slots {
+            // Spread groups across slots (same even distribution as the combined view)
+            let group_idx = slot * num_groups / num_slots;
+            let difference = self.group_magnitude(device_a, group_idx, num_groups, &visible_bins)
+                - self.group_magnitude(device_b, group_idx, num_groups, &visible_bins);
+            let difference = difference.clamp(-DIFFERENCE_RANGE_DB, DIFFERENCE_RANGE_DB);
+
+            let (rows, style) = if difference >= 0.0 {
+                (up_rows, Style::default().fg(color_a))
+            } else {
+                (down_rows, Style::default().fg(color_b))
+            };
+            let total_pixels = rows as usize * pixels_per_row;
+            let pixels = (difference.abs() / DIFFERENCE_RANGE_DB * total_pixels as f32) as usize;
+            if pixels == 0 {
+                continue;
+            }
+
+            for col in 0..self.bar_width {
+                let x = inner.x + (slot * self.bar_width + col) as u16;
+                for row in 0..pixels.div_ceil(pixels_per_row) {
+                    let filled = (pixels - row * pixels_per_row).min(pixels_per_row);
+                    // Rows grow away from the zero line in both directions
+                    let (y, symbol) = if difference >= 0.0 {
+                        (center_y - 1 - row as u16, self.glyphs.bar(filled))
+                    } else {
+                        (center_y + row as u16, self.glyphs.hanging_bar(filled))
+                    };
+                    if let Some(cell) = frame.buffer_mut().cell_mut((x, y)) {
+                        cell.set_char(symbol);
+                        cell.set_style(style);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Latest right channel levels of a device, while the spectrum is shown per channel
+    fn right_channel_bins(&self, device_id: DeviceId) -> Option<&[f32]> {
+        if !self.stereo_spectrum {
+            return None;
+        }
+        self.spectrum_data.get(&device_id)?.bins_right.as_deref()
+    }
+
+    fn render_combined_spectrum(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        device_ids: &[DeviceId],
+        show_borders: bool,
+    ) {
+        // Get first device's spectrum to determine number of bins
+        let first_spectrum = device_ids
+            .iter()
+            .find_map(|id| self.spectrum_data.get(id))
+            .unwrap();
+
+        // Only bins inside the zoom window are spread across the width
+        let visible_bins = self.frequency_zoom.bin_range(&first_spectrum.frequencies);
+        let total_bins = visible_bins.len();
+
+        // Calculate the actual inner width the same way render_custom_bars does
+        let available_width = if show_borders {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_alignment(Alignment::Left);
+            block.inner(area).width as usize
+        } else {
+            area.width as usize
+        };
+
+        if available_width == 0 {
+            return;
+        }
+
+        // A device compared pre/post EQ gets a second bar for its EQ output,
+        // otherwise one shown per channel gets one for its right channel
+        let traces: Vec<(DeviceId, Option<&[f32]>)> = device_ids
+            .iter()
+            .flat_map(|&id| {
+                let second = match self.eq_tap_spectrum.get(&id) {
+                    Some(data) => Some(data.bins.as_slice()),
+                    None => self.right_channel_bins(id),
+                };
+                std::iter::once((id, None)).chain(second.map(|bins| (id, Some(bins))))
+            })
+            .collect();
+        let bars_per_group = traces.len();
+
+        // Work in bar-sized slots so wider bars yield fewer frequency groups
+        let bar_width = self.bar_width;
+        let available_width = available_width / bar_width;
+
+        // Calculate how many frequency groups we can show and how many times to repeat each
+        let max_possible_groups = available_width / bars_per_group;
+        let num_frequency_groups = max_possible_groups.min(total_bins);
+
+        if num_frequency_groups == 0 {
+            return;
+        }
+
+        // Calculate base repetition per group and extra bars
+        let base_repetition = available_width / (num_frequency_groups * bars_per_group);
+        let total_with_base = num_frequency_groups * bars_per_group * base_repetition;
+        let extra_bars = available_width - total_with_base;
+
+        // Build bar chart data
+        let mut columns: Vec<BarColumn> = Vec::new();
+
+        // Helper function to get magnitude for a frequency range
+        let get_magnitude = |group_idx: usize, device_id: DeviceId| -> f32 {
+            self.group_magnitude(device_id, group_idx, num_frequency_groups, &visible_bins)
+        };
+        let get_held = |held: &HashMap<DeviceId, Vec<f32>>, group_idx: usize, device_id: DeviceId| {
+            let held = held.get(&device_id)?;
+            Some(self.aggregate_group(held, group_idx, num_frequency_groups, &visible_bins))
+        };
+
+        // dB → bar value (floor at 0, shaped by the amplitude transform)
+        let display_value = |magnitude: f32| -> u64 {
+            bar_value(self.amplitude_transform.apply(magnitude, self.spectrum_amplification))
+        };
+
+        // Track extra bars distributed
+        let mut extra_bars_used = 0;
+
+        // For each frequency group
+        for group_idx in 0..num_frequency_groups {
+            // Calculate repetitions for this group (distribute extra bars evenly)
+            let extra_for_this_group = if extra_bars_used < extra_bars {
+                extra_bars_used += 1;
+                1
+            } else {
+                0
+            };
+            let repetitions_for_this_group = base_repetition + extra_for_this_group;
+
+            // Repeat this frequency group's bars the calculated number of times
+            for _rep in 0..repetitions_for_this_group {
+                for &(device_id, second) in &traces {
+                    let (color, value, held) = match second {
+                        Some(bins) => (
+                            self.device_color(device_id).shade().color(),
+                            display_value(self.aggregate_group(
+                                bins,
+                                group_idx,
+                                num_frequency_groups,
+                                &visible_bins,
+                            )),
+                            BarMarks::default(),
+                        ),
+                        None => (
+                            self.get_device_color(device_id),
+                            display_value(get_magnitude(group_idx, device_id)),
+                            BarMarks {
+                                max_hold: get_held(&self.max_hold_bins, group_idx, device_id)
+                                    .map(display_value),
+                                peak: get_held(&self.peak_hold_bins, group_idx, device_id)
+                                    .map(display_value),
+                            },
+                        ),
+                    };
+
+                    // Each bar spans bar_width columns with the same pattern
+                    for _col in 0..bar_width {
+                        columns.push(BarColumn {
+                            value,
+                            style: Style::default().fg(color),
+                            marks: held,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Debug logging
+        crate::debug_log!(
+            "[SPECTRUM] area.width={}, available_slots={}, bar_width={}, bars_per_group={}, num_frequency_groups={}, base_rep={}, extra_bars={}, total_bars={}",
+            area.width,
+            available_width,
+            bar_width,
+            bars_per_group,
+            num_frequency_groups,
+            base_repetition,
+            extra_bars,
+            columns.len()
+        );
+
+        let inner = if show_borders {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_alignment(Alignment::Left);
+
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        } else {
+            // In minimal mode, use the full area without borders
+            area
+        };
+
+        crate::debug_log!(
+            "[RENDER_BARS] area.width={}, inner.width={}, bars.len()={}, show_borders={}",
+            area.width,
+            inner.width,
+            columns.len(),
+            show_borders
+        );
+
+        // We need to render bars with individual colors, but BarChart only has one style
+        // Workaround: render the spectrum using custom rendering
+        self.render_custom_bars(frame, inner, &columns, num_frequency_groups, bars_per_group);
+    }
+
+    /// Draw the bar columns into `inner`, then the EQ overlay on top
+    fn render_custom_bars(
+        &self,
+        frame: &mut Frame,
+        inner: Rect,
+        columns: &[BarColumn],
+        num_frequency_groups: usize,
+        bars_per_group: usize,
+    ) {
+        if columns.is_empty() || inner.width < 2 || inner.height < 3 {
+            return;
+        }
+
+        // Use full height for bars
+        let bar_height_area = inner.height;
+        let _label_y = inner.y + bar_height_area;
+
+        // Max height accounts for baseline (5) + amplified range (60)
+        let max_height = 65.0; // Baseline (5) + max amplified dB (60)
+
+        // Each terminal row represents 4 (braille) or 8 (blocks) vertical pixels
+        let pixels_per_row = self.glyphs.pixels_per_row();
+        let total_vertical_pixels = bar_height_area as usize * pixels_per_row;
+
+        // Bar value → height in pixels, shaped by the gamma curve
+        let height_pixels = |value: u64| -> usize {
+            let normalized = (value as f32 / max_height).clamp(0.0, 1.0);
+            (normalized.powf(self.spectrum_gamma) * total_vertical_pixels as f32) as usize
+        };
+
+        // Max hold outline first, so live bars draw over it
+        for (i, column) in columns.iter().enumerate() {
+            let Some(value) = column.marks.max_hold else {
+                continue;
+            };
+            if i >= inner.width as usize {
+                break;
+            }
+            let pixel = height_pixels(value).clamp(1, total_vertical_pixels) - 1;
+
+            // A short horizontal tick at the held pixel row
+            let y = inner.y + bar_height_area - 1 - (pixel / pixels_per_row) as u16;
+            if let Some(cell) = frame.buffer_mut().cell_mut((inner.x + i as u16, y)) {
+                cell.set_char(self.glyphs.tick(pixel % pixels_per_row));
+                cell.set_style(column.style.add_modifier(Modifier::DIM));
+            }
+        }
+
+        // Render each bar using sub-cell characters for better vertical resolution
+        for (i, column) in columns.iter().enumerate() {
+            if i >= inner.width as usize {
+                break;
+            }
+
+            // Calculate height in pixels (4x or 8x resolution)
+            let bar_height_pixels = height_pixels(column.value);
+            let bar_height_pixels = bar_height_pixels.min(total_vertical_pixels).max(1);
+
+            if bar_height_pixels > 0 {
+                let x = inner.x + i as u16;
+
+                // Calculate how many full rows and remaining pixels
+                let full_rows = bar_height_pixels / pixels_per_row;
+                let remaining_pixels = bar_height_pixels % pixels_per_row;
+
+                // Start from bottom
+                let bottom_row = inner.y + bar_height_area - 1;
+
+                // Render full rows with a full character
+                for row in 0..full_rows {
+                    let y = bottom_row.saturating_sub(row as u16);
+                    if y >= inner.y && y < inner.y + bar_height_area {
+                        let cell = frame.buffer_mut().cell_mut((x, y)).unwrap();
+                        cell.set_char(self.glyphs.bar(pixels_per_row));
+                        cell.set_style(column.style);
+                    }
+                }
+
+                // Render partial row at top if needed
+                if remaining_pixels > 0 && full_rows < bar_height_area as usize {
+                    let y = bottom_row.saturating_sub(full_rows as u16);
+                    if y >= inner.y && y < inner.y + bar_height_area {
+                        let cell = frame.buffer_mut().cell_mut((x, y)).unwrap();
+                        // Partial column: fill from bottom
+                        cell.set_char(self.glyphs.bar(remaining_pixels));
+                        cell.set_style(column.style);
+                    }
+                }
+            }
+        }
+
+        // Peak hold dots above the live bars (hidden where a bar reaches the peak's row)
+        for (i, column) in columns.iter().enumerate() {
+            let Some(peak) = column.marks.peak else {
+                continue;
+            };
+            if i >= inner.width as usize {
+                break;
+            }
+            let pixel = height_pixels(peak).clamp(1, total_vertical_pixels) - 1;
+            let bar_top = height_pixels(column.value).clamp(1, total_vertical_pixels) - 1;
+            if pixel / pixels_per_row <= bar_top / pixels_per_row {
+                continue;
+            }
+            let y = inner.y + bar_height_area - 1 - (pixel / pixels_per_row) as u16;
+            if let Some(cell) = frame.buffer_mut().cell_mut((inner.x + i as u16, y)) {
+                cell.set_char(self.glyphs.tick(pixel % pixels_per_row));
+                cell.set_style(column.style);
+            }
+        }
+
+        // Overlay the EQ response while editing filters
+        if self.focus_mode == FocusMode::SpectrumEq || self.current_tab == DeviceTab::Filters {
+            self.render_eq_curve(frame, inner, num_frequency_groups, bars_per_group);
+        }
+
+        // Draw vertical lines for selected EQ band (if in EQ mode)
+        if self.focus_mode == FocusMode::SpectrumEq
+            && let Some(device) = self.eq_device()
+            && self.eq_enabled_devices.contains(&device.id)
+        {
+            // Get the EQ band's center frequency and settings
+            if let Some(eq_settings) = self.eq_settings.get(&device.id) {
+                // The band's -3 dB range, which widens and narrows with its Q
+                if let Some(band_params) = eq_settings.bands.get(self.selected_eq_band)
+                    && let (freq_low, freq_high) = band_params.band_edges()
+                    && let Some(spectrum) = self.spectrum_data.get(&device.id)
+                {
+                    // Find the leftmost and rightmost display positions for the frequency range
+                    // Positions are relative to the zoom window (same as render_combined_spectrum)
+                    let visible_bins = self.frequency_zoom.bin_range(&spectrum.frequencies);
+                    let total_bins = visible_bins.len();
+
+                    // Find the first and last visible bins that fall within the EQ band's frequency range
+                    let mut first_bin = None;
+                    let mut last_bin = None;
+
+                    for (bin_idx, &bin_freq) in spectrum.frequencies[visible_bins.clone()]
+                        .iter()
+                        .enumerate()
+                    {
+                        if bin_freq >= freq_low && bin_freq <= freq_high {
+                            if first_bin.is_none() {
+                                first_bin = Some(bin_idx);
+                            }
+                            last_bin = Some(bin_idx);
+                        }
+                    }
+
+                    if let (Some(first_bin), Some(last_bin)) = (first_bin, last_bin) {
+                        // Map bins to display groups
+                        let first_group = (first_bin * num_frequency_groups) / total_bins;
+                        let last_group = (last_bin * num_frequency_groups) / total_bins;
+
+                        // Draw vertical lines only at the boundaries
+                        for &group_idx in &[first_group, last_group] {
+                            let x_pos = self.group_column(
+                                group_idx,
+                                num_frequency_groups,
+                                bars_per_group,
+                                inner.width,
+                            );
+                            let x = inner.x + x_pos as u16;
+
+                            if x < inner.x + inner.width {
+                                for y in 0..inner.height {
+                                    let cell_y = inner.y + y;
+                                    if let Some(cell) = frame.buffer_mut().cell_mut((x, cell_y))
+                                    {
+                                        cell.set_symbol("│");
+                                        cell.set_style(
+                                            Style::default()
+                                                .fg(Color::Yellow)
+                                                .add_modifier(Modifier::BOLD),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// First terminal column (relative to the spectrum's inner area) of a frequency group
+    /// Mirrors the repetition math in render_combined_spectrum: positions are computed
+    /// in bar-sized slots, with the leftover slots going to the leading groups
+    fn group_column(
+        &self,
+        group_idx: usize,
+        num_frequency_groups: usize,
+        bars_per_group: usize,
+        inner_width: u16,
+    ) -> usize {
+        let available_width = inner_width as usize / self.bar_width;
+        let base_repetition = available_width / (num_frequency_groups * bars_per_group);
+        let total_with_base = num_frequency_groups * bars_per_group * base_repetition;
+        let extra_bars = available_width - total_with_base;
+
+        let slot = if group_idx < extra_bars {
+            // This group gets extra repetition
+            group_idx * (base_repetition + 1) * bars_per_group
+        } else {
+            // This group gets base repetition only
+            extra_bars * (base_repetition + 1) * bars_per_group
+                + (group_idx - extra_bars) * base_repetition * bars_per_group
+        };
+        slot * self.bar_width
+    }
+
+    /// Draw the EQ device's combined filter response as a braille line over the spectrum
+    /// 0 dB sits on the middle row; each dot column is evaluated at its own frequency
+    /// so the curve follows band edits live
+    fn render_eq_curve(
+        &self,
+        frame: &mut Frame,
+        inner: Rect,
+        num_frequency_groups: usize,
+        bars_per_group: usize,
+    ) {
+        let Some(device) = self.eq_device() else {
+            return;
+        };
+        if !self.eq_enabled_devices.contains(&device.id) {
+            return;
+        }
+        let Some(eq_settings) = self.eq_settings.get(&device.id) else {
+            return;
+        };
+
+        // Use the EQ device's own spectrum for the frequency axis when it's visualized,
+        // otherwise any visualized one (all spectra share the same bin layout)
+        let mut visualized: Vec<DeviceId> = self.visualized_devices.iter().copied().collect();
+        visualized.sort_by_key(|id| id.0);
+        let Some(spectrum) = self
+            .spectrum_data
+            .get(&device.id)
+            .or_else(|| visualized.iter().find_map(|id| self.spectrum_data.get(id)))
+        else {
+            return;
+        };
+
+        let visible_bins = self.frequency_zoom.bin_range(&spectrum.frequencies);
+        let frequencies = &spectrum.frequencies[visible_bins];
+        if frequencies.is_empty() || num_frequency_groups == 0 {
+            return;
+        }
+
+        // Frequency at a fractional position along the visible bins
+        let frequency_at = |position: f32| {
+            let position = position.clamp(0.0, (frequencies.len() - 1) as f32);
+            let low = position.floor() as usize;
+            let high = (low + 1).min(frequencies.len() - 1);
+            let t = position - low as f32;
+            frequencies[low] + (frequencies[high] - frequencies[low]) * t
+        };
+
+        // One frequency per braille dot column, interpolating within each group's columns
+        let bins_per_group = frequencies.len() as f32 / num_frequency_groups as f32;
+        let mut dot_frequencies = Vec::new();
+        for group_idx in 0..num_frequency_groups {
+            let start = self.group_column(group_idx, num_frequency_groups, bars_per_group, inner.width);
+            let end = self.group_column(group_idx + 1, num_frequency_groups, bars_per_group, inner.width);
+            let dots = (end - start) * 2;
+            for dot in 0..dots {
+                let fraction = (dot as f32 + 0.5) / dots as f32;
+                dot_frequencies.push(frequency_at((group_idx as f32 + fraction) * bins_per_group - 0.5));
+            }
+        }
+        let values = eq_settings.magnitude_response(spectrum.sample_rate as f32, &dot_frequencies);
+
+        let width = inner.width as usize;
+        let height = inner.height as usize;
+        let style = Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD);
+        for (row, plotted) in eq_curve::plot(&values, EQ_CURVE_RANGE_DB, width, height)
+            .iter()
+            .enumerate()
+        {
+            for (col, &bits) in plotted.iter().enumerate() {
+                if bits == 0 {
+                    continue;
+                }
+                let position = (inner.x + col as u16, inner.y + row as u16);
+                if let Some(cell) = frame.buffer_mut().cell_mut(position) {
+                    cell.set_char(self.glyphs.dots(bits));
+                    cell.set_style(style);
+                }
+            }
+        }
+    }
 
-        // Render device list on the left
-        self.render_device_list(frame, content_chunks[0]);
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        // Prompts replace the status line while open
+        if let Some((prompt, name)) = &self.eq_preset_prompt {
+            let (title, enter) = match prompt {
+                EqPresetPrompt::Save => ("Save EQ preset as: ", ": save  "),
+                EqPresetPrompt::Load => ("Load EQ preset: ", ": load  Tab: complete  "),
+            };
+            let prompt = Line::from(vec![
+                Span::styled(
+                    title,
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{}▏", name), Style::default().fg(Color::White)),
+                Span::raw("  |  "),
+                Span::styled("Enter", Style::default().fg(Color::Cyan)),
+                Span::raw(enter),
+                Span::styled("Esc", Style::default().fg(Color::Cyan)),
+                Span::raw(": cancel  |  "),
+                Span::styled(
+                    format!("Saved: {}", self.saved_eq_presets.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            let paragraph = Paragraph::new(prompt).block(Block::default().borders(Borders::ALL));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        if let Some(name) = &self.profile_prompt {
+            let prompt = Line::from(vec![
+                Span::styled(
+                    format!("Switch profile (current: {}): ", self.profile),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{}▏", name), Style::default().fg(Color::White)),
+                Span::raw("  |  "),
+                Span::styled("Enter", Style::default().fg(Color::Cyan)),
+                Span::raw(": load/create  "),
+                Span::styled("Esc", Style::default().fg(Color::Cyan)),
+                Span::raw(": cancel  |  "),
+                Span::styled(
+                    format!("Profiles: {}", self.available_profiles.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            let paragraph = Paragraph::new(prompt).block(Block::default().borders(Borders::ALL));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        if self.jump_mode {
+            let prompt = Line::from(vec![
+                Span::styled(
+                    "Jump to device: ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("type its first letter (again for the next match)  |  "),
+                Span::styled("Esc", Style::default().fg(Color::Cyan)),
+                Span::raw(": done"),
+            ]);
+            let paragraph = Paragraph::new(prompt).block(Block::default().borders(Borders::ALL));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        if let Some(id) = &self.node_prompt {
+            let prompt = Line::from(vec![
+                Span::styled(
+                    "Visualize PipeWire node ID: ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{}▏", id), Style::default().fg(Color::White)),
+                Span::raw("  |  "),
+                Span::styled("Enter", Style::default().fg(Color::Cyan)),
+                Span::raw(": visualize  "),
+                Span::styled("Esc", Style::default().fg(Color::Cyan)),
+                Span::raw(": cancel"),
+            ]);
+            let paragraph = Paragraph::new(prompt).block(Block::default().borders(Borders::ALL));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        if let Some(path) = &self.eq_import_path {
+            let prompt = Line::from(vec![
+                Span::styled(
+                    format!("Import EQ for {}: ", self.eq_device_name()),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{}▏", path), Style::default().fg(Color::White)),
+                Span::raw("  |  "),
+                Span::styled("Enter", Style::default().fg(Color::Cyan)),
+                Span::raw(": load ParametricEQ.txt  "),
+                Span::styled("Esc", Style::default().fg(Color::Cyan)),
+                Span::raw(": cancel"),
+            ]);
+            let paragraph = Paragraph::new(prompt).block(Block::default().borders(Borders::ALL));
+            frame.render_widget(paragraph, area);
+            return;
+        }
 
-        // Render main content area on the right (with tabs)
-        self.render_main_content(frame, content_chunks[1]);
+        let mut help_spans = vec![
+            Span::styled(
+                "Status: ",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(&self.status_message, Style::default().fg(Color::White)),
+            Span::raw("  |  "),
+        ];
+        // FFT size of the selected visualization
+        if self.focus_mode == FocusMode::DeviceList
+            && let Some(device) = self.devices.get(self.selected_device)
+            && self.visualized_devices.contains(&device.id)
+        {
+            help_spans.push(Span::styled(
+                format!("FFT {}", self.fft_size(device.id)),
+                Style::default().fg(Color::Magenta),
+            ));
+            help_spans.push(Span::raw("  |  "));
+        }
+        // Off-center balance of the selected device
+        if self.focus_mode == FocusMode::DeviceList
+            && let Some(device) = self.devices.get(self.selected_device)
+            && let Some(settings) = self.volume_settings.get(&device.id)
+            && settings.balance != 0.0
+        {
+            help_spans.push(Span::styled(
+                format!("Bal {}", balance_label(settings.balance)),
+                Style::default().fg(Color::Yellow),
+            ));
+            help_spans.push(Span::raw("  |  "));
+        }
+        // Keep an A/B bypass visible after its status message is replaced
+        if self.eq_device_settings().is_some_and(|settings| settings.bypass) {
+            help_spans.push(Span::styled("EQ bypassed (B)", Style::default().fg(Color::Yellow)));
+            help_spans.push(Span::raw("  |  "));
+        }
+        // Elapsed time of the longest recording, visible whichever device is selected
+        if let Some(started) = self.recording_devices.values().min() {
+            let others = self.recording_devices.len() - 1;
+            help_spans.push(Span::styled(
+                if others > 0 {
+                    format!("● REC {} (+{})", Self::format_elapsed(started.elapsed()), others)
+                } else {
+                    format!("● REC {}", Self::format_elapsed(started.elapsed()))
+                },
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+            help_spans.push(Span::raw("  |  "));
+        }
+        help_spans.extend([
+            Span::styled(
+                self.keymap.label(match self.focus_mode {
+                    FocusMode::DeviceList => &[Action::Quit],
+                    FocusMode::SpectrumEq => &[Action::EqQuit],
+                }),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(": quit  "),
+        ]);
 
-        // Render spectrum at the bottom
-        self.render_spectrum(frame, main_chunks[1], true);
+        // Add mode-specific help
+        match self.focus_mode {
+            FocusMode::DeviceList => {
+                let actions = self.available_actions();
+                // The most used actions; the help overlay lists every binding
+                let hints: [(&[Action], &str); 10] = [
+                    (&[Action::NextTab], ": switch tab  "),
+                    (&[Action::Up, Action::Down], ": select  "),
+                    (
+                        &[Action::Link],
+                        if self.link_source.is_some() { ": link here  " } else { ": link from  " },
+                    ),
+                    (&[Action::OpenEq], ": EQ  "),
+                    (&[Action::ToggleViz], ": viz  "),
+                    (
+                        &[Action::PauseAll],
+                        if self.paused_visualizations.is_empty() {
+                            ": stop all  "
+                        } else {
+                            ": resume all  "
+                        },
+                    ),
+                    (&[Action::VolumeDown, Action::VolumeUp], ": volume  "),
+                    (&[Action::ToggleRecording], ": record  "),
+                    (
+                        &[Action::ToggleGroup],
+                        if actions.expand {
+                            ": expand/collapse  "
+                        } else if self.patch_source.is_some() {
+                            ": patch here  "
+                        } else {
+                            ": patch from  "
+                        },
+                    ),
+                    (&[Action::Help], ": all keys"),
+                ];
 
-        // Render status bar at the very bottom
-        self.render_status_bar(frame, main_chunks[2]);
+                // Actions that would do nothing for the current selection are greyed out
+                for (keys, description) in hints {
+                    let key = self.keymap.label(keys);
+                    if actions.allows(keys[0]) {
+                        help_spans.push(Span::styled(key, Style::default().fg(Color::Cyan)));
+                        help_spans.push(Span::raw(description));
+                    } else {
+                        let greyed = Style::default().fg(Color::DarkGray);
+                        help_spans.push(Span::styled(key, greyed));
+                        help_spans.push(Span::styled(description, greyed));
+                    }
+                }
+            }
+            FocusMode::SpectrumEq => {
+                let hints: [(&[Action], &str); 11] = [
+                    (&[Action::EqBack], ": back  "),
+                    (&[Action::EqPreviousBand, Action::EqNextBand], ": band  "),
+                    (&[Action::EqGainDown, Action::EqGainUp], ": gain  "),
+                    (&[Action::EqQDown, Action::EqQUp], ": width  "),
+                    (&[Action::EqBandCount], ": bands  "),
+                    (&[Action::EqMute], ": mute  "),
+                    (&[Action::EqSolo], ": solo  "),
+                    (&[Action::EqBypass], ": A/B bypass  "),
+                    (&[Action::EqCompare], ": pre/post  "),
+                    (&[Action::EqReset], ": reset  "),
+                    (&[Action::EqDisable], ": EQ off"),
+                ];
+                for (keys, description) in hints {
+                    help_spans.push(Span::styled(self.keymap.label(keys), Style::default().fg(Color::Cyan)));
+                    help_spans.push(Span::raw(description));
+                }
+            }
+        }
+
+        let status_text = vec![Line::from(help_spans)];
+
+        let paragraph = Paragraph::new(status_text)
+            .block(Block::default().borders(Borders::ALL))
+            .alignment(Alignment::Left);
+
+        frame.render_widget(paragraph, area);
+    }
+
+    /// Work out which actions apply to the current selection
+    fn available_actions(&self) -> AvailableActions {
+        // Application headers take no device actions
+        let device = if self.selected_group.is_some() {
+            None
+        } else {
+            self.devices.get(self.selected_device)
+        };
+        let has_direction = |direction: PortDirection| {
+            device.is_some_and(|d| d.ports.iter().any(|p| p.direction == direction))
+        };
+        let visualized = device.is_some_and(|d| self.visualized_devices.contains(&d.id));
+        let eq_enabled = device.is_some_and(|d| self.eq_enabled_devices.contains(&d.id));
+
+        AvailableActions {
+            cancel_commands: self.show_commands && self.queued_commands.1 > 0,
+            scroll_bin_table: self.show_bin_table,
+            goniometer: self.stereo_capture,
+            // EQ is inserted in front of the device's input ports
+            eq: eq_enabled || has_direction(PortDirection::Input),
+            // Visualization taps the first output (monitor) port
+            visualize: visualized
+                || has_direction(PortDirection::Output)
+                || device.is_some_and(|d| self.pending_visualizations.contains(d.id)),
+            pause_all: !self.visualized_devices.is_empty()
+                || !self.paused_visualizations.is_empty(),
+            record: visualized,
+            // Volume is applied by the EQ processing stream, started for it if needed
+            volume: eq_enabled || has_direction(PortDirection::Input),
+            // So are crossfeed, saturation and width
+            crossfeed: eq_enabled,
+            saturation: eq_enabled,
+            width: eq_enabled,
+            difference: self.difference_mode || self.visualized_devices.len() == 2,
+            // Cycling needs something to cycle through (or a focus to leave)
+            focus: self.visualized_devices.len() > 1 || self.focused_spectrum_device().is_some(),
+            disconnect: self.current_tab == DeviceTab::Routing
+                && self.connections.iter().any(|row| row.connection.is_some()),
+            patch: self.current_tab == DeviceTab::Routing && !self.connections.is_empty(),
+            link: self.current_tab == DeviceTab::Routing && device.is_some(),
+            hide: device.is_some(),
+            favorite: device.is_some(),
+            color: device.is_some(),
+            copy_ports: device.is_some_and(|d| !d.ports.is_empty()),
+            dump_spectrum: device.is_some_and(|d| self.spectrum_data.contains_key(&d.id)),
+            apply_eq: self
+                .eq_device()
+                .is_some_and(|d| self.eq_settings.contains_key(&d.id))
+                && self.visualized_devices.len() > 1,
+            eq_bypass: self.current_tab == DeviceTab::Filters && self.eq_device_settings().is_some(),
+            undo_hide: !self.recently_hidden.is_empty(),
+            expand: self.group_by_application
+                && (self.selected_group.is_some()
+                    || device.is_some_and(|d| d.application_name.is_some())),
+            delete_virtual: device.is_some_and(|d| d.device_type == DeviceType::Virtual),
+        }
+    }
+
+    /// Check if auto-save should be triggered (debounced by the configured delay)
+    pub fn should_auto_save(&self) -> bool {
+        if !self.config_dirty {
+            return false;
+        }
+
+        match (self.last_viz_change, self.auto_save_delay) {
+            (Some(last_change), Some(delay)) => last_change.elapsed() >= delay,
+            _ => false,
+        }
+    }
+
+    /// Set the auto-save debounce (None disables auto-save; config is still saved on exit)
+    pub fn set_auto_save_delay(&mut self, delay: Option<Duration>) {
+        self.auto_save_delay = delay;
+    }
+
+    /// Set how long a stream may stay silent before it is auto-hidden (None = never)
+    pub fn set_auto_hide_after(&mut self, after: Option<Duration>) {
+        self.auto_hide_after = after;
+    }
+
+    /// Set how long spectrum frames are batched (zero = applied as they arrive)
+    pub fn set_spectrum_batch_window(&mut self, window: Duration) {
+        self.spectrum_batch_window = window;
+        if window.is_zero() {
+            self.flush_spectrum_batch(Instant::now());
+        }
+    }
+
+    /// Set how ports are paired when linking two devices
+    pub fn set_pairing_strategy(&mut self, strategy: PairingStrategy) {
+        self.pairing_strategy = strategy;
+    }
+
+    /// Set the key bindings (built from the `[keybindings]` config section)
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    /// Mark config as saved (clear dirty flag)
+    pub fn mark_config_saved(&mut self) {
+        self.config_dirty = false;
+    }
+
+    /// Whether the first-run overlay has been dismissed, for saving
+    pub fn first_run_seen(&self) -> bool {
+        self.first_run_seen
+    }
+
+    /// Show the first-run overlay unless the config says it was dismissed
+    pub fn set_first_run_seen(&mut self, seen: bool) {
+        self.first_run_seen = seen;
+    }
+
+    /// Get reference to visualized devices set
+    pub fn get_visualized_devices(&self) -> &HashSet<DeviceId> {
+        &self.visualized_devices
+    }
+
+    /// Set spectrum amplification factor
+    pub fn set_spectrum_amplification(&mut self, amplification: f32) {
+        self.spectrum_amplification = amplification;
+    }
+
+    /// Get spectrum amplification factor
+    pub fn get_spectrum_amplification(&self) -> f32 {
+        self.spectrum_amplification
+    }
+
+    /// Get the display group aggregation strategy
+    pub fn get_spectrum_aggregation(&self) -> SpectrumAggregation {
+        self.spectrum_aggregation
+    }
+
+    /// Set the display group aggregation strategy (from config)
+    pub fn set_spectrum_aggregation(&mut self, aggregation: SpectrumAggregation) {
+        self.spectrum_aggregation = aggregation;
+    }
+
+    /// Get the spectrum bar width in columns
+    pub fn get_bar_width(&self) -> usize {
+        self.bar_width
+    }
+
+    /// Set the spectrum bar width in columns (from config, clamped to 1-3)
+    pub fn set_bar_width(&mut self, bar_width: usize) {
+        self.bar_width = bar_width.clamp(1, MAX_BAR_WIDTH);
+    }
+
+    /// Get the spectrum gamma
+    pub fn get_spectrum_gamma(&self) -> f32 {
+        self.spectrum_gamma
+    }
+
+    /// Set the spectrum gamma (from config, clamped to the allowed range)
+    pub fn set_spectrum_gamma(&mut self, gamma: f32) {
+        self.spectrum_gamma = if gamma.is_finite() {
+            // Round to the step so repeated adjustments don't drift
+            let steps = (gamma / SPECTRUM_GAMMA_STEP).round();
+            (steps * SPECTRUM_GAMMA_STEP).clamp(MIN_SPECTRUM_GAMMA, MAX_SPECTRUM_GAMMA)
+        } else {
+            1.0
+        };
+    }
+
+    /// Get the bar attack and release times (ms)
+    pub fn get_bar_envelope(&self) -> (u64, u64) {
+        (self.bar_attack_ms, self.bar_release_ms)
+    }
+
+    /// Set the bar attack and release times (ms, from config, clamped to the allowed ranges)
+    pub fn set_bar_envelope(&mut self, attack_ms: u64, release_ms: u64) {
+        self.bar_attack_ms = attack_ms.min(MAX_BAR_ATTACK_MS);
+        self.bar_release_ms = release_ms.min(MAX_BAR_RELEASE_MS);
+    }
+
+    /// Get how levels are turned into bar heights
+    pub fn get_amplitude_transform(&self) -> AmplitudeTransform {
+        self.amplitude_transform
+    }
+
+    /// Set how levels are turned into bar heights (from config)
+    pub fn set_amplitude_transform(&mut self, transform: AmplitudeTransform) {
+        self.amplitude_transform = transform;
+    }
+
+    /// Get the configured spectrum character set
+    pub fn get_glyph_mode(&self) -> GlyphMode {
+        self.glyph_mode
+    }
+
+    /// Set the spectrum character set (from config), detecting braille support for `auto`
+    pub fn set_glyph_mode(&mut self, mode: GlyphMode) {
+        self.glyph_mode = mode;
+        self.glyphs = Glyphs::detect(mode);
+        crate::debug_log!("Spectrum glyphs: {:?} (configured {:?})", self.glyphs, mode);
+    }
+
+    /// Get the spectrum refresh rate in Hz
+    pub fn get_refresh_rate(&self) -> u32 {
+        self.refresh_rate_hz
+    }
+
+    /// Set the spectrum refresh rate in Hz (from config, clamped to 1-100)
+    /// Capture streams are updated every 10 ms, so faster rates can't be delivered
+    pub fn set_refresh_rate(&mut self, hz: u32) {
+        self.refresh_rate_hz = hz.clamp(1, MAX_REFRESH_RATE_HZ);
+    }
+
+    /// Find a device by name
+    pub fn find_device_by_name(&self, name: &str) -> Option<&DeviceInfo> {
+        self.devices.iter().find(|d| d.name == name)
+    }
+
+    /// Record the loaded profile and the ones available to switch to
+    pub fn set_profiles(&mut self, current: &str, available: Vec<String>) {
+        self.profile = current.to_string();
+        self.available_profiles = available;
+    }
+
+    /// Take the profile the user asked to switch to, if any
+    pub fn take_profile_request(&mut self) -> Option<String> {
+        self.requested_profile.take()
+    }
+
+    /// Record the EQ presets saved on disk
+    pub fn set_eq_presets(&mut self, presets: Vec<String>) {
+        self.saved_eq_presets = presets;
+    }
+
+    /// Take the EQ preset the user asked to save or load, if any
+    pub fn take_eq_preset_request(&mut self) -> Option<EqPresetRequest> {
+        self.eq_preset_request.take()
+    }
+
+    /// Show a message in the status bar
+    pub fn set_status_message(&mut self, message: String) {
+        self.status_message = message;
+        self.record_status();
+    }
+
+    /// Recent status messages, oldest first
+    pub fn get_status_history(&self) -> Vec<String> {
+        self.status_history.iter().cloned().collect()
+    }
+
+    /// Whether the user asked for a support bundle (clears the request)
+    pub fn take_support_bundle_request(&mut self) -> bool {
+        std::mem::take(&mut self.support_bundle_requested)
+    }
+
+    /// Get hidden devices as Vec<String> for config saving
+    pub fn get_hidden_devices(&self) -> Vec<String> {
+        self.hidden_devices.iter().cloned().collect()
+    }
+
+    /// Restore hidden devices from config
+    pub fn restore_hidden_devices(&mut self, hidden_device_names: Vec<String>) {
+        self.hidden_devices = hidden_device_names.into_iter().collect();
+    }
+
+    /// Get favorite device names for saving (sorted, so the config doesn't churn)
+    pub fn get_favorite_devices(&self) -> Vec<String> {
+        let mut favorites: Vec<String> = self.favorite_devices.iter().cloned().collect();
+        favorites.sort();
+        favorites
     }
 
-    fn render_device_list(&self, frame: &mut Frame, area: Rect) {
-        // Build the filtered list and track the mapping from full list to filtered list
-        let mut filtered_index = 0;
-        let mut selected_filtered_index = None;
+    /// Restore favorite devices from config
+    pub fn restore_favorite_devices(&mut self, favorite_device_names: Vec<String>) {
+        self.favorite_devices = favorite_device_names.into_iter().collect();
+    }
+
+    /// Get spectrum colors by device name for saving
+    pub fn get_device_colors(&self) -> HashMap<String, DeviceColor> {
+        self.device_colors.clone()
+    }
+
+    /// Restore assigned spectrum colors from config
+    pub fn restore_device_colors(&mut self, device_colors: HashMap<String, DeviceColor>) {
+        self.device_colors = device_colors;
+    }
+
+    /// Get level calibration offsets by device name for saving
+    pub fn get_calibration(&self) -> HashMap<String, f32> {
+        self.calibration_db.clone()
+    }
+
+    /// Restore level calibration offsets from config (hand-edited values are clamped)
+    pub fn restore_calibration(&mut self, calibration_db: HashMap<String, f32>) {
+        self.calibration_db = calibration_db
+            .into_iter()
+            .filter(|(_, offset)| offset.is_finite() && *offset != 0.0)
+            .map(|(name, offset)| (name, offset.clamp(-MAX_CALIBRATION_DB, MAX_CALIBRATION_DB)))
+            .collect();
+    }
+
+    /// Get crossfeed settings by device name for saving
+    pub fn get_crossfeed_settings(&self) -> HashMap<String, CrossfeedSettings> {
+        self.crossfeed_settings.clone()
+    }
+
+    /// Get the EQ curves of devices with EQ enabled, by device name for saving
+    pub fn get_eq_settings(&self) -> HashMap<String, EqSettings> {
+        self.devices
+            .iter()
+            .filter(|d| self.eq_enabled_devices.contains(&d.id))
+            .filter_map(|d| Some((d.name.clone(), self.eq_settings.get(&d.id)?.clone())))
+            .collect()
+    }
+
+    /// Get volume settings by device name for saving (unity gain is left out)
+    pub fn get_volume_settings(&self) -> HashMap<String, VolumeSettings> {
+        self.devices
+            .iter()
+            .filter_map(|d| Some((d.name.clone(), self.volume_settings.get(&d.id)?.clone())))
+            .filter(|(_, settings)| *settings != VolumeSettings::default())
+            .collect()
+    }
+
+    /// Bring the present devices' EQ and volume in line with a loaded config
+    /// Devices named in `eq` get EQ with that curve (enabling it if needed); devices
+    /// with EQ that the config doesn't name are reset to a flat curve. Volume, which
+    /// runs in the EQ stream, goes to the config's value or unity gain; a saved
+    /// volume on a device without EQ starts its stream with a flat curve
+    pub fn apply_eq_and_volume(
+        &mut self,
+        eq: &HashMap<String, EqSettings>,
+        volume: &HashMap<String, VolumeSettings>,
+        audio_engine: &AudioEngine,
+    ) -> Result<()> {
+        let devices: Vec<(DeviceId, String)> =
+            self.devices.iter().map(|d| (d.id, d.name.clone())).collect();
+
+        for (device_id, name) in devices {
+            if !self.eq_enabled_devices.contains(&device_id) {
+                if let Some(settings) = eq.get(&name) {
+                    self.enable_eq_with(device_id, settings.clone(), audio_engine)?;
+                }
+            } else {
+                let settings = eq.get(&name).cloned().unwrap_or_default();
+                if self.eq_settings.get(&device_id) != Some(&settings) {
+                    audio_engine.send_command(AudioCommand::SetEqSettings {
+                        device_id,
+                        settings: settings.clone(),
+                    })?;
+                    self.eq_settings.insert(device_id, settings);
+                }
+            }
+
+            if !self.eq_enabled_devices.contains(&device_id) && !volume.contains_key(&name) {
+                continue;
+            }
+            let settings = volume.get(&name).cloned().unwrap_or_default();
+            if self.volume_settings.get(&device_id).cloned().unwrap_or_default() != settings {
+                audio_engine.send_command(AudioCommand::SetVolume {
+                    device_id,
+                    settings: settings.clone(),
+                })?;
+                self.volume_settings.insert(device_id, settings);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get saturation drive by device name for saving
+    pub fn get_saturation_drive(&self) -> HashMap<String, f32> {
+        self.saturation_drive.clone()
+    }
+
+    /// Restore saturation drive from config (applied when a device's EQ starts)
+    pub fn restore_saturation_drive(&mut self, device_drive: HashMap<String, f32>) {
+        self.saturation_drive = device_drive
+            .into_iter()
+            .map(|(name, drive)| (name, clamp_saturation_drive(drive)))
+            .filter(|&(_, drive)| drive > 0.0)
+            .collect();
+    }
+
+    /// Get stereo width by device name for saving
+    pub fn get_stereo_width(&self) -> HashMap<String, f32> {
+        self.stereo_width.clone()
+    }
+
+    /// Restore stereo width from config (applied when a device's EQ starts)
+    pub fn restore_stereo_width(&mut self, device_width: HashMap<String, f32>) {
+        self.stereo_width = device_width
+            .into_iter()
+            .map(|(name, width)| (name, clamp_stereo_width(width)))
+            .filter(|&(_, width)| width != 1.0)
+            .collect();
+    }
+
+    /// Restore crossfeed settings from config (applied when a device's EQ starts)
+    pub fn restore_crossfeed_settings(&mut self, settings: HashMap<String, CrossfeedSettings>) {
+        self.crossfeed_settings = settings
+            .into_iter()
+            .map(|(name, mut settings)| {
+                settings.clamp();
+                (name, settings)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    /// App with two visualized devices and spectrum data, so every spectrum path has work to do
+    fn app_with_spectrum() -> App {
+        let mut app = App::new(1.0);
+        for id in [1, 2] {
+            let device_id = DeviceId::new(id);
+            app.devices.push(DeviceInfo::new(
+                device_id,
+                format!("Device {}", id),
+                crate::audio::DeviceType::Physical,
+            ));
+            app.visualized_devices.insert(device_id);
+            app.spectrum_data.insert(
+                device_id,
+                Arc::new(SpectrumData {
+                    bins: vec![-20.0; 64],
+                    bins_right: None,
+                    frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                    sample_rate: 48000,
+                    timestamp: Instant::now(),
+                }),
+            );
+        }
+        app
+    }
+
+    #[test]
+    fn test_render_tiny_terminals_without_panicking() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        app.show_health = true;
+        app.show_commands = true;
+        app.stereo_capture = true;
+
+        // Plain spectrum, difference view, and spectrum + goniometer + bin table
+        for (difference_mode, goniometer) in [(false, false), (true, false), (false, true)] {
+            app.difference_mode = difference_mode;
+            app.goniometer = goniometer;
+            app.show_bin_table = goniometer;
+            for (width, height) in [(0, 0), (1, 1), (5, 2), (19, 3), (20, 3), (200, 3), (3, 80)] {
+                let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+                terminal
+                    .draw(|frame| app.render(frame, &audio_engine))
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_difference_spectrum_direction_and_clamp() {
+        let mut app = app_with_spectrum();
+        let set_levels = |app: &mut App, level_a: f32, level_b: f32| {
+            for (id, level) in [(1, level_a), (2, level_b)] {
+                let device_id = DeviceId::new(id);
+                let data = SpectrumData {
+                    bins: vec![level; 64],
+                    ..(*app.spectrum_data[&device_id]).clone()
+                };
+                app.spectrum_data.insert(device_id, Arc::new(data));
+            }
+        };
+        // Rows with bar cells in them; row 4 is the zero line of an 8 row plot
+        let render = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(16, 8)).unwrap();
+            terminal
+                .draw(|frame| {
+                    let area = frame.area();
+                    app.render_difference_spectrum(frame, area, DeviceId::new(1), DeviceId::new(2), false)
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            let rows: Vec<bool> = (0..8)
+                .map(|y| (0..16).any(|x| !matches!(buffer[(x, y)].symbol(), " " | "─")))
+                .collect();
+            (rows, buffer)
+        };
+
+        // A louder than B draws above the zero line, quieter below it
+        set_levels(&mut app, -10.0, -20.0);
+        let (rows, _) = render(&app);
+        assert!(rows[3] && !rows[0]);
+        assert!(!rows[4] && rows[5..].iter().all(|&row| !row));
+
+        set_levels(&mut app, -20.0, -10.0);
+        let (rows, _) = render(&app);
+        assert!(rows[..4].iter().all(|&row| !row));
+        assert!(rows[5] && !rows[7]);
+
+        // Differences past the range are drawn at the range
+        set_levels(&mut app, 0.0, -60.0);
+        let (rows, clamped) = render(&app);
+        assert!(rows[..4].iter().all(|&row| row));
+        set_levels(&mut app, 0.0, -DIFFERENCE_RANGE_DB);
+        assert_eq!(render(&app).1, clamped);
+
+        set_levels(&mut app, -60.0, 0.0);
+        let (rows, clamped) = render(&app);
+        assert!(rows[5..].iter().all(|&row| row));
+        set_levels(&mut app, -DIFFERENCE_RANGE_DB, 0.0);
+        assert_eq!(render(&app).1, clamped);
+    }
 
-        let items: Vec<ListItem> = self
-            .devices
+    #[test]
+    fn test_difference_mode_needs_two_devices() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+
+        app.handle_input(Key::Char('d'), &mut audio_engine).unwrap();
+        assert!(app.difference_mode);
+        app.handle_input(Key::Char('d'), &mut audio_engine).unwrap();
+        assert!(!app.difference_mode);
+
+        app.visualized_devices.remove(&DeviceId::new(2));
+        app.handle_input(Key::Char('d'), &mut audio_engine).unwrap();
+        assert!(!app.difference_mode);
+        assert_eq!(app.status_message, "Difference mode needs exactly 2 visualized devices (1 active)");
+
+        // A third device joining an active difference view is annotated instead
+        app.visualized_devices.insert(DeviceId::new(2));
+        app.handle_input(Key::Char('d'), &mut audio_engine).unwrap();
+        let third = DeviceId::new(3);
+        app.devices.push(DeviceInfo::new(third, String::from("Device 3"), crate::audio::DeviceType::Physical));
+        app.visualized_devices.insert(third);
+        app.spectrum_data.insert(third, Arc::clone(&app.spectrum_data[&DeviceId::new(1)]));
+
+        let mut terminal = Terminal::new(TestBackend::new(200, 30)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
             .iter()
-            .enumerate()
-            .filter_map(|(idx, device)| {
-                let is_hidden = self.hidden_devices.contains(&device.name);
-
-                // Skip hidden devices if not showing them
-                if is_hidden && !self.show_hidden {
-                    return None;
-                }
-
-                let device_type = format!("{:?}", device.device_type);
-                let is_visualized = self.visualized_devices.contains(&device.id);
-                let indicator = if is_visualized { "[x]" } else { "[ ]" };
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("[difference needs exactly 2 devices]"));
+        assert!(!text.contains("Difference - A:"));
 
-                // Grey out hidden devices when showing them
-                let (name_color, indicator_color) = if is_hidden {
-                    (Color::DarkGray, Color::DarkGray)
-                } else if is_visualized {
-                    (Color::White, Color::Cyan)
-                } else {
-                    (Color::White, Color::DarkGray)
-                };
+        app.difference_mode = false;
+        app.handle_input(Key::Char('d'), &mut audio_engine).unwrap();
+        assert!(!app.difference_mode);
+        assert_eq!(app.status_message, "Difference mode needs exactly 2 visualized devices (3 active)");
+    }
 
-                // Volume indicator
-                let volume_str = if let Some(vol) = self.volume_settings.get(&device.id) {
-                    if (vol.gain_db - 0.0).abs() < 0.1 {
-                        String::new()  // Don't show if at unity gain
-                    } else {
-                        format!(" [{:+.1}dB]", vol.gain_db)
-                    }
-                } else {
-                    String::new()
-                };
+    #[test]
+    fn test_too_small_message() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
 
-                let mut spans = vec![
-                    Span::styled(
-                        indicator,
-                        Style::default().fg(indicator_color),
-                    ),
-                    Span::raw(" "),
-                    Span::styled(
-                        &device.name,
-                        Style::default()
-                            .fg(name_color)
-                            .add_modifier(if is_hidden { Modifier::empty() } else { Modifier::BOLD }),
-                    ),
-                    Span::raw(" "),
-                    Span::styled(
-                        format!("({})", device_type),
-                        Style::default().fg(Color::DarkGray),
-                    ),
-                ];
+        let mut terminal = Terminal::new(TestBackend::new(60, 2)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
 
-                if !volume_str.is_empty() {
-                    spans.push(Span::styled(
-                        volume_str,
-                        Style::default().fg(Color::Yellow),
-                    ));
-                }
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Terminal too small (need at least 20×3)"));
+    }
 
-                let line = Line::from(spans);
+    #[test]
+    fn test_bin_table_lists_selected_device() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        app.show_bin_table = true;
+        app.bin_table_scroll = 60;
 
-                // Track which filtered index corresponds to the selected device
-                if idx == self.selected_device {
-                    selected_filtered_index = Some(filtered_index);
-                }
-                filtered_index += 1;
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
 
-                Some(ListItem::new(line))
-            })
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
             .collect();
+        assert!(text.contains("freq (Hz)"));
+        // Scrolled past the end, the table stops at the last bin
+        assert!(text.contains("63    18920.0"));
+    }
 
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Devices")
-                    .title_alignment(Alignment::Left),
-            )
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol("> ");
+    #[test]
+    fn test_status_bar_shows_recording_time() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let now = Instant::now();
+        app.recording_devices.insert(DeviceId::new(1), now - Duration::from_secs(65));
+        app.recording_devices.insert(DeviceId::new(2), now - Duration::from_secs(5));
+
+        let mut terminal = Terminal::new(TestBackend::new(240, 30)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
 
-        frame.render_stateful_widget(
-            list,
-            area,
-            &mut ratatui::widgets::ListState::default().with_selected(selected_filtered_index),
-        );
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("● REC 01:05 (+1)"));
     }
 
-    fn render_main_content(&self, frame: &mut Frame, area: Rect) {
-        // Split main content into tab bar and content area
-        let content_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Tab bar
-                Constraint::Min(0),    // Tab content
-            ])
-            .split(area);
+    #[test]
+    fn test_profile_prompt_requests_switch() {
+        let mut app = App::new(1.0);
+        let type_name = |app: &mut App, name: &str| {
+            app.profile_prompt = Some(String::new());
+            for c in name.chars() {
+                app.handle_profile_input(Key::Char(c));
+            }
+            app.handle_profile_input(Key::Char('\n'));
+        };
 
-        // Render tab bar
-        self.render_tabs(frame, content_chunks[0]);
+        type_name(&mut app, "gaming");
+        assert_eq!(app.take_profile_request().as_deref(), Some("gaming"));
 
-        // Render current tab content
-        match self.current_tab {
-            DeviceTab::Routing => self.render_routing_tab(frame, content_chunks[1]),
-            DeviceTab::Filters => self.render_filters_tab(frame, content_chunks[1]),
-        }
+        // The current profile and invalid names don't trigger a switch
+        type_name(&mut app, DEFAULT_PROFILE);
+        type_name(&mut app, "../gaming");
+        assert_eq!(app.take_profile_request(), None);
+        assert!(app.profile_prompt.is_none());
     }
 
-    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
-        let tabs = DeviceTab::all();
-        let titles: Vec<_> = tabs.iter().map(|t| t.title()).collect();
-
-        let selected_index = match self.current_tab {
-            DeviceTab::Routing => 0,
-            DeviceTab::Filters => 1,
-        };
-
-        let device_name = if !self.devices.is_empty() && self.selected_device < self.devices.len() {
-            &self.devices[self.selected_device].name
-        } else {
-            "No device selected"
+    #[test]
+    fn test_peak_hold_decays_and_resets() {
+        let mut app = app_with_spectrum();
+        app.peak_hold = true;
+        let device_id = DeviceId::new(1);
+        let update = |level: f32| AudioEvent::SpectrumUpdate {
+            device_id,
+            data: Arc::new(SpectrumData {
+                bins: vec![level; 64],
+                bins_right: None,
+                frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                sample_rate: 48000,
+                timestamp: Instant::now(),
+            }),
         };
 
-        let tabs_widget = Tabs::new(titles)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("wavewire - {}", device_name)),
-            )
-            .select(selected_index)
-            .style(Style::default().fg(Color::White))
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            );
+        // Held peaks fall slowly after the signal drops, but never below it
+        app.handle_audio_events(&[update(-10.0), update(-40.0), update(-40.0)]);
+        assert_eq!(app.peak_hold_bins[&device_id], vec![-10.0 - 2.0 * PEAK_HOLD_DECAY_DB; 64]);
+        let mut held = vec![-39.8];
+        hold_peak(&mut held, &[-40.0], PEAK_HOLD_DECAY_DB);
+        assert_eq!(held, [-40.0]);
+
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("[peak hold]"));
 
-        frame.render_widget(tabs_widget, area);
+        app.handle_audio_events(&[AudioEvent::VisualizationStopped { device_id }]);
+        assert!(!app.peak_hold_bins.contains_key(&device_id));
     }
 
-    fn render_routing_tab(&self, frame: &mut Frame, area: Rect) {
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title("Routing")
-            .title_alignment(Alignment::Left);
-
-        let content = if self.devices.is_empty() {
-            "No devices available\n\nPress 'r' to refresh device list"
-        } else {
-            "Routing configuration\n\nConfigure input/output connections for this device"
+    #[test]
+    fn test_max_hold_keeps_loudest_level() {
+        let mut app = app_with_spectrum();
+        app.max_hold = true;
+        let device_id = DeviceId::new(1);
+        let update = |level: f32| AudioEvent::SpectrumUpdate {
+            device_id,
+            data: Arc::new(SpectrumData {
+                bins: vec![level; 64],
+                bins_right: None,
+                frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                sample_rate: 48000,
+                timestamp: Instant::now(),
+            }),
         };
 
-        let paragraph = Paragraph::new(content)
-            .block(block)
-            .alignment(Alignment::Center);
+        app.handle_audio_events(&[update(-30.0), update(-10.0), update(-40.0)]);
+        assert_eq!(app.max_hold_bins[&device_id], vec![-10.0; 64]);
 
-        frame.render_widget(paragraph, area);
+        // Reset drops back to the floor, then follows the signal again
+        app.reset_max_hold();
+        app.handle_audio_events(&[update(-40.0)]);
+        assert_eq!(app.max_hold_bins[&device_id], vec![-40.0; 64]);
     }
 
-    fn render_filters_tab(&self, frame: &mut Frame, area: Rect) {
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title("Filters")
-            .title_alignment(Alignment::Left);
+    #[test]
+    fn test_status_history_keeps_recent_distinct_messages() {
+        let mut app = App::new(1.0);
+        for i in 0..STATUS_HISTORY_LEN + 5 {
+            app.set_status_message(format!("message {}", i));
+            app.set_status_message(format!("message {}", i));
+        }
 
-        if self.devices.is_empty() {
-            let paragraph =
-                Paragraph::new("No devices available\n\nPress 'r' to refresh device list")
-                    .block(block)
-                    .alignment(Alignment::Center);
-            frame.render_widget(paragraph, area);
-            return;
+        let history = app.get_status_history();
+        assert_eq!(history.len(), STATUS_HISTORY_LEN);
+        assert_eq!(history.first().map(String::as_str), Some("message 5"));
+        assert_eq!(
+            history.last(),
+            Some(&format!("message {}", STATUS_HISTORY_LEN + 4))
+        );
+    }
+
+    #[test]
+    fn test_spectrum_aggregation() {
+        let bins = [-30.0, -12.0, -24.0, -6.0];
+        assert_eq!(SpectrumAggregation::Max.aggregate(&bins), -6.0);
+        assert_eq!(SpectrumAggregation::Mean.aggregate(&bins), -18.0);
+        assert!((SpectrumAggregation::Rms.aggregate(&bins) + 10.98).abs() < 0.01);
+
+        for aggregation in [SpectrumAggregation::Max, SpectrumAggregation::Mean, SpectrumAggregation::Rms] {
+            assert_eq!(aggregation.aggregate(&[]), -60.0);
         }
 
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
+        // RMS averages power, not dB: the loud bin dominates
+        assert!((SpectrumAggregation::Rms.aggregate(&[-60.0, 0.0]) + 3.01).abs() < 0.01);
+        assert_eq!(SpectrumAggregation::Mean.aggregate(&[-60.0, 0.0]), -30.0);
+    }
 
-        // Show filter options
-        let device = &self.devices[self.selected_device];
-        let device_id = device.id;
-        let eq_enabled = self.eq_enabled_devices.contains(&device_id);
+    #[test]
+    fn test_amplitude_transforms() {
+        // Linear multiplies the height above the floor and clips
+        let linear = AmplitudeTransform::Linear;
+        assert_eq!(linear.apply(-45.0, 2.0), 30.0);
+        assert_eq!(linear.apply(-10.0, 2.0), 60.0);
+        assert_eq!(linear.apply(-80.0, 2.0), 0.0);
+
+        // Perceptual keeps the ends pinned and stays ordered, even when cranked
+        let perceptual = AmplitudeTransform::Perceptual;
+        for amplification in [0.1, 2.0, 10.0] {
+            assert!(perceptual.apply(SPECTRUM_FLOOR_DB, amplification).abs() < 1e-3);
+            assert!((perceptual.apply(0.0, amplification) - 60.0).abs() < 1e-3);
+            assert!((perceptual.apply(PERCEPTUAL_REFERENCE_DB, amplification) - 30.0).abs() < 1e-3);
+            let levels = [-55.0, -40.0, -20.0, -5.0];
+            let values: Vec<f32> = levels.iter().map(|&db| perceptual.apply(db, amplification)).collect();
+            assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        // Amplification expands the contrast around the reference level
+        assert!(perceptual.apply(-25.0, 4.0) > perceptual.apply(-25.0, 1.0));
+        assert!(perceptual.apply(-35.0, 4.0) < perceptual.apply(-35.0, 1.0));
+        assert_eq!(perceptual.apply(-30.0, 0.0), 30.0);
+    }
 
-        let filter_items: Vec<ListItem> = vec![ListItem::new(Line::from(vec![
-            Span::styled("[", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                if eq_enabled { "x" } else { " " },
-                Style::default().fg(if eq_enabled {
-                    Color::Cyan
-                } else {
-                    Color::DarkGray
-                }),
-            ),
-            Span::styled("]", Style::default().fg(Color::DarkGray)),
-            Span::raw(" "),
-            Span::styled(
-                "Equalizer",
-                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-            ),
-            if eq_enabled {
-                Span::styled(
-                    " (Press 'e' in device list to adjust)",
-                    Style::default().fg(Color::DarkGray),
-                )
-            } else {
-                Span::styled(
-                    " (Press 'e' in device list to enable)",
-                    Style::default().fg(Color::DarkGray),
-                )
-            },
-        ]))];
+    #[test]
+    fn test_bars_follow_attack_and_release() {
+        let mut app = App::new(1.0);
+        let device_id = DeviceId::new(1);
+        let start = Instant::now();
+        let frame = |level: f32, ms: u64| AudioEvent::SpectrumUpdate {
+            device_id,
+            data: Arc::new(SpectrumData {
+                bins: vec![level; 4],
+                bins_right: None,
+                frequencies: vec![1000.0; 4],
+                sample_rate: 48000,
+                timestamp: start + Duration::from_millis(ms),
+            }),
+        };
+        let bars = |app: &App| app.group_magnitude(device_id, 0, 1, &(0..4));
+
+        // Default: the jump up is nearly complete a frame later, the drop barely started
+        app.handle_audio_events(&[frame(-60.0, 0), frame(-10.0, 33)]);
+        assert!(bars(&app) > -12.0);
+        app.handle_audio_events(&[frame(-60.0, 66)]);
+        assert!((-18.0..-12.0).contains(&bars(&app)));
+        assert_eq!(app.spectrum_data[&device_id].bins[0], -60.0);
+
+        // Zero release drops straight to the new level
+        app.set_bar_envelope(10, 0);
+        app.handle_audio_events(&[frame(-60.0, 99)]);
+        assert_eq!(bars(&app), -60.0);
+
+        for _ in 0..100 {
+            app.adjust_bar_envelope(-(BAR_ATTACK_STEP_MS as i64), BAR_RELEASE_STEP_MS as i64);
+        }
+        assert_eq!(app.get_bar_envelope(), (0, MAX_BAR_RELEASE_MS));
+    }
 
-        let list = List::new(filter_items);
+    #[test]
+    fn test_bar_value_stays_in_range() {
+        assert_eq!(bar_value(30.7), 30);
+        assert_eq!(bar_value(-4.0), 0);
+        assert_eq!(bar_value(250.0), 60);
+        assert_eq!(bar_value(f32::NAN), 0);
+        assert_eq!(bar_value(f32::INFINITY), 60);
+        assert_eq!(bar_value(f32::NEG_INFINITY), 0);
+        assert_eq!(bar_value(AmplitudeTransform::Linear.apply(f32::NAN, 2.0)), 0);
+    }
 
-        frame.render_widget(list, inner);
+    #[test]
+    fn test_spectrum_gamma_is_clamped() {
+        let mut app = App::new(1.0);
+        assert_eq!(app.get_spectrum_gamma(), 1.0);
 
-        // Show EQ settings if enabled
-        if eq_enabled {
-            if let Some(settings) = self.eq_settings.get(&device_id) {
-                // Split the area to show EQ bands
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Length(3), // Filter list
-                        Constraint::Min(0),    // EQ bands display
-                    ])
-                    .split(inner);
+        for _ in 0..100 {
+            app.adjust_spectrum_gamma(-SPECTRUM_GAMMA_STEP);
+        }
+        assert_eq!(app.get_spectrum_gamma(), MIN_SPECTRUM_GAMMA);
 
-                // Render EQ bands
-                let mut band_lines = vec![
-                    Line::from(Span::styled(
-                        "EQ Bands (Press 'e' to adjust):",
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
-                    )),
-                    Line::from(""),
-                ];
+        app.set_spectrum_gamma(42.0);
+        assert_eq!(app.get_spectrum_gamma(), MAX_SPECTRUM_GAMMA);
+        app.set_spectrum_gamma(f32::NAN);
+        assert_eq!(app.get_spectrum_gamma(), 1.0);
+    }
 
-                const BANDS_HZ: [u32; 10] = [31, 63, 125, 250, 500, 1000, 2000, 4000, 8000, 20000];
-                for (i, band) in settings.bands.iter().enumerate() {
-                    let freq_str = if BANDS_HZ[i] >= 1000 {
-                        format!("{}k", BANDS_HZ[i] / 1000)
-                    } else {
-                        format!("{}Hz", BANDS_HZ[i])
-                    };
+    #[test]
+    fn test_spectrum_focus_cycles_through_devices() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
 
-                    let gain_str = format!("{:+.1}dB", band.gain_db);
-                    band_lines.push(Line::from(vec![
-                        Span::styled(
-                            format!("{:>5}: ", freq_str),
-                            Style::default().fg(Color::Cyan),
-                        ),
-                        Span::styled(
-                            gain_str,
-                            Style::default().fg(if band.gain_db.abs() < 0.1 {
-                                Color::DarkGray
-                            } else if band.gain_db > 0.0 {
-                                Color::Green
-                            } else {
-                                Color::Red
-                            }),
-                        ),
-                    ]));
-                }
+        let mut focus_order = Vec::new();
+        for _ in 0..3 {
+            app.cycle_spectrum_focus();
+            focus_order.push(app.focused_spectrum_device());
+        }
+        assert_eq!(focus_order, vec![Some(DeviceId::new(1)), Some(DeviceId::new(2)), None]);
+
+        // The title names the focused device and its position
+        app.cycle_spectrum_focus();
+        app.cycle_spectrum_focus();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("[Y] Device 2 [focus 2/2]"));
+    }
 
-                let paragraph = Paragraph::new(band_lines).alignment(Alignment::Left);
-                frame.render_widget(paragraph, chunks[1]);
+    /// App with one device per entry, hiding the devices marked `true`
+    fn app_with_hidden(hidden: &[bool], show_hidden: bool) -> App {
+        let mut app = App::new(1.0);
+        app.show_hidden = show_hidden;
+        for (i, &is_hidden) in hidden.iter().enumerate() {
+            let name = format!("Device {}", i);
+            if is_hidden {
+                app.hidden_devices.insert(name.clone());
             }
+            app.devices.push(DeviceInfo::new(
+                DeviceId::new(i as u64),
+                name,
+                crate::audio::DeviceType::Physical,
+            ));
         }
+        app
     }
 
-    fn render_spectrum(&self, frame: &mut Frame, area: Rect, show_borders: bool) {
-        // If no devices are being visualized, show a message
-        if self.visualized_devices.is_empty() {
-            if show_borders {
-                let block = Block::default()
-                    .borders(Borders::ALL)
-                    .title("Frequency Spectrum - Combined View")
-                    .title_alignment(Alignment::Left);
+    #[test]
+    fn test_next_visible_index() {
+        // (hidden flags, from, expected down, expected up) with hidden devices not shown
+        let cases: [(&[bool], usize, usize, usize); 5] = [
+            // All hidden: stay put
+            (&[true, true, true], 1, 1, 1),
+            // Hidden at the top: can't move up past them
+            (&[true, true, false, false], 2, 3, 2),
+            // Hidden at the bottom: can't move down past them
+            (&[false, false, true, true], 1, 1, 0),
+            // Alternating: skip one hidden device each way
+            (&[false, true, false, true, false], 2, 4, 0),
+            // Starting on a hidden device
+            (&[false, true, false], 1, 2, 0),
+        ];
+        for (hidden, from, down, up) in cases {
+            let app = app_with_hidden(hidden, false);
+            assert_eq!(app.next_visible_index(from, 1), down, "down from {} in {:?}", from, hidden);
+            assert_eq!(app.next_visible_index(from, -1), up, "up from {} in {:?}", from, hidden);
+        }
 
-                let paragraph = Paragraph::new(
-                    "No devices visualized\n\nPress Space on a device to start visualization",
-                )
-                .block(block)
-                .alignment(Alignment::Center);
+        // Shown hidden devices are ordinary stops, only the list ends stop movement
+        for hidden in [&[true, true, true][..], &[false, true, false, true]] {
+            let app = app_with_hidden(hidden, true);
+            assert_eq!(app.next_visible_index(1, 1), 2);
+            assert_eq!(app.next_visible_index(1, -1), 0);
+            assert_eq!(app.next_visible_index(0, -1), 0);
+            assert_eq!(app.next_visible_index(hidden.len() - 1, 1), hidden.len() - 1);
+        }
+    }
 
-                frame.render_widget(paragraph, area);
-            } else {
-                // In minimal mode, just show a simple message without borders
-                let paragraph = Paragraph::new(
-                    "No devices visualized\n\nPress Space on a device to start visualization",
-                )
-                .alignment(Alignment::Center);
+    #[test]
+    fn test_move_selection_from_hidden_device() {
+        let mut app = app_with_hidden(&[false, false, true, false], false);
+        app.selected_device = 2;
+        app.move_selection(1);
+        assert_eq!(app.selected_device, 3);
 
-                frame.render_widget(paragraph, area);
-            }
-            return;
+        app.selected_device = 2;
+        app.move_selection(-1);
+        assert_eq!(app.selected_device, 1);
+    }
+
+    #[test]
+    fn test_undo_hide_stack() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        for id in 1..=12 {
+            app.devices.push(DeviceInfo::new(
+                DeviceId::new(id),
+                format!("Device {:02}", id),
+                crate::audio::DeviceType::Physical,
+            ));
         }
 
-        // Get all visualized devices in a consistent order
-        let mut device_ids: Vec<DeviceId> = self.visualized_devices.iter().copied().collect();
-        device_ids.sort_by_key(|id| id.0); // Sort for consistent ordering
+        // Nothing to undo yet
+        app.handle_input(Key::Char('u'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "No recently hidden devices to restore");
+        assert!(app.hidden_devices.is_empty());
+        assert_eq!(app.selected_device, 0);
+        assert!(!app.config_dirty);
+
+        // One more than the stack holds: the first device hidden falls off
+        for index in 0..=HIDE_UNDO_DEPTH {
+            app.selected_device = index;
+            app.handle_input(Key::Char('h'), &mut audio_engine).unwrap();
+        }
+        assert_eq!(app.hidden_devices.len(), HIDE_UNDO_DEPTH + 1);
+        assert_eq!(app.recently_hidden.len(), HIDE_UNDO_DEPTH);
+        assert!(!app.recently_hidden.contains(&String::from("Device 01")));
+
+        // Unhiding by hand takes the device off the stack
+        app.selected_device = 9;
+        app.handle_input(Key::Char('h'), &mut audio_engine).unwrap();
+        assert!(!app.hidden_devices.contains("Device 10"));
+        assert!(!app.recently_hidden.contains(&String::from("Device 10")));
+
+        // Undo brings back the most recently hidden device and selects it
+        app.handle_input(Key::Char('u'), &mut audio_engine).unwrap();
+        assert!(!app.hidden_devices.contains("Device 11"));
+        assert_eq!(app.selected_device, 10);
+        assert_eq!(app.status_message, "Unhidden device: Device 11");
+
+        app.handle_input(Key::Char('u'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "Unhidden device: Device 09");
+        assert_eq!(app.selected_device, 8);
+        assert_eq!(app.recently_hidden.len(), HIDE_UNDO_DEPTH - 3);
+
+        // Device 01 fell off the stack, so undo stops before it
+        for _ in 0..HIDE_UNDO_DEPTH {
+            app.handle_input(Key::Char('u'), &mut audio_engine).unwrap();
+        }
+        assert_eq!(app.status_message, "No recently hidden devices to restore");
+        assert_eq!(app.hidden_devices.iter().collect::<Vec<_>>(), ["Device 01"]);
+    }
 
-        // Check if we have spectrum data for any device
-        let has_data = device_ids
-            .iter()
-            .any(|id| self.spectrum_data.contains_key(id));
-        if !has_data {
-            if show_borders {
-                let block = Block::default()
-                    .borders(Borders::ALL)
-                    .title("Frequency Spectrum - Waiting for data...")
-                    .title_alignment(Alignment::Left);
+    #[test]
+    fn test_apply_eq_to_visualized_devices() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        for i in 0..4 {
+            let mut device = DeviceInfo::new(
+                DeviceId::new(i),
+                format!("Device {}", i),
+                crate::audio::DeviceType::Physical,
+            );
+            device.ports.push(crate::audio::PortInfo::new(
+                crate::audio::PortId::new(10 + i),
+                String::from("playback_FL"),
+                PortDirection::Input,
+                format!("Device {}:playback_FL", i),
+            ));
+            app.devices.push(device);
+        }
+        // Device 0 is the source, 1 already has EQ, 2 doesn't, 3 isn't visualized
+        let mut settings = EqSettings::default();
+        settings.bands[3].gain_db = 4.0;
+        for id in [0, 1] {
+            app.eq_enabled_devices.insert(DeviceId::new(id));
+        }
+        app.eq_settings.insert(DeviceId::new(0), settings.clone());
+        app.eq_settings.insert(DeviceId::new(1), EqSettings::default());
+        for id in [0, 1, 2] {
+            app.visualized_devices.insert(DeviceId::new(id));
+        }
 
-                frame.render_widget(block, area);
-            }
-            return;
+        app.apply_eq_to_visualized(&audio_engine).unwrap();
+
+        for id in [1, 2] {
+            assert!(app.eq_enabled_devices.contains(&DeviceId::new(id)));
+            assert_eq!(app.eq_settings[&DeviceId::new(id)].bands[3].gain_db, 4.0);
         }
+        assert!(!app.eq_enabled_devices.contains(&DeviceId::new(3)));
+        assert_eq!(
+            app.status_message,
+            "Applied EQ from Device 0 to 2 device(s) (1 newly enabled)"
+        );
 
-        // Build the title with device names and colors
-        let device_info: Vec<String> = device_ids
-            .iter()
-            .enumerate()
-            .map(|(idx, &device_id)| {
-                let name = self
-                    .devices
-                    .iter()
-                    .find(|d| d.id == device_id)
-                    .map(|d| d.name.as_str())
-                    .unwrap_or("Unknown");
-                let color_name = Self::get_device_color_name(idx);
-                format!("[{}] {}", color_name, name)
-            })
-            .collect();
+        // With the queue full, curve changes are held back but enabling EQ is
+        // rejected; that device is skipped instead of aborting the rest
+        let audio_engine = AudioEngine::new().unwrap();
+        while audio_engine
+            .send_command(AudioCommand::SetRefreshRate { hz: 60 })
+            .is_ok()
+        {}
+        app.eq_enabled_devices.remove(&DeviceId::new(2));
+        app.eq_settings.remove(&DeviceId::new(2));
+        app.apply_eq_to_visualized(&audio_engine).unwrap();
+        assert!(!app.eq_enabled_devices.contains(&DeviceId::new(2)));
+        assert_eq!(
+            app.status_message,
+            "Applied EQ from Device 0 to 1 of 2 device(s) (0 newly enabled); audio engine busy, try again"
+        );
+    }
 
-        let title = format!("Frequency Spectrum - {}", device_info.join(" | "));
+    #[test]
+    fn test_visualizations_restored_after_reconnect() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        let device = |id: u64, name: &str| {
+            let mut device = DeviceInfo::new(
+                DeviceId::new(id),
+                name.to_string(),
+                crate::audio::DeviceType::Physical,
+            );
+            device.ports.push(crate::audio::PortInfo::new(
+                crate::audio::PortId::new(100 + id),
+                String::from("monitor_FL"),
+                PortDirection::Output,
+                format!("{}:monitor_FL", name),
+            ));
+            device
+        };
+        app.devices = vec![device(1, "Speakers"), device(2, "Headset")];
+        for id in [1, 2] {
+            app.visualized_devices.insert(DeviceId::new(id));
+        }
 
-        // Render the combined spectrum
-        self.render_combined_spectrum(frame, area, &title, &device_ids, show_borders);
+        app.handle_audio_events(&[
+            AudioEvent::Disconnected,
+            AudioEvent::VisualizationStopped { device_id: DeviceId::new(1) },
+            AudioEvent::VisualizationStopped { device_id: DeviceId::new(2) },
+        ]);
+        assert!(app.visualized_devices.is_empty());
+        assert!(!app.config_dirty);
+
+        // Only the speakers have come back so far, under a new ID
+        app.handle_audio_events(&[AudioEvent::Connected]);
+        app.devices = vec![device(7, "Speakers")];
+        app.restore_after_reconnect(&audio_engine).unwrap();
+        assert_eq!(audio_engine.queued_commands().0, 1);
+        assert_eq!(app.reconnect_visualizations, vec![String::from("Headset")]);
+
+        app.devices.push(device(8, "Headset"));
+        app.restore_after_reconnect(&audio_engine).unwrap();
+        assert_eq!(audio_engine.queued_commands().0, 2);
+        assert!(app.reconnect_visualizations.is_empty());
     }
 
-    fn get_device_color(idx: usize) -> Color {
-        match idx % 6 {
-            0 => Color::Cyan,
-            1 => Color::Yellow,
-            2 => Color::Magenta,
-            3 => Color::Green,
-            4 => Color::Red,
-            5 => Color::Blue,
-            _ => Color::White,
+    #[test]
+    fn test_eq_and_volume_follow_the_loaded_config() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        for (id, name) in [(1, "Speakers"), (2, "Headphones"), (3, "HDMI")] {
+            app.devices.push(DeviceInfo::new(
+                DeviceId::new(id),
+                name.to_string(),
+                crate::audio::DeviceType::Physical,
+            ));
         }
+        // The speakers have a boosted EQ and are turned down; the HDMI output has no EQ
+        let mut boosted = EqSettings::default();
+        boosted.bands[0].gain_db = 6.0;
+        app.eq_enabled_devices.insert(DeviceId::new(1));
+        app.eq_settings.insert(DeviceId::new(1), boosted.clone());
+        let quiet = VolumeSettings { gain_linear: 0.5, gain_db: -6.0, ..VolumeSettings::default() };
+        app.volume_settings.insert(DeviceId::new(1), quiet.clone());
+
+        let saved_eq = app.get_eq_settings();
+        let saved_volume = app.get_volume_settings();
+        assert_eq!(saved_eq, HashMap::from([(String::from("Speakers"), boosted.clone())]));
+        assert_eq!(saved_volume, HashMap::from([(String::from("Speakers"), quiet.clone())]));
+
+        // A profile with EQ on the headphones only
+        let eq = HashMap::from([(String::from("Headphones"), boosted.clone())]);
+        app.apply_eq_and_volume(&eq, &HashMap::new(), &audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&DeviceId::new(1)], EqSettings::default());
+        assert_eq!(app.volume_settings[&DeviceId::new(1)], VolumeSettings::default());
+        assert!(app.eq_enabled_devices.contains(&DeviceId::new(2)));
+        assert_eq!(app.eq_settings[&DeviceId::new(2)], boosted);
+        assert!(!app.eq_enabled_devices.contains(&DeviceId::new(3)));
+
+        // And back
+        app.apply_eq_and_volume(&saved_eq, &saved_volume, &audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&DeviceId::new(1)], boosted);
+        assert_eq!(app.volume_settings[&DeviceId::new(1)], quiet);
+
+        // A saved volume is restored on a device without EQ too
+        let volume = HashMap::from([(String::from("HDMI"), quiet.clone())]);
+        app.apply_eq_and_volume(&saved_eq, &volume, &audio_engine).unwrap();
+        assert_eq!(app.volume_settings[&DeviceId::new(3)], quiet);
+        assert!(!app.volume_settings.contains_key(&DeviceId::new(2)));
     }
 
-    fn get_device_color_name(idx: usize) -> &'static str {
-        match idx % 6 {
-            0 => "C", // Cyan
-            1 => "Y", // Yellow
-            2 => "M", // Magenta
-            3 => "G", // Green
-            4 => "R", // Red
-            5 => "B", // Blue
-            _ => "W",
+    #[test]
+    fn test_crossfeed_amount_and_cutoff_keys() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        app.devices.push(DeviceInfo::new(
+            DeviceId::new(1),
+            String::from("Headphones"),
+            crate::audio::DeviceType::Physical,
+        ));
+        app.eq_enabled_devices.insert(DeviceId::new(1));
+
+        for _ in 0..2 {
+            app.adjust_crossfeed(CROSSFEED_AMOUNT_STEP, 0.0, &audio_engine).unwrap();
+        }
+        app.adjust_crossfeed(0.0, -CROSSFEED_CUTOFF_STEP, &audio_engine).unwrap();
+        let settings = &app.crossfeed_settings["Headphones"];
+        assert!((settings.amount - 0.4).abs() < 1e-6);
+        assert_eq!(settings.cutoff_hz, 600.0);
+        assert_eq!(app.status_message, "Crossfeed for Headphones: 40%, 600 Hz (off)");
+
+        // Limits hold however often the key is pressed
+        for _ in 0..30 {
+            app.adjust_crossfeed(CROSSFEED_AMOUNT_STEP, CROSSFEED_CUTOFF_STEP, &audio_engine).unwrap();
         }
+        let settings = &app.crossfeed_settings["Headphones"];
+        assert_eq!((settings.amount, settings.cutoff_hz), (1.0, 2000.0));
     }
 
-    fn render_combined_spectrum(
-        &self,
-        frame: &mut Frame,
-        area: Rect,
-        title: &str,
-        device_ids: &[DeviceId],
-        show_borders: bool,
-    ) {
-        let num_devices = device_ids.len();
+    #[test]
+    fn test_stopping_all_keeps_devices_whose_stop_was_not_sent() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        for id in [1, 2, 3] {
+            app.visualized_devices.insert(DeviceId::new(id));
+        }
+        // Fill the command queue so the next stop is rejected
+        while audio_engine
+            .send_command(AudioCommand::SetRefreshRate { hz: 60 })
+            .is_ok()
+        {}
+
+        assert!(app.toggle_pause_all(&audio_engine).is_err());
+        assert!(app.paused_visualizations.is_empty());
+        assert!(app.stop_all_visualizations(&audio_engine).is_err());
+        assert_eq!(app.visualized_devices.len(), 3);
+
+        let audio_engine = AudioEngine::new().unwrap();
+        app.toggle_pause_all(&audio_engine).unwrap();
+        assert!(app.visualized_devices.is_empty());
+        assert_eq!(app.paused_visualizations.len(), 3);
+    }
 
-        // Get first device's spectrum to determine number of bins
-        let first_spectrum = device_ids
-            .iter()
-            .find_map(|id| self.spectrum_data.get(id))
-            .unwrap();
+    #[test]
+    fn test_spectrum_updates_do_not_copy_bins() {
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        let events: Vec<AudioEvent> = (0..30)
+            .map(|_| AudioEvent::SpectrumUpdate {
+                device_id,
+                data: Arc::new(SpectrumData {
+                    bins: vec![-20.0; 64],
+                    bins_right: None,
+                    frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                    sample_rate: 48000,
+                    timestamp: Instant::now(),
+                }),
+            })
+            .collect();
 
-        let total_bins = first_spectrum.bins.len();
+        // Every update's data is stored as shared, not copied
+        // (the cost difference is measured by the spectrum_handoff bench)
+        app.handle_audio_events(&events);
+        let AudioEvent::SpectrumUpdate { data, .. } = &events[29] else {
+            unreachable!();
+        };
+        assert!(Arc::ptr_eq(&app.spectrum_data[&device_id], data));
+        assert_eq!(Arc::strong_count(data), 2);
+    }
 
-        // Calculate the actual inner width the same way render_custom_bars does
-        let available_width = if show_borders {
-            let block = Block::default()
-                .borders(Borders::ALL)
-                .title(title)
-                .title_alignment(Alignment::Left);
-            block.inner(area).width as usize
-        } else {
-            area.width as usize
+    #[test]
+    fn test_spectrum_frames_are_batched() {
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        let start = Instant::now();
+        app.set_spectrum_batch_window(Duration::from_millis(100));
+        app.last_spectrum_flush = start;
+        let frame = |level: f32| AudioEvent::SpectrumUpdate {
+            device_id,
+            data: Arc::new(SpectrumData {
+                bins: vec![level; 64],
+                bins_right: None,
+                frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                sample_rate: 48000,
+                timestamp: start,
+            }),
         };
 
-        if available_width == 0 {
-            return;
-        }
+        // Frames inside the window wait, and only the latest is kept
+        app.handle_audio_events(&[frame(-10.0)]);
+        app.handle_audio_events(&[frame(-5.0)]);
+        app.flush_spectrum_batch(start + Duration::from_millis(50));
+        assert_eq!(app.spectrum_data[&device_id].bins[0], -20.0);
+        app.flush_spectrum_batch(start + Duration::from_millis(100));
+        assert_eq!(app.spectrum_data[&device_id].bins[0], -5.0);
+        assert!(app.pending_spectrum.is_empty());
+
+        // A frame still waiting when the visualization stops is dropped with it
+        app.handle_audio_events(&[
+            frame(-1.0),
+            AudioEvent::VisualizationStopped { device_id },
+        ]);
+        app.flush_spectrum_batch(start + Duration::from_secs(1));
+        assert!(!app.spectrum_data.contains_key(&device_id));
+    }
 
-        let bars_per_group = num_devices;
+    #[test]
+    fn test_stale_spectrum_falls_away() {
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        let start = Instant::now();
+        app.bar_release_ms = 300;
+        app.handle_audio_events(&[AudioEvent::SpectrumUpdate {
+            device_id,
+            data: Arc::new(SpectrumData {
+                bins: vec![-10.0; 64],
+                bins_right: None,
+                frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                sample_rate: 48000,
+                timestamp: start,
+            }),
+        }]);
+
+        // Frames a little late keep their levels, then the bars release to the floor
+        app.fade_stale_spectra(start + STALE_SPECTRUM_AFTER / 2);
+        assert_eq!(app.smoothed_bins[&device_id].bins()[0], -10.0);
+        app.fade_stale_spectra(start + STALE_SPECTRUM_AFTER * 4);
+        assert!(app.smoothed_bins[&device_id].bins()[0] < SPECTRUM_FLOOR_DB + 0.1);
+    }
 
-        // Calculate how many frequency groups we can show and how many times to repeat each
-        let max_possible_groups = available_width / bars_per_group;
-        let num_frequency_groups = max_possible_groups.min(total_bins);
+    #[test]
+    fn test_spectrum_table() {
+        let data = SpectrumData {
+            bins: vec![-12.5, -60.0],
+            bins_right: None,
+            frequencies: vec![31.25, 1000.0],
+            sample_rate: 48000,
+            timestamp: Instant::now(),
+        };
+        assert_eq!(
+            spectrum_table(&data, 0.0),
+            vec![
+                "  bin  freq (Hz)  level dB",
+                "    0       31.2    -12.50",
+                "    1     1000.0    -60.00",
+            ]
+        );
+    }
 
-        if num_frequency_groups == 0 {
-            return;
+    #[test]
+    fn test_silent_streams_are_auto_hidden() {
+        let mut app = app_with_hidden(&[false, false, false], false);
+        for device in &mut app.devices[..2] {
+            device.media_class = Some(String::from("Stream/Output/Audio"));
         }
+        app.set_auto_hide_after(Some(Duration::from_secs(10)));
+        let start = Instant::now();
+        for id in 0..3 {
+            app.last_active.insert(DeviceId::new(id), start);
+        }
+        app.selected_device = 0;
+
+        // Device 0 keeps playing, device 1 goes quiet; device 2 is a sink and never hidden
+        let later = start + Duration::from_secs(11);
+        app.handle_audio_events(&[AudioEvent::SpectrumUpdate {
+            device_id: DeviceId::new(0),
+            data: Arc::new(SpectrumData {
+                bins: vec![-20.0; 64],
+                bins_right: None,
+                frequencies: vec![0.0; 64],
+                sample_rate: 48000,
+                timestamp: later,
+            }),
+        }]);
+        app.selected_device = 1;
+        app.update_auto_hide(later);
+        assert_eq!(app.auto_hidden, HashSet::from([DeviceId::new(1)]));
+        assert_eq!(app.selected_device, 2);
+        assert!(app.get_hidden_devices().is_empty());
+
+        // Playing again brings it back
+        app.last_active.insert(DeviceId::new(1), later);
+        app.update_auto_hide(later);
+        assert!(app.auto_hidden.is_empty());
+        assert_eq!(app.status_message, "Playing again: Device 1");
+    }
 
-        // Calculate base repetition per group and extra bars
-        let base_repetition = available_width / (num_frequency_groups * bars_per_group);
-        let total_with_base = num_frequency_groups * bars_per_group * base_repetition;
-        let extra_bars = available_width - total_with_base;
-
-        // Build bar chart data
-        let mut bars_data: Vec<(&str, u64)> = Vec::new();
-        let mut bar_styles: Vec<Style> = Vec::new();
-
-        // Helper function to get magnitude for a frequency range
-        let get_magnitude = |group_idx: usize, device_id: DeviceId| -> f32 {
-            let bin_start = (group_idx * total_bins) / num_frequency_groups;
-            let bin_end = ((group_idx + 1) * total_bins) / num_frequency_groups;
-
-            if let Some(spectrum) = self.spectrum_data.get(&device_id) {
-                let mut max_mag: f32 = -60.0;
-                for bin_idx in bin_start..bin_end {
-                    if bin_idx < spectrum.bins.len() {
-                        max_mag = max_mag.max(spectrum.bins[bin_idx]);
-                    }
-                }
-                max_mag
-            } else {
-                -60.0
-            }
-        };
-
-        // Track extra bars distributed
-        let mut extra_bars_used = 0;
+    #[test]
+    fn test_unvisualized_streams_follow_node_state() {
+        let mut app = app_with_hidden(&[false, false], false);
+        for device in &mut app.devices {
+            device.media_class = Some(String::from("Stream/Output/Audio"));
+        }
+        app.set_auto_hide_after(Some(Duration::from_secs(10)));
+        let (playing, paused) = (DeviceId::new(0), DeviceId::new(1));
+        app.handle_audio_events(&[
+            AudioEvent::StreamStateChanged { device_id: playing, running: true },
+            AudioEvent::StreamStateChanged { device_id: paused, running: false },
+        ]);
+
+        // Neither is visualized; the running one stays, the idle one is hidden
+        let later = Instant::now() + Duration::from_secs(11);
+        app.update_auto_hide(later);
+        assert_eq!(app.auto_hidden, HashSet::from([paused]));
+
+        // Starting to play brings it back; stopping times silence from then
+        app.handle_audio_events(&[
+            AudioEvent::StreamStateChanged { device_id: paused, running: true },
+            AudioEvent::StreamStateChanged { device_id: playing, running: false },
+        ]);
+        let stopped = Instant::now();
+        app.update_auto_hide(stopped);
+        assert!(app.auto_hidden.is_empty());
+        app.update_auto_hide(stopped + Duration::from_secs(11));
+        assert_eq!(app.auto_hidden, HashSet::from([playing]));
+    }
 
-        // For each frequency group
-        for group_idx in 0..num_frequency_groups {
-            // Calculate repetitions for this group (distribute extra bars evenly)
-            let extra_for_this_group = if extra_bars_used < extra_bars {
-                extra_bars_used += 1;
-                1
-            } else {
-                0
-            };
-            let repetitions_for_this_group = base_repetition + extra_for_this_group;
+    #[test]
+    fn test_assigned_device_color_overrides_palette() {
+        let mut app = app_with_spectrum();
+        let (first, second) = (DeviceId::new(1), DeviceId::new(2));
+        assert_eq!(app.get_device_color_name(first), "C");
+        assert_eq!(app.get_device_color_name(second), "Y");
+
+        // Device 2 is selected by index; one press assigns the first palette color
+        app.selected_device = 1;
+        app.cycle_device_color();
+        assert_eq!(app.get_device_color(second), Color::Cyan);
+        assert_eq!(app.get_device_colors()["Device 2"], DeviceColor::Cyan);
+        app.cycle_device_color();
+        assert_eq!(app.get_device_color_name(second), "Y");
+        // Other devices keep their automatic colors
+        assert_eq!(app.get_device_color_name(first), "C");
+
+        app.restore_device_colors(HashMap::from([(String::from("Device 2"), DeviceColor::LightRed)]));
+        assert_eq!(app.get_device_color_name(second), "r");
+        app.cycle_device_color();
+        assert_eq!(app.status_message, "Device 2 color: light blue");
+        app.cycle_device_color();
+        assert_eq!(app.status_message, "Device 2 color: automatic");
+        assert!(app.get_device_colors().is_empty());
+    }
 
-            // Repeat this frequency group's bars the calculated number of times
-            for _rep in 0..repetitions_for_this_group {
-                for (device_idx, &device_id) in device_ids.iter().enumerate() {
-                    let magnitude = get_magnitude(group_idx, device_id);
+    #[test]
+    fn test_calibration_offsets_readouts_by_device_name() {
+        let mut app = app_with_spectrum();
+        let (first, second) = (DeviceId::new(1), DeviceId::new(2));
+        app.selected_device = 1;
+        app.adjust_calibration(CALIBRATION_STEP_DB);
+        assert_eq!(app.calibration(second), 1.0);
+        assert_eq!(app.calibration(first), 0.0);
+        assert_eq!(app.status_message, "Device 2 calibration: +1.0 dB");
+
+        // Back at 0 dB the device is uncalibrated again and nothing is saved
+        app.adjust_calibration(-CALIBRATION_STEP_DB);
+        assert_eq!(app.status_message, "Device 2 calibration: none (levels in dBFS)");
+        assert!(app.get_calibration().is_empty());
+
+        app.restore_calibration(HashMap::from([
+            (String::from("Device 1"), 500.0),
+            (String::from("Device 2"), f32::NAN),
+        ]));
+        assert_eq!(app.calibration(first), MAX_CALIBRATION_DB);
+        assert_eq!(app.calibration(second), 0.0);
+        app.selected_device = 0;
+        app.adjust_calibration(CALIBRATION_STEP_DB);
+        assert_eq!(app.calibration(first), MAX_CALIBRATION_DB);
+    }
 
-                    let normalized = (magnitude + 60.0_f32).max(0.0_f32).min(60.0_f32);
-                    let amplified = (normalized * self.spectrum_amplification).min(60.0_f32);
-                    let display_value = amplified as u64;
+    #[test]
+    fn test_fft_size_cycles_and_shows_in_status_bar() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        app.selected_device = 0;
+        app.visualized_devices.insert(device_id);
+        assert_eq!(app.fft_size(device_id), DEFAULT_FFT_SIZE);
+
+        // The size only changes once the audio thread confirms it
+        app.cycle_fft_size(&audio_engine).unwrap();
+        assert_eq!(app.fft_size(device_id), DEFAULT_FFT_SIZE);
+        app.handle_audio_events(&[AudioEvent::FftSizeChanged { device_id, fft_size: 16384 }]);
+        assert_eq!(app.fft_size(device_id), 16384);
+        assert_eq!(app.status_message, "FFT size for Device 1: 16384");
+
+        let mut terminal = Terminal::new(TestBackend::new(160, 40)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("FFT 16384"));
 
-                    bars_data.push(("", display_value));
-                    bar_styles.push(Style::default().fg(Self::get_device_color(device_idx)));
-                }
-            }
-        }
+        // Stopping the visualization forgets the size
+        app.handle_audio_events(&[AudioEvent::VisualizationStopped { device_id }]);
+        assert_eq!(app.fft_size(device_id), DEFAULT_FFT_SIZE);
+    }
 
-        // Debug logging
-        crate::debug_log!(
-            "[SPECTRUM] area.width={}, available_width={}, bars_per_group={}, num_frequency_groups={}, base_rep={}, extra_bars={}, total_bars={}",
-            area.width,
-            available_width,
-            bars_per_group,
-            num_frequency_groups,
-            base_repetition,
-            extra_bars,
-            bars_data.len()
-        );
+    #[test]
+    fn test_waveform_view_replaces_spectrum() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+
+        app.handle_input(Key::Char('W'), &mut audio_engine).unwrap();
+        assert!(app.waveform_view);
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetWaveform"].sent, 1);
+        app.handle_audio_events(&[AudioEvent::WaveformUpdate { device_id, samples: vec![0.0, 1.0, -1.0] }]);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Waveform - "));
+        assert!(!text.contains("Frequency Spectrum"));
 
-        // We need to render bars with individual colors, but BarChart only has one style
-        // Workaround: render the spectrum using custom rendering
-        self.render_custom_bars(
-            frame,
-            area,
-            title,
-            &bars_data,
-            &bar_styles,
-            show_borders,
-            num_frequency_groups,
-            bars_per_group,
-        );
+        // Switching back drops the samples
+        app.handle_input(Key::Char('W'), &mut audio_engine).unwrap();
+        assert!(app.waveforms.is_empty());
     }
 
-    fn render_custom_bars(
-        &self,
-        frame: &mut Frame,
-        area: Rect,
-        title: &str,
-        bars: &[(&str, u64)],
-        bar_styles: &[Style],
-        show_borders: bool,
-        num_frequency_groups: usize,
-        bars_per_group: usize,
-    ) {
-        let inner = if show_borders {
-            let block = Block::default()
-                .borders(Borders::ALL)
-                .title(title)
-                .title_alignment(Alignment::Left);
+    #[test]
+    fn test_level_meters_beside_spectrum() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+
+        // Ignored while the meters are off
+        app.handle_audio_events(&[AudioEvent::LevelUpdate { device_id, peak_db: -6.0, rms_db: -9.5 }]);
+        assert!(app.levels.is_empty());
+
+        app.handle_input(Key::Char('l'), &mut audio_engine).unwrap();
+        assert!(app.level_meters);
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetLevelMeters"].sent, 1);
+        app.handle_audio_events(&[AudioEvent::LevelUpdate { device_id, peak_db: -6.0, rms_db: -9.5 }]);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Levels (dBFS)"));
+        assert!(text.contains("Frequency Spectrum"));
+        assert!(text.contains(&format!("Pk  {}··  -6.0", "█".repeat(20))));
+        assert!(text.contains("  -9.5"));
+        assert!(text.contains("no level yet"));
+
+        app.handle_input(Key::Char('l'), &mut audio_engine).unwrap();
+        assert!(app.levels.is_empty());
+    }
 
-            let inner = block.inner(area);
-            frame.render_widget(block, area);
-            inner
-        } else {
-            // In minimal mode, use the full area without borders
-            area
+    #[test]
+    fn test_clip_indicator_holds_after_clip() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        let render = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal
+                .draw(|frame| app.render(frame, &audio_engine))
+                .unwrap();
+            terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect::<String>()
         };
+        assert!(!render(&mut app).contains("CLIP"));
 
-        crate::debug_log!(
-            "[RENDER_BARS] area.width={}, inner.width={}, bars.len()={}, show_borders={}",
-            area.width,
-            inner.width,
-            bars.len(),
-            show_borders
-        );
-
-        if bars.is_empty() || inner.width < 2 || inner.height < 3 {
-            return;
-        }
+        app.handle_audio_events(&[AudioEvent::Clipped { device_id }]);
+        assert!(app.is_clipping(device_id));
+        assert!(!app.is_clipping(DeviceId::new(2)));
+        assert!(render(&mut app).contains("CLIP"));
 
-        // Use full height for bars
-        let bar_height_area = inner.height;
-        let _label_y = inner.y + bar_height_area;
+        app.last_clip.insert(device_id, Instant::now() - CLIP_HOLD);
+        assert!(!render(&mut app).contains("CLIP"));
+    }
 
-        // Max height accounts for baseline (5) + amplified range (60)
-        let max_height = 65.0; // Baseline (5) + max amplified dB (60)
+    #[test]
+    fn test_patch_ports_in_routing_tab() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        app.current_tab = DeviceTab::Routing;
+        for (index, (name, direction)) in
+            [("monitor_FL", PortDirection::Output), ("playback_FL", PortDirection::Input)]
+                .into_iter()
+                .enumerate()
+        {
+            let device = &mut app.devices[index];
+            let pipewire_name = format!("{}:{}", device.name, name);
+            device.ports.push(crate::audio::PortInfo::new(
+                crate::audio::PortId::new(index as u64 + 1),
+                name.to_string(),
+                direction,
+                pipewire_name,
+            ));
+        }
+        app.selected_device = 0;
+        app.refresh_connections(&audio_engine);
+        assert_eq!(app.connections.len(), 1);
+        assert!(app.available_actions().allows(Action::ToggleGroup));
+
+        // Enter on the same port cancels
+        app.handle_input(Key::Char('\n'), &mut audio_engine).unwrap();
+        assert!(app.status_message.starts_with("Patching from Device 1:monitor_FL"));
+        app.handle_input(Key::Char('\n'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "Patch cancelled");
+
+        // Ports the audio thread doesn't know are reported instead of ignored
+        app.handle_input(Key::Char('\n'), &mut audio_engine).unwrap();
+        app.selected_device = 1;
+        app.refresh_connections(&audio_engine);
+        app.handle_input(Key::Char('\n'), &mut audio_engine).unwrap();
+        assert_eq!(
+            app.status_message,
+            "Cannot patch Device 1:monitor_FL -> Device 2:playback_FL: Port not found: Device 1:monitor_FL"
+        );
+        assert!(app.patch_source.is_none());
+    }
 
-        // With braille, each terminal row represents 4 vertical pixels
-        let braille_pixels_per_row = 4;
-        let total_vertical_pixels = bar_height_area as usize * braille_pixels_per_row;
+    #[test]
+    fn test_virtual_devices_are_named_and_deleted() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        app.devices[1].name = String::from("Virtual 1");
+        app.devices[1].device_type = crate::audio::DeviceType::Virtual;
+
+        app.handle_input(Key::Char('n'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "Creating virtual device Virtual 2...");
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["CreateVirtualDevice"].sent, 1);
+
+        // Only virtual devices can be deleted
+        app.selected_device = 0;
+        assert!(!app.available_actions().allows(Action::DeleteVirtualDevice));
+        app.handle_input(Key::Delete, &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "Device 1 is not a virtual device");
+        app.selected_device = 1;
+        assert!(app.available_actions().allows(Action::DeleteVirtualDevice));
+    }
 
-        // Render each bar using braille characters for better vertical resolution
-        for (i, ((_label, value), style)) in bars.iter().zip(bar_styles.iter()).enumerate() {
-            if i >= inner.width as usize {
-                break;
-            }
+    #[test]
+    fn test_eq_tap_draws_pre_and_post() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        let post = AudioEvent::EqTapSpectrum {
+            device_id,
+            data: Arc::new(SpectrumData {
+                bins: vec![-40.0; 64],
+                bins_right: None,
+                frequencies: (0..64).map(|i| 20.0 + i as f32 * 300.0).collect(),
+                sample_rate: 48000,
+                timestamp: Instant::now(),
+            }),
+        };
 
-            // Calculate height in braille pixels (4x resolution)
-            let bar_height_pixels =
-                (*value as f32 / max_height * total_vertical_pixels as f32) as usize;
-            let bar_height_pixels = bar_height_pixels.min(total_vertical_pixels).max(1);
+        // Spectra from a tap the UI didn't start are ignored
+        app.handle_audio_events(std::slice::from_ref(&post));
+        assert!(app.eq_tap_spectrum.is_empty());
 
-            if bar_height_pixels > 0 {
-                let x = inner.x + i as u16;
+        app.handle_audio_events(&[AudioEvent::EqTapStarted { device_id }, post]);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("[C] Device 1 pre [c] post | [Y] Device 2"));
+        // The post-EQ bars are drawn in the other shade of the device's color
+        assert!(buffer.content().iter().any(|cell| cell.fg == Color::LightCyan));
+
+        app.handle_audio_events(&[AudioEvent::EqTapStopped { device_id }]);
+        assert!(app.eq_tap_spectrum.is_empty() && app.eq_taps.is_empty());
+    }
 
-                // Calculate how many full rows and remaining pixels
-                let full_rows = bar_height_pixels / braille_pixels_per_row;
-                let remaining_pixels = bar_height_pixels % braille_pixels_per_row;
+    #[test]
+    fn test_health_overlay_shows_quantum() {
+        let mut app = app_with_spectrum();
+        let render = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal
+                .draw(|frame| app.render_health_overlay(frame, frame.area()))
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+        assert!(render(&app).contains("PipeWire: quantum unknown"));
 
-                // Start from bottom
-                let bottom_row = inner.y + bar_height_area - 1;
+        app.graph_clock.apply("clock.rate", Some("48000"));
+        app.graph_clock.apply("clock.quantum", Some("1024"));
+        assert!(render(&app).contains("PipeWire: 1024 samples @ 48000 Hz = 21.3 ms"));
+        app.graph_clock.apply("clock.force-quantum", Some("128"));
+        assert!(render(&app).contains("128 samples @ 48000 Hz = 2.7 ms (forced)"));
+    }
 
-                // Render full rows with full braille character
-                for row in 0..full_rows {
-                    let y = bottom_row.saturating_sub(row as u16);
-                    if y >= inner.y && y < inner.y + bar_height_area {
-                        let cell = frame.buffer_mut().cell_mut((x, y)).unwrap();
-                        // Full column: left column filled (dots 1,2,3,4)
-                        cell.set_symbol(Self::braille_char(0b1111));
-                        cell.set_style(*style);
-                    }
-                }
+    #[test]
+    fn test_first_run_overlay_and_empty_device_list() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        app.set_first_run_seen(false);
+        let render = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+            terminal
+                .draw(|frame| {
+                    app.render_device_list(frame, Rect::new(0, 0, 40, 20));
+                    app.render_first_run_overlay(frame, frame.area());
+                })
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+        let screen = render(&mut app);
+        assert!(screen.contains("Discovering audio devices…"));
+        assert!(screen.contains("Space  visualize its spectrum"));
+
+        // The key that dismisses the overlay does nothing else
+        app.handle_input(Key::Char('q'), &mut audio_engine).unwrap();
+        assert!(app.first_run_seen());
+        assert!(app.running);
+        assert!(app.config_dirty);
+    }
 
-                // Render partial row at top if needed
-                if remaining_pixels > 0 && full_rows < bar_height_area as usize {
-                    let y = bottom_row.saturating_sub(full_rows as u16);
-                    if y >= inner.y && y < inner.y + bar_height_area {
-                        let cell = frame.buffer_mut().cell_mut((x, y)).unwrap();
-                        // Partial column: fill from bottom
-                        let pattern = match remaining_pixels {
-                            1 => 0b0001, // Bottom dot only
-                            2 => 0b0011, // Bottom 2 dots
-                            3 => 0b0111, // Bottom 3 dots
-                            _ => 0b1111, // All dots
-                        };
-                        cell.set_symbol(Self::braille_char(pattern));
-                        cell.set_style(*style);
-                    }
-                }
-            }
+    #[test]
+    fn test_device_list_scrolls_and_pages() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        for id in 1..=30 {
+            app.devices.push(DeviceInfo::new(
+                DeviceId::new(id),
+                format!("Device {:02}", id),
+                crate::audio::DeviceType::Physical,
+            ));
         }
+        // Hidden devices don't take up rows or count towards the total
+        app.hidden_devices.insert(String::from("Device 02"));
+        let render = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+            terminal
+                .draw(|frame| app.render_device_list(frame, Rect::new(0, 0, 40, 12)))
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
 
-        // Draw vertical lines for selected EQ band (if in EQ mode)
-        if self.focus_mode == FocusMode::SpectrumEq && self.devices.len() > self.selected_device {
-            let device = &self.devices[self.selected_device];
-            if self.eq_enabled_devices.contains(&device.id) {
-                // Get the EQ band's center frequency and settings
-                if let Some(eq_settings) = self.eq_settings.get(&device.id) {
-                    let band_params = &eq_settings.bands[self.selected_eq_band];
-                    let center_freq = band_params.frequency;
-
-                    // Calculate the frequency range affected by this EQ band
-                    // For a peaking EQ, the -3dB bandwidth is related to Q
-                    // bandwidth (octaves) = 2 * asinh(1/(2*Q)) / ln(2)
-                    // For Q=1.41, this is approximately 1 octave
-                    // We'll use a slightly wider range for visualization (±1 octave = 0.5 to 2.0 times center)
-                    let freq_low = center_freq / 2.0;
-                    let freq_high = center_freq * 2.0;
-
-                    // Get spectrum data to access frequency information
-                    if let Some(spectrum) = self.spectrum_data.get(&device.id) {
-                        // Find the leftmost and rightmost display positions for the frequency range
-                        let total_bins = spectrum.bins.len();
-
-                        // Find the first and last bins that fall within the EQ band's frequency range
-                        let mut first_bin = None;
-                        let mut last_bin = None;
-
-                        for (bin_idx, &bin_freq) in spectrum.frequencies.iter().enumerate() {
-                            if bin_freq >= freq_low && bin_freq <= freq_high {
-                                if first_bin.is_none() {
-                                    first_bin = Some(bin_idx);
-                                }
-                                last_bin = Some(bin_idx);
-                            }
-                        }
+        let screen = render(&mut app);
+        assert!(screen.contains("Devices (1-10 of 29)"));
+        assert!(!screen.contains("Device 12"));
+
+        // A page is the ten rows that fit between the borders
+        app.handle_input(Key::PageDown, &mut audio_engine).unwrap();
+        assert_eq!(app.devices[app.selected_device].name, "Device 12");
+        let screen = render(&mut app);
+        assert!(screen.contains("Devices (2-11 of 29)"));
+        assert!(screen.contains("> [ ] Device 12"));
+        assert!(!screen.contains("Device 01"));
+
+        app.handle_input(Key::PageDown, &mut audio_engine).unwrap();
+        app.handle_input(Key::PageDown, &mut audio_engine).unwrap();
+        assert_eq!(app.devices[app.selected_device].name, "Device 30");
+        assert!(render(&mut app).contains("Devices (20-29 of 29)"));
+
+        // Going back up only scrolls once the cursor reaches the top edge
+        app.handle_input(Key::PageUp, &mut audio_engine).unwrap();
+        assert_eq!(app.devices[app.selected_device].name, "Device 20");
+        assert!(render(&mut app).contains("Devices (19-28 of 29)"));
+    }
 
-                        if let (Some(first_bin), Some(last_bin)) = (first_bin, last_bin) {
-                            // Map bins to display groups
-                            let first_group = (first_bin * num_frequency_groups) / total_bins;
-                            let last_group = (last_bin * num_frequency_groups) / total_bins;
-
-                            // Calculate repetition parameters (same as in render_combined_spectrum)
-                            let available_width = inner.width as usize;
-                            let base_repetition = available_width / (num_frequency_groups * bars_per_group);
-                            let total_with_base = num_frequency_groups * bars_per_group * base_repetition;
-                            let extra_bars = available_width - total_with_base;
-
-                            // Helper function to calculate X position for a group accounting for repetitions
-                            let calc_x_position = |group_idx: usize| -> usize {
-                                if group_idx < extra_bars {
-                                    // This group gets extra repetition
-                                    group_idx * (base_repetition + 1) * bars_per_group
-                                } else {
-                                    // This group gets base repetition only
-                                    extra_bars * (base_repetition + 1) * bars_per_group
-                                        + (group_idx - extra_bars) * base_repetition * bars_per_group
-                                }
-                            };
-
-                            // Draw vertical lines only at the boundaries
-                            for &group_idx in &[first_group, last_group] {
-                                let x_pos = calc_x_position(group_idx);
-                                let x = inner.x + x_pos as u16;
-
-                                if x < inner.x + inner.width {
-                                    for y in 0..inner.height {
-                                        let cell_y = inner.y + y;
-                                        if let Some(cell) = frame.buffer_mut().cell_mut((x, cell_y))
-                                        {
-                                            cell.set_symbol("│");
-                                            cell.set_style(
-                                                Style::default()
-                                                    .fg(Color::Yellow)
-                                                    .add_modifier(Modifier::BOLD),
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+    #[test]
+    fn test_help_overlay_lists_every_binding() {
+        let app = App::new(1.0);
+        for (width, height) in [(160, 40), (60, 12)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal
+                .draw(|frame| app.render_help_overlay(frame, frame.area()))
+                .unwrap();
+            let screen: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            assert!(screen.contains("Key bindings (?: close)"));
+            if width == 160 {
+                for name in ["toggle_viz", "zoom_reset", "new_virtual_device", "eq_next_band"] {
+                    assert!(screen.contains(name), "{} missing", name);
                 }
             }
         }
     }
 
-    /// Convert a 4-bit pattern to a braille character (both columns filled)
-    /// Bit 0 = level 1 (bottom), bit 1 = level 2, bit 2 = level 3, bit 3 = level 4 (top)
-    /// Braille layout: 1 4
-    ///                 2 5
-    ///                 3 6
-    ///                 7 8
-    fn braille_char(pattern: u8) -> &'static str {
-        match pattern {
-            0b0000 => "⠀", // blank
-            0b0001 => "⣀", // bottom row only (dots 7,8)
-            0b0011 => "⣤", // bottom 2 rows (dots 3,6,7,8)
-            0b0111 => "⣶", // bottom 3 rows (dots 2,3,5,6,7,8)
-            0b1111 => "⣿", // all 4 rows (full block)
-            _ => "⠀",      // default to blank for other patterns
+    #[test]
+    fn test_node_prompt_takes_digits_only() {
+        let audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        app.node_prompt = Some(String::new());
+        for c in "4x2".chars() {
+            app.handle_node_input(Key::Char(c), &audio_engine).unwrap();
         }
-    }
+        assert_eq!(app.node_prompt.as_deref(), Some("42"));
 
-    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
-        let mut help_spans = vec![
-            Span::styled(
-                "Status: ",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(&self.status_message, Style::default().fg(Color::White)),
-            Span::raw("  |  "),
-            Span::styled("q", Style::default().fg(Color::Cyan)),
-            Span::raw(": quit  "),
-        ];
+        app.handle_node_input(Key::Esc, &audio_engine).unwrap();
+        assert!(app.node_prompt.is_none());
+        assert_eq!(app.status_message, "Visualize by ID cancelled");
+    }
 
-        // Add mode-specific help
-        match self.focus_mode {
-            FocusMode::DeviceList => {
-                help_spans.extend_from_slice(&[
-                    Span::styled("Tab", Style::default().fg(Color::Cyan)),
-                    Span::raw(": switch tab  "),
-                    Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-                    Span::raw(": select  "),
-                    Span::styled("e", Style::default().fg(Color::Cyan)),
-                    Span::raw(": EQ  "),
-                    Span::styled("Space", Style::default().fg(Color::Cyan)),
-                    Span::raw(": viz  "),
-                    Span::styled(",/.", Style::default().fg(Color::Cyan)),
-                    Span::raw(": volume  "),
-                    Span::styled("h", Style::default().fg(Color::Cyan)),
-                    Span::raw(": hide  "),
-                    Span::styled("H", Style::default().fg(Color::Cyan)),
-                    Span::raw(": show hidden"),
-                ]);
-            }
-            FocusMode::SpectrumEq => {
-                help_spans.extend_from_slice(&[
-                    Span::styled("Esc/⌫", Style::default().fg(Color::Cyan)),
-                    Span::raw(": back  "),
-                    Span::styled("h/l", Style::default().fg(Color::Cyan)),
-                    Span::raw(": band  "),
-                    Span::styled("j/k", Style::default().fg(Color::Cyan)),
-                    Span::raw(": gain"),
-                ]);
-            }
+    #[test]
+    fn test_jump_cycles_through_devices_by_first_letter() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = App::new(1.0);
+        for (id, name) in ["Speakers", "Mic", "scarlett", "Headphones"].into_iter().enumerate() {
+            app.devices.push(DeviceInfo::new(
+                DeviceId::new(id as u64),
+                name.to_string(),
+                crate::audio::DeviceType::Physical,
+            ));
         }
 
-        let status_text = vec![Line::from(help_spans)];
+        app.handle_input(Key::Char('/'), &mut audio_engine).unwrap();
+        assert!(app.jump_mode);
+        // 'h' and 'j' jump instead of hiding or moving while jumping
+        app.handle_input(Key::Char('h'), &mut audio_engine).unwrap();
+        assert_eq!(app.selected_device, 3);
+        app.handle_input(Key::Char('S'), &mut audio_engine).unwrap();
+        assert_eq!(app.selected_device, 0);
+        app.handle_input(Key::Char('s'), &mut audio_engine).unwrap();
+        assert_eq!(app.selected_device, 2);
+        app.handle_input(Key::Char('s'), &mut audio_engine).unwrap();
+        assert_eq!(app.selected_device, 0);
+        app.handle_input(Key::Char('q'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "No device starting with 'q'");
+        assert!(app.running);
+
+        // Any other key leaves jump mode and does what it normally does
+        app.handle_input(Key::Down, &mut audio_engine).unwrap();
+        assert!(!app.jump_mode);
+        assert_eq!(app.selected_device, 1);
+    }
 
-        let paragraph = Paragraph::new(status_text)
-            .block(Block::default().borders(Borders::ALL))
-            .alignment(Alignment::Left);
+    #[test]
+    fn test_number_keys_apply_eq_presets() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        app.current_tab = DeviceTab::Filters;
+        let device_id = DeviceId::new(1);
+
+        app.handle_input(Key::Char('2'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "EQ is not enabled on Device 1 (press 'e')");
+
+        app.eq_enabled_devices.insert(device_id);
+        app.eq_settings.insert(device_id, EqSettings { bypass: true, ..EqSettings::default() });
+        app.handle_input(Key::Char('2'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "Bass boost preset on Device 1");
+        assert_eq!(app.eq_settings[&device_id].bands, EqPreset::BassBoost.to_settings().bands);
+        assert!(app.eq_settings[&device_id].bypass);
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetEqSettings"].sent, 1);
+
+        // Only on the Filters tab, and only numbers with a preset
+        app.handle_input(Key::Char('9'), &mut audio_engine).unwrap();
+        app.current_tab = DeviceTab::Routing;
+        app.handle_input(Key::Char('1'), &mut audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&device_id].bands, EqPreset::BassBoost.to_settings().bands);
+    }
 
-        frame.render_widget(paragraph, area);
+    #[test]
+    fn test_b_bypasses_eq_from_filters_tab() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        app.eq_enabled_devices.insert(device_id);
+        app.eq_settings.insert(device_id, EqPreset::Rock.to_settings());
+
+        // Only on the Filters tab
+        app.handle_input(Key::Char('b'), &mut audio_engine).unwrap();
+        assert!(!app.eq_settings[&device_id].bypass);
+
+        app.current_tab = DeviceTab::Filters;
+        app.handle_input(Key::Char('b'), &mut audio_engine).unwrap();
+        assert!(app.eq_settings[&device_id].bypass);
+        assert!(app.status_message.starts_with("B: EQ bypassed"));
+        assert!(app.get_eq_settings()["Device 1"].bypass);
+        assert!(app.config_dirty);
+
+        app.handle_input(Key::Char('b'), &mut audio_engine).unwrap();
+        assert!(!app.eq_settings[&device_id].bypass);
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetEqBypass"].sent, 2);
     }
 
-    /// Check if auto-save should be triggered (debounced after 2 seconds)
-    pub fn should_auto_save(&self) -> bool {
-        if !self.config_dirty {
-            return false;
+    #[test]
+    fn test_alt_comma_and_period_nudge_balance() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+
+        for _ in 0..3 {
+            app.handle_input(Key::Alt(','), &mut audio_engine).unwrap();
         }
+        assert!((app.volume_settings[&device_id].balance + 0.3).abs() < 1e-6);
+        assert_eq!(app.status_message, "Device 1: balance L 30%");
+        assert!(app.config_dirty);
 
-        if let Some(last_change) = self.last_viz_change {
-            last_change.elapsed() >= Duration::from_secs(2)
-        } else {
-            false
+        let mut terminal = Terminal::new(TestBackend::new(240, 30)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Bal L 30%"));
+
+        // Moving right stops at center before crossing over
+        for _ in 0..3 {
+            app.handle_input(Key::Alt('.'), &mut audio_engine).unwrap();
         }
-    }
+        assert_eq!(app.volume_settings[&device_id].balance, 0.0);
+        assert_eq!(app.status_message, "Device 1: balance center");
+        app.handle_input(Key::Alt('.'), &mut audio_engine).unwrap();
+        assert!((app.volume_settings[&device_id].balance - 0.1).abs() < 1e-6);
 
-    /// Mark config as saved (clear dirty flag)
-    pub fn mark_config_saved(&mut self) {
-        self.config_dirty = false;
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetVolume"].sent, 7);
     }
 
-    /// Get reference to visualized devices set
-    pub fn get_visualized_devices(&self) -> &HashSet<DeviceId> {
-        &self.visualized_devices
-    }
+    #[test]
+    fn test_eq_preset_prompts_request_save_and_load() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        let type_text = |app: &mut App, audio_engine: &mut AudioEngine, text: &str| {
+            for c in text.chars() {
+                app.handle_input(Key::Char(c), audio_engine).unwrap();
+            }
+        };
 
-    /// Get spectrum amplification factor
-    pub fn get_spectrum_amplification(&self) -> f32 {
-        self.spectrum_amplification
+        app.handle_input(Key::Char('E'), &mut audio_engine).unwrap();
+        assert!(app.eq_preset_prompt.is_none());
+        app.eq_enabled_devices.insert(device_id);
+        app.eq_settings.insert(device_id, EqPreset::Vocal.to_settings());
+
+        type_text(&mut app, &mut audio_engine, "Evoice\n");
+        assert_eq!(
+            app.take_eq_preset_request(),
+            Some(EqPresetRequest::Save {
+                name: String::from("voice"),
+                settings: EqPreset::Vocal.to_settings(),
+            })
+        );
+        type_text(&mut app, &mut audio_engine, "Emy voice\n");
+        assert!(app.status_message.starts_with("Invalid preset name"));
+        assert_eq!(app.take_eq_preset_request(), None);
+
+        // Loading needs a saved preset; Tab completes its name
+        app.handle_input(Key::Char(':'), &mut audio_engine).unwrap();
+        assert!(app.eq_preset_prompt.is_none());
+        app.set_eq_presets(vec![String::from("podcast"), String::from("voice")]);
+        type_text(&mut app, &mut audio_engine, ":v\t\n");
+        assert_eq!(
+            app.take_eq_preset_request(),
+            Some(EqPresetRequest::Load { device_id, name: String::from("voice") })
+        );
     }
 
-    /// Find a device by name
-    pub fn find_device_by_name(&self, name: &str) -> Option<&DeviceInfo> {
-        self.devices.iter().find(|d| d.name == name)
+    #[test]
+    fn test_bracket_keys_change_band_q() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        app.eq_enabled_devices.insert(device_id);
+        app.eq_settings.insert(device_id, EqSettings::default());
+        app.focus_mode = FocusMode::SpectrumEq;
+        app.selected_eq_band = 5;
+        let edges = |app: &App| app.eq_settings[&device_id].bands[5].band_edges();
+        let (low, high) = edges(&app);
+
+        app.handle_input(Key::Char(']'), &mut audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&device_id].bands[5].q_value, 1.76);
+        assert_eq!(app.status_message, "1000Hz: Q 1.76 (0.8 octaves)");
+        assert!(edges(&app).0 > low && edges(&app).1 < high);
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["SetEqBand"].sent, 1);
+
+        app.handle_input(Key::Char('['), &mut audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&device_id].bands[5].q_value, 1.41);
+        for _ in 0..20 {
+            app.handle_input(Key::Char('['), &mut audio_engine).unwrap();
+        }
+        assert_eq!(app.eq_settings[&device_id].bands[5].q_value, 0.5);
     }
 
-    /// Get hidden devices as Vec<String> for config saving
-    pub fn get_hidden_devices(&self) -> Vec<String> {
-        self.hidden_devices.iter().cloned().collect()
+    #[test]
+    fn test_r_resets_and_x_disables_the_eq() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        app.focus_mode = FocusMode::SpectrumEq;
+
+        // Nothing to reset or turn off without an EQ
+        app.handle_input(Key::Char('r'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "EQ is not enabled on Device 1");
+        app.handle_input(Key::Char('x'), &mut audio_engine).unwrap();
+        assert!(audio_engine.command_stats().is_empty());
+
+        app.eq_enabled_devices.insert(device_id);
+        app.eq_settings.insert(device_id, EqPreset::Rock.to_settings());
+        app.handle_input(Key::Char('r'), &mut audio_engine).unwrap();
+        assert!(app.eq_settings[&device_id].bands.iter().all(|band| band.gain_db == 0.0));
+        assert_eq!(app.status_message, "Reset EQ on Device 1");
+
+        app.handle_input(Key::Char('x'), &mut audio_engine).unwrap();
+        assert_eq!(app.status_message, "Disabling EQ on Device 1");
+        let stats: HashMap<_, _> = audio_engine.command_stats().into_iter().collect();
+        assert_eq!(stats["ResetEq"].sent, 1);
+        assert_eq!(stats["DisableEq"].sent, 1);
     }
 
-    /// Restore hidden devices from config
-    pub fn restore_hidden_devices(&mut self, hidden_device_names: Vec<String>) {
-        self.hidden_devices = hidden_device_names.into_iter().collect();
+    #[test]
+    fn test_cycle_eq_band_count() {
+        let mut audio_engine = AudioEngine::new().unwrap();
+        let mut app = app_with_spectrum();
+        let device_id = DeviceId::new(1);
+        app.eq_enabled_devices.insert(device_id);
+        app.eq_settings.insert(device_id, EqSettings::default());
+        app.focus_mode = FocusMode::SpectrumEq;
+        app.selected_eq_band = 9;
+
+        app.handle_input(Key::Char('n'), &mut audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&device_id].band_count(), 15);
+        app.handle_input(Key::Char('n'), &mut audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&device_id].band_count(), 31);
+        for _ in 0..30 {
+            app.handle_input(Key::Char('l'), &mut audio_engine).unwrap();
+        }
+        assert_eq!(app.selected_eq_band, 30);
+        assert_eq!(app.get_current_band_frequency(), 20000);
+
+        // All 31 bands fit the Filters tab, in columns
+        app.current_tab = DeviceTab::Filters;
+        let mut terminal = Terminal::new(TestBackend::new(160, 50)).unwrap();
+        terminal
+            .draw(|frame| app.render(frame, &audio_engine))
+            .unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("20Hz") && text.contains("20k: +0.0dB HS"));
+
+        // Back around to 5 bands, with the selection following
+        app.handle_input(Key::Char('n'), &mut audio_engine).unwrap();
+        assert_eq!(app.eq_settings[&device_id].band_count(), 5);
+        assert_eq!(app.selected_eq_band, 4);
+        assert_eq!(app.status_message, "5-band EQ (selected band: 16000Hz)");
     }
 }
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::audio::{DeviceId, DeviceInfo, PortDirection, PortId};
+
+/// How long to wait for a device's ports to appear before giving up
+pub const PORT_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pick the port to visualize for a device (first output port: the monitor side of a
+/// sink, or an application stream's own output, which the audio thread captures directly)
+pub fn visualization_port(device: &DeviceInfo) -> Option<PortId> {
+    device
+        .ports
+        .iter()
+        .find(|p| p.direction == PortDirection::Output)
+        .map(|p| p.id)
+}
+
+/// Result of re-checking a queued visualization request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingOutcome {
+    /// The device now has a usable port; start visualizing it
+    Ready { device_id: DeviceId, port_id: PortId },
+    /// No usable port appeared in time (or the device went away)
+    TimedOut { device_id: DeviceId },
+}
+
+/// Visualization requests waiting for their device's ports to be enumerated
+/// A PipeWire node often appears before its ports, so requests are retried
+/// as the routing graph fills in
+#[derive(Debug, Default)]
+pub struct PendingVisualizations {
+    /// Device → time the request was queued
+    requests: HashMap<DeviceId, Instant>,
+}
+
+impl PendingVisualizations {
+    /// Queue a request (keeps the original queue time if already pending)
+    pub fn queue(&mut self, device_id: DeviceId, now: Instant) {
+        self.requests.entry(device_id).or_insert(now);
+    }
+
+    /// Drop a queued request
+    pub fn cancel(&mut self, device_id: DeviceId) -> bool {
+        self.requests.remove(&device_id).is_some()
+    }
+
+    /// Drop all queued requests
+    pub fn clear(&mut self) {
+        self.requests.clear();
+    }
+
+    pub fn contains(&self, device_id: DeviceId) -> bool {
+        self.requests.contains_key(&device_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Re-check all queued requests against the current device list
+    /// Ready and timed-out requests are removed from the queue
+    pub fn poll<'a, I>(&mut self, devices: I, now: Instant) -> Vec<PendingOutcome>
+    where
+        I: IntoIterator<Item = &'a DeviceInfo>,
+    {
+        if self.requests.is_empty() {
+            return Vec::new();
+        }
+
+        let ports: HashMap<DeviceId, Option<PortId>> = devices
+            .into_iter()
+            .filter(|d| self.requests.contains_key(&d.id))
+            .map(|d| (d.id, visualization_port(d)))
+            .collect();
+
+        let mut outcomes = Vec::new();
+        self.requests.retain(|&device_id, &mut queued_at| {
+            if let Some(Some(port_id)) = ports.get(&device_id) {
+                outcomes.push(PendingOutcome::Ready {
+                    device_id,
+                    port_id: *port_id,
+                });
+                false
+            } else if now.duration_since(queued_at) >= PORT_WAIT_TIMEOUT {
+                outcomes.push(PendingOutcome::TimedOut { device_id });
+                false
+            } else {
+                true
+            }
+        });
+
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{DeviceType, PortInfo, RoutingGraph};
+
+    fn add_port(graph: &mut RoutingGraph, device_id: DeviceId, direction: PortDirection) -> PortId {
+        let port_id = graph.generate_port_id();
+        let device = graph.get_device_mut(device_id).unwrap();
+        device.ports.push(PortInfo::new(
+            port_id,
+            String::from("monitor_FL"),
+            direction,
+            format!("{}:monitor_FL", device.name),
+        ));
+        port_id
+    }
+
+    #[test]
+    fn test_pending_until_output_port_appears() {
+        let mut graph = RoutingGraph::new();
+        let device_id = graph.generate_device_id();
+        graph.add_device(DeviceInfo::new(
+            device_id,
+            String::from("speakers"),
+            DeviceType::Physical,
+        ));
+
+        let start = Instant::now();
+        let mut pending = PendingVisualizations::default();
+        pending.queue(device_id, start);
+
+        // Node known but no ports yet
+        assert!(pending.poll(graph.list_devices(), start).is_empty());
+        assert!(pending.contains(device_id));
+
+        // Input ports alone are not usable
+        add_port(&mut graph, device_id, PortDirection::Input);
+        let later = start + Duration::from_millis(100);
+        assert!(pending.poll(graph.list_devices(), later).is_empty());
+
+        // Output port arrives
+        let port_id = add_port(&mut graph, device_id, PortDirection::Output);
+        let later = start + Duration::from_millis(200);
+        assert_eq!(
+            pending.poll(graph.list_devices(), later),
+            vec![PendingOutcome::Ready { device_id, port_id }]
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_pending_times_out() {
+        let mut graph = RoutingGraph::new();
+        let device_id = graph.generate_device_id();
+        graph.add_device(DeviceInfo::new(
+            device_id,
+            String::from("speakers"),
+            DeviceType::Physical,
+        ));
+
+        let start = Instant::now();
+        let mut pending = PendingVisualizations::default();
+        pending.queue(device_id, start);
+
+        let almost = start + PORT_WAIT_TIMEOUT - Duration::from_millis(1);
+        assert!(pending.poll(graph.list_devices(), almost).is_empty());
+
+        let expired = start + PORT_WAIT_TIMEOUT;
+        assert_eq!(
+            pending.poll(graph.list_devices(), expired),
+            vec![PendingOutcome::TimedOut { device_id }]
+        );
+        assert!(pending.is_empty());
+    }
+}
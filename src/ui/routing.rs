@@ -0,0 +1,176 @@
+use crate::audio::{PortConnection, PortDirection, PortInfo};
+
+/// One row of the Routing tab: a port and one of its peers, or a port without any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingRow {
+    /// Short name of the device's own port (e.g. "monitor_FL")
+    pub port_label: String,
+    /// PipeWire name of the device's own port
+    pub port_name: String,
+    pub direction: PortDirection,
+    /// The row's connection, None if the port isn't connected
+    pub connection: Option<PortConnection>,
+}
+
+impl RoutingRow {
+    /// (source, destination) PipeWire port names of the row's connection
+    pub fn endpoints(&self) -> Option<(&str, &str)> {
+        self.connection.as_ref().map(PortConnection::endpoints)
+    }
+}
+
+/// Rows for every port of a device, in port order: one per connection, or a
+/// single unconnected row, so any port can be picked for patching
+pub fn routing_rows(ports: &[PortInfo], connections: &[PortConnection]) -> Vec<RoutingRow> {
+    ports
+        .iter()
+        .flat_map(|port| {
+            let mut rows: Vec<RoutingRow> = connections
+                .iter()
+                .filter(|connection| connection.port_name == port.pipewire_port_name)
+                .map(|connection| RoutingRow {
+                    port_label: port.name.clone(),
+                    port_name: port.pipewire_port_name.clone(),
+                    direction: port.direction,
+                    connection: Some(connection.clone()),
+                })
+                .collect();
+            if rows.is_empty() {
+                rows.push(RoutingRow {
+                    port_label: port.name.clone(),
+                    port_name: port.pipewire_port_name.clone(),
+                    direction: port.direction,
+                    connection: None,
+                });
+            }
+            rows
+        })
+        .collect()
+}
+
+/// Lay out a device's ports as a two-column diagram, one row per connection
+/// The device's ports are on the left, their peers on the right; a port with
+/// several peers is named once and fans out with box-drawing branches
+pub fn diagram_rows(rows: &[RoutingRow]) -> Vec<String> {
+    let label_width = rows
+        .iter()
+        .map(|c| c.port_label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let same_port = |other: Option<&RoutingRow>| {
+                other.is_some_and(|other| other.port_name == row.port_name)
+            };
+            let first = i == 0 || !same_port(rows.get(i - 1));
+            let last = !same_port(rows.get(i + 1));
+            let Some(connection) = &row.connection else {
+                return format!(
+                    "{:<width$} ╌╌╌╌ not connected",
+                    row.port_label,
+                    width = label_width
+                );
+            };
+
+            let connector = match (row.direction, first, last) {
+                (PortDirection::Output, true, true) => "───▶",
+                (PortDirection::Output, true, false) => "─┬─▶",
+                (PortDirection::Output, false, false) => " ├─▶",
+                (PortDirection::Output, false, true) => " └─▶",
+                (PortDirection::Input, true, true) => "◀───",
+                (PortDirection::Input, true, false) => "◀┬──",
+                (PortDirection::Input, false, false) => " ├──",
+                (PortDirection::Input, false, true) => " └──",
+            };
+            let label = if first { row.port_label.as_str() } else { "" };
+
+            format!(
+                "{:<width$} {} {}",
+                label,
+                connector,
+                connection.peer_name,
+                width = label_width
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::PortId;
+
+    fn connection(port: &str, peer: &str, direction: PortDirection) -> RoutingRow {
+        RoutingRow {
+            port_label: port.to_string(),
+            port_name: format!("Device:{}", port),
+            direction,
+            connection: Some(PortConnection {
+                port_label: port.to_string(),
+                port_name: format!("Device:{}", port),
+                peer_name: peer.to_string(),
+                direction,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_single_connections() {
+        let rows = diagram_rows(&[
+            connection("monitor_FL", "Recorder:input_FL", PortDirection::Output),
+            connection("in_FL", "Player:output_FL", PortDirection::Input),
+        ]);
+        assert_eq!(
+            rows,
+            vec![
+                "monitor_FL ───▶ Recorder:input_FL",
+                "in_FL      ◀─── Player:output_FL",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fan_out_names_port_once() {
+        let rows = diagram_rows(&[
+            connection("monitor_FL", "A:input", PortDirection::Output),
+            connection("monitor_FL", "B:input", PortDirection::Output),
+            connection("monitor_FL", "C:input", PortDirection::Output),
+        ]);
+        assert_eq!(
+            rows,
+            vec![
+                "monitor_FL ─┬─▶ A:input",
+                "            ├─▶ B:input",
+                "            └─▶ C:input",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unconnected_ports_get_a_row() {
+        let port = |name: &str, direction| {
+            PortInfo::new(PortId::new(1), name.to_string(), direction, format!("Device:{}", name))
+        };
+        let ports = [port("monitor_FL", PortDirection::Output), port("in_FL", PortDirection::Input)];
+        let connections = [PortConnection {
+            port_label: String::from("in_FL"),
+            port_name: String::from("Device:in_FL"),
+            peer_name: String::from("Player:output_FL"),
+            direction: PortDirection::Input,
+        }];
+
+        let rows = routing_rows(&ports, &connections);
+        assert_eq!(rows[0].endpoints(), None);
+        assert_eq!(rows[1].endpoints(), Some(("Player:output_FL", "Device:in_FL")));
+        assert_eq!(
+            diagram_rows(&rows),
+            vec![
+                "monitor_FL ╌╌╌╌ not connected",
+                "in_FL      ◀─── Player:output_FL",
+            ]
+        );
+    }
+}
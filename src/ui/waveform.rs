@@ -0,0 +1,71 @@
+use super::goniometer::DOT_BITS;
+
+/// Plot samples as a waveform into braille cells, at a fixed ±1.0 (0 dBFS) scale
+/// so clipped audio runs along the top and bottom edges
+/// The samples are spread over the full width and consecutive points are joined
+/// with vertical strokes, so steep edges don't leave gaps
+/// Returns `height` rows of `width` cells, each a bitmask of braille dots
+pub fn plot(samples: &[f32], width: usize, height: usize) -> Vec<Vec<u8>> {
+    let mut cells = vec![vec![0u8; width]; height];
+    if width == 0 || height == 0 || samples.is_empty() {
+        return cells;
+    }
+
+    let dots_x = width * 2;
+    let dots_y = height * 4;
+    let to_y = |sample: f32| {
+        let sample = if sample.is_nan() { 0.0 } else { sample.clamp(-1.0, 1.0) };
+        ((1.0 - sample) / 2.0 * (dots_y - 1) as f32).round() as usize
+    };
+
+    let mut previous_y = None;
+    for x in 0..dots_x {
+        let index = x * samples.len() / dots_x;
+        let y = to_y(samples[index]);
+        let (top, bottom) = match previous_y {
+            Some(previous) if previous < y => (previous + 1, y),
+            Some(previous) if previous > y => (y, previous - 1),
+            _ => (y, y),
+        };
+        for y in top..=bottom {
+            cells[y / 4][x / 2] |= DOT_BITS[y % 4][x % 2];
+        }
+        previous_y = Some(y);
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dot rows lit in each dot column
+    fn lit_rows(cells: &[Vec<u8>], x: usize) -> Vec<usize> {
+        (0..cells.len() * 4)
+            .filter(|&y| cells[y / 4][x / 2] & DOT_BITS[y % 4][x % 2] != 0)
+            .collect()
+    }
+
+    #[test]
+    fn test_silence_and_full_scale() {
+        let silence = plot(&[0.0; 64], 4, 2);
+        // 8 dot rows: 0.0 rounds to the lower middle one
+        for x in 0..8 {
+            assert_eq!(lit_rows(&silence, x), vec![4]);
+        }
+
+        let clipped = plot(&[1.0, 2.0, -1.0, -5.0], 2, 2);
+        assert_eq!(lit_rows(&clipped, 0), vec![0]);
+        assert_eq!(lit_rows(&clipped, 1), vec![0]);
+        assert_eq!(lit_rows(&clipped, 3), vec![7]);
+    }
+
+    #[test]
+    fn test_steps_are_joined() {
+        let cells = plot(&[1.0, -1.0], 1, 2);
+        assert_eq!(lit_rows(&cells, 0), vec![0]);
+        assert_eq!(lit_rows(&cells, 1), (1..=7).collect::<Vec<_>>());
+        assert!(plot(&[], 3, 3).iter().flatten().all(|&bits| bits == 0));
+    }
+}
@@ -0,0 +1,130 @@
+use std::ops::Range;
+
+/// Lowest frequency shown when fully zoomed out (matches the FFT's first bin edge)
+pub const MIN_DISPLAY_FREQ: f32 = 18.0;
+/// Highest frequency shown when fully zoomed out
+pub const MAX_DISPLAY_FREQ: f32 = 20000.0;
+
+/// Step size when moving a zoom edge, in octaves
+const EDGE_STEP_OCTAVES: f32 = 1.0 / 3.0;
+/// Narrowest allowed zoom window, in octaves
+const MIN_SPAN_OCTAVES: f32 = 1.0;
+
+/// Which edge of the zoom window to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoomEdge {
+    Low,
+    High,
+}
+
+/// Display frequency window for the spectrum
+/// Purely a display transform: bins outside the window are skipped at render time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrequencyZoom {
+    pub low_hz: f32,
+    pub high_hz: f32,
+}
+
+impl Default for FrequencyZoom {
+    fn default() -> Self {
+        Self {
+            low_hz: MIN_DISPLAY_FREQ,
+            high_hz: MAX_DISPLAY_FREQ,
+        }
+    }
+}
+
+impl FrequencyZoom {
+    /// Whether the full frequency range is shown
+    pub fn is_full_range(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reset to the full frequency range
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Move one edge of the window by a third of an octave (direction: +1 up, -1 down)
+    /// Keeps the window at least one octave wide and inside the full range
+    pub fn step_edge(&mut self, edge: ZoomEdge, direction: f32) {
+        let factor = 2f32.powf(EDGE_STEP_OCTAVES * direction.signum());
+        let min_ratio = 2f32.powf(MIN_SPAN_OCTAVES);
+
+        match edge {
+            ZoomEdge::Low => {
+                self.low_hz = (self.low_hz * factor)
+                    .clamp(MIN_DISPLAY_FREQ, self.high_hz / min_ratio);
+            }
+            ZoomEdge::High => {
+                self.high_hz = (self.high_hz * factor)
+                    .clamp(self.low_hz * min_ratio, MAX_DISPLAY_FREQ);
+            }
+        }
+    }
+
+    /// Range of bin indices whose center frequency falls inside the window
+    /// Falls back to all bins if the window contains none (e.g. low sample rate)
+    pub fn bin_range(&self, frequencies: &[f32]) -> Range<usize> {
+        if self.is_full_range() {
+            return 0..frequencies.len();
+        }
+
+        let start = frequencies.iter().position(|&f| f >= self.low_hz);
+        let end = frequencies.iter().rposition(|&f| f <= self.high_hz);
+
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => start..end + 1,
+            _ => 0..frequencies.len(),
+        }
+    }
+
+    /// Short label for the window, e.g. "2.0k-5.0k Hz"
+    pub fn label(&self) -> String {
+        format!("{}-{} Hz", format_freq(self.low_hz), format_freq(self.high_hz))
+    }
+}
+
+/// Format a frequency compactly (e.g. 125, 2.0k)
+fn format_freq(hz: f32) -> String {
+    if hz >= 1000.0 {
+        format!("{:.1}k", hz / 1000.0)
+    } else {
+        format!("{:.0}", hz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_range_uses_all_bins() {
+        let zoom = FrequencyZoom::default();
+        let freqs = [20.0, 100.0, 1000.0, 10000.0];
+        assert_eq!(zoom.bin_range(&freqs), 0..4);
+    }
+
+    #[test]
+    fn test_bin_range_selects_window() {
+        let zoom = FrequencyZoom {
+            low_hz: 2000.0,
+            high_hz: 5000.0,
+        };
+        let freqs = [500.0, 1500.0, 2500.0, 4000.0, 6000.0];
+        assert_eq!(zoom.bin_range(&freqs), 2..4);
+    }
+
+    #[test]
+    fn test_step_edge_keeps_minimum_span() {
+        let mut zoom = FrequencyZoom::default();
+        for _ in 0..100 {
+            zoom.step_edge(ZoomEdge::Low, 1.0);
+        }
+        assert!(zoom.high_hz / zoom.low_hz >= 2.0 - 1e-3);
+        assert!(zoom.high_hz <= MAX_DISPLAY_FREQ);
+
+        zoom.reset();
+        assert!(zoom.is_full_range());
+    }
+}